@@ -0,0 +1,66 @@
+#[cfg(feature = "rpc")]
+pub use self::fetch::*;
+pub use crate::views::Totals;
+
+#[cfg(feature = "rpc")]
+mod fetch {
+    use anchor_lang::prelude::Pubkey;
+    use anyhow::Result;
+    use jup_stable::state::{
+        benefactor::Benefactor, config::Config, operator::Operator, vault::Vault,
+    };
+    use solana_client::nonblocking::rpc_client::RpcClient;
+
+    use crate::{
+        jup_stable::derivation,
+        rpc::{fetch_account, fetch_all_accounts},
+    };
+
+    pub async fn fetch_config(rpc: &RpcClient) -> Result<Config> {
+        fetch_account(rpc, &derivation::find_config()).await
+    }
+
+    pub async fn fetch_vault(rpc: &RpcClient, stablecoin_mint: &Pubkey) -> Result<Vault> {
+        fetch_account(rpc, &derivation::find_vault(stablecoin_mint)).await
+    }
+
+    pub async fn fetch_benefactor(rpc: &RpcClient, authority: &Pubkey) -> Result<Benefactor> {
+        fetch_account(rpc, &derivation::find_benefactor(authority)).await
+    }
+
+    /// Resolves the vault the benefactor at `benefactor` hints redemptions at, in the benefactor's
+    /// own instance. `None` when it has no hint; the program doesn't enforce it either way.
+    pub async fn fetch_redeem_vault_hint(
+        rpc: &RpcClient,
+        benefactor: &Pubkey,
+    ) -> Result<Option<(Pubkey, Vault)>> {
+        let benefactor: Benefactor = fetch_account(rpc, benefactor).await?;
+        let Some(vault_mint) = benefactor.redeem_vault_hint() else {
+            return Ok(None);
+        };
+        let instance_mint =
+            (benefactor.instance_mint != Pubkey::default()).then_some(&benefactor.instance_mint);
+        let vault = fetch_account(
+            rpc,
+            &derivation::find_instance_vault(instance_mint, &vault_mint),
+        )
+        .await?;
+        Ok(Some((vault_mint, vault)))
+    }
+
+    pub async fn fetch_operator(rpc: &RpcClient, authority: &Pubkey) -> Result<Operator> {
+        fetch_account(rpc, &derivation::find_operator(authority)).await
+    }
+
+    pub async fn fetch_vaults(rpc: &RpcClient) -> Result<Vec<(Pubkey, Vault)>> {
+        fetch_all_accounts(rpc).await
+    }
+
+    pub async fn fetch_benefactors(rpc: &RpcClient) -> Result<Vec<(Pubkey, Benefactor)>> {
+        fetch_all_accounts(rpc).await
+    }
+
+    pub async fn fetch_operators(rpc: &RpcClient) -> Result<Vec<(Pubkey, Operator)>> {
+        fetch_all_accounts(rpc).await
+    }
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{associated_token::get_associated_token_address_with_program_id, metadata};
+
+/// Seed scoping the accounts of the stablecoin instance with LP mint `instance_mint`, `None` being
+/// the instance created by `init`.
+fn instance_seed(instance_mint: Option<&Pubkey>) -> &[u8] {
+    instance_mint.map_or(&[], |mint| mint.as_ref())
+}
+
+pub fn find_config() -> Pubkey { find_instance_config(None) }
+
+pub fn find_instance_config(instance_mint: Option<&Pubkey>) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"config", instance_seed(instance_mint)],
+        &jup_stable::id(),
+    );
+    pubkey
+}
+
+pub fn find_authority() -> Pubkey { find_instance_authority(None) }
+
+pub fn find_instance_authority(instance_mint: Option<&Pubkey>) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"authority", instance_seed(instance_mint)],
+        &jup_stable::id(),
+    );
+    pubkey
+}
+
+pub fn find_operator(authority: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) =
+        Pubkey::find_program_address(&[b"operator", authority.as_ref()], &jup_stable::id());
+    pubkey
+}
+
+pub fn find_operator_log(operator: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) =
+        Pubkey::find_program_address(&[b"operator_log", operator.as_ref()], &jup_stable::id());
+    pubkey
+}
+
+pub fn find_vault(stablecoin_mint: &Pubkey) -> Pubkey { find_instance_vault(None, stablecoin_mint) }
+
+pub fn find_instance_vault(instance_mint: Option<&Pubkey>, stablecoin_mint: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[
+            b"vault",
+            instance_seed(instance_mint),
+            stablecoin_mint.as_ref(),
+        ],
+        &jup_stable::id(),
+    );
+    pubkey
+}
+
+pub fn find_collateral_approval(mint: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) =
+        Pubkey::find_program_address(&[b"collateral_approval", mint.as_ref()], &jup_stable::id());
+    pubkey
+}
+
+pub fn find_vault_registry() -> Pubkey { find_instance_vault_registry(None) }
+
+pub fn find_instance_vault_registry(instance_mint: Option<&Pubkey>) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"vault_registry", instance_seed(instance_mint)],
+        &jup_stable::id(),
+    );
+    pubkey
+}
+
+pub fn find_vault_token_account(stablecoin_mint: &Pubkey) -> Pubkey {
+    find_vault_token_account_with_program(stablecoin_mint, &anchor_spl::token::ID)
+}
+
+pub fn find_vault_token_account_with_program(
+    stablecoin_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    find_instance_vault_token_account_with_program(None, stablecoin_mint, token_program)
+}
+
+pub fn find_instance_vault_token_account_with_program(
+    instance_mint: Option<&Pubkey>,
+    stablecoin_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    get_associated_token_address_with_program_id(
+        &find_instance_authority(instance_mint),
+        stablecoin_mint,
+        token_program,
+    )
+}
+
+pub fn find_benefactor(authority: &Pubkey) -> Pubkey { find_instance_benefactor(None, authority) }
+
+pub fn find_instance_benefactor(instance_mint: Option<&Pubkey>, authority: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[
+            b"benefactor",
+            instance_seed(instance_mint),
+            authority.as_ref(),
+        ],
+        &jup_stable::id(),
+    );
+    pubkey
+}
+
+pub fn find_benefactor_registry(page: u32) -> Pubkey {
+    find_instance_benefactor_registry(None, page)
+}
+
+pub fn find_instance_benefactor_registry(instance_mint: Option<&Pubkey>, page: u32) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[
+            b"benefactor_registry",
+            instance_seed(instance_mint),
+            &page.to_le_bytes(),
+        ],
+        &jup_stable::id(),
+    );
+    pubkey
+}
+
+pub fn find_metadata(mint: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"metadata", &metadata::ID.to_bytes(), &mint.to_bytes()],
+        &metadata::ID,
+    );
+    pubkey
+}
+
+pub fn find_event_authority() -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(&[b"__event_authority"], &jup_stable::id());
+    pubkey
+}
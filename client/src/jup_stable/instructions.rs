@@ -0,0 +1,1832 @@
+use anchor_lang::{
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        sysvar,
+    },
+    system_program, Id, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    metadata,
+};
+
+use super::derivation::{
+    find_authority, find_benefactor_registry, find_collateral_approval, find_config,
+    find_event_authority, find_instance_authority, find_instance_benefactor,
+    find_instance_benefactor_registry, find_instance_config, find_instance_vault,
+    find_instance_vault_registry, find_instance_vault_token_account_with_program, find_metadata,
+    find_operator, find_operator_log, find_vault, find_vault_registry,
+    find_vault_token_account_with_program,
+};
+
+#[derive(Debug)]
+pub struct InitInstructionAccounts {
+    pub payer: Pubkey,
+    pub upgrade_authority: Pubkey,
+    pub program_data: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub struct InitInstructionArgs {
+    pub decimals: u8,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub peg_price_usd: u64,
+    pub is_mint_redeem_enabled: bool,
+    pub default_vault_period_limit: jup_stable::state::common::PeriodLimitTemplate,
+    pub default_benefactor_period_limit: jup_stable::state::common::PeriodLimitTemplate,
+}
+
+pub fn create_init_instruction(
+    accounts: InitInstructionAccounts,
+    args: InitInstructionArgs,
+) -> Instruction {
+    let accounts = jup_stable::accounts::Init {
+        payer: accounts.payer,
+        upgrade_authority: accounts.upgrade_authority,
+        operator: find_operator(&accounts.upgrade_authority),
+        operator_log: find_operator_log(&find_operator(&accounts.upgrade_authority)),
+        config: find_config(),
+        vault_registry: find_vault_registry(),
+        benefactor_registry: find_benefactor_registry(0),
+        authority: find_authority(),
+        mint: accounts.mint,
+        metadata: find_metadata(&accounts.mint),
+        program_data: accounts.program_data,
+        program: jup_stable::id(),
+        metadata_program: metadata::ID,
+        token_program: accounts.token_program,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::Init {
+            decimals: args.decimals,
+            name: args.name,
+            symbol: args.symbol,
+            uri: args.uri,
+            params: jup_stable::instructions::InitConfigParams {
+                peg_price_usd: args.peg_price_usd,
+                is_mint_redeem_enabled: args.is_mint_redeem_enabled,
+                default_vault_period_limit: args.default_vault_period_limit,
+                default_benefactor_period_limit: args.default_benefactor_period_limit,
+            },
+        }
+        .data(),
+    }
+}
+
+#[derive(Debug)]
+pub struct InitInstanceInstructionAccounts {
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    /// LP mint of the new instance, which has to sign
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn create_init_instance_instruction(
+    accounts: InitInstanceInstructionAccounts,
+    args: InitInstructionArgs,
+) -> Instruction {
+    let instance_mint = Some(&accounts.mint);
+    let accounts = jup_stable::accounts::InitInstance {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        payer: accounts.payer,
+        authority: find_instance_authority(instance_mint),
+        mint: accounts.mint,
+        metadata: find_metadata(&accounts.mint),
+        config: find_instance_config(instance_mint),
+        vault_registry: find_instance_vault_registry(instance_mint),
+        benefactor_registry: find_instance_benefactor_registry(instance_mint, 0),
+        metadata_program: metadata::ID,
+        token_program: accounts.token_program,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::InitInstance {
+            decimals: args.decimals,
+            name: args.name,
+            symbol: args.symbol,
+            uri: args.uri,
+            params: jup_stable::instructions::InitConfigParams {
+                peg_price_usd: args.peg_price_usd,
+                is_mint_redeem_enabled: args.is_mint_redeem_enabled,
+                default_vault_period_limit: args.default_vault_period_limit,
+                default_benefactor_period_limit: args.default_benefactor_period_limit,
+            },
+        }
+        .data(),
+    }
+}
+
+pub struct ApproveCollateralInstructionAccounts {
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub mint: Pubkey,
+}
+
+pub fn create_approve_collateral_instruction(
+    accounts: ApproveCollateralInstructionAccounts,
+) -> Instruction {
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: jup_stable::accounts::ApproveCollateral {
+            operator_authority: accounts.authority,
+            operator: find_operator(&accounts.authority),
+            payer: accounts.payer,
+            mint: accounts.mint,
+            config: find_config(),
+            collateral_approval: find_collateral_approval(&accounts.mint),
+            system_program: system_program::ID,
+            operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+        }
+        .to_account_metas(Some(true)),
+        data: jup_stable::instruction::ApproveCollateral {}.data(),
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateVaultInstructionAccounts {
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn create_create_vault_instruction(accounts: CreateVaultInstructionAccounts) -> Instruction {
+    create_create_vault_instruction_for_instance(None, accounts)
+}
+
+/// Builders suffixed `_for_instance` target the stablecoin instance with LP mint `instance_mint`,
+/// `None` being the instance created by `init`.
+pub fn create_create_vault_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: CreateVaultInstructionAccounts,
+) -> Instruction {
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: jup_stable::accounts::CreateVault {
+            operator_authority: accounts.authority,
+            operator: find_operator(&accounts.authority),
+            payer: accounts.payer,
+            mint: accounts.mint,
+            config: find_instance_config(instance_mint),
+            authority: find_instance_authority(instance_mint),
+            collateral_approval: find_collateral_approval(&accounts.mint),
+            vault: find_instance_vault(instance_mint, &accounts.mint),
+            vault_registry: find_instance_vault_registry(instance_mint),
+            token_account: find_instance_vault_token_account_with_program(
+                instance_mint,
+                &accounts.mint,
+                &accounts.token_program,
+            ),
+            token_program: accounts.token_program,
+            system_program: system_program::ID,
+            associated_token_program: AssociatedToken::id(),
+            operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+        }
+        .to_account_metas(Some(true)),
+        data: jup_stable::instruction::CreateVault {}.data(),
+    }
+}
+
+pub struct CreateVaultRegistryInstructionAccounts {
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+}
+
+/// `vault_mints` are the collaterals of the vaults created before the registry existed.
+pub fn create_create_vault_registry_instruction(
+    accounts: CreateVaultRegistryInstructionAccounts,
+    vault_mints: &[Pubkey],
+) -> Instruction {
+    let mut metas = jup_stable::accounts::CreateVaultRegistry {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        payer: accounts.payer,
+        vault_registry: find_vault_registry(),
+        system_program: system_program::ID,
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+    metas.extend(
+        vault_mints
+            .iter()
+            .map(|mint| AccountMeta::new_readonly(find_vault(mint), false)),
+    );
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: metas,
+        data: jup_stable::instruction::CreateVaultRegistry {}.data(),
+    }
+}
+
+pub struct CreateBenefactorInstructionAccounts {
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+    pub benefactor_authority: Pubkey,
+    /// Registry page with room left for the new benefactor
+    pub registry_page: u32,
+}
+
+pub struct CreateBenefactorInstructionArgs {
+    pub mint_fee_rate: u16,
+    pub redeem_fee_rate: u16,
+}
+
+pub fn create_create_benefactor_instruction(
+    accounts: CreateBenefactorInstructionAccounts,
+    args: CreateBenefactorInstructionArgs,
+) -> Instruction {
+    create_create_benefactor_instruction_for_instance(None, accounts, args)
+}
+
+pub fn create_create_benefactor_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: CreateBenefactorInstructionAccounts,
+    args: CreateBenefactorInstructionArgs,
+) -> Instruction {
+    let accounts = jup_stable::accounts::CreateBenefactor {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        payer: accounts.payer,
+        benefactor_authority: accounts.benefactor_authority,
+        config: find_instance_config(instance_mint),
+        benefactor: find_instance_benefactor(instance_mint, &accounts.benefactor_authority),
+        benefactor_registry: find_instance_benefactor_registry(
+            instance_mint,
+            accounts.registry_page,
+        ),
+        system_program: system_program::ID,
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::CreateBenefactor {
+            mint_fee_rate: args.mint_fee_rate,
+            redeem_fee_rate: args.redeem_fee_rate,
+        }
+        .data(),
+    }
+}
+
+pub struct MintInstructionAccounts {
+    pub user: Pubkey,
+    pub benefactor: Pubkey,
+    pub custodian: Pubkey,
+    /// The vault's `custodian_token_account` override, if it has one; the custodian's ATA
+    /// otherwise.
+    pub custodian_token_account: Option<Pubkey>,
+    pub vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub vault_token_program: Pubkey,
+    pub lp_token_program: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+pub fn create_mint_instruction(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: MintInstructionAccounts,
+) -> Instruction {
+    mint_instruction(None, amount, min_amount_out, accounts, false)
+}
+
+pub fn create_mint_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    amount: u64,
+    min_amount_out: u64,
+    accounts: MintInstructionAccounts,
+) -> Instruction {
+    mint_instruction(instance_mint, amount, min_amount_out, accounts, false)
+}
+
+/// Checks the mint without executing it. Decode the return data as
+/// [`jup_stable::instructions::MintQuote`].
+pub fn create_mint_dry_run_instruction(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: MintInstructionAccounts,
+) -> Instruction {
+    mint_instruction(None, amount, min_amount_out, accounts, true)
+}
+
+fn mint_instruction(
+    instance_mint: Option<&Pubkey>,
+    amount: u64,
+    min_amount_out: u64,
+    accounts: MintInstructionAccounts,
+    dry_run: bool,
+) -> Instruction {
+    let user_collateral_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.vault_mint,
+        &accounts.vault_token_program,
+    );
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.lp_mint,
+        &accounts.lp_token_program,
+    );
+    let custodian_ata = accounts.custodian_token_account.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &accounts.custodian,
+            &accounts.vault_mint,
+            &accounts.vault_token_program,
+        )
+    });
+
+    let mut acc = jup_stable::accounts::Mint {
+        user: accounts.user,
+        user_collateral_token_account: user_collateral_ata,
+        user_lp_token_account: user_lp_ata,
+        config: find_instance_config(instance_mint),
+        authority: find_instance_authority(instance_mint),
+        lp_mint: accounts.lp_mint,
+        vault: find_instance_vault(instance_mint, &accounts.vault_mint),
+        custodian: accounts.custodian,
+        custodian_token_account: custodian_ata,
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        lp_token_program: accounts.lp_token_program,
+        vault_token_program: accounts.vault_token_program,
+        system_program: system_program::ID,
+        event_authority: find_event_authority(),
+        program: jup_stable::id(),
+    }
+    .to_account_metas(Some(false));
+
+    acc.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: acc,
+        data: jup_stable::instruction::Mint {
+            amount,
+            min_amount_out,
+            dry_run,
+        }
+        .data(),
+    }
+}
+
+pub struct RedeemInstructionAccounts {
+    pub user: Pubkey,
+    pub benefactor: Pubkey,
+    pub vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub vault_token_program: Pubkey,
+    pub lp_token_program: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+pub fn create_redeem_instruction(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: RedeemInstructionAccounts,
+) -> Instruction {
+    redeem_instruction(None, amount, min_amount_out, accounts, false)
+}
+
+pub fn create_redeem_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    amount: u64,
+    min_amount_out: u64,
+    accounts: RedeemInstructionAccounts,
+) -> Instruction {
+    redeem_instruction(instance_mint, amount, min_amount_out, accounts, false)
+}
+
+/// Checks the redeem without executing it. Decode the return data as
+/// [`jup_stable::instructions::RedeemQuote`].
+pub fn create_redeem_dry_run_instruction(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: RedeemInstructionAccounts,
+) -> Instruction {
+    redeem_instruction(None, amount, min_amount_out, accounts, true)
+}
+
+fn redeem_instruction(
+    instance_mint: Option<&Pubkey>,
+    amount: u64,
+    min_amount_out: u64,
+    accounts: RedeemInstructionAccounts,
+    dry_run: bool,
+) -> Instruction {
+    let user_collateral_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.vault_mint,
+        &accounts.vault_token_program,
+    );
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.lp_mint,
+        &accounts.lp_token_program,
+    );
+
+    let mut accs = jup_stable::accounts::Redeem {
+        user: accounts.user,
+        user_lp_token_account: user_lp_ata,
+        user_collateral_token_account: user_collateral_ata,
+        config: find_instance_config(instance_mint),
+        authority: find_instance_authority(instance_mint),
+        lp_mint: accounts.lp_mint,
+        vault: find_instance_vault(instance_mint, &accounts.vault_mint),
+        vault_token_account: find_instance_vault_token_account_with_program(
+            instance_mint,
+            &accounts.vault_mint,
+            &accounts.vault_token_program,
+        ),
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        lp_token_program: accounts.lp_token_program,
+        vault_token_program: accounts.vault_token_program,
+        system_program: system_program::ID,
+        event_authority: find_event_authority(),
+        program: jup_stable::id(),
+    }
+    .to_account_metas(Some(false));
+    accs.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: accs,
+        data: jup_stable::instruction::Redeem {
+            amount,
+            min_amount_out,
+            dry_run,
+        }
+        .data(),
+    }
+}
+
+pub struct RelayedMintInstructionAccounts {
+    pub relayer: Pubkey,
+    pub user: Pubkey,
+    pub benefactor: Pubkey,
+    pub custodian: Pubkey,
+    /// The vault's `custodian_token_account` override, if it has one; the custodian's ATA
+    /// otherwise.
+    pub custodian_token_account: Option<Pubkey>,
+    pub vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub vault_token_program: Pubkey,
+    pub lp_token_program: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+/// The user has to approve [`find_authority`] as delegate of its collateral ATA for at least
+/// `amount` first.
+pub fn create_relayed_mint_instruction(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: RelayedMintInstructionAccounts,
+) -> Instruction {
+    let user_collateral_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.vault_mint,
+        &accounts.vault_token_program,
+    );
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.lp_mint,
+        &accounts.lp_token_program,
+    );
+    let custodian_ata = accounts.custodian_token_account.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &accounts.custodian,
+            &accounts.vault_mint,
+            &accounts.vault_token_program,
+        )
+    });
+
+    let mut acc = jup_stable::accounts::RelayedMint {
+        relayer: accounts.relayer,
+        user: accounts.user,
+        user_collateral_token_account: user_collateral_ata,
+        user_lp_token_account: user_lp_ata,
+        config: find_config(),
+        authority: find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: find_vault(&accounts.vault_mint),
+        custodian: accounts.custodian,
+        custodian_token_account: custodian_ata,
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        lp_token_program: accounts.lp_token_program,
+        vault_token_program: accounts.vault_token_program,
+        system_program: system_program::ID,
+        event_authority: find_event_authority(),
+        program: jup_stable::id(),
+    }
+    .to_account_metas(Some(false));
+
+    acc.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: acc,
+        data: jup_stable::instruction::RelayedMint {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub struct RelayedRedeemInstructionAccounts {
+    pub relayer: Pubkey,
+    pub user: Pubkey,
+    pub benefactor: Pubkey,
+    pub vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub vault_token_program: Pubkey,
+    pub lp_token_program: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+/// The user has to approve [`find_authority`] as delegate of its jupUSD ATA for at least `amount`
+/// first.
+pub fn create_relayed_redeem_instruction(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: RelayedRedeemInstructionAccounts,
+) -> Instruction {
+    let user_collateral_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.vault_mint,
+        &accounts.vault_token_program,
+    );
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.lp_mint,
+        &accounts.lp_token_program,
+    );
+
+    let mut accs = jup_stable::accounts::RelayedRedeem {
+        relayer: accounts.relayer,
+        user: accounts.user,
+        user_lp_token_account: user_lp_ata,
+        user_collateral_token_account: user_collateral_ata,
+        config: find_config(),
+        authority: find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: find_vault(&accounts.vault_mint),
+        vault_token_account: find_vault_token_account_with_program(
+            &accounts.vault_mint,
+            &accounts.vault_token_program,
+        ),
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        lp_token_program: accounts.lp_token_program,
+        vault_token_program: accounts.vault_token_program,
+        system_program: system_program::ID,
+        event_authority: find_event_authority(),
+        program: jup_stable::id(),
+    }
+    .to_account_metas(Some(false));
+    accs.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: accs,
+        data: jup_stable::instruction::RelayedRedeem {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+/// Simulate it and decode the return data as [`jup_stable::instructions::Capacity`].
+pub fn create_get_capacity_instruction(vault_mint: Pubkey, benefactor: Pubkey) -> Instruction {
+    create_get_capacity_instruction_for_instance(None, vault_mint, benefactor)
+}
+
+pub fn create_get_capacity_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    vault_mint: Pubkey,
+    benefactor: Pubkey,
+) -> Instruction {
+    let accounts = jup_stable::accounts::GetCapacity {
+        config: find_instance_config(instance_mint),
+        vault: find_instance_vault(instance_mint, &vault_mint),
+        benefactor,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::GetCapacity {}.data(),
+    }
+}
+
+pub struct FlashMintInstructionAccounts {
+    pub user: Pubkey,
+    pub benefactor: Pubkey,
+    pub vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_token_program: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+/// `flash_mint` only succeeds when invoked by the benefactor's integrator program, so this is
+/// mostly useful to build the accounts an integrator forwards.
+pub fn create_flash_mint_instruction(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: FlashMintInstructionAccounts,
+) -> Instruction {
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.lp_mint,
+        &accounts.lp_token_program,
+    );
+
+    let mut acc = jup_stable::accounts::FlashMint {
+        user: accounts.user,
+        user_lp_token_account: user_lp_ata,
+        config: find_config(),
+        authority: find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: find_vault(&accounts.vault_mint),
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        instructions_sysvar: sysvar::instructions::ID,
+        lp_token_program: accounts.lp_token_program,
+        system_program: system_program::ID,
+        event_authority: find_event_authority(),
+        program: jup_stable::id(),
+    }
+    .to_account_metas(Some(false));
+
+    acc.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: acc,
+        data: jup_stable::instruction::FlashMint {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub struct SettleFlashMintInstructionAccounts {
+    pub payer: Pubkey,
+    pub benefactor: Pubkey,
+    pub custodian: Pubkey,
+    /// The vault's `custodian_token_account` override, if it has one; the custodian's ATA
+    /// otherwise.
+    pub custodian_token_account: Option<Pubkey>,
+    pub vault_mint: Pubkey,
+    pub vault_token_program: Pubkey,
+}
+
+pub fn create_settle_flash_mint_instruction(
+    accounts: SettleFlashMintInstructionAccounts,
+) -> Instruction {
+    let accounts = jup_stable::accounts::SettleFlashMint {
+        benefactor: accounts.benefactor,
+        payer: accounts.payer,
+        payer_collateral_token_account: get_associated_token_address_with_program_id(
+            &accounts.payer,
+            &accounts.vault_mint,
+            &accounts.vault_token_program,
+        ),
+        vault: find_vault(&accounts.vault_mint),
+        vault_mint: accounts.vault_mint,
+        custodian: accounts.custodian,
+        custodian_token_account: accounts.custodian_token_account.unwrap_or_else(|| {
+            get_associated_token_address_with_program_id(
+                &accounts.custodian,
+                &accounts.vault_mint,
+                &accounts.vault_token_program,
+            )
+        }),
+        vault_token_program: accounts.vault_token_program,
+        event_authority: find_event_authority(),
+        program: jup_stable::id(),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::SettleFlashMint {}.data(),
+    }
+}
+
+pub struct WithdrawInstructionAccounts {
+    pub operator_authority: Pubkey,
+    pub custodian: Pubkey,
+    /// The vault's `custodian_token_account` override, if it has one; the custodian's ATA
+    /// otherwise.
+    pub custodian_token_account: Option<Pubkey>,
+    pub vault_mint: Pubkey,
+    pub vault_token_program: Pubkey,
+    /// Pass the SPL memo program to also record the reference as a memo.
+    pub memo_program: Option<Pubkey>,
+}
+
+pub fn create_withdraw_instruction(
+    accounts: WithdrawInstructionAccounts,
+    amount: u64,
+    reference: Option<[u8; 32]>,
+) -> Instruction {
+    create_withdraw_instruction_for_instance(None, accounts, amount, reference)
+}
+
+pub fn create_withdraw_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: WithdrawInstructionAccounts,
+    amount: u64,
+    reference: Option<[u8; 32]>,
+) -> Instruction {
+    let accounts = jup_stable::accounts::Withdraw {
+        operator_authority: accounts.operator_authority,
+        operator: find_operator(&accounts.operator_authority),
+        custodian: accounts.custodian,
+        custodian_token_account: accounts.custodian_token_account.unwrap_or_else(|| {
+            get_associated_token_address_with_program_id(
+                &accounts.custodian,
+                &accounts.vault_mint,
+                &accounts.vault_token_program,
+            )
+        }),
+        config: find_instance_config(instance_mint),
+        authority: find_instance_authority(instance_mint),
+        vault: find_instance_vault(instance_mint, &accounts.vault_mint),
+        vault_token_account: find_instance_vault_token_account_with_program(
+            instance_mint,
+            &accounts.vault_mint,
+            &accounts.vault_token_program,
+        ),
+        vault_mint: accounts.vault_mint,
+        token_program: accounts.vault_token_program,
+        memo_program: accounts.memo_program,
+        operator_log: Some(find_operator_log(&find_operator(
+            &accounts.operator_authority,
+        ))),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::Withdraw { amount, reference }.data(),
+    }
+}
+
+pub fn create_acknowledge_deposit_instruction(
+    custodian: Pubkey,
+    vault_mint: Pubkey,
+    reference: [u8; 32],
+    amount: u64,
+) -> Instruction {
+    let accounts = jup_stable::accounts::AcknowledgeDeposit {
+        custodian,
+        vault: find_vault(&vault_mint),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::AcknowledgeDeposit { reference, amount }.data(),
+    }
+}
+
+pub struct ManageConfigInstructionAccounts {
+    pub authority: Pubkey,
+}
+
+pub fn create_manage_config_instruction(
+    accounts: ManageConfigInstructionAccounts,
+    action: jup_stable::instructions::ConfigManagementAction,
+) -> Instruction {
+    create_manage_config_instruction_for_instance(None, accounts, action)
+}
+
+pub fn create_manage_config_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: ManageConfigInstructionAccounts,
+    action: jup_stable::instructions::ConfigManagementAction,
+) -> Instruction {
+    let accounts = jup_stable::accounts::ManageConfig {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        config: find_instance_config(instance_mint),
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::ManageConfig { action }.data(),
+    }
+}
+
+pub fn create_update_pause_flag_instruction(
+    authority: Pubkey,
+    is_mint_redeem_enabled: bool,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::UpdatePauseFlag {
+            is_mint_redeem_enabled,
+        },
+    )
+}
+
+pub fn create_update_config_period_limit_instruction(
+    authority: Pubkey,
+    index: u8,
+    duration_seconds: u64,
+    max_mint_amount: u64,
+    max_redeem_amount: u64,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::UpdatePeriodLimit {
+            index,
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        },
+    )
+}
+
+pub fn create_reset_config_period_limit_instruction(authority: Pubkey, index: u8) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::ResetPeriodLimit { index },
+    )
+}
+
+pub fn create_update_restart_slot_check_instruction(
+    authority: Pubkey,
+    enabled: bool,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::UpdateRestartSlotCheck { enabled },
+    )
+}
+
+pub fn create_update_strict_period_limits_instruction(
+    authority: Pubkey,
+    enabled: bool,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::UpdateStrictPeriodLimits { enabled },
+    )
+}
+
+pub fn create_set_peg_symbol_instruction(
+    authority: Pubkey,
+    peg_symbol: [u8; jup_stable::state::config::PEG_SYMBOL_LEN],
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::SetPegSymbol { peg_symbol },
+    )
+}
+
+pub fn create_update_pause_blocks_withdraw_instruction(
+    authority: Pubkey,
+    enabled: bool,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::UpdatePauseBlocksWithdraw { enabled },
+    )
+}
+
+pub fn create_set_peg_price_usd_instruction(authority: Pubkey, peg_price_usd: u64) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::SetPegPriceUSD { peg_price_usd },
+    )
+}
+
+pub struct SetPendingUpgradeAuthorityInstructionAccounts {
+    pub operator_authority: Pubkey,
+    pub program_data: Pubkey,
+}
+
+pub fn create_set_pending_upgrade_authority_instruction(
+    accounts: SetPendingUpgradeAuthorityInstructionAccounts,
+    pending_upgrade_authority: Pubkey,
+) -> Instruction {
+    let accounts = jup_stable::accounts::SetPendingUpgradeAuthority {
+        operator_authority: accounts.operator_authority,
+        operator: find_operator(&accounts.operator_authority),
+        config: find_config(),
+        program_data: accounts.program_data,
+        program: jup_stable::id(),
+        operator_log: Some(find_operator_log(&find_operator(
+            &accounts.operator_authority,
+        ))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::SetPendingUpgradeAuthority {
+            pending_upgrade_authority,
+        }
+        .data(),
+    }
+}
+
+pub fn create_confirm_upgrade_authority_instruction(program_data: Pubkey) -> Instruction {
+    let accounts = jup_stable::accounts::ConfirmUpgradeAuthority {
+        config: find_config(),
+        program_data,
+        program: jup_stable::id(),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::ConfirmUpgradeAuthority {}.data(),
+    }
+}
+
+pub fn create_sync_supply_instruction(authority: Pubkey, lp_mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: jup_stable::accounts::SyncSupply {
+            operator_authority: authority,
+            operator: find_operator(&authority),
+            config: find_config(),
+            lp_mint,
+            operator_log: Some(find_operator_log(&find_operator(&authority))),
+        }
+        .to_account_metas(Some(true)),
+        data: jup_stable::instruction::SyncSupply {}.data(),
+    }
+}
+
+pub fn create_assert_supply_consistency_instruction(lp_mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: jup_stable::accounts::AssertSupplyConsistency {
+            config: find_config(),
+            lp_mint,
+        }
+        .to_account_metas(Some(true)),
+        data: jup_stable::instruction::AssertSupplyConsistency {}.data(),
+    }
+}
+
+pub struct BurnTreasuryInstructionAccounts {
+    pub operator_authority: Pubkey,
+    /// jupUSD token account owned by `operator_authority`
+    pub treasury_token_account: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_token_program: Pubkey,
+}
+
+pub fn create_burn_treasury_instruction(
+    accounts: BurnTreasuryInstructionAccounts,
+    amount: u64,
+) -> Instruction {
+    let accounts = jup_stable::accounts::BurnTreasury {
+        operator_authority: accounts.operator_authority,
+        operator: find_operator(&accounts.operator_authority),
+        treasury_token_account: accounts.treasury_token_account,
+        config: find_config(),
+        lp_mint: accounts.lp_mint,
+        lp_token_program: accounts.lp_token_program,
+        operator_log: Some(find_operator_log(&find_operator(
+            &accounts.operator_authority,
+        ))),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::BurnTreasury { amount }.data(),
+    }
+}
+
+pub fn create_set_reactivation_cooldown_instruction(
+    authority: Pubkey,
+    cooldown_seconds: u64,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::SetReactivationCooldown {
+            cooldown_seconds,
+        },
+    )
+}
+
+pub fn create_set_rent_treasury_instruction(
+    authority: Pubkey,
+    rent_treasury: Pubkey,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::SetRentTreasury { rent_treasury },
+    )
+}
+
+pub fn create_set_default_vault_period_limit_instruction(
+    authority: Pubkey,
+    duration_seconds: u64,
+    max_mint_amount: u64,
+    max_redeem_amount: u64,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::SetDefaultVaultPeriodLimit {
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        },
+    )
+}
+
+pub fn create_set_default_benefactor_period_limit_instruction(
+    authority: Pubkey,
+    duration_seconds: u64,
+    max_mint_amount: u64,
+    max_redeem_amount: u64,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { authority },
+        jup_stable::instructions::ConfigManagementAction::SetDefaultBenefactorPeriodLimit {
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        },
+    )
+}
+
+pub struct ManageVaultInstructionAccounts {
+    pub authority: Pubkey,
+    pub vault_mint: Pubkey,
+}
+
+pub fn create_manage_vault_instruction(
+    accounts: ManageVaultInstructionAccounts,
+    action: jup_stable::instructions::VaultManagementAction,
+) -> Instruction {
+    create_manage_vault_instruction_for_instance(None, accounts, action)
+}
+
+pub fn create_manage_vault_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: ManageVaultInstructionAccounts,
+    action: jup_stable::instructions::VaultManagementAction,
+) -> Instruction {
+    let accounts = jup_stable::accounts::ManageVault {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        config: find_instance_config(instance_mint),
+        vault: find_instance_vault(instance_mint, &accounts.vault_mint),
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::ManageVault { action }.data(),
+    }
+}
+
+// Convenience functions for common vault management actions
+pub fn create_disable_vault_instruction(authority: Pubkey, vault_mint: Pubkey) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::Disable,
+    )
+}
+
+pub fn create_set_vault_status_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    status: jup_stable::state::vault::VaultStatus,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetStatus { status },
+    )
+}
+
+pub fn create_set_custodian_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    new_custodian: Pubkey,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetCustodian { new_custodian },
+    )
+}
+
+pub fn create_update_vault_oracle_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    index: u8,
+    oracle: jup_stable::instructions::OracleConfig,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::UpdateOracle { index, oracle },
+    )
+}
+
+pub fn create_update_vault_quote_oracle_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    oracle: jup_stable::instructions::OracleConfig,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::UpdateQuoteOracle { oracle },
+    )
+}
+
+pub fn create_update_vault_period_limit_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    index: u8,
+    duration_seconds: u64,
+    max_mint_amount: u64,
+    max_redeem_amount: u64,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::UpdatePeriodLimit {
+            index,
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        },
+    )
+}
+
+pub fn create_reset_vault_period_limit_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    index: u8,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::ResetPeriodLimit { index },
+    )
+}
+
+pub fn create_set_stalesness_threshold_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    stalesness_threshold: u64,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetStalesnessThreshold {
+            stalesness_threshold,
+        },
+    )
+}
+
+pub fn create_set_min_oracle_price_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    min_oracle_price_usd: u64,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetMinOraclePrice {
+            min_oracle_price_usd,
+        },
+    )
+}
+
+pub fn create_set_max_oracle_price_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    max_oracle_price_usd: u64,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetMaxOraclePrice {
+            max_oracle_price_usd,
+        },
+    )
+}
+
+pub fn create_set_max_price_jump_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    max_price_jump_bps: u16,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetMaxPriceJump { max_price_jump_bps },
+    )
+}
+
+pub fn create_set_price_jump_window_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    price_jump_window_seconds: u32,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetPriceJumpWindow {
+            price_jump_window_seconds,
+        },
+    )
+}
+
+pub fn create_set_confidence_factor_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    confidence_factor_bps: u16,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetConfidenceFactor {
+            confidence_factor_bps,
+        },
+    )
+}
+
+pub fn create_set_symmetric_oracle_bounds_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    enabled: bool,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetSymmetricOracleBounds { enabled },
+    )
+}
+
+pub fn create_set_requires_benefactor_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    required: bool,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetRequiresBenefactor { required },
+    )
+}
+
+pub fn create_set_max_net_outstanding_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    max_net_outstanding: u64,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetMaxNetOutstanding {
+            max_net_outstanding,
+        },
+    )
+}
+
+pub fn create_set_per_tx_limits_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    max_mint_per_tx: u64,
+    max_redeem_per_tx: u64,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetPerTxLimits {
+            max_mint_per_tx,
+            max_redeem_per_tx,
+        },
+    )
+}
+
+pub fn create_set_custodian_token_account_instruction(
+    authority: Pubkey,
+    vault_mint: Pubkey,
+    custodian_token_account: Pubkey,
+) -> Instruction {
+    create_manage_vault_instruction(
+        ManageVaultInstructionAccounts {
+            authority,
+            vault_mint,
+        },
+        jup_stable::instructions::VaultManagementAction::SetCustodianTokenAccount {
+            custodian_token_account,
+        },
+    )
+}
+
+pub struct ManageBenefactorInstructionAccounts {
+    pub authority: Pubkey,
+    pub benefactor: Pubkey,
+}
+
+pub fn create_manage_benefactor_instruction(
+    accounts: ManageBenefactorInstructionAccounts,
+    action: jup_stable::instructions::BenefactorManagementAction,
+) -> Instruction {
+    create_manage_benefactor_instruction_for_instance(None, accounts, action)
+}
+
+pub fn create_manage_benefactor_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: ManageBenefactorInstructionAccounts,
+    action: jup_stable::instructions::BenefactorManagementAction,
+) -> Instruction {
+    let accounts = jup_stable::accounts::ManageBenefactor {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        config: find_instance_config(instance_mint),
+        benefactor: accounts.benefactor,
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::ManageBenefactor { action }.data(),
+    }
+}
+
+// Convenience functions for common benefactor management actions
+pub fn create_set_benefactor_status_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    status: jup_stable::state::benefactor::BenefactorStatus,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::SetStatus { status },
+    )
+}
+
+pub fn create_disable_benefactor_instruction(authority: Pubkey, benefactor: Pubkey) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::Disable,
+    )
+}
+
+pub fn create_update_fee_rates_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    mint_fee_rate: u16,
+    redeem_fee_rate: u16,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::UpdateFeeRates {
+            mint_fee_rate,
+            redeem_fee_rate,
+        },
+    )
+}
+
+pub fn create_update_benefactor_period_limit_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    index: u8,
+    duration_seconds: u64,
+    max_mint_amount: u64,
+    max_redeem_amount: u64,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::UpdatePeriodLimit {
+            index,
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        },
+    )
+}
+
+pub fn create_reset_benefactor_period_limit_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    index: u8,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::ResetPeriodLimit { index },
+    )
+}
+
+pub fn create_set_benefactor_max_net_minted_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    max_net_minted: u64,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::SetMaxNetMinted { max_net_minted },
+    )
+}
+
+pub fn create_set_benefactor_integrator_program_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    integrator_program: Pubkey,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::SetIntegratorProgram {
+            integrator_program,
+        },
+    )
+}
+
+pub struct DeleteBenefactorInstructionAccounts {
+    pub authority: Pubkey,
+    pub receiver: Pubkey,
+    pub benefactor: Pubkey,
+    /// Registry page listing the benefactor
+    pub registry_page: u32,
+}
+
+pub fn create_delete_benefactor_instruction(
+    accounts: DeleteBenefactorInstructionAccounts,
+) -> Instruction {
+    create_delete_benefactor_instruction_for_instance(None, accounts)
+}
+
+pub fn create_delete_benefactor_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: DeleteBenefactorInstructionAccounts,
+) -> Instruction {
+    let accounts = jup_stable::accounts::DeleteBenefactor {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        receiver: accounts.receiver,
+        config: find_instance_config(instance_mint),
+        benefactor: accounts.benefactor,
+        benefactor_registry: find_instance_benefactor_registry(
+            instance_mint,
+            accounts.registry_page,
+        ),
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::DeleteBenefactor {}.data(),
+    }
+}
+
+pub struct CreateBenefactorRegistryPageInstructionAccounts {
+    pub authority: Pubkey,
+    pub payer: Pubkey,
+}
+
+/// Creates registry page `page`, which has to be the next one. `benefactor_authorities` are
+/// benefactors created before the registry existed.
+pub fn create_create_benefactor_registry_page_instruction(
+    accounts: CreateBenefactorRegistryPageInstructionAccounts,
+    page: u32,
+    benefactor_authorities: &[Pubkey],
+) -> Instruction {
+    create_create_benefactor_registry_page_instruction_for_instance(
+        None,
+        accounts,
+        page,
+        benefactor_authorities,
+    )
+}
+
+pub fn create_create_benefactor_registry_page_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    accounts: CreateBenefactorRegistryPageInstructionAccounts,
+    page: u32,
+    benefactor_authorities: &[Pubkey],
+) -> Instruction {
+    let mut metas = jup_stable::accounts::CreateBenefactorRegistryPage {
+        operator_authority: accounts.authority,
+        operator: find_operator(&accounts.authority),
+        payer: accounts.payer,
+        config: find_instance_config(instance_mint),
+        benefactor_registry: find_instance_benefactor_registry(instance_mint, page),
+        system_program: system_program::ID,
+        operator_log: Some(find_operator_log(&find_operator(&accounts.authority))),
+    }
+    .to_account_metas(Some(true));
+    metas.extend(benefactor_authorities.iter().map(|authority| {
+        AccountMeta::new_readonly(find_instance_benefactor(instance_mint, authority), false)
+    }));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts: metas,
+        data: jup_stable::instruction::CreateBenefactorRegistryPage {}.data(),
+    }
+}
+
+pub struct CreateOperatorInstructionAccounts {
+    pub operator_authority: Pubkey,
+    pub payer: Pubkey,
+    pub new_operator_authority: Pubkey,
+}
+
+pub fn create_create_operator_instruction(
+    accounts: CreateOperatorInstructionAccounts,
+    role: jup_stable::state::operator::OperatorRole,
+) -> Instruction {
+    let accounts = jup_stable::accounts::CreateOperator {
+        operator_authority: accounts.operator_authority,
+        payer: accounts.payer,
+        operator: find_operator(&accounts.operator_authority),
+        new_operator_authority: accounts.new_operator_authority,
+        new_operator: find_operator(&accounts.new_operator_authority),
+        new_operator_log: find_operator_log(&find_operator(&accounts.new_operator_authority)),
+        system_program: system_program::ID,
+        operator_log: Some(find_operator_log(&find_operator(
+            &accounts.operator_authority,
+        ))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::CreateOperator { role }.data(),
+    }
+}
+
+pub struct DeleteOperatorInstructionAccounts {
+    pub operator_authority: Pubkey,
+    /// Has to be the config's rent treasury when one is set
+    pub receiver: Pubkey,
+    pub deleted_operator: Pubkey,
+}
+
+pub fn create_delete_operator_instruction(
+    accounts: DeleteOperatorInstructionAccounts,
+) -> Instruction {
+    let accounts = jup_stable::accounts::DeleteOperator {
+        operator_authority: accounts.operator_authority,
+        receiver: accounts.receiver,
+        operator: find_operator(&accounts.operator_authority),
+        config: find_config(),
+        deleted_operator: accounts.deleted_operator,
+        operator_log: Some(find_operator_log(&find_operator(
+            &accounts.operator_authority,
+        ))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::DeleteOperator {}.data(),
+    }
+}
+
+pub struct ManageOperatorInstructionAccounts {
+    pub operator_authority: Pubkey,
+    pub managed_operator: Pubkey,
+}
+
+pub fn create_manage_operator_instruction(
+    accounts: ManageOperatorInstructionAccounts,
+    action: jup_stable::instructions::OperatorManagementAction,
+) -> Instruction {
+    let accounts = jup_stable::accounts::ManageOperator {
+        operator_authority: accounts.operator_authority,
+        operator: find_operator(&accounts.operator_authority),
+        managed_operator: accounts.managed_operator,
+        system_program: system_program::ID,
+        operator_log: Some(find_operator_log(&find_operator(
+            &accounts.operator_authority,
+        ))),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::ManageOperator { action }.data(),
+    }
+}
+
+pub struct CreateOperatorLogInstructionAccounts {
+    pub operator_authority: Pubkey,
+    pub payer: Pubkey,
+}
+
+pub fn create_create_operator_log_instruction(
+    accounts: CreateOperatorLogInstructionAccounts,
+) -> Instruction {
+    let operator = find_operator(&accounts.operator_authority);
+    let accounts = jup_stable::accounts::CreateOperatorLog {
+        operator_authority: accounts.operator_authority,
+        payer: accounts.payer,
+        operator,
+        operator_log: find_operator_log(&operator),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: jup_stable::id(),
+        accounts,
+        data: jup_stable::instruction::CreateOperatorLog {}.data(),
+    }
+}
+
+pub fn create_set_benefactor_exempt_from_global_pause_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    exempt: bool,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::SetExemptFromGlobalPause { exempt },
+    )
+}
+
+/// Default `vault_mint` clears the hint, any other mint passes its vault along.
+pub fn create_set_benefactor_redeem_vault_hint_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    vault_mint: Pubkey,
+) -> Instruction {
+    create_set_benefactor_redeem_vault_hint_instruction_for_instance(
+        None, authority, benefactor, vault_mint,
+    )
+}
+
+pub fn create_set_benefactor_redeem_vault_hint_instruction_for_instance(
+    instance_mint: Option<&Pubkey>,
+    authority: Pubkey,
+    benefactor: Pubkey,
+    vault_mint: Pubkey,
+) -> Instruction {
+    let mut ix = create_manage_benefactor_instruction_for_instance(
+        instance_mint,
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::SetRedeemVaultHint { vault_mint },
+    );
+    if vault_mint != Pubkey::default() {
+        ix.accounts.push(AccountMeta::new_readonly(
+            find_instance_vault(instance_mint, &vault_mint),
+            false,
+        ));
+    }
+    ix
+}
+
+/// Default `relayer` clears the slot.
+pub fn create_set_benefactor_relayer_instruction(
+    authority: Pubkey,
+    benefactor: Pubkey,
+    index: u8,
+    relayer: Pubkey,
+) -> Instruction {
+    create_manage_benefactor_instruction(
+        ManageBenefactorInstructionAccounts {
+            authority,
+            benefactor,
+        },
+        jup_stable::instructions::BenefactorManagementAction::SetRelayer { index, relayer },
+    )
+}
@@ -0,0 +1,177 @@
+//! Address lookup tables holding the accounts every mint and redeem shares (program, config,
+//! authority, vaults and their oracles), so transactions composing them with other instructions
+//! only spend one byte per account instead of 32.
+//!
+//! Create the table with [`create_lookup_table_instruction`], fill it with
+//! [`create_extend_lookup_table_instructions`], then compile transactions against it with
+//! [`compile_v0_message`] or the mint/redeem message builders.
+
+use anchor_lang::{
+    solana_program::{
+        address_lookup_table::{
+            instruction::{create_lookup_table, extend_lookup_table},
+            AddressLookupTableAccount,
+        },
+        hash::Hash,
+        instruction::Instruction,
+        message::{v0, CompileError, VersionedMessage},
+        pubkey::Pubkey,
+    },
+    system_program,
+};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use jup_stable::state::{
+    config::Config,
+    vault::{OracleType, Vault},
+};
+
+use super::{
+    derivation::{find_authority, find_config, find_event_authority, find_vault},
+    instructions::{
+        create_mint_instruction, create_redeem_instruction, MintInstructionAccounts,
+        RedeemInstructionAccounts,
+    },
+};
+
+/// Addresses one extend instruction adds while its transaction stays within the packet size.
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 30;
+
+/// Account the vault's `oracle` slot reads from, `None` for an empty slot.
+pub fn oracle_account(oracle: &OracleType) -> Option<Pubkey> {
+    match oracle {
+        OracleType::Pyth(pyth) => Some(pyth.account),
+        OracleType::SwitchboardOnDemand(switchboard) => Some(switchboard.account),
+        OracleType::Doves(doves) => Some(doves.account),
+        OracleType::Empty(_) => None,
+    }
+}
+
+/// Oracle accounts a mint or redeem against `vault` passes as remaining accounts, in order.
+pub fn oracle_accounts(vault: &Vault) -> Vec<Pubkey> {
+    vault.oracles.iter().filter_map(oracle_account).collect()
+}
+
+/// Accounts of `vault` shared by every mint and redeem against it.
+pub fn vault_static_addresses(vault: &Vault) -> Vec<Pubkey> {
+    let custodian_token_account = if vault.custodian_token_account == Pubkey::default() {
+        get_associated_token_address_with_program_id(
+            &vault.custodian,
+            &vault.mint,
+            &vault.token_program,
+        )
+    } else {
+        vault.custodian_token_account
+    };
+
+    let mut addresses = vec![
+        find_vault(&vault.mint),
+        vault.mint,
+        vault.token_account,
+        vault.token_program,
+        vault.custodian,
+        custodian_token_account,
+    ];
+    addresses.extend(oracle_accounts(vault));
+    addresses
+}
+
+/// Protocol accounts shared by every mint and redeem, followed by those of each vault, without
+/// duplicates.
+pub fn static_addresses(config: &Config, vaults: &[Vault]) -> Vec<Pubkey> {
+    let mut addresses = vec![
+        jup_stable::id(),
+        find_config(),
+        find_authority(),
+        find_event_authority(),
+        config.mint,
+        config.token_program,
+        system_program::ID,
+    ];
+    for vault in vaults {
+        for address in vault_static_addresses(vault) {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+    }
+    addresses
+}
+
+/// Addresses of `addresses` that `lookup_table` does not hold yet.
+pub fn missing_addresses(
+    lookup_table: &AddressLookupTableAccount,
+    addresses: &[Pubkey],
+) -> Vec<Pubkey> {
+    addresses
+        .iter()
+        .filter(|address| !lookup_table.addresses.contains(address))
+        .copied()
+        .collect()
+}
+
+/// Returns the instruction creating a lookup table owned by `authority`, and the table address.
+/// `recent_slot` must be a recent finalized slot; it seeds the address.
+pub fn create_lookup_table_instruction(
+    authority: Pubkey,
+    payer: Pubkey,
+    recent_slot: u64,
+) -> (Instruction, Pubkey) {
+    create_lookup_table(authority, payer, recent_slot)
+}
+
+/// Extends `lookup_table` with `addresses`, [`MAX_ADDRESSES_PER_EXTEND`] at a time. Each
+/// instruction goes in its own transaction; the first may share the one creating the table.
+pub fn create_extend_lookup_table_instructions(
+    lookup_table: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    addresses: &[Pubkey],
+) -> Vec<Instruction> {
+    addresses
+        .chunks(MAX_ADDRESSES_PER_EXTEND)
+        .map(|chunk| extend_lookup_table(lookup_table, authority, Some(payer), chunk.to_vec()))
+        .collect()
+}
+
+/// Compiles `instructions` into a v0 message resolving every account it can through
+/// `lookup_tables`. Signers and invoked programs always stay in the static account keys.
+pub fn compile_v0_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage, CompileError> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)?;
+    Ok(VersionedMessage::V0(message))
+}
+
+/// Mint as a v0 message, with `pre_instructions` (compute budget, ATA creation, ...) ahead of it.
+pub fn create_mint_message(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: MintInstructionAccounts,
+    pre_instructions: Vec<Instruction>,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage, CompileError> {
+    let payer = accounts.user;
+    let mut instructions = pre_instructions;
+    instructions.push(create_mint_instruction(amount, min_amount_out, accounts));
+    compile_v0_message(&payer, &instructions, lookup_tables, recent_blockhash)
+}
+
+/// Redeem as a v0 message, with `pre_instructions` (compute budget, ATA creation, ...) ahead of
+/// it.
+pub fn create_redeem_message(
+    amount: u64,
+    min_amount_out: u64,
+    accounts: RedeemInstructionAccounts,
+    pre_instructions: Vec<Instruction>,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage, CompileError> {
+    let payer = accounts.user;
+    let mut instructions = pre_instructions;
+    instructions.push(create_redeem_instruction(amount, min_amount_out, accounts));
+    compile_v0_message(&payer, &instructions, lookup_tables, recent_blockhash)
+}
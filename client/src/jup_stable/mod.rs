@@ -0,0 +1,6 @@
+pub mod accounts;
+pub mod derivation;
+pub mod instructions;
+pub mod lookup_table;
+
+pub use jup_stable::{self as program, state, ID};
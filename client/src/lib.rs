@@ -0,0 +1,15 @@
+//! Rust client for the JupUSD programs: PDA derivation and typed instruction builders for
+//! `jup-stable` and `psm`, plus re-exports of their account and argument types. [`views`] holds
+//! plain structs with the account totals decoded.
+//!
+//! With the `rpc` feature, each program module also exposes async fetchers that load and decode
+//! its accounts through a nonblocking `RpcClient`.
+
+pub use jupusd_errors as errors;
+pub use jupusd_math as math;
+
+pub mod jup_stable;
+pub mod psm;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod views;
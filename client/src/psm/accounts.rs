@@ -0,0 +1,35 @@
+//! Pool totals are decoded through [`PoolView`], which converts the `[u8; 16]` fields to `u128`.
+
+#[cfg(feature = "rpc")]
+pub use self::fetch::*;
+pub use crate::views::PoolView;
+
+#[cfg(feature = "rpc")]
+mod fetch {
+    use anchor_lang::prelude::Pubkey;
+    use anyhow::Result;
+    use psm::state::{config::Config, pool::Pool};
+    use solana_client::nonblocking::rpc_client::RpcClient;
+
+    use crate::{
+        psm::derivation,
+        rpc::{fetch_account, fetch_all_accounts},
+    };
+
+    pub async fn fetch_config(rpc: &RpcClient) -> Result<Config> {
+        fetch_account(rpc, &derivation::find_config()).await
+    }
+
+    pub async fn fetch_pool(
+        rpc: &RpcClient,
+        redemption_mint: &Pubkey,
+        settlement_mint: &Pubkey,
+    ) -> Result<Pool> {
+        let pool = derivation::find_pool(redemption_mint, settlement_mint);
+        fetch_account(rpc, &pool).await
+    }
+
+    pub async fn fetch_pools(rpc: &RpcClient) -> Result<Vec<(Pubkey, Pool)>> {
+        fetch_all_accounts(rpc).await
+    }
+}
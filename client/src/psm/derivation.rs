@@ -0,0 +1,82 @@
+use anchor_lang::prelude::Pubkey;
+
+pub fn find_config() -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(&[b"config"], &psm::id());
+    pubkey
+}
+
+pub fn find_authority() -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(&[b"authority"], &psm::id());
+    pubkey
+}
+
+pub fn find_treasury() -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(&[b"treasury"], &psm::id());
+    pubkey
+}
+
+pub fn find_allowed_mint_list() -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(&[b"allowed_mint_list"], &psm::id());
+    pubkey
+}
+
+pub fn find_withdrawal_destination_list() -> Pubkey {
+    let (pubkey, _bump) =
+        Pubkey::find_program_address(&[b"withdrawal_destination_list"], &psm::id());
+    pubkey
+}
+
+pub fn find_pool(redemption_mint: &Pubkey, settlement_mint: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"pool", redemption_mint.as_ref(), settlement_mint.as_ref()],
+        &psm::id(),
+    );
+    pubkey
+}
+
+pub fn find_pool_redemption_token_account(pool: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"pool_redemption_token_account", pool.as_ref()],
+        &psm::id(),
+    );
+    pubkey
+}
+
+pub fn find_pool_settlement_token_account(pool: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"pool_settlement_token_account", pool.as_ref()],
+        &psm::id(),
+    );
+    pubkey
+}
+
+pub fn find_event_authority() -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(&[b"__event_authority"], &psm::id());
+    pubkey
+}
+
+pub fn find_reserve(redemption_mint: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) =
+        Pubkey::find_program_address(&[b"reserve", redemption_mint.as_ref()], &psm::id());
+    pubkey
+}
+
+pub fn find_reserve_redemption_token_account(reserve: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) = Pubkey::find_program_address(
+        &[b"reserve_redemption_token_account", reserve.as_ref()],
+        &psm::id(),
+    );
+    pubkey
+}
+
+pub fn find_pending_withdrawal(pool: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) =
+        Pubkey::find_program_address(&[b"pending_withdrawal", pool.as_ref()], &psm::id());
+    pubkey
+}
+
+pub fn find_fee_exempt_list(pool: &Pubkey) -> Pubkey {
+    let (pubkey, _bump) =
+        Pubkey::find_program_address(&[b"fee_exempt_list", pool.as_ref()], &psm::id());
+    pubkey
+}
@@ -0,0 +1,1331 @@
+use anchor_lang::{
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        sysvar,
+    },
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use super::derivation::{
+    find_allowed_mint_list, find_authority, find_config, find_event_authority,
+    find_fee_exempt_list, find_pending_withdrawal, find_pool, find_pool_redemption_token_account,
+    find_pool_settlement_token_account, find_reserve, find_reserve_redemption_token_account,
+    find_treasury, find_withdrawal_destination_list,
+};
+use crate::jup_stable::derivation as stable_pda;
+
+pub struct InitInstructionAccounts {
+    pub payer: Pubkey,
+    pub upgrade_authority: Pubkey,
+    pub program_data: Pubkey,
+}
+
+pub fn create_init_instruction(accounts: InitInstructionAccounts) -> Instruction {
+    let accounts = psm::accounts::Init {
+        payer: accounts.payer,
+        upgrade_authority: accounts.upgrade_authority,
+        config: find_config(),
+        authority: find_authority(),
+        program_data: accounts.program_data,
+        program: psm::id(),
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::Init {}.data(),
+    }
+}
+
+pub struct ManageConfigInstructionAccounts {
+    pub admin: Pubkey,
+}
+
+pub fn create_manage_config_instruction(
+    accounts: ManageConfigInstructionAccounts,
+    action: psm::instructions::ConfigManagementAction,
+) -> Instruction {
+    let accounts = psm::accounts::ManageConfig {
+        admin: accounts.admin,
+        config: find_config(),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ManageConfig { action }.data(),
+    }
+}
+
+pub fn create_manage_config_batch_instruction(
+    accounts: ManageConfigInstructionAccounts,
+    actions: Vec<psm::instructions::ConfigManagementAction>,
+) -> Instruction {
+    let accounts = psm::accounts::ManageConfig {
+        admin: accounts.admin,
+        config: find_config(),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ManageConfigBatch { actions }.data(),
+    }
+}
+
+pub fn create_add_admin_instruction(admin: Pubkey, new_admin: Pubkey) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::AddAdmin { admin: new_admin },
+    )
+}
+
+pub fn create_remove_admin_instruction(admin: Pubkey, remove_admin: Pubkey) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::RemoveAdmin {
+            admin: remove_admin,
+        },
+    )
+}
+
+pub fn create_update_pause_flag_instruction(admin: Pubkey, is_paused: bool) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::UpdatePauseFlag { is_paused },
+    )
+}
+
+pub fn create_set_guardian_instruction(admin: Pubkey, guardian: Pubkey) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::SetGuardian { guardian },
+    )
+}
+
+pub fn create_guardian_pause_instruction(guardian: Pubkey) -> Instruction {
+    let accounts = psm::accounts::GuardianPause {
+        guardian,
+        config: find_config(),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::GuardianPause {}.data(),
+    }
+}
+
+pub fn create_add_pauser_instruction(admin: Pubkey, pauser: Pubkey) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::AddPauser { pauser },
+    )
+}
+
+pub fn create_set_withdrawal_delay_instruction(
+    admin: Pubkey,
+    withdrawal_delay: u64,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::SetWithdrawalDelay { withdrawal_delay },
+    )
+}
+
+pub fn create_set_pool_creation_fee_instruction(
+    admin: Pubkey,
+    pool_creation_fee: u64,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::SetPoolCreationFee { pool_creation_fee },
+    )
+}
+
+pub fn create_set_permissionless_pool_creation_instruction(
+    admin: Pubkey,
+    enabled: bool,
+) -> Instruction {
+    create_manage_config_instruction(
+        ManageConfigInstructionAccounts { admin },
+        psm::instructions::ConfigManagementAction::SetPermissionlessPoolCreation { enabled },
+    )
+}
+
+pub fn create_withdraw_treasury_instruction(
+    admin: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = psm::accounts::WithdrawTreasury {
+        admin,
+        config: find_config(),
+        treasury: find_treasury(),
+        destination,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::WithdrawTreasury { amount }.data(),
+    }
+}
+
+// CreatePool instruction
+pub struct CreatePoolInstructionAccounts {
+    pub admin: Pubkey,
+    pub payer: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+    pub settlement_token_program: Pubkey,
+}
+
+pub fn create_create_pool_instruction(accounts: CreatePoolInstructionAccounts) -> Instruction {
+    create_pool_instruction(accounts, None)
+}
+
+/// Pool creation by a non-admin while permissionless pool creation is enabled; both mints have to
+/// be on the allowed mint list.
+pub fn create_permissionless_create_pool_instruction(
+    accounts: CreatePoolInstructionAccounts,
+) -> Instruction {
+    create_pool_instruction(accounts, Some(find_allowed_mint_list()))
+}
+
+fn create_pool_instruction(
+    accounts: CreatePoolInstructionAccounts,
+    allowed_mint_list: Option<Pubkey>,
+) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+    let accounts = psm::accounts::CreatePool {
+        admin: accounts.admin,
+        payer: accounts.payer,
+        redemption_mint: accounts.redemption_mint,
+        settlement_mint: accounts.settlement_mint,
+        config: find_config(),
+        authority: find_authority(),
+        treasury: find_treasury(),
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        redemption_token_program: accounts.redemption_token_program,
+        settlement_token_program: accounts.settlement_token_program,
+        system_program: system_program::ID,
+        allowed_mint_list,
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::CreatePool {}.data(),
+    }
+}
+
+// ManagePool instruction
+pub struct ManagePoolInstructionAccounts {
+    pub admin: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+}
+
+pub fn create_manage_pool_instruction(
+    accounts: ManagePoolInstructionAccounts,
+    action: psm::instructions::PoolManagementAction,
+) -> Instruction {
+    let accounts = psm::accounts::ManagePool {
+        admin: accounts.admin,
+        config: find_config(),
+        pool: find_pool(&accounts.redemption_mint, &accounts.settlement_mint),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ManagePool { action }.data(),
+    }
+}
+
+/// Pools need a supply period limit before they can be activated.
+pub fn create_activate_pool_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::ActivatePool {
+        admin,
+        config: find_config(),
+        authority: find_authority(),
+        redemption_mint,
+        settlement_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ActivatePool {}.data(),
+    }
+}
+
+/// Pools can only be paused or disabled here, see [`create_activate_pool_instruction`].
+pub fn create_set_pool_status_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    status: psm::state::pool::PoolStatus,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetStatus { status },
+    )
+}
+
+pub fn create_set_retire_settlement_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    retire_settlement: bool,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetRetireSettlement { retire_settlement },
+    )
+}
+
+pub fn create_set_min_redeem_amount_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    min_redeem_amount: u64,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetMinRedeemAmount { min_redeem_amount },
+    )
+}
+
+pub fn create_set_custodian_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    custodian: Pubkey,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetCustodian { custodian },
+    )
+}
+
+pub fn create_set_rounding_mode_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    rounding_mode: psm::state::pool::RoundingMode,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetRoundingMode { rounding_mode },
+    )
+}
+
+pub fn create_set_pool_admin_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    pool_admin: Pubkey,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetAdmin { admin: pool_admin },
+    )
+}
+
+pub fn create_set_haircut_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    haircut_bps: u16,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetHaircut { haircut_bps },
+    )
+}
+
+/// A zero `max_fee_bps` turns the pool's utilization fee off.
+pub fn create_set_utilization_fee_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    min_fee_bps: u16,
+    max_fee_bps: u16,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetUtilizationFee {
+            min_fee_bps,
+            max_fee_bps,
+        },
+    )
+}
+
+/// A zero `duration_seconds` lifts the pool's supply limit.
+pub fn create_set_supply_period_limit_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    duration_seconds: u64,
+    max_supply_amount: u64,
+) -> Instruction {
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetSupplyPeriodLimit {
+            duration_seconds,
+            max_supply_amount,
+        },
+    )
+}
+
+/// Zero-pads `label`, which has to fit in 32 bytes.
+pub fn create_set_pool_label_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    label: &str,
+) -> Instruction {
+    let mut padded_label = [0u8; 32];
+    padded_label[..label.len()].copy_from_slice(label.as_bytes());
+
+    create_manage_pool_instruction(
+        ManagePoolInstructionAccounts {
+            admin,
+            redemption_mint,
+            settlement_mint,
+        },
+        psm::instructions::PoolManagementAction::SetLabel {
+            label: padded_label,
+        },
+    )
+}
+
+pub fn create_sweep_settlement_instruction(
+    custodian: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    settlement_token_program: Pubkey,
+) -> Instruction {
+    sweep_settlement_instruction(
+        custodian,
+        redemption_mint,
+        settlement_mint,
+        settlement_token_program,
+        None,
+    )
+}
+
+/// Sweep once the config restricts withdrawal destinations; the custodian has to be approved on
+/// the withdrawal destination list.
+pub fn create_restricted_sweep_settlement_instruction(
+    custodian: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    settlement_token_program: Pubkey,
+) -> Instruction {
+    sweep_settlement_instruction(
+        custodian,
+        redemption_mint,
+        settlement_mint,
+        settlement_token_program,
+        Some(find_withdrawal_destination_list()),
+    )
+}
+
+fn sweep_settlement_instruction(
+    custodian: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    settlement_token_program: Pubkey,
+    withdrawal_destination_list: Option<Pubkey>,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::SweepSettlement {
+        custodian,
+        custodian_token_account: get_associated_token_address_with_program_id(
+            &custodian,
+            &settlement_mint,
+            &settlement_token_program,
+        ),
+        config: find_config(),
+        authority: find_authority(),
+        settlement_mint,
+        pool,
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        settlement_token_program,
+        withdrawal_destination_list,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::SweepSettlement {}.data(),
+    }
+}
+
+pub struct SupplyInstructionAccounts {
+    pub admin: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+}
+
+pub fn create_supply_instruction(accounts: SupplyInstructionAccounts, amount: u64) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+    let admin_redemption_token_account = get_associated_token_address_with_program_id(
+        &accounts.admin,
+        &accounts.redemption_mint,
+        &accounts.redemption_token_program,
+    );
+
+    let accounts = psm::accounts::Supply {
+        admin: accounts.admin,
+        admin_redemption_token_account,
+        config: find_config(),
+        redemption_mint: accounts.redemption_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        redemption_token_program: accounts.redemption_token_program,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::Supply { amount }.data(),
+    }
+}
+
+pub struct SupplyFromTreasuryInstructionAccounts {
+    pub operator_authority: Pubkey,
+    pub treasury_token_account: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+}
+
+pub fn create_supply_from_treasury_instruction(
+    accounts: SupplyFromTreasuryInstructionAccounts,
+    amount: u64,
+) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+
+    let accounts = psm::accounts::SupplyFromTreasury {
+        operator_authority: accounts.operator_authority,
+        operator: stable_pda::find_operator(&accounts.operator_authority),
+        treasury_token_account: accounts.treasury_token_account,
+        config: find_config(),
+        redemption_mint: accounts.redemption_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        redemption_token_program: accounts.redemption_token_program,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::SupplyFromTreasury { amount }.data(),
+    }
+}
+
+pub struct ReplenishInstructionAccounts {
+    pub admin: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+    pub settlement_token_program: Pubkey,
+    pub jup_stable_custodian: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+pub fn create_replenish_instruction(
+    accounts: ReplenishInstructionAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+    let jup_stable_custodian_token_account = get_associated_token_address_with_program_id(
+        &accounts.jup_stable_custodian,
+        &accounts.settlement_mint,
+        &accounts.settlement_token_program,
+    );
+
+    let mut acc = psm::accounts::Replenish {
+        admin: accounts.admin,
+        config: find_config(),
+        authority: find_authority(),
+        redemption_mint: accounts.redemption_mint,
+        settlement_mint: accounts.settlement_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        jup_stable_config: stable_pda::find_config(),
+        jup_stable_authority: stable_pda::find_authority(),
+        jup_stable_vault: stable_pda::find_vault(&accounts.settlement_mint),
+        jup_stable_custodian: accounts.jup_stable_custodian,
+        jup_stable_custodian_token_account,
+        jup_stable_benefactor: stable_pda::find_benefactor(&find_authority()),
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::id(),
+        redemption_token_program: accounts.redemption_token_program,
+        settlement_token_program: accounts.settlement_token_program,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    acc.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: psm::id(),
+        accounts: acc,
+        data: psm::instruction::Replenish {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub struct MintAndSupplyInstructionAccounts {
+    pub admin: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+    pub settlement_token_program: Pubkey,
+    pub jup_stable_custodian: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+pub fn create_mint_and_supply_instruction(
+    accounts: MintAndSupplyInstructionAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+    let admin_settlement_token_account = get_associated_token_address_with_program_id(
+        &accounts.admin,
+        &accounts.settlement_mint,
+        &accounts.settlement_token_program,
+    );
+    let jup_stable_custodian_token_account = get_associated_token_address_with_program_id(
+        &accounts.jup_stable_custodian,
+        &accounts.settlement_mint,
+        &accounts.settlement_token_program,
+    );
+
+    let mut acc = psm::accounts::MintAndSupply {
+        admin: accounts.admin,
+        admin_settlement_token_account,
+        config: find_config(),
+        authority: find_authority(),
+        redemption_mint: accounts.redemption_mint,
+        settlement_mint: accounts.settlement_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        jup_stable_config: stable_pda::find_config(),
+        jup_stable_authority: stable_pda::find_authority(),
+        jup_stable_vault: stable_pda::find_vault(&accounts.settlement_mint),
+        jup_stable_custodian: accounts.jup_stable_custodian,
+        jup_stable_custodian_token_account,
+        jup_stable_benefactor: stable_pda::find_benefactor(&find_authority()),
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::id(),
+        redemption_token_program: accounts.redemption_token_program,
+        settlement_token_program: accounts.settlement_token_program,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    acc.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: psm::id(),
+        accounts: acc,
+        data: psm::instruction::MintAndSupply {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub struct RetireSettlementInstructionAccounts {
+    pub admin: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+    pub settlement_token_program: Pubkey,
+    pub remaining_accounts: Vec<Pubkey>,
+}
+
+pub fn create_retire_settlement_instruction(
+    accounts: RetireSettlementInstructionAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+
+    let mut acc = psm::accounts::RetireSettlement {
+        admin: accounts.admin,
+        config: find_config(),
+        authority: find_authority(),
+        settlement_mint: accounts.settlement_mint,
+        redemption_mint: accounts.redemption_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        jup_stable_config: stable_pda::find_config(),
+        jup_stable_authority: stable_pda::find_authority(),
+        jup_stable_vault: stable_pda::find_vault(&accounts.redemption_mint),
+        jup_stable_vault_token_account: stable_pda::find_vault_token_account_with_program(
+            &accounts.redemption_mint,
+            &accounts.redemption_token_program,
+        ),
+        jup_stable_benefactor: stable_pda::find_benefactor(&find_authority()),
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::id(),
+        redemption_token_program: accounts.redemption_token_program,
+        settlement_token_program: accounts.settlement_token_program,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    acc.extend(
+        accounts
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+
+    Instruction {
+        program_id: psm::id(),
+        accounts: acc,
+        data: psm::instruction::RetireSettlement {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub struct RedeemInstructionAccounts {
+    pub user: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+    pub settlement_token_program: Pubkey,
+}
+
+pub fn create_redeem_instruction(
+    accounts: RedeemInstructionAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    redeem_instruction(accounts, amount, min_amount_out, false)
+}
+
+/// Redemption by a user on the pool's fee exempt list, skipping the utilization fee.
+pub fn create_fee_exempt_redeem_instruction(
+    accounts: RedeemInstructionAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    redeem_instruction(accounts, amount, min_amount_out, true)
+}
+
+fn redeem_instruction(
+    accounts: RedeemInstructionAccounts,
+    amount: u64,
+    min_amount_out: u64,
+    fee_exempt: bool,
+) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+    let user_redemption_token_account = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.redemption_mint,
+        &accounts.redemption_token_program,
+    );
+    let user_settlement_token_account = get_associated_token_address_with_program_id(
+        &accounts.user,
+        &accounts.settlement_mint,
+        &accounts.settlement_token_program,
+    );
+
+    let accounts = psm::accounts::Redeem {
+        user: accounts.user,
+        user_redemption_token_account,
+        user_settlement_token_account,
+        config: find_config(),
+        authority: find_authority(),
+        settlement_mint: accounts.settlement_mint,
+        redemption_mint: accounts.redemption_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        redemption_token_program: accounts.redemption_token_program,
+        settlement_token_program: accounts.settlement_token_program,
+        system_program: system_program::ID,
+        fee_exempt_list: fee_exempt.then(|| find_fee_exempt_list(&pool)),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::Redeem {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub struct WithdrawInstructionAccounts {
+    pub admin: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub settlement_token_program: Pubkey,
+}
+
+pub fn create_withdraw_instruction(
+    accounts: WithdrawInstructionAccounts,
+    amount: u64,
+) -> Instruction {
+    withdraw_instruction(accounts, amount, None)
+}
+
+/// Withdrawal once the config restricts withdrawal destinations; the admin has to be approved on
+/// the withdrawal destination list.
+pub fn create_restricted_withdraw_instruction(
+    accounts: WithdrawInstructionAccounts,
+    amount: u64,
+) -> Instruction {
+    withdraw_instruction(accounts, amount, Some(find_withdrawal_destination_list()))
+}
+
+fn withdraw_instruction(
+    accounts: WithdrawInstructionAccounts,
+    amount: u64,
+    withdrawal_destination_list: Option<Pubkey>,
+) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+    let admin_settlement_token_account = get_associated_token_address_with_program_id(
+        &accounts.admin,
+        &accounts.settlement_mint,
+        &accounts.settlement_token_program,
+    );
+
+    let accounts = psm::accounts::Withdraw {
+        admin: accounts.admin,
+        admin_settlement_token_account,
+        config: find_config(),
+        authority: find_authority(),
+        settlement_mint: accounts.settlement_mint,
+        pool,
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        settlement_token_program: accounts.settlement_token_program,
+        system_program: system_program::ID,
+        withdrawal_destination_list,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::Withdraw { amount }.data(),
+    }
+}
+
+pub fn create_quote_redeem_instruction(
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::QuoteRedeem {
+        config: find_config(),
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::QuoteRedeem { amount }.data(),
+    }
+}
+
+pub fn create_view_pool_instruction(
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+) -> Instruction {
+    let accounts = psm::accounts::ViewPool {
+        pool: find_pool(&redemption_mint, &settlement_mint),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ViewPool {}.data(),
+    }
+}
+
+pub struct MigratePoolInstructionAccounts {
+    pub admin: Pubkey,
+    pub payer: Pubkey,
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub new_settlement_mint: Pubkey,
+    pub redemption_token_program: Pubkey,
+    pub settlement_token_program: Pubkey,
+    pub new_settlement_token_program: Pubkey,
+}
+
+pub fn create_migrate_pool_instruction(accounts: MigratePoolInstructionAccounts) -> Instruction {
+    let pool = find_pool(&accounts.redemption_mint, &accounts.settlement_mint);
+    let new_pool = find_pool(&accounts.redemption_mint, &accounts.new_settlement_mint);
+
+    let accounts = psm::accounts::MigratePool {
+        admin: accounts.admin,
+        payer: accounts.payer,
+        config: find_config(),
+        authority: find_authority(),
+        treasury: find_treasury(),
+        redemption_mint: accounts.redemption_mint,
+        settlement_mint: accounts.settlement_mint,
+        new_settlement_mint: accounts.new_settlement_mint,
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        new_pool,
+        new_redemption_token_account: find_pool_redemption_token_account(&new_pool),
+        new_settlement_token_account: find_pool_settlement_token_account(&new_pool),
+        redemption_token_program: accounts.redemption_token_program,
+        settlement_token_program: accounts.settlement_token_program,
+        new_settlement_token_program: accounts.new_settlement_token_program,
+        system_program: system_program::ID,
+        allowed_mint_list: None,
+        event_authority: find_event_authority(),
+        program: psm::id(),
+    }
+    .to_account_metas(Some(true));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::MigratePool {}.data(),
+    }
+}
+
+/// Bumps the config, and the pool for the mint pair when given, to the current layout versions.
+pub fn create_migrate_instruction(
+    admin: Pubkey,
+    payer: Pubkey,
+    pool_mints: Option<(Pubkey, Pubkey)>,
+) -> Instruction {
+    let accounts = psm::accounts::Migrate {
+        admin,
+        payer,
+        config: find_config(),
+        pool: pool_mints.map(|(redemption_mint, settlement_mint)| {
+            find_pool(&redemption_mint, &settlement_mint)
+        }),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::Migrate {}.data(),
+    }
+}
+
+pub fn create_create_reserve_instruction(
+    admin: Pubkey,
+    payer: Pubkey,
+    redemption_mint: Pubkey,
+    redemption_token_program: Pubkey,
+) -> Instruction {
+    let reserve = find_reserve(&redemption_mint);
+    let accounts = psm::accounts::CreateReserve {
+        admin,
+        payer,
+        redemption_mint,
+        config: find_config(),
+        authority: find_authority(),
+        reserve,
+        redemption_token_account: find_reserve_redemption_token_account(&reserve),
+        redemption_token_program,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::CreateReserve {}.data(),
+    }
+}
+
+pub fn create_attach_reserve_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::AttachReserve {
+        admin,
+        config: find_config(),
+        authority: find_authority(),
+        pool,
+        redemption_token_account: find_pool_redemption_token_account(&pool),
+        reserve: find_reserve(&redemption_mint),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::AttachReserve {}.data(),
+    }
+}
+
+pub fn create_detach_reserve_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::DetachReserve {
+        admin,
+        config: find_config(),
+        authority: find_authority(),
+        pool,
+        pool_redemption_token_account: find_pool_redemption_token_account(&pool),
+        reserve: find_reserve(&redemption_mint),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::DetachReserve {}.data(),
+    }
+}
+
+pub fn create_propose_withdrawal_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    settlement_token_program: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::ProposeWithdrawal {
+        admin,
+        destination: get_associated_token_address_with_program_id(
+            &admin,
+            &settlement_mint,
+            &settlement_token_program,
+        ),
+        config: find_config(),
+        pool,
+        pending_withdrawal: find_pending_withdrawal(&pool),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ProposeWithdrawal { amount }.data(),
+    }
+}
+
+pub fn create_execute_withdrawal_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    settlement_token_program: Pubkey,
+) -> Instruction {
+    execute_withdrawal_instruction(
+        admin,
+        redemption_mint,
+        settlement_mint,
+        settlement_token_program,
+        None,
+    )
+}
+
+/// Execution once the config restricts withdrawal destinations; the destination owner has to be
+/// approved on the withdrawal destination list.
+pub fn create_restricted_execute_withdrawal_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    settlement_token_program: Pubkey,
+) -> Instruction {
+    execute_withdrawal_instruction(
+        admin,
+        redemption_mint,
+        settlement_mint,
+        settlement_token_program,
+        Some(find_withdrawal_destination_list()),
+    )
+}
+
+fn execute_withdrawal_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    settlement_token_program: Pubkey,
+    withdrawal_destination_list: Option<Pubkey>,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::ExecuteWithdrawal {
+        admin,
+        proposer: admin,
+        destination: get_associated_token_address_with_program_id(
+            &admin,
+            &settlement_mint,
+            &settlement_token_program,
+        ),
+        config: find_config(),
+        authority: find_authority(),
+        settlement_mint,
+        pool,
+        settlement_token_account: find_pool_settlement_token_account(&pool),
+        pending_withdrawal: find_pending_withdrawal(&pool),
+        settlement_token_program,
+        withdrawal_destination_list,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ExecuteWithdrawal {}.data(),
+    }
+}
+
+pub fn create_cancel_withdrawal_instruction(
+    signer: Pubkey,
+    proposer: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::CancelWithdrawal {
+        signer,
+        proposer,
+        config: find_config(),
+        pool,
+        pending_withdrawal: find_pending_withdrawal(&pool),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::CancelWithdrawal {}.data(),
+    }
+}
+
+pub fn create_create_fee_exempt_list_instruction(
+    admin: Pubkey,
+    payer: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::CreateFeeExemptList {
+        admin,
+        payer,
+        config: find_config(),
+        pool,
+        fee_exempt_list: find_fee_exempt_list(&pool),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::CreateFeeExemptList {}.data(),
+    }
+}
+
+pub fn create_manage_fee_exempt_list_instruction(
+    admin: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    action: psm::instructions::FeeExemptListAction,
+) -> Instruction {
+    let pool = find_pool(&redemption_mint, &settlement_mint);
+    let accounts = psm::accounts::ManageFeeExemptList {
+        admin,
+        config: find_config(),
+        pool,
+        fee_exempt_list: find_fee_exempt_list(&pool),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ManageFeeExemptList { action }.data(),
+    }
+}
+
+pub fn create_create_allowed_mint_list_instruction(admin: Pubkey, payer: Pubkey) -> Instruction {
+    let accounts = psm::accounts::CreateAllowedMintList {
+        admin,
+        payer,
+        config: find_config(),
+        allowed_mint_list: find_allowed_mint_list(),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::CreateAllowedMintList {}.data(),
+    }
+}
+
+pub fn create_manage_allowed_mint_list_instruction(
+    admin: Pubkey,
+    action: psm::instructions::AllowedMintListAction,
+) -> Instruction {
+    let accounts = psm::accounts::ManageAllowedMintList {
+        admin,
+        config: find_config(),
+        allowed_mint_list: find_allowed_mint_list(),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ManageAllowedMintList { action }.data(),
+    }
+}
+
+pub fn create_create_withdrawal_destination_list_instruction(
+    admin: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    let accounts = psm::accounts::CreateWithdrawalDestinationList {
+        admin,
+        payer,
+        config: find_config(),
+        withdrawal_destination_list: find_withdrawal_destination_list(),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::CreateWithdrawalDestinationList {}.data(),
+    }
+}
+
+pub fn create_manage_withdrawal_destination_list_instruction(
+    signer: Pubkey,
+    action: psm::instructions::WithdrawalDestinationListAction,
+) -> Instruction {
+    let accounts = psm::accounts::ManageWithdrawalDestinationList {
+        signer,
+        config: find_config(),
+        withdrawal_destination_list: find_withdrawal_destination_list(),
+    }
+    .to_account_metas(Some(false));
+
+    Instruction {
+        program_id: psm::id(),
+        accounts,
+        data: psm::instruction::ManageWithdrawalDestinationList { action }.data(),
+    }
+}
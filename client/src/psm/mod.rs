@@ -0,0 +1,5 @@
+pub mod accounts;
+pub mod derivation;
+pub mod instructions;
+
+pub use psm::{self as program, state, ID};
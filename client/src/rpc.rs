@@ -0,0 +1,86 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount},
+    AccountDeserialize, Discriminator, Owner,
+};
+use anyhow::{Context, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+
+/// Fetches `address` and decodes it as `T`, checking the anchor discriminator.
+pub async fn fetch_account<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("failed to fetch account {address}"))?;
+    T::try_deserialize(&mut account.data.as_slice())
+        .with_context(|| format!("failed to decode account {address}"))
+}
+
+/// Like [`fetch_account`], but returns `None` when the account does not exist.
+pub async fn fetch_optional_account<T: AccountDeserialize>(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<Option<T>> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())
+        .await
+        .with_context(|| format!("failed to fetch account {address}"))?
+        .value;
+    account
+        .map(|account| {
+            T::try_deserialize(&mut account.data.as_slice())
+                .with_context(|| format!("failed to decode account {address}"))
+        })
+        .transpose()
+}
+
+/// Fetches the lookup table at `address` in the form v0 message compilation takes.
+pub async fn fetch_lookup_table(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<AddressLookupTableAccount> {
+    let account = rpc
+        .get_account(address)
+        .await
+        .with_context(|| format!("failed to fetch lookup table {address}"))?;
+    let table = AddressLookupTable::deserialize(&account.data)
+        .with_context(|| format!("failed to decode lookup table {address}"))?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// Enumerates every `T` owned by its program, filtering server-side on the discriminator.
+pub async fn fetch_all_accounts<T>(rpc: &RpcClient) -> Result<Vec<(Pubkey, T)>>
+where T: AccountDeserialize + Discriminator + Owner {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            T::DISCRIMINATOR,
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let accounts = rpc
+        .get_program_accounts_with_config(&T::owner(), config)
+        .await
+        .context("failed to fetch program accounts")?;
+
+    accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let decoded = T::try_deserialize(&mut account.data.as_slice())
+                .with_context(|| format!("failed to decode account {address}"))?;
+            Ok((address, decoded))
+        })
+        .collect()
+}
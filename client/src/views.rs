@@ -0,0 +1,30 @@
+//! Plain views of program accounts, with the `[u8; 16]` little-endian totals decoded to `u128`.
+
+pub use jup_stable::instructions::{Capacity, MintQuote, RedeemQuote};
+use jup_stable::state::{benefactor::Benefactor, vault::Vault};
+pub use psm::state::pool::PoolView;
+
+/// Lifetime mint/redeem totals of a vault or benefactor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub minted: u128,
+    pub redeemed: u128,
+}
+
+impl From<&Vault> for Totals {
+    fn from(vault: &Vault) -> Self {
+        Totals {
+            minted: vault.total_minted(),
+            redeemed: vault.total_redeemed(),
+        }
+    }
+}
+
+impl From<&Benefactor> for Totals {
+    fn from(benefactor: &Benefactor) -> Self {
+        Totals {
+            minted: benefactor.total_minted(),
+            redeemed: benefactor.total_redeemed(),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Error codes of the JupUSD programs in one namespace. Both programs number their errors from
+//! Anchor's default offset, so a code alone doesn't say which error it is: a [`ProgramErrorCode`]
+//! pairs it with the program that raised it, and the failures both programs share map to a single
+//! [`CommonError`].
+//!
+//! Codes on deployed programs never move, new errors only get appended.
+
+use anchor_lang::{error::ERROR_CODE_OFFSET, prelude::Pubkey};
+pub use jup_stable::error::JupStableError;
+pub use psm::error::PSmError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorProgram {
+    JupStable,
+    Psm,
+}
+
+impl ErrorProgram {
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Self> {
+        if *program_id == jup_stable::ID {
+            Some(ErrorProgram::JupStable)
+        } else if *program_id == psm::ID {
+            Some(ErrorProgram::Psm)
+        } else {
+            None
+        }
+    }
+
+    pub fn program_id(self) -> Pubkey {
+        match self {
+            ErrorProgram::JupStable => jup_stable::ID,
+            ErrorProgram::Psm => psm::ID,
+        }
+    }
+}
+
+/// An error code along with the program that raised it. Codes of different programs overlap, so
+/// they are only compared in this form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramErrorCode {
+    pub program: ErrorProgram,
+    pub code: u32,
+}
+
+impl ProgramErrorCode {
+    /// `None` for programs outside the workspace.
+    pub fn new(program_id: &Pubkey, code: u32) -> Option<Self> {
+        Some(Self {
+            program: ErrorProgram::from_program_id(program_id)?,
+            code,
+        })
+    }
+
+    /// Position of the error among the ones its program defines, `None` for codes below the
+    /// programs' shared offset, such as Anchor's own errors.
+    pub fn index(self) -> Option<u32> { self.code.checked_sub(ERROR_CODE_OFFSET) }
+}
+
+/// Failures both programs raise, under whichever code each program gives them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommonError {
+    SomeError,
+    AdminArrayFull,
+    NotAuthorized,
+    BadInput,
+    ProtocolPaused,
+    InvalidAuthority,
+    InvalidTokenProgram,
+    InvalidCustodian,
+    InsufficientAmount,
+    SlippageToleranceExceeded,
+    ZeroAmount,
+    MathOverflow,
+}
+
+impl CommonError {
+    pub const ALL: [CommonError; 12] = [
+        CommonError::SomeError,
+        CommonError::AdminArrayFull,
+        CommonError::NotAuthorized,
+        CommonError::BadInput,
+        CommonError::ProtocolPaused,
+        CommonError::InvalidAuthority,
+        CommonError::InvalidTokenProgram,
+        CommonError::InvalidCustodian,
+        CommonError::InsufficientAmount,
+        CommonError::SlippageToleranceExceeded,
+        CommonError::ZeroAmount,
+        CommonError::MathOverflow,
+    ];
+
+    /// Code `program` raises the error with.
+    pub fn code(self, program: ErrorProgram) -> ProgramErrorCode {
+        let code = match program {
+            ErrorProgram::JupStable => self.jup_stable().into(),
+            ErrorProgram::Psm => self.psm().into(),
+        };
+        ProgramErrorCode { program, code }
+    }
+
+    /// Resolves a program's error code, `None` for codes specific to one program.
+    pub fn from_code(code: ProgramErrorCode) -> Option<Self> {
+        CommonError::ALL
+            .into_iter()
+            .find(|error| error.code(code.program) == code)
+    }
+
+    pub fn jup_stable(self) -> JupStableError {
+        match self {
+            CommonError::SomeError => JupStableError::SomeError,
+            CommonError::AdminArrayFull => JupStableError::AdminArrayFull,
+            CommonError::NotAuthorized => JupStableError::NotAuthorized,
+            CommonError::BadInput => JupStableError::BadInput,
+            CommonError::ProtocolPaused => JupStableError::ProtocolPaused,
+            CommonError::InvalidAuthority => JupStableError::InvalidAuthority,
+            CommonError::InvalidTokenProgram => JupStableError::InvalidTokenProgram,
+            CommonError::InvalidCustodian => JupStableError::InvalidCustodian,
+            CommonError::InsufficientAmount => JupStableError::InsufficientAmount,
+            CommonError::SlippageToleranceExceeded => JupStableError::SlippageToleranceExceeded,
+            CommonError::ZeroAmount => JupStableError::ZeroAmount,
+            CommonError::MathOverflow => JupStableError::MathOverflow,
+        }
+    }
+
+    pub fn psm(self) -> PSmError {
+        match self {
+            CommonError::SomeError => PSmError::SomeError,
+            CommonError::AdminArrayFull => PSmError::AdminArrayFull,
+            CommonError::NotAuthorized => PSmError::NotAuthorized,
+            CommonError::BadInput => PSmError::BadInput,
+            CommonError::ProtocolPaused => PSmError::ProtocolPaused,
+            CommonError::InvalidAuthority => PSmError::InvalidAuthority,
+            CommonError::InvalidTokenProgram => PSmError::InvalidTokenProgram,
+            CommonError::InvalidCustodian => PSmError::InvalidCustodian,
+            CommonError::InsufficientAmount => PSmError::InsufficientAmount,
+            CommonError::SlippageToleranceExceeded => PSmError::SlippageToleranceExceeded,
+            CommonError::ZeroAmount => PSmError::ZeroAmount,
+            CommonError::MathOverflow => PSmError::MathOverflow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_start_at_the_anchor_offset() {
+        assert_eq!(
+            CommonError::SomeError.code(ErrorProgram::JupStable).index(),
+            Some(0)
+        );
+        assert_eq!(
+            CommonError::SomeError.code(ErrorProgram::Psm).index(),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn shared_errors_resolve_from_either_program() {
+        for program in [ErrorProgram::JupStable, ErrorProgram::Psm] {
+            for error in CommonError::ALL {
+                assert_eq!(
+                    CommonError::from_code(error.code(program)),
+                    Some(error),
+                    "{error:?} should resolve from {program:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_code_resolves_per_program() {
+        let code = CommonError::ZeroAmount.code(ErrorProgram::JupStable).code;
+        assert_ne!(code, CommonError::ZeroAmount.code(ErrorProgram::Psm).code);
+        assert_eq!(
+            ProgramErrorCode::new(&jup_stable::ID, code).and_then(CommonError::from_code),
+            Some(CommonError::ZeroAmount)
+        );
+        assert_ne!(
+            ProgramErrorCode::new(&psm::ID, code).and_then(CommonError::from_code),
+            Some(CommonError::ZeroAmount)
+        );
+
+        assert_eq!(ProgramErrorCode::new(&Pubkey::default(), code), None);
+    }
+}
@@ -0,0 +1,626 @@
+//! Decodes the events `jup-stable` and `psm` emit, through `emit_cpi!` or as `Program data:` logs,
+//! into serde-serializable structs, so indexers don't have to parse Anchor's framing themselves.
+//!
+//! Events are recognised by program id and discriminator. Discriminators this crate does not know
+//! yet are skipped rather than rejected, so an indexer running an older version of the decoder
+//! keeps working when the programs start emitting a new event version.
+
+use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::Pubkey, AnchorDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+mod transaction;
+
+pub use transaction::{decode_inner_instructions, decode_log_messages, decode_transaction};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintV0 {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub oracle_price: u64,
+    pub one_to_one_amount: u64,
+    pub oracle_amount: u64,
+    pub mint_amount: u64,
+}
+
+impl From<jup_stable::instructions::MintV0Event> for MintV0 {
+    fn from(event: jup_stable::instructions::MintV0Event) -> Self {
+        MintV0 {
+            amount: event.amount,
+            net_amount: event.net_amount,
+            oracle_price: event.oracle_price,
+            one_to_one_amount: event.one_to_one_amount,
+            oracle_amount: event.oracle_amount,
+            mint_amount: event.mint_amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedeemV0 {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub oracle_price: u64,
+    pub one_to_one_amount: u64,
+    pub oracle_amount: u64,
+    pub redeem_amount: u64,
+}
+
+impl From<jup_stable::instructions::RedeemV0Event> for RedeemV0 {
+    fn from(event: jup_stable::instructions::RedeemV0Event) -> Self {
+        RedeemV0 {
+            amount: event.amount,
+            net_amount: event.net_amount,
+            oracle_price: event.oracle_price,
+            one_to_one_amount: event.one_to_one_amount,
+            oracle_amount: event.oracle_amount,
+            redeem_amount: event.redeem_amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintV1 {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub oracle_price: u64,
+    pub one_to_one_amount: u64,
+    pub oracle_amount: u64,
+    pub mint_amount: u64,
+    pub fee_amount: u64,
+    pub fee_rate_bps: u16,
+}
+
+impl From<jup_stable::instructions::MintV1Event> for MintV1 {
+    fn from(event: jup_stable::instructions::MintV1Event) -> Self {
+        MintV1 {
+            amount: event.amount,
+            net_amount: event.net_amount,
+            oracle_price: event.oracle_price,
+            one_to_one_amount: event.one_to_one_amount,
+            oracle_amount: event.oracle_amount,
+            mint_amount: event.mint_amount,
+            fee_amount: event.fee_amount,
+            fee_rate_bps: event.fee_rate_bps,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedeemV1 {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub oracle_price: u64,
+    pub one_to_one_amount: u64,
+    pub oracle_amount: u64,
+    pub redeem_amount: u64,
+    pub fee_amount: u64,
+    pub fee_rate_bps: u16,
+}
+
+impl From<jup_stable::instructions::RedeemV1Event> for RedeemV1 {
+    fn from(event: jup_stable::instructions::RedeemV1Event) -> Self {
+        RedeemV1 {
+            amount: event.amount,
+            net_amount: event.net_amount,
+            oracle_price: event.oracle_price,
+            one_to_one_amount: event.one_to_one_amount,
+            oracle_amount: event.oracle_amount,
+            redeem_amount: event.redeem_amount,
+            fee_amount: event.fee_amount,
+            fee_rate_bps: event.fee_rate_bps,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountTouched {
+    #[serde(with = "pubkey_string::vec")]
+    pub accounts: Vec<Pubkey>,
+}
+
+impl From<jup_stable::event::AccountTouchedEvent> for AccountTouched {
+    fn from(event: jup_stable::event::AccountTouchedEvent) -> Self {
+        AccountTouched {
+            accounts: event.accounts,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Withdraw {
+    #[serde(with = "pubkey_string")]
+    pub vault: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub custodian: Pubkey,
+    pub amount: u64,
+    pub reference: Option<[u8; 32]>,
+    #[serde(with = "pubkey_string")]
+    pub operator: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub destination: Pubkey,
+    pub vault_balance: u64,
+}
+
+impl From<jup_stable::event::WithdrawEvent> for Withdraw {
+    fn from(event: jup_stable::event::WithdrawEvent) -> Self {
+        Withdraw {
+            vault: event.vault,
+            custodian: event.custodian,
+            amount: event.amount,
+            reference: event.reference,
+            operator: event.operator,
+            destination: event.destination,
+            vault_balance: event.vault_balance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeAuthorityProposed {
+    #[serde(with = "pubkey_string")]
+    pub operator: Pubkey,
+    #[serde(with = "pubkey_string::option")]
+    pub upgrade_authority: Option<Pubkey>,
+    #[serde(with = "pubkey_string")]
+    pub pending_upgrade_authority: Pubkey,
+}
+
+impl From<jup_stable::event::UpgradeAuthorityProposedEvent> for UpgradeAuthorityProposed {
+    fn from(event: jup_stable::event::UpgradeAuthorityProposedEvent) -> Self {
+        UpgradeAuthorityProposed {
+            operator: event.operator,
+            upgrade_authority: event.upgrade_authority,
+            pending_upgrade_authority: event.pending_upgrade_authority,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeAuthorityConfirmed {
+    #[serde(with = "pubkey_string")]
+    pub upgrade_authority: Pubkey,
+}
+
+impl From<jup_stable::event::UpgradeAuthorityConfirmedEvent> for UpgradeAuthorityConfirmed {
+    fn from(event: jup_stable::event::UpgradeAuthorityConfirmedEvent) -> Self {
+        UpgradeAuthorityConfirmed {
+            upgrade_authority: event.upgrade_authority,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlashMint {
+    #[serde(with = "pubkey_string")]
+    pub benefactor: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub mint_amount: u64,
+}
+
+impl From<jup_stable::event::FlashMintEvent> for FlashMint {
+    fn from(event: jup_stable::event::FlashMintEvent) -> Self {
+        FlashMint {
+            benefactor: event.benefactor,
+            vault: event.vault,
+            amount: event.amount,
+            mint_amount: event.mint_amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlashMintSettled {
+    #[serde(with = "pubkey_string")]
+    pub benefactor: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub vault: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+impl From<jup_stable::event::FlashMintSettledEvent> for FlashMintSettled {
+    fn from(event: jup_stable::event::FlashMintSettledEvent) -> Self {
+        FlashMintSettled {
+            benefactor: event.benefactor,
+            vault: event.vault,
+            payer: event.payer,
+            amount: event.amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupplyMismatch {
+    #[serde(with = "pubkey_string")]
+    pub lp_mint: Pubkey,
+    pub supply: u64,
+    pub tracked_supply: u64,
+}
+
+impl From<jup_stable::event::SupplyMismatchEvent> for SupplyMismatch {
+    fn from(event: jup_stable::event::SupplyMismatchEvent) -> Self {
+        SupplyMismatch {
+            lp_mint: event.lp_mint,
+            supply: event.supply,
+            tracked_supply: event.tracked_supply,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreasuryBurn {
+    #[serde(with = "pubkey_string")]
+    pub operator: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub supply: u64,
+    pub tracked_supply: u64,
+}
+
+impl From<jup_stable::event::TreasuryBurnEvent> for TreasuryBurn {
+    fn from(event: jup_stable::event::TreasuryBurnEvent) -> Self {
+        TreasuryBurn {
+            operator: event.operator,
+            token_account: event.token_account,
+            amount: event.amount,
+            supply: event.supply,
+            tracked_supply: event.tracked_supply,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PegPriceUpdated {
+    #[serde(with = "pubkey_string")]
+    pub operator: Pubkey,
+    pub old_peg_price_usd: u64,
+    pub new_peg_price_usd: u64,
+    pub timestamp: i64,
+}
+
+impl From<jup_stable::event::PegPriceUpdatedEvent> for PegPriceUpdated {
+    fn from(event: jup_stable::event::PegPriceUpdatedEvent) -> Self {
+        PegPriceUpdated {
+            operator: event.operator,
+            old_peg_price_usd: event.old_peg_price_usd,
+            new_peg_price_usd: event.new_peg_price_usd,
+            timestamp: event.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositAcknowledged {
+    #[serde(with = "pubkey_string")]
+    pub vault: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub custodian: Pubkey,
+    pub reference: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+impl From<jup_stable::event::DepositAcknowledgedEvent> for DepositAcknowledged {
+    fn from(event: jup_stable::event::DepositAcknowledgedEvent) -> Self {
+        DepositAcknowledged {
+            vault: event.vault,
+            custodian: event.custodian,
+            reference: event.reference,
+            amount: event.amount,
+            timestamp: event.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolMigrated {
+    #[serde(with = "pubkey_string")]
+    pub pool: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub new_pool: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub settlement_mint: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub new_settlement_mint: Pubkey,
+    pub redemption_amount: u64,
+}
+
+impl From<psm::instructions::PoolMigratedEvent> for PoolMigrated {
+    fn from(event: psm::instructions::PoolMigratedEvent) -> Self {
+        PoolMigrated {
+            pool: event.pool,
+            new_pool: event.new_pool,
+            settlement_mint: event.settlement_mint,
+            new_settlement_mint: event.new_settlement_mint,
+            redemption_amount: event.redemption_amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "name", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    MintV0(MintV0),
+    RedeemV0(RedeemV0),
+    MintV1(MintV1),
+    RedeemV1(RedeemV1),
+    AccountTouched(AccountTouched),
+    Withdraw(Withdraw),
+    UpgradeAuthorityProposed(UpgradeAuthorityProposed),
+    UpgradeAuthorityConfirmed(UpgradeAuthorityConfirmed),
+    FlashMint(FlashMint),
+    FlashMintSettled(FlashMintSettled),
+    SupplyMismatch(SupplyMismatch),
+    TreasuryBurn(TreasuryBurn),
+    PegPriceUpdated(PegPriceUpdated),
+    DepositAcknowledged(DepositAcknowledged),
+    PoolMigrated(PoolMigrated),
+}
+
+/// Where an event was found. `inner_index` is the position of the event self-CPI among the inner
+/// instructions of top-level instruction `instruction_index`, and `None` for events emitted as
+/// logs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventContext {
+    pub slot: u64,
+    pub signature: String,
+    pub instruction_index: u8,
+    pub inner_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedEvent {
+    #[serde(flatten)]
+    pub context: EventContext,
+    #[serde(with = "pubkey_string")]
+    pub program_id: Pubkey,
+    pub event: Event,
+}
+
+/// Decodes the data of an instruction invoked on `program_id`. Returns `None` when it isn't an
+/// event self-CPI of `jup-stable` or `psm`, or when the event discriminator is unknown.
+pub fn decode_event(program_id: &Pubkey, data: &[u8]) -> Result<Option<Event>> {
+    match data.strip_prefix(EVENT_IX_TAG_LE) {
+        Some(data) => decode_event_data(program_id, data),
+        None => Ok(None),
+    }
+}
+
+/// Decodes an event payload (discriminator followed by the borsh-encoded event), as carried by a
+/// `Program data:` log or an event self-CPI once its tag is stripped.
+pub fn decode_event_data(program_id: &Pubkey, data: &[u8]) -> Result<Option<Event>> {
+    if *program_id == jup_stable::ID {
+        if let Some(event) = decode_as::<jup_stable::instructions::MintV0Event>(data)? {
+            return Ok(Some(Event::MintV0(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::instructions::RedeemV0Event>(data)? {
+            return Ok(Some(Event::RedeemV0(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::instructions::MintV1Event>(data)? {
+            return Ok(Some(Event::MintV1(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::instructions::RedeemV1Event>(data)? {
+            return Ok(Some(Event::RedeemV1(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::AccountTouchedEvent>(data)? {
+            return Ok(Some(Event::AccountTouched(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::WithdrawEvent>(data)? {
+            return Ok(Some(Event::Withdraw(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::UpgradeAuthorityProposedEvent>(data)? {
+            return Ok(Some(Event::UpgradeAuthorityProposed(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::UpgradeAuthorityConfirmedEvent>(data)? {
+            return Ok(Some(Event::UpgradeAuthorityConfirmed(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::FlashMintEvent>(data)? {
+            return Ok(Some(Event::FlashMint(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::FlashMintSettledEvent>(data)? {
+            return Ok(Some(Event::FlashMintSettled(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::SupplyMismatchEvent>(data)? {
+            return Ok(Some(Event::SupplyMismatch(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::TreasuryBurnEvent>(data)? {
+            return Ok(Some(Event::TreasuryBurn(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::PegPriceUpdatedEvent>(data)? {
+            return Ok(Some(Event::PegPriceUpdated(event.into())));
+        }
+        if let Some(event) = decode_as::<jup_stable::event::DepositAcknowledgedEvent>(data)? {
+            return Ok(Some(Event::DepositAcknowledged(event.into())));
+        }
+    } else if *program_id == psm::ID {
+        if let Some(event) = decode_as::<psm::instructions::PoolMigratedEvent>(data)? {
+            return Ok(Some(Event::PoolMigrated(event.into())));
+        }
+    }
+
+    Ok(None)
+}
+
+fn decode_as<E: Discriminator + AnchorDeserialize>(data: &[u8]) -> Result<Option<E>> {
+    let Some(mut payload) = data.strip_prefix(E::DISCRIMINATOR) else {
+        return Ok(None);
+    };
+    E::deserialize(&mut payload)
+        .map(Some)
+        .context("failed to deserialize event")
+}
+
+mod pubkey_string {
+    use std::str::FromStr;
+
+    use anchor_lang::prelude::Pubkey;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Pubkey::from_str(&value).map_err(D::Error::custom)
+    }
+
+    pub mod option {
+        use std::str::FromStr;
+
+        use anchor_lang::prelude::Pubkey;
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            pubkey: &Option<Pubkey>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match pubkey {
+                Some(pubkey) => serializer.collect_str(pubkey),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Pubkey>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|value| Pubkey::from_str(&value).map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+
+    pub mod vec {
+        use std::str::FromStr;
+
+        use anchor_lang::prelude::Pubkey;
+        use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            pubkeys: &[Pubkey],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(pubkeys.len()))?;
+            for pubkey in pubkeys {
+                seq.serialize_element(&pubkey.to_string())?;
+            }
+            seq.end()
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<Pubkey>, D::Error> {
+            Vec::<String>::deserialize(deserializer)?
+                .iter()
+                .map(|value| Pubkey::from_str(value).map_err(D::Error::custom))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_cpi_data(event: &impl anchor_lang::Event) -> Vec<u8> {
+        [EVENT_IX_TAG_LE, &event.data()].concat()
+    }
+
+    #[test]
+    fn decodes_mint_event() {
+        let event = jup_stable::instructions::MintV0Event {
+            amount: 100,
+            net_amount: 99,
+            oracle_price: 1_000_000,
+            one_to_one_amount: 99,
+            oracle_amount: 100,
+            mint_amount: 99,
+        };
+        let decoded = decode_event(&jup_stable::ID, &event_cpi_data(&event)).unwrap();
+        assert_eq!(decoded, Some(Event::MintV0(event.into())));
+    }
+
+    #[test]
+    fn decodes_mint_event_with_fee() {
+        let event = jup_stable::instructions::MintV1Event {
+            amount: 100,
+            net_amount: 99,
+            oracle_price: 1_000_000,
+            one_to_one_amount: 99,
+            oracle_amount: 100,
+            mint_amount: 99,
+            fee_amount: 1,
+            fee_rate_bps: 100,
+        };
+        let decoded = decode_event(&jup_stable::ID, &event_cpi_data(&event)).unwrap();
+        assert_eq!(decoded, Some(Event::MintV1(event.into())));
+    }
+
+    #[test]
+    fn ignores_other_programs_and_unknown_events() {
+        let event = jup_stable::instructions::RedeemV0Event {
+            amount: 1,
+            net_amount: 1,
+            oracle_price: 1,
+            one_to_one_amount: 1,
+            oracle_amount: 1,
+            redeem_amount: 1,
+        };
+        let data = event_cpi_data(&event);
+        assert_eq!(decode_event(&psm::ID, &data).unwrap(), None);
+
+        let mut unknown = data;
+        unknown[EVENT_IX_TAG_LE.len()] ^= 0xff;
+        assert_eq!(decode_event(&jup_stable::ID, &unknown).unwrap(), None);
+    }
+
+    #[test]
+    fn decodes_logged_account_touched_event() {
+        use anchor_lang::Event as _;
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let accounts = vec![Pubkey::new_unique()];
+        let event = jup_stable::event::AccountTouchedEvent {
+            accounts: accounts.clone(),
+        };
+        let logs = [
+            format!("Program {} invoke [1]", psm::ID),
+            format!("Program {} success", psm::ID),
+            format!("Program {} invoke [1]", jup_stable::ID),
+            "Program log: Instruction: ManageConfig".to_string(),
+            format!("Program data: {}", STANDARD.encode(event.data())),
+            format!("Program {} success", jup_stable::ID),
+        ];
+
+        let decoded = decode_log_messages(1, "sig".to_string(), &logs).unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].context.instruction_index, 1);
+        assert_eq!(decoded[0].context.inner_index, None);
+        assert_eq!(decoded[0].program_id, jup_stable::ID);
+        assert_eq!(
+            decoded[0].event,
+            Event::AccountTouched(AccountTouched { accounts })
+        );
+    }
+
+    #[test]
+    fn serializes_pubkeys_as_base58() {
+        let pool = Pubkey::new_unique();
+        let event = Event::PoolMigrated(PoolMigrated {
+            pool,
+            new_pool: Pubkey::new_unique(),
+            settlement_mint: Pubkey::new_unique(),
+            new_settlement_mint: Pubkey::new_unique(),
+            redemption_amount: 1,
+        });
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["name"], "pool_migrated");
+        assert_eq!(json["data"]["pool"], pool.to_string());
+        assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+    }
+}
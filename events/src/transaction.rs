@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction, UiInnerInstructions, UiInstruction, UiLoadedAddresses, UiMessage,
+    UiParsedInstruction,
+};
+
+use crate::{decode_event, decode_event_data, DecodedEvent, EventContext};
+
+/// Decodes every `jup-stable` and `psm` event emitted by a transaction fetched with
+/// `get_transaction`, self-CPI events first and logged events after. Works with the binary,
+/// base64 and json encodings; failed transactions yield no events.
+pub fn decode_transaction(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<Vec<DecodedEvent>> {
+    let Some(meta) = &transaction.transaction.meta else {
+        return Ok(Vec::new());
+    };
+    if meta.err.is_some() {
+        return Ok(Vec::new());
+    }
+    let no_inner_instructions = Vec::new();
+    let inner_instructions = match &meta.inner_instructions {
+        OptionSerializer::Some(inner_instructions) => inner_instructions,
+        _ => &no_inner_instructions,
+    };
+
+    let (signature, account_keys) = match &transaction.transaction.transaction {
+        EncodedTransaction::Json(ui_transaction) => {
+            let signature = first_signature(&ui_transaction.signatures)?;
+            match &ui_transaction.message {
+                UiMessage::Raw(message) => {
+                    let mut keys = parse_pubkeys(&message.account_keys)?;
+                    append_loaded_addresses(&mut keys, &meta.loaded_addresses)?;
+                    (signature, keys)
+                },
+                // Parsed messages already include the keys loaded from lookup tables.
+                UiMessage::Parsed(message) => {
+                    let keys = message
+                        .account_keys
+                        .iter()
+                        .map(|account| parse_pubkey(&account.pubkey))
+                        .collect::<Result<Vec<_>>>()?;
+                    (signature, keys)
+                },
+            }
+        },
+        encoded => {
+            let decoded = encoded
+                .decode()
+                .ok_or_else(|| anyhow!("unsupported transaction encoding"))?;
+            let signature = decoded
+                .signatures
+                .first()
+                .ok_or_else(|| anyhow!("transaction has no signature"))?
+                .to_string();
+            let mut keys = decoded.message.static_account_keys().to_vec();
+            append_loaded_addresses(&mut keys, &meta.loaded_addresses)?;
+            (signature, keys)
+        },
+    };
+
+    let mut events = decode_inner_instructions(
+        transaction.slot,
+        signature.clone(),
+        &account_keys,
+        inner_instructions,
+    )?;
+    if let OptionSerializer::Some(logs) = &meta.log_messages {
+        events.extend(decode_log_messages(transaction.slot, signature, logs)?);
+    }
+
+    Ok(events)
+}
+
+/// Decodes events from inner instructions, resolving program ids against `account_keys` (static
+/// keys followed by loaded writable and readonly addresses).
+pub fn decode_inner_instructions(
+    slot: u64,
+    signature: String,
+    account_keys: &[Pubkey],
+    inner_instructions: &[UiInnerInstructions],
+) -> Result<Vec<DecodedEvent>> {
+    let mut events = Vec::new();
+    for inner in inner_instructions {
+        for (inner_index, instruction) in inner.instructions.iter().enumerate() {
+            let (program_id, data) = match instruction {
+                UiInstruction::Compiled(instruction) => {
+                    let program_id = account_keys
+                        .get(instruction.program_id_index as usize)
+                        .copied()
+                        .ok_or_else(|| anyhow!("program id index out of bounds"))?;
+                    (program_id, &instruction.data)
+                },
+                UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+                    (parse_pubkey(&instruction.program_id)?, &instruction.data)
+                },
+                // Fully parsed instructions belong to programs the RPC node knows, never ours.
+                UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => continue,
+            };
+
+            let data = bs58::decode(data)
+                .into_vec()
+                .context("invalid instruction data")?;
+            if let Some(event) = decode_event(&program_id, &data)? {
+                events.push(DecodedEvent {
+                    context: EventContext {
+                        slot,
+                        signature: signature.clone(),
+                        instruction_index: inner.index,
+                        inner_index: Some(inner_index),
+                    },
+                    program_id,
+                    event,
+                });
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// Decodes events emitted with `emit!`, which show up as `Program data:` logs attributed to the
+/// program currently executing according to the invoke/success log stack.
+pub fn decode_log_messages(
+    slot: u64,
+    signature: String,
+    logs: &[String],
+) -> Result<Vec<DecodedEvent>> {
+    let mut events = Vec::new();
+    let mut invocations: Vec<Pubkey> = Vec::new();
+    let mut instruction_index: Option<u8> = None;
+
+    for log in logs {
+        let Some(message) = log.strip_prefix("Program ") else {
+            continue;
+        };
+
+        if let Some(data) = message.strip_prefix("data: ") {
+            let Some(program_id) = invocations.last() else {
+                continue;
+            };
+            let data = STANDARD.decode(data).context("invalid event log data")?;
+            if let Some(event) = decode_event_data(program_id, &data)? {
+                events.push(DecodedEvent {
+                    context: EventContext {
+                        slot,
+                        signature: signature.clone(),
+                        instruction_index: instruction_index.unwrap_or_default(),
+                        inner_index: None,
+                    },
+                    program_id: *program_id,
+                    event,
+                });
+            }
+            continue;
+        }
+
+        let mut words = message.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some(program_id), Some("invoke"), depth) => {
+                if depth == Some("[1]") {
+                    instruction_index = Some(instruction_index.map_or(0, |index| index + 1));
+                }
+                invocations.push(parse_pubkey(program_id)?);
+            },
+            (Some(_), Some("success" | "failed:"), _) => {
+                invocations.pop();
+            },
+            _ => {},
+        }
+    }
+
+    Ok(events)
+}
+
+fn append_loaded_addresses(
+    keys: &mut Vec<Pubkey>,
+    loaded_addresses: &OptionSerializer<UiLoadedAddresses>,
+) -> Result<()> {
+    if let OptionSerializer::Some(loaded) = loaded_addresses {
+        keys.extend(parse_pubkeys(&loaded.writable)?);
+        keys.extend(parse_pubkeys(&loaded.readonly)?);
+    }
+    Ok(())
+}
+
+fn first_signature(signatures: &[String]) -> Result<String> {
+    signatures
+        .first()
+        .cloned()
+        .ok_or_else(|| anyhow!("transaction has no signature"))
+}
+
+fn parse_pubkeys(keys: &[String]) -> Result<Vec<Pubkey>> {
+    keys.iter().map(|key| parse_pubkey(key)).collect()
+}
+
+fn parse_pubkey(key: &str) -> Result<Pubkey> {
+    Pubkey::from_str(key).with_context(|| format!("invalid pubkey {key}"))
+}
@@ -0,0 +1,264 @@
+//! Mint/redeem pricing for jup-stable. The program and off-chain quoters both call into this
+//! crate, so a quote computed here matches what the program mints or redeems to the smallest unit.
+
+#![cfg_attr(not(test), no_std)]
+
+use core::fmt;
+
+use rust_decimal::prelude::ToPrimitive;
+pub use rust_decimal::Decimal;
+
+/// Denominator of benefactor fee rates, which are expressed in basis points.
+pub const FEE_RATE_DENOMINATOR: u128 = 10_000;
+
+/// Most decimals a collateral mint may have. Quotes scale by `10^decimals` and stay far from the
+/// `Decimal` and `i64` limits up to it.
+pub const MAX_COLLATERAL_DECIMALS: u8 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    MathOverflow,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::MathOverflow => f.write_str("math overflow"),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+/// Output of a mint or redeem quote. `amount` is the lower of `one_to_one_amount` (fee-adjusted,
+/// at the peg) and `oracle_amount` (gross, at the oracle price).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Quote {
+    pub amount: u64,
+    pub one_to_one_amount: u64,
+    pub oracle_amount: u64,
+}
+
+/// Fee charged on `amount` at `fee_rate` basis points, rounded up.
+pub fn calculate_fee(amount: u64, fee_rate: u16) -> u64 {
+    (amount as u128 * fee_rate as u128).div_ceil(FEE_RATE_DENOMINATOR) as u64
+}
+
+pub fn calculate_mint_amount(
+    price: Decimal,
+    amount: Decimal,
+    peg_price: Decimal,
+    expected_decimals: u32,
+) -> Result<Decimal> {
+    amount
+        .checked_mul(price)
+        .and_then(|value| value.checked_div(peg_price))
+        .and_then(|value| value.checked_mul(pow10(expected_decimals)?))
+        .ok_or(MathError::MathOverflow)
+}
+
+pub fn calculate_redeem_amount(
+    price: Decimal,
+    lp_amount: Decimal,
+    peg_price: Decimal,
+    expected_decimals: u32,
+) -> Result<Decimal> {
+    lp_amount
+        .checked_mul(peg_price)
+        .and_then(|value| value.checked_div(price))
+        .and_then(|value| value.checked_mul(pow10(expected_decimals)?))
+        .ok_or(MathError::MathOverflow)
+}
+
+/// Quotes a mint of `amount` vault tokens, of which `net_amount` remains after the benefactor fee.
+pub fn compute_mint_amount(
+    amount: u64,
+    net_amount: u64,
+    oracle_price: Decimal,
+    peg_price: Decimal,
+    vault_mint_decimals: u8,
+    lp_mint_decimals: u8,
+) -> Result<Quote> {
+    let vault_decimals = vault_mint_decimals as u32;
+    let lp_decimals = lp_mint_decimals as u32;
+
+    // Calculate 1:1 exchange rate amount (net amount after fees)
+    let one_to_one_amount = to_decimal(net_amount, vault_decimals)?
+        .checked_div(peg_price)
+        .and_then(|value| value.checked_mul(pow10(lp_decimals)?))
+        .ok_or(MathError::MathOverflow)?;
+
+    // Calculate oracle-based amount
+    let oracle_amount = calculate_mint_amount(
+        oracle_price,
+        to_decimal(amount, vault_decimals)?,
+        peg_price,
+        lp_decimals,
+    )?;
+
+    Ok(Quote {
+        amount: decimal_to_u64(oracle_amount.min(one_to_one_amount))?,
+        one_to_one_amount: decimal_to_u64(one_to_one_amount)?,
+        oracle_amount: decimal_to_u64(oracle_amount)?,
+    })
+}
+
+/// Quotes a redeem of `amount` jupUSD, of which `net_amount` remains after the benefactor fee.
+pub fn compute_redeem_amount(
+    amount: u64,
+    net_amount: u64,
+    oracle_price: Decimal,
+    peg_price: Decimal,
+    lp_mint_decimals: u8,
+    vault_mint_decimals: u8,
+) -> Result<Quote> {
+    let lp_decimals = lp_mint_decimals as u32;
+    let vault_decimals = vault_mint_decimals as u32;
+
+    // Calculate 1:1 exchange rate amount (net amount after fees)
+    let one_to_one_amount = to_decimal(net_amount, lp_decimals)?
+        .checked_mul(peg_price)
+        .and_then(|value| value.checked_mul(pow10(vault_decimals)?))
+        .ok_or(MathError::MathOverflow)?;
+
+    // Calculate oracle-based amount
+    let oracle_amount = calculate_redeem_amount(
+        oracle_price,
+        to_decimal(amount, lp_decimals)?,
+        peg_price,
+        vault_decimals,
+    )?;
+
+    Ok(Quote {
+        amount: decimal_to_u64(oracle_amount.min(one_to_one_amount))?,
+        one_to_one_amount: decimal_to_u64(one_to_one_amount)?,
+        oracle_amount: decimal_to_u64(oracle_amount)?,
+    })
+}
+
+/// Quotes a mint end to end: deducts the benefactor fee and prices the remainder.
+pub fn quote_mint(
+    amount: u64,
+    mint_fee_rate: u16,
+    oracle_price: Decimal,
+    peg_price: Decimal,
+    vault_mint_decimals: u8,
+    lp_mint_decimals: u8,
+) -> Result<Quote> {
+    let net_amount = amount
+        .checked_sub(calculate_fee(amount, mint_fee_rate))
+        .ok_or(MathError::MathOverflow)?;
+    compute_mint_amount(
+        amount,
+        net_amount,
+        oracle_price,
+        peg_price,
+        vault_mint_decimals,
+        lp_mint_decimals,
+    )
+}
+
+/// Quotes a redeem end to end: deducts the benefactor fee and prices the remainder.
+pub fn quote_redeem(
+    amount: u64,
+    redeem_fee_rate: u16,
+    oracle_price: Decimal,
+    peg_price: Decimal,
+    lp_mint_decimals: u8,
+    vault_mint_decimals: u8,
+) -> Result<Quote> {
+    let net_amount = amount
+        .checked_sub(calculate_fee(amount, redeem_fee_rate))
+        .ok_or(MathError::MathOverflow)?;
+    compute_redeem_amount(
+        amount,
+        net_amount,
+        oracle_price,
+        peg_price,
+        lp_mint_decimals,
+        vault_mint_decimals,
+    )
+}
+
+pub fn decimal_to_u64(value: Decimal) -> Result<u64> {
+    value.to_u64().ok_or(MathError::MathOverflow)
+}
+
+fn to_decimal(amount: u64, decimals: u32) -> Result<Decimal> {
+    let amount = amount.try_into().map_err(|_| MathError::MathOverflow)?;
+    Decimal::try_new(amount, decimals).map_err(|_| MathError::MathOverflow)
+}
+
+fn pow10(decimals: u32) -> Option<Decimal> { 10_i64.checked_pow(decimals).map(Decimal::from) }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn fee_rounds_up() {
+        assert_eq!(calculate_fee(1, 1), 1);
+        assert_eq!(calculate_fee(10_000, 1), 1);
+        assert_eq!(calculate_fee(10_001, 1), 2);
+        assert_eq!(calculate_fee(1_000_000, 0), 0);
+    }
+
+    #[test]
+    fn quote_mint_at_peg_deducts_fee() {
+        let quote = quote_mint(1_000_000, 10, Decimal::ONE, Decimal::ONE, 6, 6).unwrap();
+        assert_eq!(quote.one_to_one_amount, 999_000);
+        assert_eq!(quote.oracle_amount, 1_000_000);
+        assert_eq!(quote.amount, 999_000);
+    }
+
+    #[test]
+    fn quote_at_extreme_collateral_decimals() {
+        let one = Decimal::ONE;
+        let max = MAX_COLLATERAL_DECIMALS;
+
+        assert_eq!(quote_mint(1, 0, one, one, 0, 6).unwrap().amount, 1_000_000);
+        assert_eq!(
+            quote_mint(10_u64.pow(max.into()), 0, one, one, max, 6)
+                .unwrap()
+                .amount,
+            1_000_000
+        );
+        assert_eq!(
+            quote_redeem(1_000_000, 0, one, one, 6, 0).unwrap().amount,
+            1
+        );
+        assert_eq!(
+            quote_redeem(1_000_000, 0, one, one, 6, max).unwrap().amount,
+            10_u64.pow(max.into())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn quote_never_panics_within_collateral_decimals(
+            amount: u64,
+            fee_rate in 0u16..=10_000,
+            mantissa in 1i64..=100_000,
+            decimals in 0u8..=MAX_COLLATERAL_DECIMALS,
+        ) {
+            let price = Decimal::new(mantissa, 4);
+            let _ = quote_mint(amount, fee_rate, price, Decimal::ONE, decimals, 6);
+            let _ = quote_redeem(amount, fee_rate, price, Decimal::ONE, 6, decimals);
+        }
+
+        #[test]
+        fn quote_never_exceeds_either_leg(
+            amount in 0u64..=1_000_000_000_000,
+            fee_rate in 0u16..=10_000,
+            mantissa in 1i64..=100_000,
+            decimals in 0u8..=9,
+        ) {
+            let price = Decimal::new(mantissa, 4);
+            let quote = quote_mint(amount, fee_rate, price, Decimal::ONE, decimals, 6).unwrap();
+            prop_assert!(quote.amount <= quote.one_to_one_amount);
+            prop_assert!(quote.amount <= quote.oracle_amount);
+        }
+    }
+}
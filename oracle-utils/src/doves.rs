@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use doves::AgPriceFeed;
+use rust_decimal::Decimal;
+
+use crate::{require_updated_since_restart, OracleError, OraclePrice, OracleResult, ParseOptions};
+
+pub(crate) fn parse(
+    oracle: &AccountInfo,
+    clock: &Clock,
+    options: ParseOptions,
+) -> OracleResult<(OraclePrice, Decimal)> {
+    let price = AgPriceFeed::try_deserialize(&mut &oracle.data.borrow()[..])?;
+
+    if options.check_restart_slot {
+        // Doves feeds only carry a timestamp, so estimate the slot they were published at
+        let age_ms = clock.unix_timestamp.saturating_sub(price.timestamp).max(0) as u64 * 1000;
+        let age_slots = age_ms / clock::DEFAULT_MS_PER_SLOT;
+        require_updated_since_restart(clock.slot.saturating_sub(age_slots))?;
+    }
+
+    let s: i64 = options
+        .stalesness_threshold
+        .try_into()
+        .map_err(|_| OracleError::MathOverflow)?;
+    if price.timestamp + s <= clock.unix_timestamp {
+        return Err(OracleError::BadOracle);
+    }
+
+    if price.price == 0 {
+        return Err(OracleError::BadOracle);
+    }
+
+    let scale = price
+        .expo
+        .abs()
+        .try_into()
+        .map_err(|_| OracleError::MathOverflow)?;
+    Ok((
+        OraclePrice(Decimal::from_i128_with_scale(price.price as i128, scale)),
+        Decimal::ZERO,
+    ))
+}
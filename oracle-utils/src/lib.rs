@@ -0,0 +1,365 @@
+//! Oracle price parsing and aggregation for the JupUSD programs. Each provider lives behind its
+//! own feature (`pyth`, `switchboard`, `doves`, all on by default), so a program only links the
+//! SDKs of the feeds it accepts.
+//!
+//! Failures are reported as [`OracleError`] and mapped onto the calling program's error codes
+//! there; errors raised by a provider SDK are passed through unchanged.
+
+use core::fmt;
+
+use anchor_lang::{prelude::*, solana_program::sysvar::last_restart_slot::LastRestartSlot};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+#[cfg(feature = "doves")]
+pub mod doves;
+#[cfg(feature = "pyth")]
+pub mod pyth;
+#[cfg(feature = "switchboard")]
+pub mod switchboard;
+
+#[cfg(feature = "pyth")]
+pub use pyth::PYTH_RECEIVER_PROGRAM_ID;
+#[cfg(feature = "switchboard")]
+pub use switchboard::SWITCHBOARD_ON_DEMAND_PROGRAM_ID;
+
+/// Widest confidence interval, and widest spread between oracles, a price is accepted with.
+pub const MAX_CONFIDENCE_BPS: u64 = 200u64;
+
+#[derive(Debug)]
+pub enum OracleError {
+    BadOracle,
+    PriceConfidenceTooWide,
+    NoOraclesFound,
+    MissingOracleAccounts,
+    NoValidPrice,
+    MathOverflow,
+    /// Raised by a provider SDK or while deserializing a feed account
+    Provider(anchor_lang::error::Error),
+}
+
+impl fmt::Display for OracleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OracleError::BadOracle => f.write_str("bad oracle"),
+            OracleError::PriceConfidenceTooWide => f.write_str("price confidence too wide"),
+            OracleError::NoOraclesFound => f.write_str("no oracles found"),
+            OracleError::MissingOracleAccounts => f.write_str("missing oracle accounts"),
+            OracleError::NoValidPrice => f.write_str("no valid price"),
+            OracleError::MathOverflow => f.write_str("math overflow"),
+            OracleError::Provider(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<anchor_lang::error::Error> for OracleError {
+    fn from(err: anchor_lang::error::Error) -> Self { OracleError::Provider(err) }
+}
+
+impl From<ProgramError> for OracleError {
+    fn from(err: ProgramError) -> Self { OracleError::Provider(err.into()) }
+}
+
+pub type OracleResult<T> = core::result::Result<T, OracleError>;
+
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
+pub struct OraclePrice(pub Decimal);
+
+/// The price `parse_oracles` settled on, with the confidence interval of the oracle it came from
+/// (zero for oracles without one) and the spread across all the given oracles.
+#[derive(Debug)]
+pub struct OracleQuote {
+    pub price: OraclePrice,
+    pub confidence: Decimal,
+    pub spread_bps: u16,
+}
+
+/// A configured feed and the account it is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleSource {
+    #[cfg(feature = "pyth")]
+    Pyth { feed_id: [u8; 32], account: Pubkey },
+    #[cfg(feature = "switchboard")]
+    SwitchboardOnDemand { account: Pubkey },
+    #[cfg(feature = "doves")]
+    Doves { account: Pubkey },
+}
+
+/// Staleness and restart rules every feed is read under.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    pub stalesness_threshold: u64,
+    pub check_restart_slot: bool,
+}
+
+impl OracleSource {
+    /// Reads the feed from `account_info`, returning its price and confidence interval.
+    pub fn parse(
+        &self,
+        account_info: &AccountInfo,
+        clock: &Clock,
+        options: ParseOptions,
+    ) -> OracleResult<(OraclePrice, Decimal)> {
+        match (self, account_info.owner) {
+            #[cfg(feature = "pyth")]
+            (OracleSource::Pyth { feed_id, account }, &PYTH_RECEIVER_PROGRAM_ID) => {
+                require_account(account, account_info)?;
+                pyth::parse(feed_id, account_info, clock, options)
+            },
+            #[cfg(feature = "switchboard")]
+            (OracleSource::SwitchboardOnDemand { account }, &SWITCHBOARD_ON_DEMAND_PROGRAM_ID) => {
+                require_account(account, account_info)?;
+                switchboard::parse(account_info, clock, options)
+            },
+            #[cfg(feature = "doves")]
+            (OracleSource::Doves { account }, &::doves::ID_CONST) => {
+                require_account(account, account_info)?;
+                doves::parse(account_info, clock, options)
+            },
+            _ => Err(OracleError::BadOracle),
+        }
+    }
+}
+
+/// Reads every source from the matching account, in order, and aggregates the prices.
+pub fn parse_oracles(
+    sources: &[OracleSource],
+    oracle_accounts: &[AccountInfo],
+    clock: &Clock,
+    options: ParseOptions,
+) -> OracleResult<OracleQuote> {
+    if sources.is_empty() {
+        return Err(OracleError::NoOraclesFound);
+    }
+    if oracle_accounts.len() < sources.len() {
+        return Err(OracleError::MissingOracleAccounts);
+    }
+
+    let prices = sources
+        .iter()
+        .zip(oracle_accounts.iter())
+        .map(|(source, account_info)| source.parse(account_info, clock, options))
+        .collect::<OracleResult<Vec<_>>>()?;
+
+    aggregate(prices)
+}
+
+/// Settles on the lowest price, the most conservative one for collateral, once the spread between
+/// the highest and lowest stays within [`MAX_CONFIDENCE_BPS`].
+pub fn aggregate(prices: Vec<(OraclePrice, Decimal)>) -> OracleResult<OracleQuote> {
+    let mut spread_bps = 0;
+    if prices.len() > 1 {
+        let min_price = prices
+            .iter()
+            .map(|(p, _)| p.0)
+            .min()
+            .ok_or(OracleError::NoValidPrice)?;
+        let max_price = prices
+            .iter()
+            .map(|(p, _)| p.0)
+            .max()
+            .ok_or(OracleError::NoValidPrice)?;
+
+        let spread = (max_price - min_price) * Decimal::from(10_000u64) / min_price;
+        if spread > Decimal::from(MAX_CONFIDENCE_BPS) {
+            return Err(OracleError::PriceConfidenceTooWide);
+        }
+        spread_bps = spread.to_u16().ok_or(OracleError::MathOverflow)?;
+    }
+
+    let (price, confidence) = prices
+        .into_iter()
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .ok_or(OracleError::NoValidPrice)?;
+    Ok(OracleQuote {
+        price,
+        confidence,
+        spread_bps,
+    })
+}
+
+/// Converts `base`, a collateral price in USD, into the currency `conversion` quotes USD in,
+/// e.g. EUR for a USD/EUR feed. Confidences add up to first order and the wider spread is kept.
+pub fn compose(base: OracleQuote, conversion: OracleQuote) -> OracleResult<OracleQuote> {
+    let price = base
+        .price
+        .0
+        .checked_mul(conversion.price.0)
+        .ok_or(OracleError::MathOverflow)?;
+    let confidence = base
+        .confidence
+        .checked_mul(conversion.price.0)
+        .zip(conversion.confidence.checked_mul(base.price.0))
+        .and_then(|(base_part, conversion_part)| base_part.checked_add(conversion_part))
+        .ok_or(OracleError::MathOverflow)?;
+
+    Ok(OracleQuote {
+        price: OraclePrice(price),
+        confidence,
+        spread_bps: base.spread_bps.max(conversion.spread_bps),
+    })
+}
+
+/// Fails when `confidence` is wider than [`MAX_CONFIDENCE_BPS`] of `price`.
+pub fn check_confidence(price: Decimal, confidence: Decimal) -> OracleResult<()> {
+    if confidence * Decimal::from(10_000) / Decimal::from(MAX_CONFIDENCE_BPS) >= price {
+        return Err(OracleError::PriceConfidenceTooWide);
+    }
+    Ok(())
+}
+
+fn require_account(expected: &Pubkey, account_info: &AccountInfo) -> OracleResult<()> {
+    if expected != account_info.key {
+        return Err(OracleError::BadOracle);
+    }
+    Ok(())
+}
+
+/// Rejects prices last updated at or before the cluster's last restart slot: after a restart the
+/// clock can jump while feeds still hold pre-restart values that look fresh.
+pub(crate) fn require_updated_since_restart(update_slot: u64) -> OracleResult<()> {
+    let last_restart_slot = LastRestartSlot::get()?;
+    if update_slot <= last_restart_slot.last_restart_slot {
+        return Err(OracleError::BadOracle);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(mantissa: i64) -> (OraclePrice, Decimal) {
+        (OraclePrice(Decimal::new(mantissa, 4)), Decimal::new(1, 4))
+    }
+
+    #[test]
+    fn aggregate_picks_lowest_price() {
+        let quote = aggregate(vec![price(10_010), price(10_000), price(10_005)]).unwrap();
+        assert_eq!(quote.price, OraclePrice(Decimal::new(10_000, 4)));
+        assert_eq!(quote.confidence, Decimal::new(1, 4));
+        assert_eq!(quote.spread_bps, 10);
+    }
+
+    #[test]
+    fn aggregate_single_price_has_no_spread() {
+        let quote = aggregate(vec![price(9_990)]).unwrap();
+        assert_eq!(quote.price, OraclePrice(Decimal::new(9_990, 4)));
+        assert_eq!(quote.spread_bps, 0);
+    }
+
+    #[test]
+    fn aggregate_rejects_wide_spread() {
+        assert!(aggregate(vec![price(10_000), price(10_200)]).is_ok());
+        assert!(matches!(
+            aggregate(vec![price(10_000), price(10_201)]),
+            Err(OracleError::PriceConfidenceTooWide)
+        ));
+    }
+
+    #[test]
+    fn aggregate_rejects_no_prices() {
+        assert!(matches!(aggregate(vec![]), Err(OracleError::NoValidPrice)));
+    }
+
+    #[test]
+    fn compose_converts_price_and_confidence() {
+        let base = aggregate(vec![price(10_000), price(10_010)]).unwrap();
+        let conversion = OracleQuote {
+            price: OraclePrice(Decimal::new(9_200, 4)),
+            confidence: Decimal::new(2, 4),
+            spread_bps: 3,
+        };
+
+        let quote = compose(base, conversion).unwrap();
+        assert_eq!(quote.price, OraclePrice(Decimal::new(9_200, 4)));
+        assert_eq!(quote.confidence, Decimal::new(292, 6));
+        assert_eq!(quote.spread_bps, 10);
+    }
+
+    #[test]
+    fn confidence_must_stay_below_bound() {
+        let price = Decimal::ONE;
+        assert!(check_confidence(price, Decimal::new(199, 4)).is_ok());
+        assert!(matches!(
+            check_confidence(price, Decimal::new(200, 4)),
+            Err(OracleError::PriceConfidenceTooWide)
+        ));
+    }
+
+    #[test]
+    fn parse_oracles_requires_sources_and_accounts() {
+        let clock = Clock::default();
+        let options = ParseOptions {
+            stalesness_threshold: 60,
+            check_restart_slot: false,
+        };
+        assert!(matches!(
+            parse_oracles(&[], &[], &clock, options),
+            Err(OracleError::NoOraclesFound)
+        ));
+
+        #[cfg(feature = "doves")]
+        assert!(matches!(
+            parse_oracles(
+                &[OracleSource::Doves {
+                    account: Pubkey::new_unique(),
+                }],
+                &[],
+                &clock,
+                options,
+            ),
+            Err(OracleError::MissingOracleAccounts)
+        ));
+    }
+
+    #[cfg(feature = "pyth")]
+    #[test]
+    fn parse_rejects_unexpected_owner_or_account() {
+        let clock = Clock::default();
+        let options = ParseOptions {
+            stalesness_threshold: 60,
+            check_restart_slot: false,
+        };
+        let key = Pubkey::new_unique();
+        let source = OracleSource::Pyth {
+            feed_id: [1; 32],
+            account: key,
+        };
+
+        let mut lamports = 0;
+        let mut data = vec![];
+        let wrong_owner = Pubkey::new_unique();
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &wrong_owner,
+            false,
+            0,
+        );
+        assert!(matches!(
+            source.parse(&account_info, &clock, options),
+            Err(OracleError::BadOracle)
+        ));
+
+        let other_key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![];
+        let account_info = AccountInfo::new(
+            &other_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &PYTH_RECEIVER_PROGRAM_ID,
+            false,
+            0,
+        );
+        assert!(matches!(
+            source.parse(&account_info, &clock, options),
+            Err(OracleError::BadOracle)
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{Price as PriceV2, PriceUpdateV2};
+use rust_decimal::Decimal;
+
+use crate::{
+    check_confidence, require_updated_since_restart, OracleError, OraclePrice, OracleResult,
+    ParseOptions,
+};
+
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+pub(crate) fn parse(
+    feed_id: &[u8; 32],
+    oracle: &AccountInfo,
+    clock: &Clock,
+    options: ParseOptions,
+) -> OracleResult<(OraclePrice, Decimal)> {
+    // No longer possible: https://github.com/coral-xyz/anchor/pull/2770
+    // let price_feed = Account::<'_, PriceUpdateV2>::try_from(&info).unwrap();
+    let price_feed = PriceUpdateV2::try_deserialize(&mut &oracle.data.borrow()[..])?;
+
+    if options.check_restart_slot {
+        require_updated_since_restart(price_feed.posted_slot)?;
+    }
+
+    let price: PriceV2 =
+        price_feed.get_price_no_older_than(clock, options.stalesness_threshold, feed_id)?;
+
+    if price.price <= 0 {
+        return Err(OracleError::BadOracle);
+    }
+    let price_u64: u64 = price
+        .price
+        .try_into()
+        .map_err(|_| OracleError::MathOverflow)?;
+    let scale = price
+        .exponent
+        .abs()
+        .try_into()
+        .map_err(|_| OracleError::MathOverflow)?;
+
+    let price_dec = Decimal::from_i128_with_scale(price_u64.into(), scale);
+    let confidence = Decimal::from_i128_with_scale(price.conf.into(), scale);
+    check_confidence(price_dec, confidence)?;
+
+    Ok((OraclePrice(price_dec), confidence))
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use rust_decimal::Decimal;
+use switchboard_on_demand::PullFeedAccountData;
+
+use crate::{
+    check_confidence, require_updated_since_restart, OracleError, OraclePrice, OracleResult,
+    ParseOptions,
+};
+
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey =
+    pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+pub(crate) fn parse(
+    oracle: &AccountInfo,
+    clock: &Clock,
+    options: ParseOptions,
+) -> OracleResult<(OraclePrice, Decimal)> {
+    let slot_treshold = options.stalesness_threshold * 1000 / clock::DEFAULT_MS_PER_SLOT;
+
+    let feed_account = oracle.try_borrow_data()?;
+    let price_feed =
+        PullFeedAccountData::parse(feed_account).map_err(|_| OracleError::BadOracle)?;
+
+    if options.check_restart_slot {
+        require_updated_since_restart(price_feed.last_update_slot())?;
+    }
+
+    let price = price_feed
+        .get_value(clock.slot, slot_treshold, 1, true)
+        .map_err(|_| OracleError::BadOracle)?;
+
+    let stalesness_threshold =
+        i64::try_from(options.stalesness_threshold).map_err(|_| OracleError::MathOverflow)?;
+    if price_feed.last_update_timestamp + stalesness_threshold < clock.unix_timestamp {
+        return Err(OracleError::BadOracle);
+    }
+
+    let stdev = price_feed.result.std_dev().ok_or(OracleError::BadOracle)?;
+
+    if price == Decimal::ZERO {
+        return Err(OracleError::BadOracle);
+    }
+    check_confidence(price, stdev)?;
+
+    Ok((OraclePrice(price), stdev))
+}
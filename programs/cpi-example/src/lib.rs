@@ -0,0 +1,487 @@
+#![allow(unexpected_cfgs)]
+
+//! Minimal downstream program that mints, flash mints and redeems jupUSD and swaps through a PSM
+//! pool by CPI.
+//! It exists to keep the `cpi` feature of `jup-stable` and `psm` exercised and as a template for
+//! integrators; every account is validated by the callee. The `holder_*` instructions act for the
+//! program's own `holder` PDA, which signs the CPI with its seeds.
+
+use anchor_lang::prelude::*;
+use jup_stable::program::JupStable;
+use psm::program::Psm;
+
+declare_id!("3YCx2N3BcXqeiJfLFNfUxyQRZgNkmYtEjntatEaf6Tnc");
+
+pub const HOLDER_PREFIX: &[u8] = b"holder";
+
+#[program]
+pub mod cpi_example {
+    use super::*;
+
+    /// Oracle accounts for the jup-stable vault are passed as remaining_accounts.
+    pub fn mint_jupusd<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintJupusd<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        jup_stable::cpi::mint(
+            ctx.accounts
+                .mint_ctx()
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            min_amount_out,
+            false,
+        )
+    }
+
+    /// Oracle accounts for the jup-stable vault are passed as remaining_accounts.
+    pub fn redeem_jupusd<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemJupusd<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        jup_stable::cpi::redeem(
+            ctx.accounts
+                .redeem_ctx()
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            min_amount_out,
+            false,
+        )
+    }
+
+    /// Oracle accounts for the jup-stable vault are passed as remaining_accounts. The transaction
+    /// has to settle the flash mint with a top-level `settle_flash_mint` afterwards.
+    pub fn flash_mint_jupusd<'info>(
+        ctx: Context<'_, '_, '_, 'info, FlashMintJupusd<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        jup_stable::cpi::flash_mint(
+            ctx.accounts
+                .flash_mint_ctx()
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            min_amount_out,
+        )
+    }
+
+    /// Mints for the `holder` PDA, which has to be an active benefactor. Oracle accounts for the
+    /// jup-stable vault are passed as remaining_accounts.
+    pub fn holder_mint_jupusd<'info>(
+        ctx: Context<'_, '_, '_, 'info, HolderMintJupusd<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        let bump = [ctx.bumps.holder];
+        let signer_seeds: &[&[&[u8]]] = &[&[HOLDER_PREFIX, &bump]];
+        jup_stable::cpi::mint(
+            ctx.accounts
+                .mint_ctx()
+                .with_signer(signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            min_amount_out,
+            false,
+        )
+    }
+
+    /// Redeems the jupUSD of the `holder` PDA. Oracle accounts for the jup-stable vault are passed
+    /// as remaining_accounts.
+    pub fn holder_redeem_jupusd<'info>(
+        ctx: Context<'_, '_, '_, 'info, HolderRedeemJupusd<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        let bump = [ctx.bumps.holder];
+        let signer_seeds: &[&[&[u8]]] = &[&[HOLDER_PREFIX, &bump]];
+        jup_stable::cpi::redeem(
+            ctx.accounts
+                .redeem_ctx()
+                .with_signer(signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            min_amount_out,
+            false,
+        )
+    }
+
+    pub fn psm_redeem(ctx: Context<PsmRedeem>, amount: u64, min_amount_out: u64) -> Result<()> {
+        psm::cpi::redeem(ctx.accounts.redeem_ctx(), amount, min_amount_out)
+    }
+}
+
+#[derive(Accounts)]
+pub struct MintJupusd<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub user_collateral_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub user_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub custodian: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub custodian_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub lp_token_program: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+}
+
+impl<'info> MintJupusd<'info> {
+    fn mint_ctx(&self) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Mint<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Mint {
+            user: self.user.to_account_info(),
+            user_collateral_token_account: self.user_collateral_token_account.to_account_info(),
+            user_lp_token_account: self.user_lp_token_account.to_account_info(),
+            config: self.config.to_account_info(),
+            authority: self.authority.to_account_info(),
+            lp_mint: self.lp_mint.to_account_info(),
+            vault: self.vault.to_account_info(),
+            vault_mint: self.vault_mint.to_account_info(),
+            custodian: self.custodian.to_account_info(),
+            custodian_token_account: self.custodian_token_account.to_account_info(),
+            benefactor: self.benefactor.to_account_info(),
+            lp_token_program: self.lp_token_program.to_account_info(),
+            vault_token_program: self.vault_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        CpiContext::new(self.jup_stable_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct FlashMintJupusd<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub user_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub lp_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+}
+
+impl<'info> FlashMintJupusd<'info> {
+    fn flash_mint_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::FlashMint<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::FlashMint {
+            user: self.user.to_account_info(),
+            user_lp_token_account: self.user_lp_token_account.to_account_info(),
+            config: self.config.to_account_info(),
+            authority: self.authority.to_account_info(),
+            lp_mint: self.lp_mint.to_account_info(),
+            vault: self.vault.to_account_info(),
+            vault_mint: self.vault_mint.to_account_info(),
+            benefactor: self.benefactor.to_account_info(),
+            instructions_sysvar: self.instructions_sysvar.to_account_info(),
+            lp_token_program: self.lp_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        CpiContext::new(self.jup_stable_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct RedeemJupusd<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub user_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub user_collateral_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub lp_token_program: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+}
+
+impl<'info> RedeemJupusd<'info> {
+    fn redeem_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Redeem<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Redeem {
+            user: self.user.to_account_info(),
+            user_lp_token_account: self.user_lp_token_account.to_account_info(),
+            user_collateral_token_account: self.user_collateral_token_account.to_account_info(),
+            config: self.config.to_account_info(),
+            authority: self.authority.to_account_info(),
+            lp_mint: self.lp_mint.to_account_info(),
+            vault: self.vault.to_account_info(),
+            vault_token_account: self.vault_token_account.to_account_info(),
+            vault_mint: self.vault_mint.to_account_info(),
+            benefactor: self.benefactor.to_account_info(),
+            lp_token_program: self.lp_token_program.to_account_info(),
+            vault_token_program: self.vault_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        CpiContext::new(self.jup_stable_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct HolderMintJupusd<'info> {
+    /// CHECK: PDA signing for the mint
+    #[account(mut, seeds = [HOLDER_PREFIX], bump)]
+    pub holder: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub holder_collateral_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub holder_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub custodian: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub custodian_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub lp_token_program: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+}
+
+impl<'info> HolderMintJupusd<'info> {
+    fn mint_ctx(&self) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Mint<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Mint {
+            user: self.holder.to_account_info(),
+            user_collateral_token_account: self.holder_collateral_token_account.to_account_info(),
+            user_lp_token_account: self.holder_lp_token_account.to_account_info(),
+            config: self.config.to_account_info(),
+            authority: self.authority.to_account_info(),
+            lp_mint: self.lp_mint.to_account_info(),
+            vault: self.vault.to_account_info(),
+            vault_mint: self.vault_mint.to_account_info(),
+            custodian: self.custodian.to_account_info(),
+            custodian_token_account: self.custodian_token_account.to_account_info(),
+            benefactor: self.benefactor.to_account_info(),
+            lp_token_program: self.lp_token_program.to_account_info(),
+            vault_token_program: self.vault_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        CpiContext::new(self.jup_stable_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct HolderRedeemJupusd<'info> {
+    /// CHECK: PDA signing for the redeem
+    #[account(mut, seeds = [HOLDER_PREFIX], bump)]
+    pub holder: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub holder_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub holder_collateral_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub lp_token_program: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+}
+
+impl<'info> HolderRedeemJupusd<'info> {
+    fn redeem_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Redeem<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Redeem {
+            user: self.holder.to_account_info(),
+            user_lp_token_account: self.holder_lp_token_account.to_account_info(),
+            user_collateral_token_account: self.holder_collateral_token_account.to_account_info(),
+            config: self.config.to_account_info(),
+            authority: self.authority.to_account_info(),
+            lp_mint: self.lp_mint.to_account_info(),
+            vault: self.vault.to_account_info(),
+            vault_token_account: self.vault_token_account.to_account_info(),
+            vault_mint: self.vault_mint.to_account_info(),
+            benefactor: self.benefactor.to_account_info(),
+            lp_token_program: self.lp_token_program.to_account_info(),
+            vault_token_program: self.vault_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        CpiContext::new(self.jup_stable_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct PsmRedeem<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: checked by psm
+    #[account(mut)]
+    pub user_redemption_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    #[account(mut)]
+    pub user_settlement_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    #[account(mut)]
+    pub settlement_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    #[account(mut)]
+    pub redemption_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    #[account(mut)]
+    pub redemption_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    #[account(mut)]
+    pub settlement_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    pub redemption_token_program: UncheckedAccount<'info>,
+    /// CHECK: checked by psm
+    pub settlement_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub psm_program: Program<'info, Psm>,
+}
+
+impl<'info> PsmRedeem<'info> {
+    fn redeem_ctx(&self) -> CpiContext<'_, '_, '_, 'info, psm::cpi::accounts::Redeem<'info>> {
+        let cpi_accounts = psm::cpi::accounts::Redeem {
+            user: self.user.to_account_info(),
+            user_redemption_token_account: self.user_redemption_token_account.to_account_info(),
+            user_settlement_token_account: self.user_settlement_token_account.to_account_info(),
+            config: self.config.to_account_info(),
+            authority: self.authority.to_account_info(),
+            settlement_mint: self.settlement_mint.to_account_info(),
+            redemption_mint: self.redemption_mint.to_account_info(),
+            pool: self.pool.to_account_info(),
+            redemption_token_account: self.redemption_token_account.to_account_info(),
+            settlement_token_account: self.settlement_token_account.to_account_info(),
+            redemption_token_program: self.redemption_token_program.to_account_info(),
+            settlement_token_program: self.settlement_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            fee_exempt_list: None,
+        };
+        CpiContext::new(self.psm_program.to_account_info(), cpi_accounts)
+    }
+}
@@ -0,0 +1,293 @@
+use anchor_spl::token_interface::TokenAccount;
+use jup_stable::state::benefactor::Benefactor;
+use jupusd_client::jup_stable::{
+    derivation::find_vault_token_account,
+    instructions::{
+        create_set_benefactor_integrator_program_instruction, create_settle_flash_mint_instruction,
+        SettleFlashMintInstructionAccounts,
+    },
+};
+use solana_program_test::*;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::{
+    faciliter::{new_fixture, setup_holder, setup_jup_stable, setup_psm_pool, COLLATERAL_DECIMALS},
+    instructions::{
+        create_flash_mint_jupusd_instruction, create_holder_mint_jupusd_instruction,
+        create_holder_redeem_jupusd_instruction, create_mint_jupusd_instruction,
+        create_psm_redeem_instruction, create_redeem_jupusd_instruction,
+    },
+};
+
+#[tokio::test]
+async fn mint_and_redeem_through_cpi() -> anyhow::Result<()> {
+    let test_f = new_fixture().await;
+    let user_balance = 1_000 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    let ctx = setup_jup_stable(&test_f, user_balance).await?;
+    let accounts = &ctx.accounts;
+
+    let amount = 100 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    test_f
+        .send_ixs(
+            &[create_mint_jupusd_instruction(accounts, amount, amount)],
+            &[&ctx.user],
+        )
+        .await?;
+
+    let user_lp_ata = get_associated_token_address(&ctx.user.pubkey(), &accounts.lp_mint);
+    let user_lp: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(
+        user_lp.amount, amount,
+        "Minted jupUSD should reach the user"
+    );
+
+    test_f
+        .mint_tokens(&find_vault_token_account(&accounts.vault_mint), amount)
+        .await;
+    test_f
+        .send_ixs(
+            &[create_redeem_jupusd_instruction(accounts, amount, amount)],
+            &[&ctx.user],
+        )
+        .await?;
+
+    let user_lp: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp.amount, 0, "Redeem should burn the user's jupUSD");
+    let user_collateral: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &ctx.user.pubkey(),
+            &accounts.vault_mint,
+        ))
+        .await;
+    assert_eq!(user_collateral.amount, user_balance);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn holder_pda_mints_and_redeems_with_signer_seeds() -> anyhow::Result<()> {
+    let test_f = new_fixture().await;
+    let ctx = setup_jup_stable(&test_f, 0).await?;
+    let holder_balance = 1_000 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    let holder = setup_holder(&test_f, &ctx.accounts, holder_balance).await?;
+
+    let amount = 100 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    test_f
+        .send_ixs(
+            &[create_holder_mint_jupusd_instruction(
+                &holder,
+                amount,
+                amount,
+                Some(holder.oracle),
+            )],
+            &[&ctx.user],
+        )
+        .await?;
+
+    let holder_lp_ata = get_associated_token_address(&holder.user, &holder.lp_mint);
+    let holder_lp: TokenAccount = test_f.load_and_deserialize(&holder_lp_ata).await;
+    assert_eq!(
+        holder_lp.amount, amount,
+        "Minted jupUSD should reach the holder PDA"
+    );
+
+    test_f
+        .mint_tokens(&find_vault_token_account(&holder.vault_mint), amount)
+        .await;
+    test_f
+        .send_ixs(
+            &[create_holder_redeem_jupusd_instruction(
+                &holder, amount, amount,
+            )],
+            &[&ctx.user],
+        )
+        .await?;
+
+    let holder_lp: TokenAccount = test_f.load_and_deserialize(&holder_lp_ata).await;
+    assert_eq!(
+        holder_lp.amount, 0,
+        "Redeem should burn the holder's jupUSD"
+    );
+    let holder_collateral: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &holder.user,
+            &holder.vault_mint,
+        ))
+        .await;
+    assert_eq!(holder_collateral.amount, holder_balance);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn holder_mint_requires_oracle_remaining_account() -> anyhow::Result<()> {
+    let test_f = new_fixture().await;
+    let ctx = setup_jup_stable(&test_f, 0).await?;
+    let holder_balance = 1_000 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    let holder = setup_holder(&test_f, &ctx.accounts, holder_balance).await?;
+
+    let amount = 100 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    let result = test_f
+        .send_ixs(
+            &[create_holder_mint_jupusd_instruction(
+                &holder, amount, amount, None,
+            )],
+            &[&ctx.user],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Mint should fail without the vault oracle in remaining_accounts"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_holder_mint_jupusd_instruction(
+                &holder,
+                amount,
+                amount,
+                Some(holder.oracle),
+            )],
+            &[&ctx.user],
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn psm_redeem_through_cpi() -> anyhow::Result<()> {
+    let test_f = new_fixture().await;
+    let user_balance = 1_000 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    let ctx = setup_jup_stable(&test_f, user_balance).await?;
+    let accounts = &ctx.accounts;
+
+    let supply = 500 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    setup_psm_pool(&test_f, accounts.vault_mint, accounts.lp_mint, supply).await?;
+
+    let amount = 100 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    test_f
+        .send_ixs(
+            &[
+                create_mint_jupusd_instruction(accounts, amount, amount),
+                create_psm_redeem_instruction(
+                    ctx.user.pubkey(),
+                    accounts.vault_mint,
+                    accounts.lp_mint,
+                    amount,
+                    0,
+                ),
+            ],
+            &[&ctx.user],
+        )
+        .await?;
+
+    let user_lp: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &ctx.user.pubkey(),
+            &accounts.lp_mint,
+        ))
+        .await;
+    assert_eq!(user_lp.amount, 0, "PSM should take the minted jupUSD");
+    let user_collateral: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &ctx.user.pubkey(),
+            &accounts.vault_mint,
+        ))
+        .await;
+    assert!(
+        user_collateral.amount > user_balance - amount,
+        "PSM should pay collateral back out"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flash_mint_is_settled_in_the_same_transaction() -> anyhow::Result<()> {
+    let test_f = new_fixture().await;
+    let user_balance = 1_000 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    let ctx = setup_jup_stable(&test_f, user_balance).await?;
+    let accounts = &ctx.accounts;
+
+    let amount = 100 * 10_u64.pow(COLLATERAL_DECIMALS.into());
+    let settle_ix = create_settle_flash_mint_instruction(SettleFlashMintInstructionAccounts {
+        payer: ctx.user.pubkey(),
+        benefactor: accounts.benefactor,
+        custodian: accounts.custodian,
+        custodian_token_account: None,
+        vault_mint: accounts.vault_mint,
+        vault_token_program: spl_token::ID,
+    });
+
+    let result = test_f
+        .send_ixs(
+            &[
+                create_flash_mint_jupusd_instruction(accounts, amount, amount),
+                settle_ix.clone(),
+            ],
+            &[&ctx.user],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Flash mint should require the integrator to be whitelisted"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_set_benefactor_integrator_program_instruction(
+                test_f.deployer.pubkey(),
+                accounts.benefactor,
+                cpi_example::ID,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let result = test_f
+        .send_ixs(
+            &[create_flash_mint_jupusd_instruction(
+                accounts, amount, amount,
+            )],
+            &[&ctx.user],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Flash mint should require a settlement later in the transaction"
+    );
+
+    test_f
+        .send_ixs(
+            &[
+                create_flash_mint_jupusd_instruction(accounts, amount, amount),
+                settle_ix,
+            ],
+            &[&ctx.user],
+        )
+        .await?;
+
+    let user_lp: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &ctx.user.pubkey(),
+            &accounts.lp_mint,
+        ))
+        .await;
+    assert_eq!(user_lp.amount, amount, "Flash mint should credit jupUSD");
+    let custodian_collateral: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &accounts.custodian,
+            &accounts.vault_mint,
+        ))
+        .await;
+    assert_eq!(
+        custodian_collateral.amount, amount,
+        "Settlement should deliver the collateral to custody"
+    );
+    let benefactor: Benefactor = test_f.load_and_deserialize(&accounts.benefactor).await;
+    assert_eq!(benefactor.flash_mint_debt, 0);
+
+    Ok(())
+}
@@ -0,0 +1,297 @@
+use anyhow::Result;
+use cpi_example::HOLDER_PREFIX;
+use fixtures::{oracle::PythPriceUpdateBuilder, test::TestFixture};
+use jup_stable::{
+    instructions::OracleConfig,
+    state::{
+        benefactor::BenefactorStatus,
+        common::{PeriodLimitTemplate, MAX_DURATION_SECONDS},
+        vault::VaultStatus,
+    },
+};
+use jupusd_client::{
+    jup_stable::{derivation as stable_pda, instructions as stable_ix},
+    psm::instructions as psm_ix,
+};
+use solana_sdk::{
+    bpf_loader_upgradeable::get_program_data_address, pubkey::Pubkey, signature::Keypair,
+    signer::Signer,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+use crate::common::instructions::JupStableAccounts;
+
+pub const COLLATERAL_DECIMALS: u8 = 6;
+const FEED_ID: [u8; 32] = [1; 32];
+const PERIOD_LIMIT_AMOUNT: u64 = 1_000_000_000_000;
+
+pub struct TestContext {
+    pub user: Keypair,
+    pub accounts: JupStableAccounts,
+}
+
+pub async fn new_fixture() -> TestFixture {
+    TestFixture::new_with_programs(&[("cpi_example", cpi_example::ID)]).await
+}
+
+/// Initializes jup-stable with a single pyth-priced vault and a funded user who is also an
+/// active benefactor, so mints and redeems can be routed through the example program.
+pub async fn setup_jup_stable(test_f: &TestFixture, user_balance: u64) -> Result<TestContext> {
+    let admin = test_f.deployer.pubkey();
+    let lp_mint = Keypair::new();
+    test_f
+        .send_ixs(
+            &[
+                stable_ix::create_init_instruction(
+                    stable_ix::InitInstructionAccounts {
+                        payer: admin,
+                        upgrade_authority: admin,
+                        program_data: get_program_data_address(&jup_stable::ID),
+                        mint: lp_mint.pubkey(),
+                        token_program: spl_token::ID,
+                    },
+                    stable_ix::InitInstructionArgs {
+                        decimals: 6,
+                        name: "Jupiter USD".to_string(),
+                        symbol: "JUPUSD".to_string(),
+                        uri: "https://jup.ag/jupusd".to_string(),
+                        peg_price_usd: 10_000,
+                        is_mint_redeem_enabled: true,
+                        default_vault_period_limit: PeriodLimitTemplate::default(),
+                        default_benefactor_period_limit: PeriodLimitTemplate::default(),
+                    },
+                ),
+                stable_ix::create_update_config_period_limit_instruction(
+                    admin,
+                    0,
+                    3600,
+                    PERIOD_LIMIT_AMOUNT,
+                    PERIOD_LIMIT_AMOUNT,
+                ),
+            ],
+            &[&test_f.deployer, &lp_mint],
+        )
+        .await?;
+
+    let vault_mint = test_f.create_spl_mint(COLLATERAL_DECIMALS).await;
+    let oracle = Keypair::new().pubkey();
+    let publish_time = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(
+            &oracle,
+            PythPriceUpdateBuilder::new(FEED_ID)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+
+    let custodian = Keypair::new().pubkey();
+    test_f
+        .send_ixs(
+            &[
+                stable_ix::create_approve_collateral_instruction(
+                    stable_ix::ApproveCollateralInstructionAccounts {
+                        authority: admin,
+                        payer: admin,
+                        mint: vault_mint,
+                    },
+                ),
+                stable_ix::create_create_vault_instruction(
+                    stable_ix::CreateVaultInstructionAccounts {
+                        authority: admin,
+                        payer: admin,
+                        mint: vault_mint,
+                        token_program: spl_token::ID,
+                    },
+                ),
+                stable_ix::create_set_custodian_instruction(admin, vault_mint, custodian),
+                stable_ix::create_update_vault_oracle_instruction(
+                    admin,
+                    vault_mint,
+                    0,
+                    OracleConfig::Pyth(FEED_ID, oracle),
+                ),
+                stable_ix::create_update_vault_period_limit_instruction(
+                    admin,
+                    vault_mint,
+                    0,
+                    3600,
+                    PERIOD_LIMIT_AMOUNT,
+                    PERIOD_LIMIT_AMOUNT,
+                ),
+                stable_ix::create_set_vault_status_instruction(
+                    admin,
+                    vault_mint,
+                    VaultStatus::Enabled,
+                ),
+                create_associated_token_account(&admin, &custodian, &vault_mint, &spl_token::ID),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+    let benefactor = create_funded_benefactor(
+        test_f,
+        user.pubkey(),
+        vault_mint,
+        lp_mint.pubkey(),
+        user_balance,
+    )
+    .await?;
+
+    let accounts = JupStableAccounts {
+        user: user.pubkey(),
+        benefactor,
+        custodian,
+        vault_mint,
+        lp_mint: lp_mint.pubkey(),
+        oracle,
+    };
+
+    Ok(TestContext { user, accounts })
+}
+
+/// Makes the example program's `holder` PDA an active benefactor holding `balance` collateral,
+/// and returns `accounts` rewired to act for it.
+pub async fn setup_holder(
+    test_f: &TestFixture,
+    accounts: &JupStableAccounts,
+    balance: u64,
+) -> Result<JupStableAccounts> {
+    let (holder, _) = Pubkey::find_program_address(&[HOLDER_PREFIX], &cpi_example::ID);
+    let benefactor = create_funded_benefactor(
+        test_f,
+        holder,
+        accounts.vault_mint,
+        accounts.lp_mint,
+        balance,
+    )
+    .await?;
+
+    Ok(JupStableAccounts {
+        user: holder,
+        benefactor,
+        custodian: accounts.custodian,
+        vault_mint: accounts.vault_mint,
+        lp_mint: accounts.lp_mint,
+        oracle: accounts.oracle,
+    })
+}
+
+async fn create_funded_benefactor(
+    test_f: &TestFixture,
+    owner: Pubkey,
+    vault_mint: Pubkey,
+    lp_mint: Pubkey,
+    balance: u64,
+) -> Result<Pubkey> {
+    let admin = test_f.deployer.pubkey();
+    let benefactor = stable_pda::find_benefactor(&owner);
+    test_f
+        .send_ixs(
+            &[
+                stable_ix::create_create_benefactor_instruction(
+                    stable_ix::CreateBenefactorInstructionAccounts {
+                        authority: admin,
+                        payer: admin,
+                        benefactor_authority: owner,
+                        registry_page: 0,
+                    },
+                    stable_ix::CreateBenefactorInstructionArgs {
+                        mint_fee_rate: 0,
+                        redeem_fee_rate: 0,
+                    },
+                ),
+                stable_ix::create_set_benefactor_status_instruction(
+                    admin,
+                    benefactor,
+                    BenefactorStatus::Active,
+                ),
+                stable_ix::create_update_benefactor_period_limit_instruction(
+                    admin,
+                    benefactor,
+                    0,
+                    3600,
+                    PERIOD_LIMIT_AMOUNT,
+                    PERIOD_LIMIT_AMOUNT,
+                ),
+                create_associated_token_account(&admin, &owner, &vault_mint, &spl_token::ID),
+                create_associated_token_account(&admin, &owner, &lp_mint, &spl_token::ID),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    test_f
+        .mint_tokens(&get_associated_token_address(&owner, &vault_mint), balance)
+        .await;
+
+    Ok(benefactor)
+}
+
+/// Creates an active PSM pool paying out `redemption_mint` against `settlement_mint`, supplied
+/// with `supply` redemption tokens.
+pub async fn setup_psm_pool(
+    test_f: &TestFixture,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    supply: u64,
+) -> Result<()> {
+    let admin = test_f.deployer.pubkey();
+    test_f
+        .send_ixs(
+            &[
+                psm_ix::create_init_instruction(psm_ix::InitInstructionAccounts {
+                    payer: admin,
+                    upgrade_authority: admin,
+                    program_data: get_program_data_address(&psm::ID),
+                }),
+                psm_ix::create_create_pool_instruction(psm_ix::CreatePoolInstructionAccounts {
+                    admin,
+                    payer: admin,
+                    redemption_mint,
+                    settlement_mint,
+                    redemption_token_program: spl_token::ID,
+                    settlement_token_program: spl_token::ID,
+                }),
+                psm_ix::create_set_supply_period_limit_instruction(
+                    admin,
+                    redemption_mint,
+                    settlement_mint,
+                    MAX_DURATION_SECONDS,
+                    u64::MAX,
+                ),
+                psm_ix::create_activate_pool_instruction(admin, redemption_mint, settlement_mint),
+                create_associated_token_account(&admin, &admin, &redemption_mint, &spl_token::ID),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    test_f
+        .mint_tokens(
+            &get_associated_token_address(&admin, &redemption_mint),
+            supply,
+        )
+        .await;
+    test_f
+        .send_ixs(
+            &[psm_ix::create_supply_instruction(
+                psm_ix::SupplyInstructionAccounts {
+                    admin,
+                    redemption_mint,
+                    settlement_mint,
+                    redemption_token_program: spl_token::ID,
+                },
+                supply,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    Ok(())
+}
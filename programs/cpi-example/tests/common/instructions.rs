@@ -0,0 +1,254 @@
+use anchor_lang::{solana_program::sysvar, system_program, InstructionData, ToAccountMetas};
+use jupusd_client::{jup_stable::derivation as stable_pda, psm::derivation as psm_pda};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+pub struct JupStableAccounts {
+    pub user: Pubkey,
+    pub benefactor: Pubkey,
+    pub custodian: Pubkey,
+    pub vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub oracle: Pubkey,
+}
+
+pub fn create_mint_jupusd_instruction(
+    accounts: &JupStableAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut metas = cpi_example::accounts::MintJupusd {
+        user: accounts.user,
+        user_collateral_token_account: get_associated_token_address(
+            &accounts.user,
+            &accounts.vault_mint,
+        ),
+        user_lp_token_account: get_associated_token_address(&accounts.user, &accounts.lp_mint),
+        config: stable_pda::find_config(),
+        authority: stable_pda::find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: stable_pda::find_vault(&accounts.vault_mint),
+        vault_mint: accounts.vault_mint,
+        custodian: accounts.custodian,
+        custodian_token_account: get_associated_token_address(
+            &accounts.custodian,
+            &accounts.vault_mint,
+        ),
+        benefactor: accounts.benefactor,
+        lp_token_program: spl_token::ID,
+        vault_token_program: spl_token::ID,
+        system_program: system_program::ID,
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::ID,
+    }
+    .to_account_metas(None);
+    metas.push(AccountMeta::new_readonly(accounts.oracle, false));
+
+    Instruction {
+        program_id: cpi_example::ID,
+        accounts: metas,
+        data: cpi_example::instruction::MintJupusd {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub fn create_flash_mint_jupusd_instruction(
+    accounts: &JupStableAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut metas = cpi_example::accounts::FlashMintJupusd {
+        user: accounts.user,
+        user_lp_token_account: get_associated_token_address(&accounts.user, &accounts.lp_mint),
+        config: stable_pda::find_config(),
+        authority: stable_pda::find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: stable_pda::find_vault(&accounts.vault_mint),
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        instructions_sysvar: sysvar::instructions::ID,
+        lp_token_program: spl_token::ID,
+        system_program: system_program::ID,
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::ID,
+    }
+    .to_account_metas(None);
+    metas.push(AccountMeta::new_readonly(accounts.oracle, false));
+
+    Instruction {
+        program_id: cpi_example::ID,
+        accounts: metas,
+        data: cpi_example::instruction::FlashMintJupusd {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub fn create_redeem_jupusd_instruction(
+    accounts: &JupStableAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut metas = cpi_example::accounts::RedeemJupusd {
+        user: accounts.user,
+        user_lp_token_account: get_associated_token_address(&accounts.user, &accounts.lp_mint),
+        user_collateral_token_account: get_associated_token_address(
+            &accounts.user,
+            &accounts.vault_mint,
+        ),
+        config: stable_pda::find_config(),
+        authority: stable_pda::find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: stable_pda::find_vault(&accounts.vault_mint),
+        vault_token_account: stable_pda::find_vault_token_account(&accounts.vault_mint),
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        lp_token_program: spl_token::ID,
+        vault_token_program: spl_token::ID,
+        system_program: system_program::ID,
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::ID,
+    }
+    .to_account_metas(None);
+    metas.push(AccountMeta::new_readonly(accounts.oracle, false));
+
+    Instruction {
+        program_id: cpi_example::ID,
+        accounts: metas,
+        data: cpi_example::instruction::RedeemJupusd {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+/// Like [`create_mint_jupusd_instruction`], for an `accounts.user` that is the `holder` PDA.
+pub fn create_holder_mint_jupusd_instruction(
+    accounts: &JupStableAccounts,
+    amount: u64,
+    min_amount_out: u64,
+    oracle: Option<Pubkey>,
+) -> Instruction {
+    let mut metas = cpi_example::accounts::HolderMintJupusd {
+        holder: accounts.user,
+        holder_collateral_token_account: get_associated_token_address(
+            &accounts.user,
+            &accounts.vault_mint,
+        ),
+        holder_lp_token_account: get_associated_token_address(&accounts.user, &accounts.lp_mint),
+        config: stable_pda::find_config(),
+        authority: stable_pda::find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: stable_pda::find_vault(&accounts.vault_mint),
+        vault_mint: accounts.vault_mint,
+        custodian: accounts.custodian,
+        custodian_token_account: get_associated_token_address(
+            &accounts.custodian,
+            &accounts.vault_mint,
+        ),
+        benefactor: accounts.benefactor,
+        lp_token_program: spl_token::ID,
+        vault_token_program: spl_token::ID,
+        system_program: system_program::ID,
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::ID,
+    }
+    .to_account_metas(None);
+    metas.extend(oracle.map(|oracle| AccountMeta::new_readonly(oracle, false)));
+
+    Instruction {
+        program_id: cpi_example::ID,
+        accounts: metas,
+        data: cpi_example::instruction::HolderMintJupusd {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+/// Like [`create_redeem_jupusd_instruction`], for an `accounts.user` that is the `holder` PDA.
+pub fn create_holder_redeem_jupusd_instruction(
+    accounts: &JupStableAccounts,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut metas = cpi_example::accounts::HolderRedeemJupusd {
+        holder: accounts.user,
+        holder_lp_token_account: get_associated_token_address(&accounts.user, &accounts.lp_mint),
+        holder_collateral_token_account: get_associated_token_address(
+            &accounts.user,
+            &accounts.vault_mint,
+        ),
+        config: stable_pda::find_config(),
+        authority: stable_pda::find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: stable_pda::find_vault(&accounts.vault_mint),
+        vault_token_account: stable_pda::find_vault_token_account(&accounts.vault_mint),
+        vault_mint: accounts.vault_mint,
+        benefactor: accounts.benefactor,
+        lp_token_program: spl_token::ID,
+        vault_token_program: spl_token::ID,
+        system_program: system_program::ID,
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::ID,
+    }
+    .to_account_metas(None);
+    metas.push(AccountMeta::new_readonly(accounts.oracle, false));
+
+    Instruction {
+        program_id: cpi_example::ID,
+        accounts: metas,
+        data: cpi_example::instruction::HolderRedeemJupusd {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
+
+pub fn create_psm_redeem_instruction(
+    user: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let pool = psm_pda::find_pool(&redemption_mint, &settlement_mint);
+    let accounts = cpi_example::accounts::PsmRedeem {
+        user,
+        user_redemption_token_account: get_associated_token_address(&user, &redemption_mint),
+        user_settlement_token_account: get_associated_token_address(&user, &settlement_mint),
+        config: psm_pda::find_config(),
+        authority: psm_pda::find_authority(),
+        settlement_mint,
+        redemption_mint,
+        pool,
+        redemption_token_account: psm_pda::find_pool_redemption_token_account(&pool),
+        settlement_token_account: psm_pda::find_pool_settlement_token_account(&pool),
+        redemption_token_program: spl_token::ID,
+        settlement_token_program: spl_token::ID,
+        system_program: system_program::ID,
+        psm_program: psm::ID,
+    }
+    .to_account_metas(None);
+
+    Instruction {
+        program_id: cpi_example::ID,
+        accounts,
+        data: cpi_example::instruction::PsmRedeem {
+            amount,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
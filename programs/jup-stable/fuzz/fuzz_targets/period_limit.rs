@@ -0,0 +1,99 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use jup_stable::state::common::PeriodLimit;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Update {
+        duration_seconds: u64,
+        max_mint_amount: u64,
+        max_redeem_amount: u64,
+        current_time: i64,
+    },
+    RollWindow {
+        current_time: i64,
+    },
+    Mint {
+        amount: u64,
+        current_time: i64,
+    },
+    Redeem {
+        amount: u64,
+        current_time: i64,
+    },
+    Reset,
+}
+
+/// Mirrors `Vault::can_mint` followed by `Vault::record_mint`, reverting on failure like a
+/// failed transaction would.
+fn mint(limit: &mut PeriodLimit, amount: u64, current_time: i64) {
+    let before = *limit;
+    limit.roll_window(current_time);
+    match limit.check_mint_limit(amount) {
+        Ok(()) => limit.record_mint(amount),
+        Err(_) => *limit = before,
+    }
+}
+
+fn redeem(limit: &mut PeriodLimit, amount: u64, current_time: i64) {
+    let before = *limit;
+    limit.roll_window(current_time);
+    match limit.check_redeem_limit(amount) {
+        Ok(()) => limit.record_redeem(amount),
+        Err(_) => *limit = before,
+    }
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut limit = PeriodLimit::default();
+
+    for op in ops {
+        let before = limit;
+        match op {
+            Op::Update {
+                duration_seconds,
+                max_mint_amount,
+                max_redeem_amount,
+                current_time,
+            } => {
+                if limit
+                    .update(
+                        duration_seconds,
+                        max_mint_amount,
+                        max_redeem_amount,
+                        current_time,
+                    )
+                    .is_err()
+                {
+                    limit = before;
+                }
+            },
+            Op::RollWindow { current_time } => {
+                limit.roll_window(current_time);
+                // Moving the clock backwards must never open a fresh window
+                if current_time < before.window_start {
+                    assert_eq!(limit.minted_amount, before.minted_amount);
+                    assert_eq!(limit.redeemed_amount, before.redeemed_amount);
+                    assert_eq!(limit.window_start, before.window_start);
+                }
+            },
+            Op::Mint {
+                amount,
+                current_time,
+            } => mint(&mut limit, amount, current_time),
+            Op::Redeem {
+                amount,
+                current_time,
+            } => redeem(&mut limit, amount, current_time),
+            Op::Reset => limit.reset(),
+        }
+
+        if limit.duration_seconds != 0 {
+            assert!(limit.is_valid());
+            assert!(limit.minted_amount <= limit.max_mint_amount);
+            assert!(limit.redeemed_amount <= limit.max_redeem_amount);
+        }
+    }
+});
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 #[error_code]
 pub enum JupStableError {
-    #[msg("")]
+    #[msg("Unspecified Error")]
     SomeError,
     #[msg("Admin Array Full")]
     AdminArrayFull,
@@ -74,4 +74,64 @@ pub enum JupStableError {
     PriceConfidenceTooWide,
     #[msg("Operator Cannot Delete Itself")]
     OperatorCannotDeleteItself,
+    #[msg("Price Jump Too Large")]
+    PriceJumpTooLarge,
+    #[msg("Missing Operator Log")]
+    MissingOperatorLog,
+    #[msg("Invalid Operator Log")]
+    InvalidOperatorLog,
+    #[msg("No Pending Upgrade Authority")]
+    NoPendingUpgradeAuthority,
+    #[msg("Upgrade Authority Mismatch")]
+    UpgradeAuthorityMismatch,
+    #[msg("Net Minted Limit Exceeded")]
+    NetMintedLimitExceeded,
+    #[msg("Net Outstanding Limit Exceeded")]
+    NetOutstandingLimitExceeded,
+    #[msg("Reactivation Cooldown")]
+    ReactivationCooldown,
+    #[msg("Vault Already Registered")]
+    VaultAlreadyRegistered,
+    #[msg("Vault Registry Full")]
+    VaultRegistryFull,
+    #[msg("Benefactor Already Registered")]
+    BenefactorAlreadyRegistered,
+    #[msg("Benefactor Not Registered")]
+    BenefactorNotRegistered,
+    #[msg("Benefactor Registry Page Full")]
+    BenefactorRegistryPageFull,
+    #[msg("Integrator Not Whitelisted")]
+    IntegratorNotWhitelisted,
+    #[msg("Flash Mint Pending")]
+    FlashMintPending,
+    #[msg("Missing Flash Mint Settlement")]
+    MissingFlashMintSettlement,
+    #[msg("No Pending Flash Mint")]
+    NoPendingFlashMint,
+    #[msg("Invalid Custodian Token Account")]
+    InvalidCustodianTokenAccount,
+    #[msg("Invalid Rent Receiver")]
+    InvalidRentReceiver,
+    #[msg("Period Limit Required")]
+    PeriodLimitRequired,
+    #[msg("Per Transaction Limit Exceeded")]
+    PerTxLimitExceeded,
+    #[msg("Unsupported Collateral Decimals")]
+    UnsupportedCollateralDecimals,
+    #[msg("Supply Mismatch")]
+    SupplyMismatch,
+    #[msg("Invalid Decimals")]
+    InvalidDecimals,
+    #[msg("Role Not Held")]
+    RoleNotHeld,
+    #[msg("Invalid Peg Symbol")]
+    InvalidPegSymbol,
+    #[msg("Instance Mismatch")]
+    InstanceMismatch,
+    #[msg("Invalid Relayer")]
+    InvalidRelayer,
+    #[msg("Missing Quote Oracle")]
+    MissingQuoteOracle,
+    #[msg("Missing Vault Account")]
+    MissingVaultAccount,
 }
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+/// Lists the program accounts an instruction mutated, so indexers can refetch exactly those
+/// instead of diffing every program account. Mint and redeem emit it through `emit_cpi!` next to
+/// their other events; operator instructions don't carry the event authority and log it with
+/// `emit!`.
+#[event]
+pub struct AccountTouchedEvent {
+    pub accounts: Vec<Pubkey>,
+}
+
+/// Emitted by `withdraw`. `reference` is the caller's reconciliation id for the custody movement,
+/// also written as a hex memo when the memo program is passed. `vault_balance` is what the vault
+/// token account holds after the transfer.
+#[event]
+pub struct WithdrawEvent {
+    pub vault: Pubkey,
+    pub custodian: Pubkey,
+    pub amount: u64,
+    pub reference: Option<[u8; 32]>,
+    pub operator: Pubkey,
+    pub destination: Pubkey,
+    pub vault_balance: u64,
+}
+
+/// Emitted by `set_pending_upgrade_authority`, with the upgrade authority the program data
+/// account reported at that time.
+#[event]
+pub struct UpgradeAuthorityProposedEvent {
+    pub operator: Pubkey,
+    pub upgrade_authority: Option<Pubkey>,
+    pub pending_upgrade_authority: Pubkey,
+}
+
+/// Emitted by `flash_mint` next to `MintV1Event`. `amount` is the collateral the matching
+/// `settle_flash_mint` pulls into custody.
+#[event]
+pub struct FlashMintEvent {
+    pub benefactor: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub mint_amount: u64,
+}
+
+/// Emitted by `settle_flash_mint` once `payer` covered the pending flash mint of `benefactor`.
+#[event]
+pub struct FlashMintSettledEvent {
+    pub benefactor: Pubkey,
+    pub vault: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted by `assert_supply_consistency` when the LP mint supply exceeds what the program issued,
+/// right before it pauses mint and redeem.
+#[event]
+pub struct SupplyMismatchEvent {
+    pub lp_mint: Pubkey,
+    pub supply: u64,
+    pub tracked_supply: u64,
+}
+
+/// Emitted by `confirm_upgrade_authority` once the program data account reports the pending
+/// upgrade authority.
+#[event]
+pub struct UpgradeAuthorityConfirmedEvent {
+    pub upgrade_authority: Pubkey,
+}
+
+/// Emitted by `burn_treasury`. `supply` and `tracked_supply` are read after the burn.
+#[event]
+pub struct TreasuryBurnEvent {
+    pub operator: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub supply: u64,
+    pub tracked_supply: u64,
+}
+
+/// Emitted by `acknowledge_deposit` once the custodian confirmed receiving `amount` of the vault's
+/// collateral. `reference` ties the receipt to the mint it settles, as agreed off-chain.
+#[event]
+pub struct DepositAcknowledgedEvent {
+    pub vault: Pubkey,
+    pub custodian: Pubkey,
+    pub reference: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `manage_config` when a peg manager changes the peg price.
+#[event]
+pub struct PegPriceUpdatedEvent {
+    pub operator: Pubkey,
+    pub old_peg_price_usd: u64,
+    pub new_peg_price_usd: u64,
+    pub timestamp: i64,
+}
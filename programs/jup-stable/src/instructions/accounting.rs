@@ -0,0 +1,318 @@
+//! Pricing, limit checks and bookkeeping shared by every instruction that mints or redeems against
+//! a vault: `mint`, `redeem`, their relayed variants and `flash_mint`.
+
+use anchor_lang::prelude::*;
+use rust_decimal::Decimal;
+
+use super::{MintV1Event, RedeemV1Event};
+use crate::{
+    error::JupStableError,
+    math::{compute_mint_amount, compute_redeem_amount, decimal_to_u64},
+    oracle::{self, OraclePrice, OracleQuote},
+    state::{
+        benefactor::Benefactor,
+        config::{Config, PEG_PRICE_DECIMALS},
+        vault::Vault,
+    },
+};
+
+/// The accounts a mint or redeem is priced against, checked against and recorded on.
+pub(crate) struct Accounting<'a> {
+    pub config: &'a mut Config,
+    pub vault: &'a mut Vault,
+    pub benefactor: &'a mut Benefactor,
+    pub clock: &'a Clock,
+}
+
+/// A mint of `amount` collateral, net of the benefactor's fee, priced against the vault's oracles.
+pub(crate) struct MintPricing {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub mint_amount: u64,
+    /// Benefactor mint fee rate `net_amount` was taken at
+    pub fee_rate_bps: u16,
+    one_to_one_amount: u64,
+    oracle_amount: u64,
+    oracle_price: OraclePrice,
+    quote: OracleQuote,
+}
+
+impl MintPricing {
+    /// Benefactor fee taken out of `amount`, in collateral
+    pub fn fee_amount(&self) -> u64 { self.amount - self.net_amount }
+}
+
+/// A redeem of `amount` jupUSD, net of the benefactor's fee, priced against the vault's oracles.
+pub(crate) struct RedeemPricing {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub redeem_amount: u64,
+    /// Benefactor redeem fee rate `net_amount` was taken at
+    pub fee_rate_bps: u16,
+    one_to_one_amount: u64,
+    oracle_amount: u64,
+    oracle_price: OraclePrice,
+    quote: OracleQuote,
+}
+
+impl RedeemPricing {
+    /// Benefactor fee taken out of `amount`, in jupUSD
+    pub fn fee_amount(&self) -> u64 { self.amount - self.net_amount }
+}
+
+impl Accounting<'_> {
+    /// Reads the vault's price from `oracle_accounts`, the quote oracle's last, checks it against
+    /// the vault's bounds and last price, and returns it along with the conservative price.
+    fn price(
+        &self,
+        oracle_accounts: &[AccountInfo],
+        is_mint: bool,
+    ) -> Result<(OracleQuote, OraclePrice)> {
+        let quote =
+            oracle::parse_vault_oracles(self.config, self.vault, oracle_accounts, self.clock)?;
+
+        self.vault.validate_oracle_price(&quote.price, is_mint)?;
+        self.vault
+            .check_price_jump(&quote.price, self.clock.unix_timestamp)?;
+        let oracle_price = self.vault.conservative_price(&quote, is_mint)?;
+        Ok((quote, oracle_price))
+    }
+
+    fn peg_price(&self) -> Result<Decimal> {
+        Ok(Decimal::new(
+            self.config.peg_price_usd.try_into()?,
+            PEG_PRICE_DECIMALS,
+        ))
+    }
+
+    pub fn price_mint(
+        &self,
+        oracle_accounts: &[AccountInfo],
+        amount: u64,
+        vault_mint_decimals: u8,
+        lp_mint_decimals: u8,
+    ) -> Result<MintPricing> {
+        let (quote, oracle_price) = self.price(oracle_accounts, true)?;
+        let fee_rate_bps = self.benefactor.mint_fee_rate;
+        let net_amount = amount
+            .checked_sub(jupusd_math::calculate_fee(amount, fee_rate_bps))
+            .ok_or(JupStableError::MathOverflow)?;
+
+        let (mint_amount, one_to_one_amount, oracle_amount) = compute_mint_amount(
+            amount,
+            net_amount,
+            &oracle_price,
+            self.peg_price()?,
+            vault_mint_decimals,
+            lp_mint_decimals,
+        )?;
+
+        Ok(MintPricing {
+            amount,
+            net_amount,
+            mint_amount,
+            fee_rate_bps,
+            one_to_one_amount,
+            oracle_amount,
+            oracle_price,
+            quote,
+        })
+    }
+
+    pub fn price_redeem(
+        &self,
+        oracle_accounts: &[AccountInfo],
+        amount: u64,
+        lp_mint_decimals: u8,
+        vault_mint_decimals: u8,
+    ) -> Result<RedeemPricing> {
+        let (quote, oracle_price) = self.price(oracle_accounts, false)?;
+        let fee_rate_bps = self.benefactor.redeem_fee_rate;
+        let net_amount = amount
+            .checked_sub(jupusd_math::calculate_fee(amount, fee_rate_bps))
+            .ok_or(JupStableError::MathOverflow)?;
+
+        let (redeem_amount, one_to_one_amount, oracle_amount) = compute_redeem_amount(
+            amount,
+            net_amount,
+            &oracle_price,
+            self.peg_price()?,
+            lp_mint_decimals,
+            vault_mint_decimals,
+        )?;
+
+        Ok(RedeemPricing {
+            amount,
+            net_amount,
+            redeem_amount,
+            fee_rate_bps,
+            one_to_one_amount,
+            oracle_amount,
+            oracle_price,
+            quote,
+        })
+    }
+
+    /// Checks the mint against the config, benefactor and vault limits, the jupUSD supply and
+    /// `min_amount_out`, without touching any account. `label` names the instruction in the
+    /// slippage log.
+    pub fn peek_mint(
+        &self,
+        pricing: &MintPricing,
+        lp_supply: u64,
+        min_amount_out: u64,
+        label: &str,
+    ) -> Result<()> {
+        let current_time = self.clock.unix_timestamp;
+        let strict_period_limits = self.config.is_strict_period_limits();
+        self.config.can_mint(pricing.mint_amount, current_time)?;
+        self.benefactor
+            .can_mint(pricing.mint_amount, current_time, strict_period_limits)?;
+        self.vault
+            .can_mint(pricing.mint_amount, current_time, strict_period_limits)?;
+        self.config.check_supply_consistency(lp_supply)?;
+
+        require!(pricing.mint_amount > 0, JupStableError::ZeroAmount);
+        if pricing.mint_amount < min_amount_out {
+            msg!(
+                "{}: amount out {}, min amount out {}",
+                label,
+                pricing.mint_amount,
+                min_amount_out
+            );
+            return err!(JupStableError::SlippageToleranceExceeded);
+        }
+        Ok(())
+    }
+
+    /// Checks the redeem against the config, vault and benefactor limits, the jupUSD supply,
+    /// `min_amount_out` and the `vault_balance` it is paid from, without touching any account.
+    /// `label` names the instruction in the slippage log.
+    pub fn peek_redeem(
+        &self,
+        pricing: &RedeemPricing,
+        lp_supply: u64,
+        vault_balance: u64,
+        min_amount_out: u64,
+        label: &str,
+    ) -> Result<()> {
+        let current_time = self.clock.unix_timestamp;
+        let strict_period_limits = self.config.is_strict_period_limits();
+        self.config.can_redeem(
+            pricing.net_amount,
+            current_time,
+            self.benefactor.is_exempt_from_global_pause(),
+        )?;
+        self.vault
+            .can_redeem(pricing.net_amount, current_time, strict_period_limits)?;
+        self.benefactor
+            .can_redeem(pricing.net_amount, current_time, strict_period_limits)?;
+        self.config.check_supply_consistency(lp_supply)?;
+
+        require!(pricing.redeem_amount > 0, JupStableError::ZeroAmount);
+        if pricing.redeem_amount < min_amount_out {
+            msg!(
+                "{}: amount out {}, min amount out {}",
+                label,
+                pricing.redeem_amount,
+                min_amount_out
+            );
+            return err!(JupStableError::SlippageToleranceExceeded);
+        }
+        if vault_balance < pricing.redeem_amount {
+            msg!(
+                "vault: requested {}, available {}",
+                pricing.redeem_amount,
+                vault_balance
+            );
+            return err!(JupStableError::VaultIsDry);
+        }
+        Ok(())
+    }
+
+    /// [`Accounting::peek_mint`], then rolls the period windows the mint is recorded in.
+    pub fn check_mint(
+        &mut self,
+        pricing: &MintPricing,
+        lp_supply: u64,
+        min_amount_out: u64,
+        label: &str,
+    ) -> Result<()> {
+        self.peek_mint(pricing, lp_supply, min_amount_out, label)?;
+        self.roll_period_limits();
+        Ok(())
+    }
+
+    /// [`Accounting::peek_redeem`], then rolls the period windows the redeem is recorded in.
+    pub fn check_redeem(
+        &mut self,
+        pricing: &RedeemPricing,
+        lp_supply: u64,
+        vault_balance: u64,
+        min_amount_out: u64,
+        label: &str,
+    ) -> Result<()> {
+        self.peek_redeem(pricing, lp_supply, vault_balance, min_amount_out, label)?;
+        self.roll_period_limits();
+        Ok(())
+    }
+
+    fn roll_period_limits(&mut self) {
+        let current_time = self.clock.unix_timestamp;
+        self.config.roll_period_limits(current_time);
+        self.benefactor.roll_period_limits(current_time);
+        self.vault.roll_period_limits(current_time);
+    }
+
+    pub fn mint_event(&self, pricing: &MintPricing) -> Result<MintV1Event> {
+        Ok(MintV1Event {
+            amount: pricing.amount,
+            net_amount: pricing.net_amount,
+            oracle_price: decimal_to_u64(pricing.oracle_price.0 * Decimal::from(10_i64.pow(6)))?,
+            one_to_one_amount: pricing.one_to_one_amount,
+            oracle_amount: pricing.oracle_amount,
+            mint_amount: pricing.mint_amount,
+            fee_amount: pricing.fee_amount(),
+            fee_rate_bps: pricing.fee_rate_bps,
+        })
+    }
+
+    pub fn redeem_event(&self, pricing: &RedeemPricing) -> Result<RedeemV1Event> {
+        Ok(RedeemV1Event {
+            amount: pricing.amount,
+            net_amount: pricing.net_amount,
+            oracle_price: decimal_to_u64(pricing.oracle_price.0 * Decimal::from(10_i64.pow(6)))?,
+            one_to_one_amount: pricing.one_to_one_amount,
+            oracle_amount: pricing.oracle_amount,
+            redeem_amount: pricing.redeem_amount,
+            fee_amount: pricing.fee_amount(),
+            fee_rate_bps: pricing.fee_rate_bps,
+        })
+    }
+
+    /// Records the minted jupUSD on the config, benefactor and vault, and the price it was minted at.
+    pub fn record_mint(&mut self, pricing: &MintPricing) -> Result<()> {
+        self.config.record_mint(pricing.mint_amount);
+        self.benefactor.record_mint(pricing.mint_amount);
+        self.vault.record_mint(pricing.mint_amount);
+        self.vault.record_price(
+            &pricing.quote.price,
+            pricing.quote.spread_bps,
+            self.clock.unix_timestamp,
+        )
+    }
+
+    /// Records the redeemed and burnt jupUSD on the config, benefactor and vault, and the price it
+    /// was redeemed at.
+    pub fn record_redeem(&mut self, pricing: &RedeemPricing) -> Result<()> {
+        self.config.record_redeem(pricing.net_amount);
+        self.config.record_burn(pricing.amount);
+        self.benefactor.record_redeem(pricing.net_amount);
+        self.vault.record_redeem(pricing.net_amount);
+        self.vault.record_price(
+            &pricing.quote.price,
+            pricing.quote.spread_bps,
+            self.clock.unix_timestamp,
+        )
+    }
+}
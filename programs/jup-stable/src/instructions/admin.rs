@@ -1,10 +1,18 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
 
 use crate::{
     error::JupStableError,
+    event::{
+        AccountTouchedEvent, PegPriceUpdatedEvent, SupplyMismatchEvent, TreasuryBurnEvent,
+        UpgradeAuthorityConfirmedEvent, UpgradeAuthorityProposedEvent,
+    },
+    program::JupStable,
     state::{
-        config::{Config, PEG_PRICE_DECIMALS},
+        common::PeriodLimitTemplate,
+        config::{check_peg_price_usd, check_peg_symbol, Config, CONFIG_PREFIX, PEG_SYMBOL_LEN},
         operator::{Operator, OperatorRole},
+        operator_log::{OperatorAction, OperatorLog},
     },
 };
 
@@ -18,6 +26,11 @@ pub struct ManageConfig<'info> {
     pub operator: AccountLoader<'info, Operator>,
     #[account(mut)]
     pub config: AccountLoader<'info, Config>,
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -38,6 +51,38 @@ pub enum ConfigManagementAction {
     SetPegPriceUSD {
         peg_price_usd: u64,
     },
+    UpdateRestartSlotCheck {
+        enabled: bool,
+    },
+    UpdateStrictPeriodLimits {
+        enabled: bool,
+    },
+    UpdatePauseBlocksWithdraw {
+        enabled: bool,
+    },
+    SetReactivationCooldown {
+        cooldown_seconds: u64,
+    },
+    /// Default lets closures refund anywhere again
+    SetRentTreasury {
+        rent_treasury: Pubkey,
+    },
+    /// Zero duration stops applying a template to new vaults
+    SetDefaultVaultPeriodLimit {
+        duration_seconds: u64,
+        max_mint_amount: u64,
+        max_redeem_amount: u64,
+    },
+    /// Zero duration stops applying a template to new benefactors
+    SetDefaultBenefactorPeriodLimit {
+        duration_seconds: u64,
+        max_mint_amount: u64,
+        max_redeem_amount: u64,
+    },
+    /// Uppercase ASCII letters and digits, zero padded, e.g. `b"EUR\0\0\0\0\0"`
+    SetPegSymbol {
+        peg_symbol: [u8; PEG_SYMBOL_LEN],
+    },
 }
 
 pub fn manage_config(ctx: Context<ManageConfig>, action: ConfigManagementAction) -> Result<()> {
@@ -86,15 +131,303 @@ pub fn manage_config(ctx: Context<ManageConfig>, action: ConfigManagementAction)
         ConfigManagementAction::SetPegPriceUSD { peg_price_usd } => {
             operator.is(OperatorRole::PegManager)?;
 
-            require!(peg_price_usd > 0, JupStableError::InvalidPegPriceUSD);
-            require!(
-                peg_price_usd < 2 * 10_u64.pow(PEG_PRICE_DECIMALS),
-                JupStableError::InvalidPegPriceUSD
-            );
+            check_peg_price_usd(peg_price_usd)?;
+
+            let old_peg_price_usd = config.set_peg_price_usd(peg_price_usd);
+            emit!(PegPriceUpdatedEvent {
+                operator: ctx.accounts.operator.key(),
+                old_peg_price_usd,
+                new_peg_price_usd: peg_price_usd,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        },
+        ConfigManagementAction::UpdateRestartSlotCheck { enabled } => {
+            operator.is(OperatorRole::Admin)?;
+
+            config.update_restart_slot_check(enabled);
+        },
+        ConfigManagementAction::UpdateStrictPeriodLimits { enabled } => {
+            operator.is(OperatorRole::Admin)?;
+
+            config.update_strict_period_limits(enabled);
+        },
+        ConfigManagementAction::UpdatePauseBlocksWithdraw { enabled } => {
+            operator.is(OperatorRole::Admin)?;
+
+            config.update_pause_blocks_withdraw(enabled);
+        },
+        ConfigManagementAction::SetReactivationCooldown { cooldown_seconds } => {
+            operator.is(OperatorRole::Admin)?;
+
+            config.set_reactivation_cooldown_seconds(cooldown_seconds);
+        },
+        ConfigManagementAction::SetRentTreasury { rent_treasury } => {
+            operator.is(OperatorRole::Admin)?;
+
+            config.set_rent_treasury(rent_treasury);
+        },
+        ConfigManagementAction::SetDefaultVaultPeriodLimit {
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        } => {
+            operator.is(OperatorRole::PeriodManager)?;
 
-            config.set_peg_price_usd(peg_price_usd);
+            config.default_vault_period_limit =
+                PeriodLimitTemplate::new(duration_seconds, max_mint_amount, max_redeem_amount)?;
+        },
+        ConfigManagementAction::SetDefaultBenefactorPeriodLimit {
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        } => {
+            operator.is(OperatorRole::PeriodManager)?;
+
+            config.default_benefactor_period_limit =
+                PeriodLimitTemplate::new(duration_seconds, max_mint_amount, max_redeem_amount)?;
+        },
+        ConfigManagementAction::SetPegSymbol { peg_symbol } => {
+            operator.is(OperatorRole::Admin)?;
+
+            check_peg_symbol(&peg_symbol)?;
+
+            config.set_peg_symbol(peg_symbol);
         },
     }
 
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::ManageConfig,
+        ctx.accounts.config.key(),
+    )?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.config.key()],
+    });
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct SetPendingUpgradeAuthority<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+    pub program_data: Account<'info, ProgramData>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, JupStable>,
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
+}
+
+/// Announces the upgrade authority the program is about to be handed to. Passing the default
+/// pubkey cancels a pending handover.
+pub fn set_pending_upgrade_authority(
+    ctx: Context<SetPendingUpgradeAuthority>,
+    pending_upgrade_authority: Pubkey,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let operator = ctx.accounts.operator.load()?;
+    operator.is(OperatorRole::Admin)?;
+
+    config.set_pending_upgrade_authority(pending_upgrade_authority);
+
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::SetPendingUpgradeAuthority,
+        ctx.accounts.config.key(),
+    )?;
+
+    emit!(UpgradeAuthorityProposedEvent {
+        operator: ctx.accounts.operator.key(),
+        upgrade_authority: ctx.accounts.program_data.upgrade_authority_address,
+        pending_upgrade_authority,
+    });
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.config.key()],
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfirmUpgradeAuthority<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+    pub program_data: Account<'info, ProgramData>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, JupStable>,
+}
+
+/// Permissionless: only succeeds once the program data account reports the pending upgrade
+/// authority, which then gets cleared.
+pub fn confirm_upgrade_authority(ctx: Context<ConfirmUpgradeAuthority>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let upgrade_authority =
+        config.confirm_upgrade_authority(ctx.accounts.program_data.upgrade_authority_address)?;
+
+    emit!(UpgradeAuthorityConfirmedEvent { upgrade_authority });
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.config.key()],
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SyncSupply<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+    #[account(
+        mut,
+        constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
+}
+
+/// Resets the tracked supply to the LP mint supply and turns the supply consistency check on.
+pub fn sync_supply(ctx: Context<SyncSupply>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let operator = ctx.accounts.operator.load()?;
+    operator.is(OperatorRole::Admin)?;
+
+    config.sync_supply(ctx.accounts.lp_mint.supply);
+
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::SyncSupply,
+        ctx.accounts.config.key(),
+    )?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.config.key()],
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AssertSupplyConsistency<'info> {
+    #[account(
+        mut,
+        constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Permissionless: pauses mint and redeem when the LP mint supply exceeds what the program
+/// issued. Succeeds either way, so the pause sticks.
+pub fn assert_supply_consistency(ctx: Context<AssertSupplyConsistency>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let supply = ctx.accounts.lp_mint.supply;
+    if config.check_supply_consistency(supply).is_ok() {
+        return Ok(());
+    }
+
+    config.update_mint_redeem_enabled(false);
+
+    emit!(SupplyMismatchEvent {
+        lp_mint: ctx.accounts.lp_mint.key(),
+        supply,
+        tracked_supply: config.tracked_supply,
+    });
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.config.key()],
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BurnTreasury<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = operator_authority,
+        token::token_program = lp_token_program,
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+        constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
+        constraint = config.load()?.token_program == lp_token_program.key() @ JupStableError::InvalidTokenProgram,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    #[account(mut)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
+}
+
+/// Burns protocol-held jupUSD, e.g. collected fees, out of a token account the operator owns.
+/// Unlike a raw SPL burn it also lowers the tracked supply.
+pub fn burn_treasury(ctx: Context<BurnTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, JupStableError::ZeroAmount);
+
+    let operator = ctx.accounts.operator.load()?;
+    operator.is(OperatorRole::CollateralManager)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::BurnTreasury,
+        ctx.accounts.treasury_token_account.key(),
+    )?;
+
+    require!(
+        ctx.accounts.treasury_token_account.amount >= amount,
+        JupStableError::InsufficientAmount
+    );
+
+    burn(ctx.accounts.burn_treasury_tokens(), amount)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.record_burn(amount);
+
+    ctx.accounts.lp_mint.reload()?;
+
+    emit!(TreasuryBurnEvent {
+        operator: ctx.accounts.operator.key(),
+        token_account: ctx.accounts.treasury_token_account.key(),
+        amount,
+        supply: ctx.accounts.lp_mint.supply,
+        tracked_supply: config.tracked_supply,
+    });
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.config.key()],
+    });
+    Ok(())
+}
+
+impl<'info> BurnTreasury<'info> {
+    fn burn_treasury_tokens(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.lp_mint.to_account_info(),
+            from: self.treasury_token_account.to_account_info(),
+            authority: self.operator_authority.to_account_info(),
+        };
+        let cpi_program = self.lp_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
@@ -2,9 +2,14 @@ use anchor_lang::prelude::*;
 
 use crate::{
     error::JupStableError,
+    event::AccountTouchedEvent,
     state::{
         benefactor::{Benefactor, BenefactorStatus, BENEFACTOR_PREFIX},
+        benefactor_registry::{BenefactorRegistry, BENEFACTOR_REGISTRY_PREFIX},
+        config::{Config, CONFIG_PREFIX},
         operator::{Operator, OperatorRole},
+        operator_log::{OperatorAction, OperatorLog},
+        vault::Vault,
     },
 };
 
@@ -22,16 +27,40 @@ pub struct CreateBenefactor<'info> {
     /// CHECK:
     pub benefactor_authority: UncheckedAccount<'info>,
 
+    /// Ahead of `benefactor`, whose address is seeded by the config's instance
+    pub config: AccountLoader<'info, Config>,
+
     #[account(
         init,
         payer = payer,
         space = 8 + Benefactor::MAX_SIZE,
-        seeds = [BENEFACTOR_PREFIX, benefactor_authority.key().as_ref()],
+        seeds = [
+            BENEFACTOR_PREFIX,
+            config.load()?.instance_seed(),
+            benefactor_authority.key().as_ref(),
+        ],
         bump
     )]
     pub benefactor: AccountLoader<'info, Benefactor>,
 
+    #[account(
+        mut,
+        seeds = [
+            BENEFACTOR_REGISTRY_PREFIX,
+            config.load()?.instance_seed(),
+            &benefactor_registry.load()?.page.to_le_bytes(),
+        ],
+        bump = benefactor_registry.load()?.bump,
+    )]
+    pub benefactor_registry: AccountLoader<'info, BenefactorRegistry>,
+
     pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 pub fn create_benefactor(
@@ -41,10 +70,16 @@ pub fn create_benefactor(
 ) -> Result<()> {
     let operator = ctx.accounts.operator.load()?;
     operator.is(OperatorRole::BenefactorManager)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::CreateBenefactor,
+        ctx.accounts.benefactor.key(),
+    )?;
 
     require!(mint_fee_rate <= 10000, JupStableError::InvalidFeeRate);
     require!(redeem_fee_rate <= 10000, JupStableError::InvalidFeeRate);
 
+    let config = ctx.accounts.config.load()?;
     let mut benefactor = ctx.accounts.benefactor.load_init()?;
 
     *benefactor = Benefactor {
@@ -52,9 +87,25 @@ pub fn create_benefactor(
         status: BenefactorStatus::Disabled,
         mint_fee_rate,
         redeem_fee_rate,
+        instance_mint: config.instance_mint(),
         ..Default::default()
     };
+    let current_time = Clock::get()?.unix_timestamp;
+    config
+        .default_benefactor_period_limit
+        .apply(&mut benefactor.period_limits[0], current_time)?;
 
+    ctx.accounts
+        .benefactor_registry
+        .load_mut()?
+        .push(ctx.accounts.benefactor_authority.key())?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.benefactor.key(),
+            ctx.accounts.benefactor_registry.key(),
+        ],
+    });
     Ok(())
 }
 
@@ -67,8 +118,20 @@ pub struct ManageBenefactor<'info> {
     )]
     pub operator: AccountLoader<'info, Operator>,
 
+    #[account(
+        seeds = [CONFIG_PREFIX, benefactor.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
     #[account(mut)]
     pub benefactor: AccountLoader<'info, Benefactor>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -90,10 +153,32 @@ pub enum BenefactorManagementAction {
     ResetPeriodLimit {
         index: u8,
     },
+    SetMaxNetMinted {
+        max_net_minted: u64,
+    },
+    SetIntegratorProgram {
+        integrator_program: Pubkey,
+    },
+    /// Lets the benefactor keep redeeming during a global pause
+    SetExemptFromGlobalPause {
+        exempt: bool,
+    },
+    /// Client-side hint, not enforced on redeem. Default clears it; any other mint needs its
+    /// vault as remaining account
+    SetRedeemVaultHint {
+        vault_mint: Pubkey,
+    },
+    /// Default clears the slot
+    SetRelayer {
+        index: u8,
+        relayer: Pubkey,
+    },
 }
 
-pub fn manage_benefactor(
-    ctx: Context<ManageBenefactor>,
+/// `SetRedeemVaultHint` takes the vault of the new hint as its remaining account, so only a vault
+/// of the benefactor's instance can be hinted.
+pub fn manage_benefactor<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ManageBenefactor<'info>>,
     action: BenefactorManagementAction,
 ) -> Result<()> {
     let mut benefactor = ctx.accounts.benefactor.load_mut()?;
@@ -104,11 +189,19 @@ pub fn manage_benefactor(
             operator.is(OperatorRole::BenefactorDisabler)?;
 
             benefactor.is_active()?;
-            benefactor.status = BenefactorStatus::Disabled;
+            benefactor.disable(Clock::get()?.unix_timestamp);
         },
         BenefactorManagementAction::SetStatus { status } => {
             operator.is(OperatorRole::BenefactorManager)?;
 
+            if status == BenefactorStatus::Active {
+                let config = ctx.accounts.config.load()?;
+                benefactor.check_reactivation(
+                    config.reactivation_cooldown_seconds,
+                    Clock::get()?.unix_timestamp,
+                )?;
+            }
+
             benefactor.set_status(status);
         },
         BenefactorManagementAction::UpdateFeeRates {
@@ -145,8 +238,57 @@ pub fn manage_benefactor(
 
             benefactor.reset_period_limit(index.into())?;
         },
+        BenefactorManagementAction::SetMaxNetMinted { max_net_minted } => {
+            operator.is(OperatorRole::PeriodManager)?;
+
+            benefactor.set_max_net_minted(max_net_minted);
+        },
+        BenefactorManagementAction::SetIntegratorProgram { integrator_program } => {
+            operator.is(OperatorRole::BenefactorManager)?;
+
+            benefactor.set_integrator_program(integrator_program);
+        },
+        BenefactorManagementAction::SetExemptFromGlobalPause { exempt } => {
+            operator.is(OperatorRole::Admin)?;
+
+            benefactor.set_exempt_from_global_pause(exempt);
+        },
+        BenefactorManagementAction::SetRedeemVaultHint { vault_mint } => {
+            operator.is(OperatorRole::BenefactorManager)?;
+
+            if vault_mint != Pubkey::default() {
+                let account = ctx
+                    .remaining_accounts
+                    .first()
+                    .ok_or(JupStableError::MissingVaultAccount)?;
+                let vault = AccountLoader::<Vault>::try_from(account)?;
+                let vault = vault.load()?;
+                require_keys_eq!(vault.mint, vault_mint, JupStableError::InvalidVaultMint);
+                require_keys_eq!(
+                    vault.instance_mint,
+                    benefactor.instance_mint,
+                    JupStableError::InstanceMismatch
+                );
+            }
+
+            benefactor.set_redeem_vault_hint(vault_mint);
+        },
+        BenefactorManagementAction::SetRelayer { index, relayer } => {
+            operator.is(OperatorRole::BenefactorManager)?;
+
+            benefactor.set_relayer(index.into(), relayer)?;
+        },
     }
 
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::ManageBenefactor,
+        ctx.accounts.benefactor.key(),
+    )?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.benefactor.key()],
+    });
     Ok(())
 }
 
@@ -159,19 +301,141 @@ pub struct DeleteBenefactor<'info> {
     )]
     pub operator: AccountLoader<'info, Operator>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = config.load()?.is_rent_receiver(&receiver.key()) @ JupStableError::InvalidRentReceiver,
+    )]
     /// CHECK: Will only receive rent
     pub receiver: UncheckedAccount<'info>,
 
+    #[account(
+        seeds = [CONFIG_PREFIX, benefactor.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
     #[account(
         mut,
         close = receiver,
     )]
     pub benefactor: AccountLoader<'info, Benefactor>,
+
+    #[account(
+        mut,
+        seeds = [
+            BENEFACTOR_REGISTRY_PREFIX,
+            benefactor.load()?.instance_seed(),
+            &benefactor_registry.load()?.page.to_le_bytes(),
+        ],
+        bump = benefactor_registry.load()?.bump,
+    )]
+    pub benefactor_registry: AccountLoader<'info, BenefactorRegistry>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 pub fn delete_benefactor(ctx: Context<DeleteBenefactor>) -> Result<()> {
     let operator = ctx.accounts.operator.load()?;
     operator.is(OperatorRole::BenefactorManager)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::DeleteBenefactor,
+        ctx.accounts.benefactor.key(),
+    )?;
+
+    let authority = ctx.accounts.benefactor.load()?.authority;
+    ctx.accounts
+        .benefactor_registry
+        .load_mut()?
+        .remove(&authority)?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.benefactor.key(),
+            ctx.accounts.benefactor_registry.key(),
+        ],
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateBenefactorRegistryPage<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BenefactorRegistry::MAX_SIZE,
+        seeds = [
+            BENEFACTOR_REGISTRY_PREFIX,
+            config.load()?.instance_seed(),
+            &config.load()?.benefactor_registry_pages.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub benefactor_registry: AccountLoader<'info, BenefactorRegistry>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
+}
+
+/// Opens the next registry page once the existing ones are full. The remaining accounts are
+/// benefactors created before the registry existed, registered on the new page.
+pub fn create_benefactor_registry_page<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateBenefactorRegistryPage<'info>>,
+) -> Result<()> {
+    let operator = ctx.accounts.operator.load()?;
+    operator.is(OperatorRole::BenefactorManager)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::CreateBenefactorRegistryPage,
+        ctx.accounts.benefactor_registry.key(),
+    )?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut benefactor_registry = ctx.accounts.benefactor_registry.load_init()?;
+    *benefactor_registry = BenefactorRegistry {
+        page: config.benefactor_registry_pages,
+        bump: ctx.bumps.benefactor_registry,
+        ..Default::default()
+    };
+    config.benefactor_registry_pages += 1;
+
+    for account in ctx.remaining_accounts {
+        let benefactor = AccountLoader::<Benefactor>::try_from(account)?;
+        let benefactor = benefactor.load()?;
+        require_keys_eq!(
+            benefactor.instance_mint,
+            config.instance_mint(),
+            JupStableError::InstanceMismatch
+        );
+        benefactor_registry.push(benefactor.authority)?;
+    }
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.benefactor_registry.key(),
+        ],
+    });
     Ok(())
 }
@@ -0,0 +1,274 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked,
+    },
+    Discriminator,
+};
+use anchor_spl::token_interface::{
+    mint_to, transfer_checked, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use super::accounting::Accounting;
+use crate::{
+    authority_seeds,
+    error::JupStableError,
+    event::{AccountTouchedEvent, FlashMintEvent, FlashMintSettledEvent},
+    state::{
+        benefactor::Benefactor,
+        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
+        vault::{Vault, VAULT_PREFIX},
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FlashMint<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+    )]
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+        constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
+        constraint = config.load()?.authority == authority.key() @ JupStableError::InvalidAuthority,
+        constraint = config.load()?.token_program == lp_token_program.key() @ JupStableError::InvalidTokenProgram,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    #[account(
+        seeds = [AUTHORITY_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.authority_bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub lp_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX, config.load()?.instance_seed(), vault_mint.key().as_ref()],
+        bump = vault.load()?.bump,
+        constraint = vault.load()?.mint == vault_mint.key() @ JupStableError::InvalidVaultMint,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub vault_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(
+        mut,
+        constraint = benefactor.load()?.authority == user.key() @ JupStableError::InvalidBenefactor,
+        constraint = benefactor.load()?.instance_mint == config.load()?.instance_mint() @ JupStableError::InstanceMismatch,
+    )]
+    pub benefactor: AccountLoader<'info, Benefactor>,
+
+    /// CHECK: checked with address constraint
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mints jupUSD against `amount` collateral that is only pulled by a `settle_flash_mint` later in
+/// the same transaction. The call has to come from the benefactor's integrator program, and the
+/// settlement has to be a top-level instruction so the transaction fails as a whole if it does.
+pub fn flash_mint(ctx: Context<FlashMint>, amount: u64, min_amount_out: u64) -> Result<()> {
+    require!(amount > 0, JupStableError::ZeroAmount);
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let mut benefactor = ctx.accounts.benefactor.load_mut()?;
+    let mut config = ctx.accounts.config.load_mut()?;
+
+    let instructions_sysvar = ctx.accounts.instructions_sysvar.to_account_info();
+    let current_index = load_current_index_checked(&instructions_sysvar)?;
+    let caller = load_instruction_at_checked(current_index.into(), &instructions_sysvar)?;
+    benefactor.can_flash_mint(&caller.program_id)?;
+    require!(
+        has_settlement(
+            &instructions_sysvar,
+            current_index.into(),
+            &ctx.accounts.benefactor.key(),
+        )?,
+        JupStableError::MissingFlashMintSettlement
+    );
+
+    let clock = Clock::get()?;
+    let mut accounting = Accounting {
+        config: &mut config,
+        vault: &mut vault,
+        benefactor: &mut benefactor,
+        clock: &clock,
+    };
+
+    // Oracle accounts are passed as remaining_accounts, the quote oracle's last
+    let pricing = accounting.price_mint(
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.vault_mint.decimals,
+        ctx.accounts.lp_mint.decimals,
+    )?;
+    accounting.check_mint(
+        &pricing,
+        ctx.accounts.lp_mint.supply,
+        min_amount_out,
+        "flash mint",
+    )?;
+
+    emit_cpi!(accounting.mint_event(&pricing)?);
+    accounting.record_mint(&pricing)?;
+    benefactor.record_flash_mint(ctx.accounts.vault.key(), amount);
+
+    mint_to(
+        ctx.accounts
+            .mint_lp_tokens()
+            .with_signer(&[authority_seeds!(config)]),
+        pricing.mint_amount,
+    )?;
+
+    emit_cpi!(FlashMintEvent {
+        benefactor: ctx.accounts.benefactor.key(),
+        vault: ctx.accounts.vault.key(),
+        amount,
+        mint_amount: pricing.mint_amount,
+    });
+    emit_cpi!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.vault.key(),
+            ctx.accounts.benefactor.key()
+        ],
+    });
+    Ok(())
+}
+
+/// Looks for a top-level `settle_flash_mint` of `benefactor` after instruction `current_index`.
+fn has_settlement(
+    instructions_sysvar: &AccountInfo,
+    current_index: usize,
+    benefactor: &Pubkey,
+) -> Result<bool> {
+    let mut index = current_index + 1;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        if instruction.program_id == crate::ID
+            && instruction
+                .data
+                .starts_with(crate::instruction::SettleFlashMint::DISCRIMINATOR)
+            && instruction
+                .accounts
+                .first()
+                .is_some_and(|account| account.pubkey == *benefactor)
+        {
+            return Ok(true);
+        }
+        index += 1;
+    }
+    Ok(false)
+}
+
+impl<'info> FlashMint<'info> {
+    fn mint_lp_tokens(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.lp_mint.to_account_info(),
+            to: self.user_lp_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.lp_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleFlashMint<'info> {
+    /// Kept first: `flash_mint` matches the settlement on it
+    #[account(
+        mut,
+        constraint = benefactor.load()?.flash_mint_vault == vault.key() @ JupStableError::NoPendingFlashMint,
+    )]
+    pub benefactor: AccountLoader<'info, Benefactor>,
+
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = vault_mint,
+        token::authority = payer,
+    )]
+    pub payer_collateral_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [VAULT_PREFIX, vault.load()?.instance_seed(), vault_mint.key().as_ref()],
+        bump = vault.load()?.bump,
+        constraint = vault.load()?.custodian == custodian.key() @ JupStableError::InvalidCustodian,
+        constraint = vault.load()?.mint == vault_mint.key() @ JupStableError::InvalidVaultMint,
+        constraint = vault.load()?.token_program == vault_token_program.key() @ JupStableError::InvalidTokenProgram,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub vault_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    /// CHECK: checked with constraint on vault
+    pub custodian: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = vault_mint,
+        token::authority = custodian,
+        token::token_program = vault_token_program,
+        constraint = vault.load()?.resolve_custodian_token_account() == custodian_token_account.key() @ JupStableError::InvalidCustodianTokenAccount,
+    )]
+    pub custodian_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub vault_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pulls the collateral the benefactor's pending flash mint owes into custody. Anyone may pay it.
+pub fn settle_flash_mint(ctx: Context<SettleFlashMint>) -> Result<()> {
+    let debt = ctx.accounts.benefactor.load()?.flash_mint_debt;
+    require!(debt > 0, JupStableError::NoPendingFlashMint);
+
+    let amount_before = ctx.accounts.custodian_token_account.amount;
+    let expected_amount_after = amount_before
+        .checked_add(debt)
+        .ok_or(JupStableError::MathOverflow)?;
+    transfer_checked(
+        ctx.accounts.deposit_collateral(),
+        debt,
+        ctx.accounts.vault_mint.decimals,
+    )?;
+    ctx.accounts.custodian_token_account.reload()?;
+    let amount_after = ctx.accounts.custodian_token_account.amount;
+    require!(
+        amount_after == expected_amount_after,
+        JupStableError::InsufficientAmount
+    );
+
+    ctx.accounts.benefactor.load_mut()?.settle_flash_mint();
+
+    emit_cpi!(FlashMintSettledEvent {
+        benefactor: ctx.accounts.benefactor.key(),
+        vault: ctx.accounts.vault.key(),
+        payer: ctx.accounts.payer.key(),
+        amount: debt,
+    });
+    emit_cpi!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.benefactor.key()],
+    });
+    Ok(())
+}
+
+impl<'info> SettleFlashMint<'info> {
+    fn deposit_collateral(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.payer_collateral_token_account.to_account_info(),
+            mint: self.vault_mint.to_account_info(),
+            to: self.custodian_token_account.to_account_info(),
+            authority: self.payer.to_account_info(),
+        };
+        let cpi_program = self.vault_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
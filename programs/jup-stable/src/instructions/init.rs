@@ -10,13 +10,34 @@ use anchor_spl::{
 
 use crate::{
     authority_seeds,
+    error::JupStableError,
+    event::AccountTouchedEvent,
     program::JupStable,
     state::{
-        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
-        operator::{Operator, OperatorStatus, OPERATOR_PREFIX},
+        benefactor_registry::{BenefactorRegistry, BENEFACTOR_REGISTRY_PREFIX},
+        common::PeriodLimitTemplate,
+        config::{
+            check_peg_price_usd, Config, AUTHORITY_PREFIX, CONFIG_PREFIX, MAX_LP_DECIMALS,
+            MIN_LP_DECIMALS,
+        },
+        operator::{Operator, OperatorRole, OperatorStatus, OPERATOR_PREFIX},
+        operator_log::{OperatorAction, OperatorLog, OPERATOR_LOG_PREFIX},
+        vault_registry::{VaultRegistry, VAULT_REGISTRY_PREFIX},
     },
 };
 
+/// Config values `init` starts from, so a deployment doesn't need follow-up `manage_config` calls
+/// before it matches its intent.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitConfigParams {
+    pub peg_price_usd: u64,
+    pub is_mint_redeem_enabled: bool,
+    /// Zero duration leaves new vaults without a template
+    pub default_vault_period_limit: PeriodLimitTemplate,
+    /// Zero duration leaves new benefactors without a template
+    pub default_benefactor_period_limit: PeriodLimitTemplate,
+}
+
 #[derive(Accounts)]
 #[instruction(decimals: u8)]
 pub struct Init<'info> {
@@ -32,6 +53,14 @@ pub struct Init<'info> {
         bump
     )]
     pub operator: AccountLoader<'info, Operator>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OperatorLog::MAX_SIZE,
+        seeds = [OPERATOR_LOG_PREFIX, operator.key().as_ref()],
+        bump
+    )]
+    pub operator_log: AccountLoader<'info, OperatorLog>,
     #[account(
         init,
         payer = payer,
@@ -40,6 +69,22 @@ pub struct Init<'info> {
         bump
     )]
     pub config: AccountLoader<'info, Config>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + VaultRegistry::MAX_SIZE,
+        seeds = [VAULT_REGISTRY_PREFIX],
+        bump
+    )]
+    pub vault_registry: AccountLoader<'info, VaultRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BenefactorRegistry::MAX_SIZE,
+        seeds = [BENEFACTOR_REGISTRY_PREFIX, &0u32.to_le_bytes()],
+        bump
+    )]
+    pub benefactor_registry: AccountLoader<'info, BenefactorRegistry>,
     #[account(
         mut,
         seeds = [AUTHORITY_PREFIX],
@@ -75,11 +120,14 @@ pub struct Init<'info> {
 
 pub fn init(
     ctx: Context<Init>,
-    _decimals: u8,
+    decimals: u8,
     name: String,
     symbol: String,
     uri: String,
+    params: InitConfigParams,
 ) -> Result<()> {
+    let initial_config = initial_config(decimals, &params)?;
+
     let mut config = ctx.accounts.config.load_init()?;
     *config = Config {
         mint: ctx.accounts.mint.key(),
@@ -87,7 +135,18 @@ pub fn init(
         config_bump: ctx.bumps.config,
         authority_bump: ctx.bumps.authority,
         token_program: ctx.accounts.token_program.key(),
-        decimals: ctx.accounts.mint.decimals,
+        ..initial_config
+    };
+
+    let mut vault_registry = ctx.accounts.vault_registry.load_init()?;
+    *vault_registry = VaultRegistry {
+        bump: ctx.bumps.vault_registry,
+        ..Default::default()
+    };
+
+    let mut benefactor_registry = ctx.accounts.benefactor_registry.load_init()?;
+    *benefactor_registry = BenefactorRegistry {
+        bump: ctx.bumps.benefactor_registry,
         ..Default::default()
     };
 
@@ -96,6 +155,14 @@ pub fn init(
         operator_authority: ctx.accounts.upgrade_authority.key(),
         role: u64::MAX,
         status: OperatorStatus::Enabled,
+        has_log: 1,
+        ..Default::default()
+    };
+
+    let mut operator_log = ctx.accounts.operator_log.load_init()?;
+    *operator_log = OperatorLog {
+        operator: ctx.accounts.operator.key(),
+        bump: ctx.bumps.operator_log,
         ..Default::default()
     };
 
@@ -103,24 +170,201 @@ pub fn init(
     metadata::create_metadata_accounts_v3(
         ctx.accounts
             .create_metadata()
-            .with_signer(&[authority_seeds!(config.authority_bump)]),
-        DataV2 {
-            name: name.clone(),
-            symbol: symbol.clone(),
-            uri: uri.clone(),
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        },
+            .with_signer(&[authority_seeds!(config)]),
+        metadata_data(name, symbol, uri),
+        true,
+        true,
+        None,
+    )?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.vault_registry.key(),
+            ctx.accounts.benefactor_registry.key(),
+            ctx.accounts.operator.key(),
+            ctx.accounts.operator_log.key(),
+        ],
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct InitInstance<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [AUTHORITY_PREFIX, mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: checked with seeds constraint
+    pub authority: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = decimals,
+        mint::authority = authority,
+        mint::token_program = token_program,
+        mint::freeze_authority = authority,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        address = Metadata::find_pda(&mint.key()).0
+    )]
+    /// CHECK: checked with constraint
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Config::MAX_SIZE,
+        seeds = [CONFIG_PREFIX, mint.key().as_ref()],
+        bump
+    )]
+    pub config: AccountLoader<'info, Config>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + VaultRegistry::MAX_SIZE,
+        seeds = [VAULT_REGISTRY_PREFIX, mint.key().as_ref()],
+        bump
+    )]
+    pub vault_registry: AccountLoader<'info, VaultRegistry>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BenefactorRegistry::MAX_SIZE,
+        seeds = [BENEFACTOR_REGISTRY_PREFIX, mint.key().as_ref(), &0u32.to_le_bytes()],
+        bump
+    )]
+    pub benefactor_registry: AccountLoader<'info, BenefactorRegistry>,
+
+    pub metadata_program: Program<'info, anchor_spl::metadata::Metadata>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
+}
+
+/// Creates another stablecoin next to the one `init` created. Its config, authority, registries,
+/// vaults and benefactors are seeded by its LP mint, so it shares nothing with the other instances
+/// but the operators and the collateral approvals.
+pub fn init_instance(
+    ctx: Context<InitInstance>,
+    decimals: u8,
+    name: String,
+    symbol: String,
+    uri: String,
+    params: InitConfigParams,
+) -> Result<()> {
+    let operator = ctx.accounts.operator.load()?;
+    operator.is(OperatorRole::Admin)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::InitInstance,
+        ctx.accounts.config.key(),
+    )?;
+
+    let initial_config = initial_config(decimals, &params)?;
+
+    let mut config = ctx.accounts.config.load_init()?;
+    *config = Config {
+        mint: ctx.accounts.mint.key(),
+        authority: ctx.accounts.authority.key(),
+        config_bump: ctx.bumps.config,
+        authority_bump: ctx.bumps.authority,
+        token_program: ctx.accounts.token_program.key(),
+        instance_seeded: 1,
+        ..initial_config
+    };
+
+    let mut vault_registry = ctx.accounts.vault_registry.load_init()?;
+    *vault_registry = VaultRegistry {
+        bump: ctx.bumps.vault_registry,
+        ..Default::default()
+    };
+
+    let mut benefactor_registry = ctx.accounts.benefactor_registry.load_init()?;
+    *benefactor_registry = BenefactorRegistry {
+        bump: ctx.bumps.benefactor_registry,
+        ..Default::default()
+    };
+
+    metadata::create_metadata_accounts_v3(
+        ctx.accounts
+            .create_metadata()
+            .with_signer(&[authority_seeds!(config)]),
+        metadata_data(name, symbol, uri),
         true,
         true,
         None,
     )?;
 
+    emit!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.vault_registry.key(),
+            ctx.accounts.benefactor_registry.key(),
+        ],
+    });
     Ok(())
 }
 
+/// Validates `decimals` and `params`, and returns the config fields they set.
+fn initial_config(decimals: u8, params: &InitConfigParams) -> Result<Config> {
+    require!(
+        (MIN_LP_DECIMALS..=MAX_LP_DECIMALS).contains(&decimals),
+        JupStableError::InvalidDecimals
+    );
+    check_peg_price_usd(params.peg_price_usd)?;
+
+    let mut config = Config {
+        decimals,
+        benefactor_registry_pages: 1,
+        peg_price_usd: params.peg_price_usd,
+        default_vault_period_limit: template(&params.default_vault_period_limit)?,
+        default_benefactor_period_limit: template(&params.default_benefactor_period_limit)?,
+        ..Default::default()
+    };
+    config.update_mint_redeem_enabled(params.is_mint_redeem_enabled);
+    Ok(config)
+}
+
+fn metadata_data(name: String, symbol: String, uri: String) -> DataV2 {
+    DataV2 {
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    }
+}
+
+fn template(limit: &PeriodLimitTemplate) -> Result<PeriodLimitTemplate> {
+    PeriodLimitTemplate::new(
+        limit.duration_seconds,
+        limit.max_mint_amount,
+        limit.max_redeem_amount,
+    )
+}
+
 impl<'info> Init<'info> {
     fn create_metadata(&self) -> CpiContext<'_, '_, '_, 'info, CreateMetadataAccountsV3<'info>> {
         let cpi_accounts = CreateMetadataAccountsV3 {
@@ -137,3 +381,20 @@ impl<'info> Init<'info> {
         CpiContext::new(cpi_program, cpi_accounts)
     }
 }
+
+impl<'info> InitInstance<'info> {
+    fn create_metadata(&self) -> CpiContext<'_, '_, '_, 'info, CreateMetadataAccountsV3<'info>> {
+        let cpi_accounts = CreateMetadataAccountsV3 {
+            metadata: self.metadata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            mint_authority: self.authority.to_account_info(),
+            payer: self.payer.to_account_info(),
+            update_authority: self.authority.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        };
+
+        let cpi_program = self.metadata_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
@@ -1,13 +1,18 @@
 pub use admin::*;
 pub use benefactor::*;
+pub use flash_mint::*;
 pub use init::*;
 pub use operator::*;
+pub use relay::*;
 pub use user::*;
 pub use vault::*;
 
+mod accounting;
 mod admin;
 mod benefactor;
+mod flash_mint;
 mod init;
 mod operator;
+mod relay;
 mod user;
 mod vault;
@@ -2,7 +2,12 @@ use anchor_lang::prelude::*;
 
 use crate::{
     error::JupStableError,
-    state::operator::{Operator, OperatorRole, OperatorStatus, OPERATOR_PREFIX},
+    event::AccountTouchedEvent,
+    state::{
+        config::{Config, CONFIG_PREFIX},
+        operator::{Operator, OperatorRole, OperatorStatus, OPERATOR_PREFIX},
+        operator_log::{OperatorAction, OperatorLog, OPERATOR_LOG_PREFIX},
+    },
 };
 
 #[derive(Accounts)]
@@ -25,39 +30,89 @@ pub struct CreateOperator<'info> {
         bump
     )]
     pub new_operator: AccountLoader<'info, Operator>,
+    /// Created along with the operator, so none of its actions goes unrecorded
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OperatorLog::MAX_SIZE,
+        seeds = [OPERATOR_LOG_PREFIX, new_operator.key().as_ref()],
+        bump
+    )]
+    pub new_operator_log: AccountLoader<'info, OperatorLog>,
     pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 pub fn create_operator(ctx: Context<CreateOperator>, role: OperatorRole) -> Result<()> {
     let operator = ctx.accounts.operator.load()?;
     operator.is(OperatorRole::Admin)?;
+    operator.can_grant(role)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::CreateOperator,
+        ctx.accounts.new_operator.key(),
+    )?;
 
     let mut new_operator = ctx.accounts.new_operator.load_init()?;
     *new_operator = Operator {
         operator_authority: ctx.accounts.new_operator_authority.key(),
         status: OperatorStatus::Enabled,
+        has_log: 1,
         ..Default::default()
     };
     new_operator.set_role(role);
 
+    let mut new_operator_log = ctx.accounts.new_operator_log.load_init()?;
+    *new_operator_log = OperatorLog {
+        operator: ctx.accounts.new_operator.key(),
+        bump: ctx.bumps.new_operator_log,
+        ..Default::default()
+    };
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.new_operator.key(),
+            ctx.accounts.new_operator_log.key(),
+        ],
+    });
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct DeleteOperator<'info> {
     pub operator_authority: Signer<'info>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.load()?.is_rent_receiver(&receiver.key()) @ JupStableError::InvalidRentReceiver,
+    )]
+    /// CHECK: Will only receive rent
+    pub receiver: UncheckedAccount<'info>,
     #[account(
         has_one = operator_authority @ JupStableError::NotAuthorized,
     )]
     pub operator: AccountLoader<'info, Operator>,
 
+    /// Operators are deployment-wide, so their rent goes by the config `init` created
+    #[account(
+        seeds = [CONFIG_PREFIX],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
     #[account(
         mut,
-        close = payer
+        close = receiver
     )]
     pub deleted_operator: AccountLoader<'info, Operator>,
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 pub fn delete_operator(ctx: Context<DeleteOperator>) -> Result<()> {
@@ -68,7 +123,15 @@ pub fn delete_operator(ctx: Context<DeleteOperator>) -> Result<()> {
 
     let operator = ctx.accounts.operator.load()?;
     operator.is(OperatorRole::Admin)?;
-
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::DeleteOperator,
+        ctx.accounts.deleted_operator.key(),
+    )?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.deleted_operator.key()],
+    });
     Ok(())
 }
 
@@ -83,6 +146,11 @@ pub struct ManageOperator<'info> {
     #[account(mut)]
     pub managed_operator: AccountLoader<'info, Operator>,
     pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -98,6 +166,14 @@ pub fn manage_operator(
 ) -> Result<()> {
     let operator = ctx.accounts.operator.load()?;
     operator.is(OperatorRole::Admin)?;
+    if let OperatorManagementAction::SetRole { role } = action {
+        operator.can_grant(role)?;
+    }
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::ManageOperator,
+        ctx.accounts.managed_operator.key(),
+    )?;
     drop(operator);
 
     let mut managed_operator = ctx.accounts.managed_operator.load_mut()?;
@@ -113,5 +189,51 @@ pub fn manage_operator(
         },
     }
 
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.managed_operator.key()],
+    });
+    Ok(())
+}
+
+/// Operators created before logs became mandatory have to create theirs once before acting again.
+#[derive(Accounts)]
+pub struct CreateOperatorLog<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OperatorLog::MAX_SIZE,
+        seeds = [OPERATOR_LOG_PREFIX, operator.key().as_ref()],
+        bump
+    )]
+    pub operator_log: AccountLoader<'info, OperatorLog>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_operator_log(ctx: Context<CreateOperatorLog>) -> Result<()> {
+    let mut operator = ctx.accounts.operator.load_mut()?;
+    require!(
+        operator.status == OperatorStatus::Enabled,
+        JupStableError::OperatorDisabled
+    );
+    operator.has_log = 1;
+
+    let mut operator_log = ctx.accounts.operator_log.load_init()?;
+    *operator_log = OperatorLog {
+        operator: ctx.accounts.operator.key(),
+        bump: ctx.bumps.operator_log,
+        ..Default::default()
+    };
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.operator.key(), ctx.accounts.operator_log.key()],
+    });
     Ok(())
 }
@@ -0,0 +1,332 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    burn, mint_to, transfer_checked, Burn, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use super::accounting::Accounting;
+use crate::{
+    authority_seeds,
+    error::JupStableError,
+    event::AccountTouchedEvent,
+    state::{
+        benefactor::Benefactor,
+        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
+        vault::{Vault, VAULT_PREFIX},
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RelayedMint<'info> {
+    pub relayer: Signer<'info>,
+    /// CHECK: checked with constraint on benefactor
+    pub user: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = vault_mint,
+        token::authority = user,
+    )]
+    pub user_collateral_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+    )]
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+        constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
+        constraint = config.load()?.authority == authority.key() @ JupStableError::InvalidAuthority,
+        constraint = config.load()?.token_program == lp_token_program.key() @ JupStableError::InvalidTokenProgram,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    #[account(
+        seeds = [AUTHORITY_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.authority_bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub lp_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX, config.load()?.instance_seed(), vault_mint.key().as_ref()],
+        bump = vault.load()?.bump,
+        constraint = vault.load()?.custodian == custodian.key() @ JupStableError::InvalidCustodian,
+        constraint = vault.load()?.mint == vault_mint.key() @ JupStableError::InvalidVaultMint,
+        constraint = vault.load()?.token_program == vault_token_program.key() @ JupStableError::InvalidTokenProgram,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub vault_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    /// CHECK: checked with constraint on vault
+    pub custodian: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = vault_mint,
+        token::authority = custodian,
+        token::token_program = vault_token_program,
+        constraint = vault.load()?.resolve_custodian_token_account() == custodian_token_account.key() @ JupStableError::InvalidCustodianTokenAccount,
+    )]
+    pub custodian_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = benefactor.load()?.authority == user.key() @ JupStableError::InvalidBenefactor,
+        constraint = benefactor.load()?.is_relayer(&relayer.key()) @ JupStableError::InvalidRelayer,
+        constraint = benefactor.load()?.instance_mint == config.load()?.instance_mint() @ JupStableError::InstanceMismatch,
+    )]
+    pub benefactor: AccountLoader<'info, Benefactor>,
+
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    pub vault_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mints for the benefactor's authority without its signature. The relayer signs instead, and the
+/// collateral is pulled by the authority PDA, which the user has to approve as delegate of its
+/// collateral token account beforehand.
+pub fn relayed_mint(ctx: Context<RelayedMint>, amount: u64, min_amount_out: u64) -> Result<()> {
+    require!(amount > 0, JupStableError::ZeroAmount);
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let mut benefactor = ctx.accounts.benefactor.load_mut()?;
+    let mut config = ctx.accounts.config.load_mut()?;
+
+    let clock = Clock::get()?;
+    let mut accounting = Accounting {
+        config: &mut config,
+        vault: &mut vault,
+        benefactor: &mut benefactor,
+        clock: &clock,
+    };
+
+    // Oracle accounts are passed as remaining_accounts, the quote oracle's last
+    let pricing = accounting.price_mint(
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.vault_mint.decimals,
+        ctx.accounts.lp_mint.decimals,
+    )?;
+    accounting.check_mint(
+        &pricing,
+        ctx.accounts.lp_mint.supply,
+        min_amount_out,
+        "relayed mint",
+    )?;
+
+    emit_cpi!(accounting.mint_event(&pricing)?);
+    accounting.record_mint(&pricing)?;
+
+    let amount_before = ctx.accounts.custodian_token_account.amount;
+    transfer_checked(
+        ctx.accounts
+            .deposit_collateral()
+            .with_signer(&[authority_seeds!(config)]),
+        amount,
+        ctx.accounts.vault_mint.decimals,
+    )?;
+    ctx.accounts.custodian_token_account.reload()?;
+    let amount_after = ctx.accounts.custodian_token_account.amount;
+    require!(
+        amount_after == amount_before + amount,
+        JupStableError::InsufficientAmount
+    );
+
+    mint_to(
+        ctx.accounts
+            .mint_lp_tokens()
+            .with_signer(&[authority_seeds!(config)]),
+        pricing.mint_amount,
+    )?;
+
+    emit_cpi!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.vault.key(),
+            ctx.accounts.benefactor.key()
+        ],
+    });
+    Ok(())
+}
+
+impl<'info> RelayedMint<'info> {
+    fn deposit_collateral(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.user_collateral_token_account.to_account_info(),
+            mint: self.vault_mint.to_account_info(),
+            to: self.custodian_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.vault_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    fn mint_lp_tokens(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_accounts = MintTo {
+            mint: self.lp_mint.to_account_info(),
+            to: self.user_lp_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.lp_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RelayedRedeem<'info> {
+    pub relayer: Signer<'info>,
+    /// CHECK: checked with constraint on benefactor
+    pub user: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = user,
+    )]
+    pub user_lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = vault_mint,
+        token::authority = user,
+    )]
+    pub user_collateral_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+        constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
+        constraint = config.load()?.authority == authority.key() @ JupStableError::InvalidAuthority,
+        constraint = config.load()?.token_program == lp_token_program.key() @ JupStableError::InvalidTokenProgram,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    #[account(
+        seeds = [AUTHORITY_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.authority_bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub lp_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX, config.load()?.instance_seed(), vault_mint.key().as_ref()],
+        bump = vault.load()?.bump,
+        constraint = vault.load()?.mint == vault_mint.key() @ JupStableError::InvalidVaultMint,
+        constraint = vault.load()?.token_account == vault_token_account.key() @ JupStableError::InvalidVaultTokenAccount,
+        constraint = vault.load()?.token_program == vault_token_program.key() @ JupStableError::InvalidTokenProgram,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(mut)]
+    pub vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub vault_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(
+        mut,
+        constraint = benefactor.load()?.authority == user.key() @ JupStableError::InvalidBenefactor,
+        constraint = benefactor.load()?.is_relayer(&relayer.key()) @ JupStableError::InvalidRelayer,
+        constraint = benefactor.load()?.instance_mint == config.load()?.instance_mint() @ JupStableError::InstanceMismatch,
+    )]
+    pub benefactor: AccountLoader<'info, Benefactor>,
+
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    pub vault_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Redeems for the benefactor's authority without its signature. The relayer signs instead, and
+/// the jupUSD is burnt by the authority PDA, which the user has to approve as delegate of its
+/// jupUSD token account beforehand. The collateral only ever goes to the user's own account.
+pub fn relayed_redeem(ctx: Context<RelayedRedeem>, amount: u64, min_amount_out: u64) -> Result<()> {
+    require!(amount > 0, JupStableError::ZeroAmount);
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let mut benefactor = ctx.accounts.benefactor.load_mut()?;
+    let mut config = ctx.accounts.config.load_mut()?;
+
+    let clock = Clock::get()?;
+    let mut accounting = Accounting {
+        config: &mut config,
+        vault: &mut vault,
+        benefactor: &mut benefactor,
+        clock: &clock,
+    };
+
+    let pricing = accounting.price_redeem(
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.lp_mint.decimals,
+        ctx.accounts.vault_mint.decimals,
+    )?;
+    accounting.check_redeem(
+        &pricing,
+        ctx.accounts.lp_mint.supply,
+        ctx.accounts.vault_token_account.amount,
+        min_amount_out,
+        "relayed redeem",
+    )?;
+
+    emit_cpi!(accounting.redeem_event(&pricing)?);
+    accounting.record_redeem(&pricing)?;
+
+    burn(
+        ctx.accounts
+            .burn_lp_tokens()
+            .with_signer(&[authority_seeds!(config)]),
+        amount,
+    )?;
+
+    let amount_before = ctx.accounts.vault_token_account.amount;
+    transfer_checked(
+        ctx.accounts
+            .withdraw_collateral()
+            .with_signer(&[authority_seeds!(config)]),
+        pricing.redeem_amount,
+        ctx.accounts.vault_mint.decimals,
+    )?;
+    ctx.accounts.vault_token_account.reload()?;
+    let amount_after = ctx.accounts.vault_token_account.amount;
+    require!(
+        amount_after == amount_before - pricing.redeem_amount,
+        JupStableError::InsufficientAmount
+    );
+
+    emit_cpi!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.vault.key(),
+            ctx.accounts.benefactor.key()
+        ],
+    });
+    Ok(())
+}
+
+impl<'info> RelayedRedeem<'info> {
+    fn burn_lp_tokens(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_accounts = Burn {
+            mint: self.lp_mint.to_account_info(),
+            from: self.user_lp_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.lp_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    fn withdraw_collateral(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault_token_account.to_account_info(),
+            mint: self.vault_mint.to_account_info(),
+            to: self.user_collateral_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.vault_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
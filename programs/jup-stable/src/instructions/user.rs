@@ -1,17 +1,17 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::program::set_return_data};
 use anchor_spl::token_interface::{
     burn, mint_to, transfer_checked, Burn, MintTo, TokenAccount, TokenInterface, TransferChecked,
 };
-use rust_decimal::{prelude::ToPrimitive, Decimal};
 
+use super::accounting::Accounting;
 use crate::{
     authority_seeds,
     error::JupStableError,
-    oracle::OraclePrice,
+    event::AccountTouchedEvent,
     state::{
         benefactor::Benefactor,
-        config::{Config, AUTHORITY_PREFIX, PEG_PRICE_DECIMALS},
-        vault::Vault,
+        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
+        vault::{Vault, VAULT_PREFIX},
     },
 };
 
@@ -35,18 +35,26 @@ pub struct Mint<'info> {
 
     #[account(
         mut,
+        seeds = [CONFIG_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
         constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
         constraint = config.load()?.authority == authority.key() @ JupStableError::InvalidAuthority,
         constraint = config.load()?.token_program == lp_token_program.key() @ JupStableError::InvalidTokenProgram,
     )]
     pub config: AccountLoader<'info, Config>,
     /// CHECK: checked with constraint
+    #[account(
+        seeds = [AUTHORITY_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.authority_bump,
+    )]
     pub authority: UncheckedAccount<'info>,
     #[account(mut)]
     pub lp_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
 
     #[account(
         mut,
+        seeds = [VAULT_PREFIX, config.load()?.instance_seed(), vault_mint.key().as_ref()],
+        bump = vault.load()?.bump,
         constraint = vault.load()?.custodian == custodian.key() @ JupStableError::InvalidCustodian,
         constraint = vault.load()?.mint == vault_mint.key() @ JupStableError::InvalidVaultMint,
         constraint = vault.load()?.token_program == vault_token_program.key() @ JupStableError::InvalidTokenProgram,
@@ -58,15 +66,17 @@ pub struct Mint<'info> {
     pub custodian: UncheckedAccount<'info>,
     #[account(
         mut,
-        associated_token::authority = custodian,
-        associated_token::mint = vault_mint,
-        associated_token::token_program = vault_token_program,
+        token::mint = vault_mint,
+        token::authority = custodian,
+        token::token_program = vault_token_program,
+        constraint = vault.load()?.resolve_custodian_token_account() == custodian_token_account.key() @ JupStableError::InvalidCustodianTokenAccount,
     )]
     pub custodian_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        constraint = benefactor.load()?.authority == user.key() @ JupStableError::InvalidBenefactor,
+        constraint = benefactor.load()?.authority == user.key() || (!vault.load()?.requires_benefactor() && benefactor.load()?.authority == vault.key()) @ JupStableError::InvalidBenefactor,
+        constraint = benefactor.load()?.instance_mint == config.load()?.instance_mint() @ JupStableError::InstanceMismatch,
     )]
     pub benefactor: AccountLoader<'info, Benefactor>,
 
@@ -75,7 +85,9 @@ pub struct Mint<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn mint(ctx: Context<Mint>, amount: u64, min_amount_out: u64) -> Result<()> {
+/// With `dry_run`, runs every check of a mint, including the user's collateral balance, and
+/// returns the [`MintQuote`] as return data without moving tokens or updating any account.
+pub fn mint(ctx: Context<Mint>, amount: u64, min_amount_out: u64, dry_run: bool) -> Result<()> {
     require!(amount > 0, JupStableError::ZeroAmount);
 
     let mut vault = ctx.accounts.vault.load_mut()?;
@@ -83,53 +95,52 @@ pub fn mint(ctx: Context<Mint>, amount: u64, min_amount_out: u64) -> Result<()>
     let mut config = ctx.accounts.config.load_mut()?;
 
     let clock = Clock::get()?;
-    let current_time = clock.unix_timestamp;
-
-    // Oracle accounts are passed as remaining_accounts
-    let oracle_accounts = &ctx.remaining_accounts;
-    let oracle_price = OraclePrice::parse_oracles(
-        &vault.oracles,
-        oracle_accounts,
-        &clock,
-        vault.stalesness_threshold,
-    )?;
-
-    vault.validate_oracle_price(&oracle_price, true)?;
-
-    let peg_price = Decimal::new(config.peg_price_usd.try_into()?, PEG_PRICE_DECIMALS);
-    let net_amount = amount - benefactor.calculate_mint_fee(amount);
-
-    let (mint_amount, one_to_one_amount, oracle_amount) = compute_mint_amount(
+    let mut accounting = Accounting {
+        config: &mut config,
+        vault: &mut vault,
+        benefactor: &mut benefactor,
+        clock: &clock,
+    };
+
+    // Oracle accounts are passed as remaining_accounts, the quote oracle's last
+    let pricing = accounting.price_mint(
+        ctx.remaining_accounts,
         amount,
-        net_amount,
-        &oracle_price,
-        peg_price,
         ctx.accounts.vault_mint.decimals,
         ctx.accounts.lp_mint.decimals,
     )?;
+    if dry_run {
+        accounting.peek_mint(
+            &pricing,
+            ctx.accounts.lp_mint.supply,
+            min_amount_out,
+            "mint",
+        )?;
+        require!(
+            ctx.accounts.user_collateral_token_account.amount >= amount,
+            JupStableError::InsufficientAmount
+        );
+        let mut return_data = Vec::new();
+        MintQuote {
+            amount,
+            net_amount: pricing.net_amount,
+            fee_amount: pricing.fee_amount(),
+            fee_rate_bps: pricing.fee_rate_bps,
+            mint_amount: pricing.mint_amount,
+        }
+        .serialize(&mut return_data)?;
+        set_return_data(&return_data);
+        return Ok(());
+    }
 
-    emit_cpi!(MintV0Event {
-        amount,
-        net_amount,
-        oracle_price: decimal_to_u64(oracle_price.0 * Decimal::from(10_i64.pow(6)))?,
-        one_to_one_amount,
-        oracle_amount,
-        mint_amount,
-    });
-
-    config.can_mint(mint_amount, current_time)?;
-    benefactor.can_mint(mint_amount, current_time)?;
-    vault.can_mint(mint_amount, current_time)?;
-
-    require!(mint_amount > 0, JupStableError::ZeroAmount);
-    require!(
-        mint_amount >= min_amount_out,
-        JupStableError::SlippageToleranceExceeded
-    );
-
-    config.record_mint(mint_amount);
-    benefactor.record_mint(mint_amount);
-    vault.record_mint(mint_amount);
+    accounting.check_mint(
+        &pricing,
+        ctx.accounts.lp_mint.supply,
+        min_amount_out,
+        "mint",
+    )?;
+    emit_cpi!(accounting.mint_event(&pricing)?);
+    accounting.record_mint(&pricing)?;
 
     let amount_before = ctx.accounts.custodian_token_account.amount;
     transfer_checked(
@@ -147,10 +158,17 @@ pub fn mint(ctx: Context<Mint>, amount: u64, min_amount_out: u64) -> Result<()>
     mint_to(
         ctx.accounts
             .mint_lp_tokens()
-            .with_signer(&[authority_seeds!(config.authority_bump)]),
-        mint_amount,
+            .with_signer(&[authority_seeds!(config)]),
+        pricing.mint_amount,
     )?;
 
+    emit_cpi!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.vault.key(),
+            ctx.accounts.benefactor.key()
+        ],
+    });
     Ok(())
 }
 
@@ -197,18 +215,26 @@ pub struct Redeem<'info> {
 
     #[account(
         mut,
+        seeds = [CONFIG_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
         constraint = config.load()?.mint == lp_mint.key() @ JupStableError::InvalidLPMint,
         constraint = config.load()?.authority == authority.key() @ JupStableError::InvalidAuthority,
         constraint = config.load()?.token_program == lp_token_program.key() @ JupStableError::InvalidTokenProgram,
     )]
     pub config: AccountLoader<'info, Config>,
     /// CHECK: checked with constraint
+    #[account(
+        seeds = [AUTHORITY_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.authority_bump,
+    )]
     pub authority: UncheckedAccount<'info>,
     #[account(mut)]
     pub lp_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
 
     #[account(
         mut,
+        seeds = [VAULT_PREFIX, config.load()?.instance_seed(), vault_mint.key().as_ref()],
+        bump = vault.load()?.bump,
         constraint = vault.load()?.mint == vault_mint.key() @ JupStableError::InvalidVaultMint,
         constraint = vault.load()?.token_account == vault_token_account.key() @ JupStableError::InvalidVaultTokenAccount,
         constraint = vault.load()?.token_program == vault_token_program.key() @ JupStableError::InvalidTokenProgram,
@@ -221,7 +247,8 @@ pub struct Redeem<'info> {
 
     #[account(
         mut,
-        constraint = benefactor.load()?.authority == user.key() @ JupStableError::InvalidBenefactor,
+        constraint = benefactor.load()?.authority == user.key() || (!vault.load()?.requires_benefactor() && benefactor.load()?.authority == vault.key()) @ JupStableError::InvalidBenefactor,
+        constraint = benefactor.load()?.instance_mint == config.load()?.instance_mint() @ JupStableError::InstanceMismatch,
     )]
     pub benefactor: AccountLoader<'info, Benefactor>,
 
@@ -230,7 +257,9 @@ pub struct Redeem<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn redeem(ctx: Context<Redeem>, amount: u64, min_amount_out: u64) -> Result<()> {
+/// With `dry_run`, runs every check of a redeem, including the user's jupUSD balance, and returns
+/// the [`RedeemQuote`] as return data without moving tokens or updating any account.
+pub fn redeem(ctx: Context<Redeem>, amount: u64, min_amount_out: u64, dry_run: bool) -> Result<()> {
     require!(amount > 0, JupStableError::ZeroAmount);
 
     let mut vault = ctx.accounts.vault.load_mut()?;
@@ -238,56 +267,53 @@ pub fn redeem(ctx: Context<Redeem>, amount: u64, min_amount_out: u64) -> Result<
     let mut config = ctx.accounts.config.load_mut()?;
 
     let clock = Clock::get()?;
-    let current_time = clock.unix_timestamp;
-
-    let oracle_accounts = &ctx.remaining_accounts;
-    let oracle_price = OraclePrice::parse_oracles(
-        &vault.oracles,
-        oracle_accounts,
-        &clock,
-        vault.stalesness_threshold,
-    )?;
-
-    vault.validate_oracle_price(&oracle_price, false)?;
-
-    let peg_price = Decimal::new(config.peg_price_usd.try_into()?, PEG_PRICE_DECIMALS);
-    let net_amount = amount - benefactor.calculate_redeem_fee(amount);
-
-    let (redeem_amount, one_to_one_amount, oracle_amount) = compute_redeem_amount(
+    let mut accounting = Accounting {
+        config: &mut config,
+        vault: &mut vault,
+        benefactor: &mut benefactor,
+        clock: &clock,
+    };
+
+    let pricing = accounting.price_redeem(
+        ctx.remaining_accounts,
         amount,
-        net_amount,
-        &oracle_price,
-        peg_price,
         ctx.accounts.lp_mint.decimals,
         ctx.accounts.vault_mint.decimals,
     )?;
+    if dry_run {
+        accounting.peek_redeem(
+            &pricing,
+            ctx.accounts.lp_mint.supply,
+            ctx.accounts.vault_token_account.amount,
+            min_amount_out,
+            "redeem",
+        )?;
+        require!(
+            ctx.accounts.user_lp_token_account.amount >= amount,
+            JupStableError::InsufficientAmount
+        );
+        let mut return_data = Vec::new();
+        RedeemQuote {
+            amount,
+            net_amount: pricing.net_amount,
+            fee_amount: pricing.fee_amount(),
+            fee_rate_bps: pricing.fee_rate_bps,
+            redeem_amount: pricing.redeem_amount,
+        }
+        .serialize(&mut return_data)?;
+        set_return_data(&return_data);
+        return Ok(());
+    }
 
-    emit_cpi!(RedeemV0Event {
-        amount,
-        net_amount,
-        oracle_price: decimal_to_u64(oracle_price.0 * Decimal::from(10_i64.pow(6)))?,
-        one_to_one_amount,
-        oracle_amount,
-        redeem_amount,
-    });
-
-    config.can_redeem(net_amount, current_time)?;
-    vault.can_redeem(net_amount, current_time)?;
-    benefactor.can_redeem(net_amount, current_time)?;
-
-    require!(redeem_amount > 0, JupStableError::ZeroAmount);
-    require!(
-        redeem_amount >= min_amount_out,
-        JupStableError::SlippageToleranceExceeded
-    );
-    require!(
-        ctx.accounts.vault_token_account.amount >= redeem_amount,
-        JupStableError::VaultIsDry
-    );
-
-    config.record_redeem(net_amount);
-    benefactor.record_redeem(net_amount);
-    vault.record_redeem(net_amount);
+    accounting.check_redeem(
+        &pricing,
+        ctx.accounts.lp_mint.supply,
+        ctx.accounts.vault_token_account.amount,
+        min_amount_out,
+        "redeem",
+    )?;
+    emit_cpi!(accounting.redeem_event(&pricing)?);
+    accounting.record_redeem(&pricing)?;
 
     burn(ctx.accounts.burn_lp_tokens(), amount)?;
 
@@ -295,17 +321,24 @@ pub fn redeem(ctx: Context<Redeem>, amount: u64, min_amount_out: u64) -> Result<
     transfer_checked(
         ctx.accounts
             .withdraw_collateral()
-            .with_signer(&[authority_seeds!(config.authority_bump)]),
-        redeem_amount,
+            .with_signer(&[authority_seeds!(config)]),
+        pricing.redeem_amount,
         ctx.accounts.vault_mint.decimals,
     )?;
     ctx.accounts.vault_token_account.reload()?;
     let amount_after = ctx.accounts.vault_token_account.amount;
     require!(
-        amount_after == amount_before - redeem_amount,
+        amount_after == amount_before - pricing.redeem_amount,
         JupStableError::InsufficientAmount
     );
 
+    emit_cpi!(AccountTouchedEvent {
+        accounts: vec![
+            ctx.accounts.config.key(),
+            ctx.accounts.vault.key(),
+            ctx.accounts.benefactor.key()
+        ],
+    });
     Ok(())
 }
 
@@ -332,95 +365,76 @@ impl<'info> Redeem<'info> {
     }
 }
 
-pub fn calculate_mint_amount(
-    price: &OraclePrice,
-    amount: Decimal,
-    peg_price: Decimal,
-    expected_decimals: u32,
-) -> Result<Decimal> {
-    Ok((amount * price.0 / peg_price) * Decimal::from(10_i64.pow(expected_decimals)))
+/// Amounts a `mint` would settle, returned by a dry run. `fee_amount` is in collateral, taken at
+/// the benefactor's `fee_rate_bps`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct MintQuote {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub fee_amount: u64,
+    pub fee_rate_bps: u16,
+    pub mint_amount: u64,
 }
 
-pub fn calculate_redeem_amount(
-    price: &OraclePrice,
-    lp_amount: Decimal,
-    peg_price: Decimal,
-    expected_decimals: u32,
-) -> Result<Decimal> {
-    Ok((lp_amount * peg_price / price.0) * Decimal::from(10_i64.pow(expected_decimals)))
+/// Amounts a `redeem` would settle, returned by a dry run. `fee_amount` is in jupUSD, taken at the
+/// benefactor's `fee_rate_bps`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct RedeemQuote {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub fee_amount: u64,
+    pub fee_rate_bps: u16,
+    pub redeem_amount: u64,
 }
 
-fn compute_mint_amount(
-    amount: u64,
-    net_amount: u64,
-    oracle_price: &OraclePrice,
-    peg_price: Decimal,
-    vault_mint_decimals: u8,
-    lp_mint_decimals: u8,
-) -> Result<(u64, u64, u64)> {
-    let vault_decimals = vault_mint_decimals as u32;
-    let lp_decimals = lp_mint_decimals as u32;
-
-    // Calculate 1:1 exchange rate amount (net amount after fees)
-    let one_to_one_amount = Decimal::new(net_amount.try_into()?, vault_decimals) / peg_price
-        * Decimal::from(10_i64.pow(lp_decimals));
-
-    // Calculate oracle-based amount
-    let oracle_amount = calculate_mint_amount(
-        oracle_price,
-        Decimal::new(amount.try_into()?, vault_decimals),
-        peg_price,
-        lp_decimals,
-    )?;
-
-    // Take the minimum and convert back to u64
-    let mint_amount_decimal = oracle_amount.min(one_to_one_amount);
-    let mint_amount = decimal_to_u64(mint_amount_decimal)?;
+#[derive(Accounts)]
+pub struct GetCapacity<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX, config.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    #[account(
+        seeds = [VAULT_PREFIX, config.load()?.instance_seed(), vault.load()?.mint.as_ref()],
+        bump = vault.load()?.bump,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        constraint = benefactor.load()?.instance_mint == config.load()?.instance_mint() @ JupStableError::InstanceMismatch,
+    )]
+    pub benefactor: AccountLoader<'info, Benefactor>,
+}
 
-    Ok((
-        mint_amount,
-        decimal_to_u64(one_to_one_amount)?,
-        decimal_to_u64(oracle_amount)?,
-    ))
+/// Largest amounts, in jupUSD, that `benefactor` can mint and redeem against `vault` right now
+/// under the period limits and caps of the config, vault and benefactor. `redeem_amount` is net
+/// of the redeem fee. Returned by the `get_capacity` instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct Capacity {
+    pub mint_amount: u64,
+    pub redeem_amount: u64,
 }
 
-fn compute_redeem_amount(
-    amount: u64,
-    net_amount: u64,
-    oracle_price: &OraclePrice,
-    peg_price: Decimal,
-    lp_mint_decimals: u8,
-    vault_mint_decimals: u8,
-) -> Result<(u64, u64, u64)> {
-    let lp_decimals = lp_mint_decimals as u32;
-    let vault_decimals = vault_mint_decimals as u32;
-
-    // Calculate 1:1 exchange rate amount (net amount after fees)
-    let one_to_one_amount = Decimal::new(net_amount.try_into()?, lp_decimals)
-        * peg_price
-        * Decimal::from(10_i64.pow(vault_decimals));
-
-    // Calculate oracle-based amount
-    let oracle_amount = calculate_redeem_amount(
-        oracle_price,
-        Decimal::new(amount.try_into()?, lp_decimals),
-        peg_price,
-        vault_decimals,
-    )?;
+pub fn get_capacity(ctx: Context<GetCapacity>) -> Result<Capacity> {
+    let config = ctx.accounts.config.load()?;
+    let vault = ctx.accounts.vault.load()?;
+    let benefactor = ctx.accounts.benefactor.load()?;
 
-    // Take the minimum and convert to u64
-    let redeem_amount_decimal = oracle_amount.min(one_to_one_amount);
-    let redeem_amount = decimal_to_u64(redeem_amount_decimal)?;
+    let current_time = Clock::get()?.unix_timestamp;
+    let strict_period_limits = config.is_strict_period_limits();
 
-    Ok((
-        redeem_amount,
-        decimal_to_u64(one_to_one_amount)?,
-        decimal_to_u64(oracle_amount)?,
-    ))
-}
+    let mint_amount = config
+        .mint_capacity(current_time)
+        .min(vault.mint_capacity(current_time, strict_period_limits))
+        .min(benefactor.mint_capacity(current_time, strict_period_limits));
+    let redeem_amount = config
+        .redeem_capacity(current_time, benefactor.is_exempt_from_global_pause())
+        .min(vault.redeem_capacity(current_time, strict_period_limits))
+        .min(benefactor.redeem_capacity(current_time, strict_period_limits));
 
-fn decimal_to_u64(value: Decimal) -> Result<u64> {
-    value.to_u64().ok_or(error!(JupStableError::MathOverflow))
+    Ok(Capacity {
+        mint_amount,
+        redeem_amount,
+    })
 }
 
 #[event]
@@ -442,3 +456,33 @@ pub struct RedeemV0Event {
     pub oracle_amount: u64,
     pub redeem_amount: u64,
 }
+
+/// Emitted by `mint`, `relayed_mint` and `flash_mint` in place of `MintV0Event`, with the fee the
+/// mint was priced with.
+#[event]
+pub struct MintV1Event {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub oracle_price: u64,
+    pub one_to_one_amount: u64,
+    pub oracle_amount: u64,
+    pub mint_amount: u64,
+    /// Benefactor fee taken out of `amount`, in collateral
+    pub fee_amount: u64,
+    pub fee_rate_bps: u16,
+}
+
+/// Emitted by `redeem` and `relayed_redeem` in place of `RedeemV0Event`, with the fee the redeem was
+/// priced with.
+#[event]
+pub struct RedeemV1Event {
+    pub amount: u64,
+    pub net_amount: u64,
+    pub oracle_price: u64,
+    pub one_to_one_amount: u64,
+    pub oracle_amount: u64,
+    pub redeem_amount: u64,
+    /// Benefactor fee taken out of `amount`, in jupUSD
+    pub fee_amount: u64,
+    pub fee_rate_bps: u16,
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
+    memo::{build_memo, BuildMemo, Memo},
     token_2022::{transfer_checked, TransferChecked},
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
@@ -8,13 +9,17 @@ use anchor_spl::{
 use crate::{
     authority_seeds,
     error::JupStableError,
+    event::{AccountTouchedEvent, DepositAcknowledgedEvent, WithdrawEvent},
     state::{
-        config::{Config, AUTHORITY_PREFIX},
+        collateral_approval::{CollateralApproval, COLLATERAL_APPROVAL_PREFIX},
+        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
         operator::{Operator, OperatorRole},
+        operator_log::{OperatorAction, OperatorLog},
         vault::{
             DovesOracle, EmptyOracle, OracleType, PythV2Oracle, SwitchboardOnDemandOracle, Vault,
             VaultStatus, VAULT_PREFIX,
         },
+        vault_registry::{VaultRegistry, VAULT_REGISTRY_PREFIX},
     },
 };
 
@@ -49,6 +54,73 @@ impl From<OracleConfig> for OracleType {
     }
 }
 
+#[derive(Accounts)]
+pub struct ApproveCollateral<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CollateralApproval::MAX_SIZE,
+        seeds = [COLLATERAL_APPROVAL_PREFIX, mint.key().as_ref()],
+        bump
+    )]
+    pub collateral_approval: AccountLoader<'info, CollateralApproval>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
+}
+
+/// Admin approval of `mint` as collateral, so a VaultManager alone can't onboard any token.
+pub fn approve_collateral(ctx: Context<ApproveCollateral>) -> Result<()> {
+    let operator = ctx.accounts.operator.load()?;
+    operator.is(OperatorRole::Admin)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::ApproveCollateral,
+        ctx.accounts.collateral_approval.key(),
+    )?;
+
+    let mint = ctx.accounts.mint.key();
+    require!(
+        mint != ctx.accounts.config.load()?.mint,
+        JupStableError::InvalidVaultMint
+    );
+
+    let mut collateral_approval = ctx.accounts.collateral_approval.load_init()?;
+    *collateral_approval = CollateralApproval {
+        mint,
+        approved_by: ctx.accounts.operator.key(),
+        approved_at: Clock::get()?.unix_timestamp,
+        bump: ctx.bumps.collateral_approval,
+        ..Default::default()
+    };
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.collateral_approval.key()],
+    });
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct CreateVault<'info> {
     pub operator_authority: Signer<'info>,
@@ -68,15 +140,28 @@ pub struct CreateVault<'info> {
     /// CHECK: checked with constraint
     pub authority: UncheckedAccount<'info>,
 
+    #[account(
+        seeds = [COLLATERAL_APPROVAL_PREFIX, mint.key().as_ref()],
+        bump = collateral_approval.load()?.bump,
+    )]
+    pub collateral_approval: AccountLoader<'info, CollateralApproval>,
+
     #[account(
         init,
         payer = payer,
         space = 8 + Vault::MAX_SIZE,
-        seeds = [VAULT_PREFIX, mint.key().as_ref()],
+        seeds = [VAULT_PREFIX, config.load()?.instance_seed(), mint.key().as_ref()],
         bump
     )]
     pub vault: AccountLoader<'info, Vault>,
 
+    #[account(
+        mut,
+        seeds = [VAULT_REGISTRY_PREFIX, config.load()?.instance_seed()],
+        bump = vault_registry.load()?.bump,
+    )]
+    pub vault_registry: AccountLoader<'info, VaultRegistry>,
+
     #[account(
         init_if_needed,
         payer = payer,
@@ -89,15 +174,30 @@ pub struct CreateVault<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
     let config = ctx.accounts.config.load()?;
     let operator = ctx.accounts.operator.load()?;
     operator.is(OperatorRole::VaultManager)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::CreateVault,
+        ctx.accounts.vault.key(),
+    )?;
 
     let mint = ctx.accounts.mint.key();
     require!(mint != config.mint, JupStableError::InvalidVaultMint);
+    require!(
+        ctx.accounts.mint.decimals <= jupusd_math::MAX_COLLATERAL_DECIMALS,
+        JupStableError::UnsupportedCollateralDecimals
+    );
 
     let mut vault = ctx.accounts.vault.load_init()?;
     *vault = Vault {
@@ -107,9 +207,76 @@ pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
         token_program: ctx.accounts.token_program.key(),
         status: VaultStatus::Disabled,
         bump: ctx.bumps.vault,
+        instance_mint: config.instance_mint(),
+        ..Default::default()
+    };
+    config
+        .default_vault_period_limit
+        .apply(&mut vault.period_limits[0], Clock::get()?.unix_timestamp)?;
+
+    ctx.accounts.vault_registry.load_mut()?.push(mint)?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.vault.key(), ctx.accounts.vault_registry.key()],
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateVaultRegistry<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ JupStableError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + VaultRegistry::MAX_SIZE,
+        seeds = [VAULT_REGISTRY_PREFIX],
+        bump
+    )]
+    pub vault_registry: AccountLoader<'info, VaultRegistry>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
+}
+
+/// Creates the registry for deployments initialized before it existed. The remaining accounts are
+/// the vaults created so far, registered in the order given.
+pub fn create_vault_registry<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateVaultRegistry<'info>>,
+) -> Result<()> {
+    let operator = ctx.accounts.operator.load()?;
+    operator.is(OperatorRole::Admin)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::CreateVaultRegistry,
+        ctx.accounts.vault_registry.key(),
+    )?;
+
+    let mut vault_registry = ctx.accounts.vault_registry.load_init()?;
+    *vault_registry = VaultRegistry {
+        bump: ctx.bumps.vault_registry,
         ..Default::default()
     };
+    for account in ctx.remaining_accounts {
+        let vault = AccountLoader::<Vault>::try_from(account)?;
+        vault_registry.push(vault.load()?.mint)?;
+    }
 
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.vault_registry.key()],
+    });
     Ok(())
 }
 
@@ -121,8 +288,20 @@ pub struct ManageVault<'info> {
     )]
     pub operator: AccountLoader<'info, Operator>,
 
+    #[account(
+        seeds = [CONFIG_PREFIX, vault.load()?.instance_seed()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
     #[account(mut)]
     pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -156,6 +335,42 @@ pub enum VaultManagementAction {
     SetMaxOraclePrice {
         max_oracle_price_usd: u64,
     },
+    SetMaxPriceJump {
+        max_price_jump_bps: u16,
+    },
+    /// Zero falls back to the staleness threshold
+    SetPriceJumpWindow {
+        price_jump_window_seconds: u32,
+    },
+    SetConfidenceFactor {
+        confidence_factor_bps: u16,
+    },
+    /// Checks both oracle price bounds on mint and redeem alike
+    SetSymmetricOracleBounds {
+        enabled: bool,
+    },
+    /// When disabled, any user may also mint and redeem against the vault through its public
+    /// benefactor, the one whose authority is the vault address itself
+    SetRequiresBenefactor {
+        required: bool,
+    },
+    SetMaxNetOutstanding {
+        max_net_outstanding: u64,
+    },
+    /// Zero leaves that side uncapped
+    SetPerTxLimits {
+        max_mint_per_tx: u64,
+        max_redeem_per_tx: u64,
+    },
+    /// Default goes back to the custodian's ATA
+    SetCustodianTokenAccount {
+        custodian_token_account: Pubkey,
+    },
+    /// USD to peg currency feed the vault's prices are converted with. Mint and redeem fail
+    /// without one unless the stablecoin is pegged to USD, where it is `OracleConfig::None`.
+    UpdateQuoteOracle {
+        oracle: OracleConfig,
+    },
 }
 
 pub fn manage_vault(ctx: Context<ManageVault>, action: VaultManagementAction) -> Result<()> {
@@ -167,12 +382,18 @@ pub fn manage_vault(ctx: Context<ManageVault>, action: VaultManagementAction) ->
             operator.is(OperatorRole::VaultDisabler)?;
 
             vault.is_enabled()?;
-            vault.status = VaultStatus::Disabled;
+            vault.disable(Clock::get()?.unix_timestamp);
         },
         VaultManagementAction::SetStatus { status } => {
             operator.is(OperatorRole::VaultManager)?;
 
             if status == VaultStatus::Enabled {
+                let config = ctx.accounts.config.load()?;
+                vault.check_reactivation(
+                    config.reactivation_cooldown_seconds,
+                    Clock::get()?.unix_timestamp,
+                )?;
+
                 require!(
                     vault.custodian != Pubkey::default(),
                     JupStableError::InvalidCustodian
@@ -231,7 +452,7 @@ pub fn manage_vault(ctx: Context<ManageVault>, action: VaultManagementAction) ->
                 JupStableError::InvalidCustodian
             );
 
-            vault.custodian = new_custodian;
+            vault.set_custodian(new_custodian);
         },
         VaultManagementAction::SetStalesnessThreshold {
             stalesness_threshold,
@@ -266,8 +487,75 @@ pub fn manage_vault(ctx: Context<ManageVault>, action: VaultManagementAction) ->
 
             vault.set_max_oracle_price_usd(max_oracle_price_usd);
         },
+        VaultManagementAction::SetMaxPriceJump { max_price_jump_bps } => {
+            operator.is(OperatorRole::VaultManager)?;
+
+            require!(max_price_jump_bps <= 10_000, JupStableError::BadInput);
+
+            vault.set_max_price_jump_bps(max_price_jump_bps);
+        },
+        VaultManagementAction::SetPriceJumpWindow {
+            price_jump_window_seconds,
+        } => {
+            operator.is(OperatorRole::VaultManager)?;
+
+            vault.set_price_jump_window_seconds(price_jump_window_seconds);
+        },
+        VaultManagementAction::SetConfidenceFactor {
+            confidence_factor_bps,
+        } => {
+            operator.is(OperatorRole::VaultManager)?;
+
+            vault.set_confidence_factor_bps(confidence_factor_bps);
+        },
+        VaultManagementAction::SetSymmetricOracleBounds { enabled } => {
+            operator.is(OperatorRole::VaultManager)?;
+
+            vault.set_symmetric_oracle_bounds(enabled);
+        },
+        VaultManagementAction::SetRequiresBenefactor { required } => {
+            operator.is(OperatorRole::VaultManager)?;
+
+            vault.set_requires_benefactor(required);
+        },
+        VaultManagementAction::SetMaxNetOutstanding {
+            max_net_outstanding,
+        } => {
+            operator.is(OperatorRole::PeriodManager)?;
+
+            vault.set_max_net_outstanding(max_net_outstanding);
+        },
+        VaultManagementAction::SetPerTxLimits {
+            max_mint_per_tx,
+            max_redeem_per_tx,
+        } => {
+            operator.is(OperatorRole::PeriodManager)?;
+
+            vault.set_per_tx_limits(max_mint_per_tx, max_redeem_per_tx);
+        },
+        VaultManagementAction::SetCustodianTokenAccount {
+            custodian_token_account,
+        } => {
+            operator.is(OperatorRole::VaultManager)?;
+
+            vault.set_custodian_token_account(custodian_token_account);
+        },
+        VaultManagementAction::UpdateQuoteOracle { oracle } => {
+            operator.is(OperatorRole::VaultManager)?;
+
+            vault.update_quote_oracle(&oracle.into());
+        },
     }
 
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::ManageVault,
+        ctx.accounts.vault.key(),
+    )?;
+
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.vault.key()],
+    });
     Ok(())
 }
 
@@ -284,9 +572,10 @@ pub struct Withdraw<'info> {
 
     #[account(
         mut,
-        associated_token::mint = vault_mint,
-        associated_token::authority = custodian,
-        associated_token::token_program = token_program,
+        token::mint = vault_mint,
+        token::authority = custodian,
+        token::token_program = token_program,
+        constraint = vault.load()?.resolve_custodian_token_account() == custodian_token_account.key() @ JupStableError::InvalidCustodianTokenAccount,
     )]
     pub custodian_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -303,6 +592,7 @@ pub struct Withdraw<'info> {
         constraint = vault.load()?.mint == vault_mint.key() @ JupStableError::InvalidVaultMint,
         constraint = vault.load()?.token_account == vault_token_account.key() @ JupStableError::InvalidVaultTokenAccount,
         constraint = vault.load()?.token_program == token_program.key() @ JupStableError::InvalidTokenProgram,
+        constraint = vault.load()?.instance_mint == config.load()?.instance_mint() @ JupStableError::InstanceMismatch,
     )]
     pub vault: AccountLoader<'info, Vault>,
 
@@ -312,18 +602,32 @@ pub struct Withdraw<'info> {
     pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
 
     pub token_program: Interface<'info, TokenInterface>,
+
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    #[account(
+        mut,
+        has_one = operator @ JupStableError::InvalidOperatorLog,
+    )]
+    pub operator_log: Option<AccountLoader<'info, OperatorLog>>,
 }
 
-pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+pub fn withdraw(ctx: Context<Withdraw>, amount: u64, reference: Option<[u8; 32]>) -> Result<()> {
     require!(amount > 0, JupStableError::ZeroAmount);
 
     let operator = ctx.accounts.operator.load()?;
     operator.is(OperatorRole::CollateralManager)?;
+    OperatorLog::record(
+        &ctx.accounts.operator_log,
+        OperatorAction::Withdraw,
+        ctx.accounts.vault.key(),
+    )?;
 
     let vault = ctx.accounts.vault.load()?;
     let config = ctx.accounts.config.load()?;
 
     vault.is_enabled()?;
+    config.check_withdraw_allowed()?;
 
     require!(
         ctx.accounts.vault_token_account.amount >= amount,
@@ -333,11 +637,33 @@ pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     transfer_checked(
         ctx.accounts
             .withdraw_from_vault()
-            .with_signer(&[authority_seeds!(config.authority_bump)]),
+            .with_signer(&[authority_seeds!(config)]),
         amount,
         ctx.accounts.vault_mint.decimals,
     )?;
 
+    if let (Some(reference), Some(memo_program)) = (reference, &ctx.accounts.memo_program) {
+        let memo = reference
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        build_memo(
+            CpiContext::new(memo_program.to_account_info(), BuildMemo {}),
+            memo.as_bytes(),
+        )?;
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+
+    emit!(WithdrawEvent {
+        vault: ctx.accounts.vault.key(),
+        custodian: ctx.accounts.custodian.key(),
+        amount,
+        reference,
+        operator: ctx.accounts.operator.key(),
+        destination: ctx.accounts.custodian_token_account.key(),
+        vault_balance: ctx.accounts.vault_token_account.amount,
+    });
     Ok(())
 }
 
@@ -353,3 +679,38 @@ impl<'info> Withdraw<'info> {
         CpiContext::new(cpi_program, cpi_accounts)
     }
 }
+
+#[derive(Accounts)]
+pub struct AcknowledgeDeposit<'info> {
+    pub custodian: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = vault.load()?.custodian == custodian.key() @ JupStableError::InvalidCustodian,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+}
+
+pub fn acknowledge_deposit(
+    ctx: Context<AcknowledgeDeposit>,
+    reference: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, JupStableError::ZeroAmount);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    vault.record_acknowledged_deposit(amount, current_time);
+
+    emit!(DepositAcknowledgedEvent {
+        vault: ctx.accounts.vault.key(),
+        custodian: ctx.accounts.custodian.key(),
+        reference,
+        amount,
+        timestamp: current_time,
+    });
+    emit!(AccountTouchedEvent {
+        accounts: vec![ctx.accounts.vault.key()],
+    });
+    Ok(())
+}
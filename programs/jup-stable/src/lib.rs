@@ -3,7 +3,9 @@
 use anchor_lang::prelude::*;
 
 pub mod error;
+pub mod event;
 pub mod instructions;
+pub mod math;
 pub mod oracle;
 pub mod state;
 
@@ -37,13 +39,45 @@ pub mod jup_stable {
         name: String,
         symbol: String,
         uri: String,
+        params: InitConfigParams,
     ) -> Result<()> {
-        instructions::init(ctx, decimals, name, symbol, uri)
+        instructions::init(ctx, decimals, name, symbol, uri, params)
+    }
+    pub fn init_instance(
+        ctx: Context<InitInstance>,
+        decimals: u8,
+        name: String,
+        symbol: String,
+        uri: String,
+        params: InitConfigParams,
+    ) -> Result<()> {
+        instructions::init_instance(ctx, decimals, name, symbol, uri, params)
     }
     pub fn manage_config(ctx: Context<ManageConfig>, action: ConfigManagementAction) -> Result<()> {
         instructions::manage_config(ctx, action)
     }
 
+    pub fn set_pending_upgrade_authority(
+        ctx: Context<SetPendingUpgradeAuthority>,
+        pending_upgrade_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::set_pending_upgrade_authority(ctx, pending_upgrade_authority)
+    }
+
+    pub fn confirm_upgrade_authority(ctx: Context<ConfirmUpgradeAuthority>) -> Result<()> {
+        instructions::confirm_upgrade_authority(ctx)
+    }
+
+    pub fn sync_supply(ctx: Context<SyncSupply>) -> Result<()> { instructions::sync_supply(ctx) }
+
+    pub fn assert_supply_consistency(ctx: Context<AssertSupplyConsistency>) -> Result<()> {
+        instructions::assert_supply_consistency(ctx)
+    }
+
+    pub fn burn_treasury(ctx: Context<BurnTreasury>, amount: u64) -> Result<()> {
+        instructions::burn_treasury(ctx, amount)
+    }
+
     pub fn create_operator(ctx: Context<CreateOperator>, role: OperatorRole) -> Result<()> {
         instructions::create_operator(ctx, role)
     }
@@ -54,14 +88,36 @@ pub mod jup_stable {
     ) -> Result<()> {
         instructions::manage_operator(ctx, action)
     }
+    pub fn approve_collateral(ctx: Context<ApproveCollateral>) -> Result<()> {
+        instructions::approve_collateral(ctx)
+    }
+
     pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> { instructions::create_vault(ctx) }
 
+    pub fn create_vault_registry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateVaultRegistry<'info>>,
+    ) -> Result<()> {
+        instructions::create_vault_registry(ctx)
+    }
+
     pub fn manage_vault(ctx: Context<ManageVault>, action: VaultManagementAction) -> Result<()> {
         instructions::manage_vault(ctx, action)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        instructions::withdraw(ctx, amount)
+    pub fn withdraw(
+        ctx: Context<Withdraw>,
+        amount: u64,
+        reference: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::withdraw(ctx, amount, reference)
+    }
+
+    pub fn acknowledge_deposit(
+        ctx: Context<AcknowledgeDeposit>,
+        reference: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        instructions::acknowledge_deposit(ctx, reference, amount)
     }
 
     pub fn create_benefactor(
@@ -76,8 +132,18 @@ pub mod jup_stable {
         instructions::delete_operator(ctx)
     }
 
-    pub fn manage_benefactor(
-        ctx: Context<ManageBenefactor>,
+    pub fn create_operator_log(ctx: Context<CreateOperatorLog>) -> Result<()> {
+        instructions::create_operator_log(ctx)
+    }
+
+    pub fn create_benefactor_registry_page<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateBenefactorRegistryPage<'info>>,
+    ) -> Result<()> {
+        instructions::create_benefactor_registry_page(ctx)
+    }
+
+    pub fn manage_benefactor<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManageBenefactor<'info>>,
         action: BenefactorManagementAction,
     ) -> Result<()> {
         instructions::manage_benefactor(ctx, action)
@@ -88,11 +154,46 @@ pub mod jup_stable {
     }
 
     // User Instructions
-    pub fn mint(ctx: Context<Mint>, amount: u64, min_amount_out: u64) -> Result<()> {
-        instructions::mint(ctx, amount, min_amount_out)
+    pub fn mint(
+        ctx: Context<Mint>,
+        amount: u64,
+        min_amount_out: u64,
+        dry_run: bool,
+    ) -> Result<()> {
+        instructions::mint(ctx, amount, min_amount_out, dry_run)
+    }
+
+    pub fn redeem(
+        ctx: Context<Redeem>,
+        amount: u64,
+        min_amount_out: u64,
+        dry_run: bool,
+    ) -> Result<()> {
+        instructions::redeem(ctx, amount, min_amount_out, dry_run)
+    }
+
+    pub fn relayed_mint(ctx: Context<RelayedMint>, amount: u64, min_amount_out: u64) -> Result<()> {
+        instructions::relayed_mint(ctx, amount, min_amount_out)
+    }
+
+    pub fn relayed_redeem(
+        ctx: Context<RelayedRedeem>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::relayed_redeem(ctx, amount, min_amount_out)
+    }
+
+    pub fn get_capacity(ctx: Context<GetCapacity>) -> Result<Capacity> {
+        instructions::get_capacity(ctx)
+    }
+
+    // Integrator Instructions
+    pub fn flash_mint(ctx: Context<FlashMint>, amount: u64, min_amount_out: u64) -> Result<()> {
+        instructions::flash_mint(ctx, amount, min_amount_out)
     }
 
-    pub fn redeem(ctx: Context<Redeem>, amount: u64, min_amount_out: u64) -> Result<()> {
-        instructions::redeem(ctx, amount, min_amount_out)
+    pub fn settle_flash_mint(ctx: Context<SettleFlashMint>) -> Result<()> {
+        instructions::settle_flash_mint(ctx)
     }
 }
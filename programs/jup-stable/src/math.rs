@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use jupusd_math::MathError;
+use rust_decimal::Decimal;
+
+use crate::{error::JupStableError, oracle::OraclePrice};
+
+impl From<MathError> for JupStableError {
+    fn from(err: MathError) -> Self {
+        match err {
+            MathError::MathOverflow => JupStableError::MathOverflow,
+        }
+    }
+}
+
+pub fn calculate_mint_amount(
+    price: &OraclePrice,
+    amount: Decimal,
+    peg_price: Decimal,
+    expected_decimals: u32,
+) -> Result<Decimal> {
+    jupusd_math::calculate_mint_amount(price.0, amount, peg_price, expected_decimals)
+        .map_err(|err| error!(JupStableError::from(err)))
+}
+
+pub fn calculate_redeem_amount(
+    price: &OraclePrice,
+    lp_amount: Decimal,
+    peg_price: Decimal,
+    expected_decimals: u32,
+) -> Result<Decimal> {
+    jupusd_math::calculate_redeem_amount(price.0, lp_amount, peg_price, expected_decimals)
+        .map_err(|err| error!(JupStableError::from(err)))
+}
+
+/// Returns `(mint_amount, one_to_one_amount, oracle_amount)`, where the minted amount is the
+/// lower of the fee-adjusted 1:1 amount and the oracle-priced amount.
+pub fn compute_mint_amount(
+    amount: u64,
+    net_amount: u64,
+    oracle_price: &OraclePrice,
+    peg_price: Decimal,
+    vault_mint_decimals: u8,
+    lp_mint_decimals: u8,
+) -> Result<(u64, u64, u64)> {
+    let quote = jupusd_math::compute_mint_amount(
+        amount,
+        net_amount,
+        oracle_price.0,
+        peg_price,
+        vault_mint_decimals,
+        lp_mint_decimals,
+    )
+    .map_err(|err| error!(JupStableError::from(err)))?;
+
+    Ok((quote.amount, quote.one_to_one_amount, quote.oracle_amount))
+}
+
+/// Returns `(redeem_amount, one_to_one_amount, oracle_amount)`, where the redeemed amount is the
+/// lower of the fee-adjusted 1:1 amount and the oracle-priced amount.
+pub fn compute_redeem_amount(
+    amount: u64,
+    net_amount: u64,
+    oracle_price: &OraclePrice,
+    peg_price: Decimal,
+    lp_mint_decimals: u8,
+    vault_mint_decimals: u8,
+) -> Result<(u64, u64, u64)> {
+    let quote = jupusd_math::compute_redeem_amount(
+        amount,
+        net_amount,
+        oracle_price.0,
+        peg_price,
+        lp_mint_decimals,
+        vault_mint_decimals,
+    )
+    .map_err(|err| error!(JupStableError::from(err)))?;
+
+    Ok((quote.amount, quote.one_to_one_amount, quote.oracle_amount))
+}
+
+pub fn decimal_to_u64(value: Decimal) -> Result<u64> {
+    jupusd_math::decimal_to_u64(value).map_err(|err| error!(JupStableError::from(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const PEG_PRICE: Decimal = Decimal::ONE;
+
+    fn price() -> impl Strategy<Value = OraclePrice> {
+        // 0.0001 to 10.0000, the range the vault oracle bounds care about
+        (1i64..=100_000).prop_map(|mantissa| OraclePrice(Decimal::new(mantissa, 4)))
+    }
+
+    proptest! {
+        #[test]
+        fn mint_then_redeem_never_creates_value(
+            amount in 0u64..=1_000_000_000,
+            price in price(),
+            vault_decimals in 0u8..=9,
+            lp_decimals in 0u8..=9,
+        ) {
+            let (mint_amount, _, _) =
+                compute_mint_amount(amount, amount, &price, PEG_PRICE, vault_decimals, lp_decimals)
+                    .unwrap();
+            let (redeem_amount, _, _) = compute_redeem_amount(
+                mint_amount,
+                mint_amount,
+                &price,
+                PEG_PRICE,
+                lp_decimals,
+                vault_decimals,
+            )
+            .unwrap();
+            prop_assert!(redeem_amount <= amount);
+        }
+
+        #[test]
+        fn mint_amount_is_monotonic(
+            a in 0u64..=1_000_000_000,
+            b in 0u64..=1_000_000_000,
+            price in price(),
+            vault_decimals in 0u8..=9,
+            lp_decimals in 0u8..=9,
+        ) {
+            let (low, high) = (a.min(b), a.max(b));
+            let (low_out, _, _) =
+                compute_mint_amount(low, low, &price, PEG_PRICE, vault_decimals, lp_decimals)
+                    .unwrap();
+            let (high_out, _, _) =
+                compute_mint_amount(high, high, &price, PEG_PRICE, vault_decimals, lp_decimals)
+                    .unwrap();
+            prop_assert!(low_out <= high_out);
+        }
+
+        #[test]
+        fn redeem_amount_is_monotonic(
+            a in 0u64..=1_000_000_000,
+            b in 0u64..=1_000_000_000,
+            price in price(),
+            vault_decimals in 0u8..=9,
+            lp_decimals in 0u8..=9,
+        ) {
+            let (low, high) = (a.min(b), a.max(b));
+            let (low_out, _, _) =
+                compute_redeem_amount(low, low, &price, PEG_PRICE, lp_decimals, vault_decimals)
+                    .unwrap();
+            let (high_out, _, _) =
+                compute_redeem_amount(high, high, &price, PEG_PRICE, lp_decimals, vault_decimals)
+                    .unwrap();
+            prop_assert!(low_out <= high_out);
+        }
+
+        #[test]
+        fn arbitrary_inputs_do_not_panic(
+            amount in any::<u64>(),
+            net_amount in any::<u64>(),
+            mantissa in any::<i64>(),
+            scale in 0u32..=28,
+            peg_mantissa in any::<i64>(),
+            vault_decimals in any::<u8>(),
+            lp_decimals in any::<u8>(),
+        ) {
+            let price = OraclePrice(Decimal::new(mantissa, scale));
+            let peg_price = Decimal::new(peg_mantissa, 4);
+            let _ = compute_mint_amount(
+                amount,
+                net_amount,
+                &price,
+                peg_price,
+                vault_decimals,
+                lp_decimals,
+            );
+            let _ = compute_redeem_amount(
+                amount,
+                net_amount,
+                &price,
+                peg_price,
+                lp_decimals,
+                vault_decimals,
+            );
+        }
+    }
+}
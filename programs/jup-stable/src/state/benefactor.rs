@@ -1,15 +1,47 @@
-use std::mem::size_of;
+use std::mem::{offset_of, size_of};
 
 use anchor_lang::prelude::*;
 use bytemuck::{Pod, Zeroable};
 use static_assertions::const_assert_eq;
 
-use crate::{error::JupStableError, state::common::PeriodLimit};
+use crate::{
+    error::JupStableError,
+    state::{
+        common::{
+            check_period_limit_configured, check_reactivation_cooldown, peek_mint_limits,
+            peek_redeem_limits, period_mint_capacity, period_redeem_capacity, roll_period_limits,
+            PeriodLimit,
+        },
+        config::instance_seed,
+    },
+};
 
 const_assert_eq!(Benefactor::MAX_SIZE, size_of::<Benefactor>());
 
+// Offsets of the deployed layout
+const_assert_eq!(size_of::<Benefactor>(), 528);
+const_assert_eq!(offset_of!(Benefactor, authority), 0);
+const_assert_eq!(offset_of!(Benefactor, status), 32);
+const_assert_eq!(offset_of!(Benefactor, exempt_from_global_pause), 33);
+const_assert_eq!(offset_of!(Benefactor, mint_fee_rate), 40);
+const_assert_eq!(offset_of!(Benefactor, redeem_fee_rate), 42);
+const_assert_eq!(offset_of!(Benefactor, period_limits), 48);
+const_assert_eq!(offset_of!(Benefactor, total_minted), 240);
+const_assert_eq!(offset_of!(Benefactor, total_redeemed), 256);
+const_assert_eq!(offset_of!(Benefactor, max_net_minted), 272);
+const_assert_eq!(offset_of!(Benefactor, disabled_at), 280);
+const_assert_eq!(offset_of!(Benefactor, integrator_program), 288);
+const_assert_eq!(offset_of!(Benefactor, flash_mint_vault), 320);
+const_assert_eq!(offset_of!(Benefactor, flash_mint_debt), 352);
+const_assert_eq!(offset_of!(Benefactor, redeem_vault_hint), 360);
+const_assert_eq!(offset_of!(Benefactor, instance_mint), 392);
+const_assert_eq!(offset_of!(Benefactor, relayers), 424);
+const_assert_eq!(offset_of!(Benefactor, reserved), 488);
+
+#[constant]
 pub const BENEFACTOR_PREFIX: &[u8; 10] = b"benefactor";
 pub const MAX_PERIOD_LIMIT: usize = 4;
+pub const MAX_RELAYERS: usize = 2;
 
 #[repr(u8)]
 #[derive(Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
@@ -25,7 +57,9 @@ unsafe impl Zeroable for BenefactorStatus {}
 pub struct Benefactor {
     pub authority: Pubkey,
     pub status: BenefactorStatus,
-    pub _padding0: [u8; 7],
+    /// Non-zero lets the benefactor keep redeeming while mint and redeem are globally paused
+    pub exempt_from_global_pause: u8,
+    pub _padding0: [u8; 6],
 
     pub mint_fee_rate: u16,
     pub redeem_fee_rate: u16,
@@ -36,7 +70,32 @@ pub struct Benefactor {
     pub total_minted: [u8; 16],
     pub total_redeemed: [u8; 16],
 
-    pub reserved: [u8; 256],
+    /// Cap on `total_minted - total_redeemed`, zero meaning uncapped
+    pub max_net_minted: u64,
+    /// When a disabler role last stopped the benefactor, zero once re-activated
+    pub disabled_at: i64,
+
+    /// Program allowed to `flash_mint` on behalf of the benefactor, default meaning none
+    pub integrator_program: Pubkey,
+    /// Vault the pending flash mint settles into
+    pub flash_mint_vault: Pubkey,
+    /// Collateral the pending flash mint still owes, zero once settled
+    pub flash_mint_debt: u64,
+
+    /// Collateral mint clients should redeem against when the integration doesn't pick a vault,
+    /// default meaning none. A hint only: checked against the vault when set, but `redeem` pays
+    /// out of whichever vault it is passed
+    pub redeem_vault_hint: Pubkey,
+
+    /// LP mint of the instance the benefactor was created in, default for the instance created by
+    /// `init`
+    pub instance_mint: Pubkey,
+
+    /// Keys allowed to submit `relayed_mint` and `relayed_redeem` for the benefactor's authority,
+    /// default meaning an empty slot
+    pub relayers: [Pubkey; MAX_RELAYERS],
+
+    pub reserved: [u8; 40],
 }
 
 impl Default for Benefactor {
@@ -44,25 +103,40 @@ impl Default for Benefactor {
         Benefactor {
             authority: Pubkey::default(),
             status: BenefactorStatus::Disabled,
-            _padding0: [0; 7],
+            exempt_from_global_pause: 0,
+            _padding0: [0; 6],
             mint_fee_rate: 0,
             redeem_fee_rate: 0,
             _padding1: [0; 4],
             period_limits: [PeriodLimit::default(); MAX_PERIOD_LIMIT],
             total_minted: [0; 16],
             total_redeemed: [0; 16],
-            reserved: [0; 256],
+            max_net_minted: 0,
+            disabled_at: 0,
+            integrator_program: Pubkey::default(),
+            flash_mint_vault: Pubkey::default(),
+            flash_mint_debt: 0,
+            redeem_vault_hint: Pubkey::default(),
+            instance_mint: Pubkey::default(),
+            relayers: [Pubkey::default(); MAX_RELAYERS],
+            reserved: [0; 40],
         }
     }
 }
 
 impl Benefactor {
     pub const MAX_SIZE: usize = 32 + // authority
-        1 + 7 + // status + padding
+        1 + 1 + 6 + // status, exempt_from_global_pause + padding
         2 + 2 + 4 + // fee rates (2 u16 fields) + padding
         PeriodLimit::MAX_SIZE * MAX_PERIOD_LIMIT + // rate limit windows
         16 + 16 + // total stats
-        256;
+        8 + // max_net_minted
+        8 + // disabled_at
+        32 + 32 + 8 + // flash mint integrator and pending settlement
+        32 + // redeem_vault_hint
+        32 + // instance_mint
+        32 * MAX_RELAYERS + // relayers
+        40;
 
     pub fn is_active(&self) -> Result<()> {
         require!(
@@ -80,36 +154,164 @@ impl Benefactor {
         Ok(())
     }
 
-    pub fn set_status(&mut self, status: BenefactorStatus) { self.status = status; }
+    pub fn set_status(&mut self, status: BenefactorStatus) {
+        if status == BenefactorStatus::Active {
+            self.disabled_at = 0;
+        }
+        self.status = status;
+    }
+
+    pub fn instance_seed(&self) -> &[u8] { instance_seed(&self.instance_mint) }
+
+    pub fn is_exempt_from_global_pause(&self) -> bool { self.exempt_from_global_pause == 1 }
+
+    pub fn set_exempt_from_global_pause(&mut self, exempt: bool) {
+        self.exempt_from_global_pause = if exempt { 1 } else { 0 };
+    }
+
+    pub fn disable(&mut self, current_time: i64) {
+        self.status = BenefactorStatus::Disabled;
+        self.disabled_at = current_time;
+    }
+
+    pub fn check_reactivation(&self, cooldown_seconds: u64, current_time: i64) -> Result<()> {
+        check_reactivation_cooldown(
+            "benefactor",
+            self.disabled_at,
+            cooldown_seconds,
+            current_time,
+        )
+    }
 
-    pub fn can_mint(&mut self, amount: u64, current_time: i64) -> Result<()> {
+    pub fn can_mint(
+        &self,
+        amount: u64,
+        current_time: i64,
+        strict_period_limits: bool,
+    ) -> Result<()> {
         self.is_active()?;
+        self.check_net_minted(amount)?;
+        check_period_limit_configured("benefactor", &self.period_limits, strict_period_limits)?;
 
-        for window in &mut self.period_limits {
-            window.roll_window(current_time);
-            window.check_mint_limit(amount)?;
+        peek_mint_limits("benefactor", &self.period_limits, amount, current_time)
+    }
+
+    pub fn can_redeem(
+        &self,
+        amount: u64,
+        current_time: i64,
+        strict_period_limits: bool,
+    ) -> Result<()> {
+        self.is_active()?;
+        check_period_limit_configured("benefactor", &self.period_limits, strict_period_limits)?;
+
+        peek_redeem_limits("benefactor", &self.period_limits, amount, current_time)
+    }
+
+    /// Rolls the period windows ahead of `record_mint` or `record_redeem`.
+    pub fn roll_period_limits(&mut self, current_time: i64) {
+        roll_period_limits(&mut self.period_limits, current_time);
+    }
+
+    /// Amount `can_mint` would still accept at `current_time`, including the net minted cap.
+    pub fn mint_capacity(&self, current_time: i64, strict_period_limits: bool) -> u64 {
+        if self.status != BenefactorStatus::Active {
+            return 0;
         }
 
-        Ok(())
+        let capacity =
+            period_mint_capacity(&self.period_limits, current_time, strict_period_limits);
+        if self.max_net_minted != 0 {
+            let available = (self.max_net_minted as u128).saturating_sub(self.net_minted());
+            return capacity.min(available as u64);
+        }
+        capacity
     }
 
-    pub fn can_redeem(&mut self, amount: u64, current_time: i64) -> Result<()> {
-        self.is_active()?;
+    /// Amount `can_redeem` would still accept at `current_time`.
+    pub fn redeem_capacity(&self, current_time: i64, strict_period_limits: bool) -> u64 {
+        if self.status != BenefactorStatus::Active {
+            return 0;
+        }
 
-        for window in &mut self.period_limits {
-            window.roll_window(current_time);
-            window.check_redeem_limit(amount)?;
+        period_redeem_capacity(&self.period_limits, current_time, strict_period_limits)
+    }
+
+    pub fn total_minted(&self) -> u128 { u128::from_le_bytes(self.total_minted) }
+
+    pub fn total_redeemed(&self) -> u128 { u128::from_le_bytes(self.total_redeemed) }
+
+    pub fn net_minted(&self) -> u128 { self.total_minted().saturating_sub(self.total_redeemed()) }
+
+    pub fn set_max_net_minted(&mut self, max_net_minted: u64) {
+        self.max_net_minted = max_net_minted;
+    }
+
+    fn check_net_minted(&self, amount: u64) -> Result<()> {
+        if self.max_net_minted == 0 {
+            return Ok(());
         }
 
+        let available = (self.max_net_minted as u128).saturating_sub(self.net_minted());
+        if amount as u128 > available {
+            msg!(
+                "benefactor net minted limit: requested {}, available {}",
+                amount,
+                available
+            );
+            return err!(JupStableError::NetMintedLimitExceeded);
+        }
         Ok(())
     }
 
+    pub fn set_integrator_program(&mut self, integrator_program: Pubkey) {
+        self.integrator_program = integrator_program;
+    }
+
+    pub fn redeem_vault_hint(&self) -> Option<Pubkey> {
+        (self.redeem_vault_hint != Pubkey::default()).then_some(self.redeem_vault_hint)
+    }
+
+    pub fn set_redeem_vault_hint(&mut self, redeem_vault_hint: Pubkey) {
+        self.redeem_vault_hint = redeem_vault_hint;
+    }
+
+    pub fn is_relayer(&self, relayer: &Pubkey) -> bool {
+        *relayer != Pubkey::default() && self.relayers.contains(relayer)
+    }
+
+    pub fn set_relayer(&mut self, index: usize, relayer: Pubkey) -> Result<()> {
+        require!(index < MAX_RELAYERS, JupStableError::InvalidRelayer);
+        self.relayers[index] = relayer;
+        Ok(())
+    }
+
+    /// Checks `program` may flash mint for the benefactor and no earlier flash mint is pending.
+    pub fn can_flash_mint(&self, program: &Pubkey) -> Result<()> {
+        require!(
+            self.integrator_program != Pubkey::default() && self.integrator_program == *program,
+            JupStableError::IntegratorNotWhitelisted
+        );
+        require!(self.flash_mint_debt == 0, JupStableError::FlashMintPending);
+        Ok(())
+    }
+
+    pub fn record_flash_mint(&mut self, vault: Pubkey, debt: u64) {
+        self.flash_mint_vault = vault;
+        self.flash_mint_debt = debt;
+    }
+
+    pub fn settle_flash_mint(&mut self) {
+        self.flash_mint_vault = Pubkey::default();
+        self.flash_mint_debt = 0;
+    }
+
     pub fn calculate_mint_fee(&self, amount: u64) -> u64 {
-        (amount as u128 * self.mint_fee_rate as u128).div_ceil(10000) as u64
+        jupusd_math::calculate_fee(amount, self.mint_fee_rate)
     }
 
     pub fn calculate_redeem_fee(&self, amount: u64) -> u64 {
-        (amount as u128 * self.redeem_fee_rate as u128).div_ceil(10000) as u64
+        jupusd_math::calculate_fee(amount, self.redeem_fee_rate)
     }
 
     pub fn record_mint(&mut self, amount: u64) {
@@ -0,0 +1,97 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+use crate::error::JupStableError;
+
+const_assert_eq!(
+    BenefactorRegistry::MAX_SIZE,
+    size_of::<BenefactorRegistry>()
+);
+const_assert_eq!(size_of::<BenefactorRegistry>() % 8, 0);
+
+#[constant]
+pub const BENEFACTOR_REGISTRY_PREFIX: &[u8; 19] = b"benefactor_registry";
+pub const BENEFACTORS_PER_PAGE: usize = 64;
+
+/// One page of benefactor authorities. Pages are numbered from zero up to
+/// `Config::benefactor_registry_pages`, so walking them lists every benefactor exactly once.
+#[account(zero_copy)]
+pub struct BenefactorRegistry {
+    pub page: u32,
+    pub count: u32,
+    pub authorities: [Pubkey; BENEFACTORS_PER_PAGE],
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    pub reserved: [u8; 64],
+}
+
+impl Default for BenefactorRegistry {
+    fn default() -> Self {
+        BenefactorRegistry {
+            page: 0,
+            count: 0,
+            authorities: [Pubkey::default(); BENEFACTORS_PER_PAGE],
+            bump: 0,
+            _padding: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl BenefactorRegistry {
+    pub const MAX_SIZE: usize = 4 + 4 + 32 * BENEFACTORS_PER_PAGE + 1 + 7 + 64;
+
+    pub fn authorities(&self) -> &[Pubkey] { &self.authorities[..self.count as usize] }
+
+    pub fn push(&mut self, authority: Pubkey) -> Result<()> {
+        require!(
+            !self.authorities().contains(&authority),
+            JupStableError::BenefactorAlreadyRegistered
+        );
+        require!(
+            (self.count as usize) < BENEFACTORS_PER_PAGE,
+            JupStableError::BenefactorRegistryPageFull
+        );
+
+        self.authorities[self.count as usize] = authority;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Removes `authority`, moving the last entry of the page into its slot.
+    pub fn remove(&mut self, authority: &Pubkey) -> Result<()> {
+        let index = self
+            .authorities()
+            .iter()
+            .position(|registered| registered == authority)
+            .ok_or(JupStableError::BenefactorNotRegistered)?;
+
+        let last = self.count as usize - 1;
+        self.authorities[index] = self.authorities[last];
+        self.authorities[last] = Pubkey::default();
+        self.count -= 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benefactor_registry_push_remove() {
+        let mut registry = BenefactorRegistry::default();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+
+        registry.push(first).unwrap();
+        registry.push(second).unwrap();
+        assert!(registry.push(first).is_err());
+
+        registry.remove(&first).unwrap();
+        assert_eq!(registry.authorities(), &[second]);
+        assert!(registry.remove(&first).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+const_assert_eq!(
+    CollateralApproval::MAX_SIZE,
+    size_of::<CollateralApproval>()
+);
+const_assert_eq!(size_of::<CollateralApproval>() % 8, 0);
+
+#[constant]
+pub const COLLATERAL_APPROVAL_PREFIX: &[u8; 19] = b"collateral_approval";
+
+/// Admin sign-off on a collateral mint, required before a vault can be created for it.
+#[account(zero_copy)]
+pub struct CollateralApproval {
+    pub mint: Pubkey,
+    /// Operator that approved the mint
+    pub approved_by: Pubkey,
+    pub approved_at: i64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    pub reserved: [u8; 64],
+}
+
+impl Default for CollateralApproval {
+    fn default() -> Self {
+        CollateralApproval {
+            mint: Pubkey::default(),
+            approved_by: Pubkey::default(),
+            approved_at: 0,
+            bump: 0,
+            _padding: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl CollateralApproval {
+    pub const MAX_SIZE: usize = 32 + 32 + 8 + 1 + 7 + 64;
+}
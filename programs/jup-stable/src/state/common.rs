@@ -7,7 +7,7 @@ pub const MAX_DURATION_SECONDS: u64 = 86400 * 30; // 30 days
 pub const MIN_DURATION_SECONDS: u64 = 30; // 30 seconds
 
 #[repr(C)]
-#[derive(Default, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+#[derive(Debug, Default, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
 pub struct PeriodLimit {
     /// Window duration in seconds (0 = disabled)
     pub duration_seconds: u64,
@@ -55,13 +55,17 @@ impl PeriodLimit {
         Ok(())
     }
 
+    /// Opens a fresh window once `duration_seconds` have passed since `window_start`. A clock
+    /// earlier than `window_start` keeps the current window and its counters untouched, so a
+    /// regressing validator clock can neither reset the limits nor move the window.
     pub fn roll_window(&mut self, current_time: i64) {
-        if self.duration_seconds == 0 {
+        if self.duration_seconds == 0 || current_time < self.window_start {
             return;
         }
 
-        let window_elapsed = current_time - self.window_start;
-        if window_elapsed >= self.duration_seconds as i64 {
+        // Saturate so arbitrary clock values can never overflow or roll early
+        let window_elapsed = current_time.saturating_sub(self.window_start);
+        if window_elapsed >= i64::try_from(self.duration_seconds).unwrap_or(i64::MAX) {
             self.minted_amount = 0;
             self.redeemed_amount = 0;
             self.window_start = current_time;
@@ -73,7 +77,11 @@ impl PeriodLimit {
             return Ok(());
         }
 
-        if self.minted_amount + amount > self.max_mint_amount {
+        let minted_amount = self
+            .minted_amount
+            .checked_add(amount)
+            .ok_or(error!(JupStableError::MathOverflow))?;
+        if minted_amount > self.max_mint_amount {
             return err!(JupStableError::MintLimitExceeded);
         }
 
@@ -85,7 +93,11 @@ impl PeriodLimit {
             return Ok(());
         }
 
-        if self.redeemed_amount + amount > self.max_redeem_amount {
+        let redeemed_amount = self
+            .redeemed_amount
+            .checked_add(amount)
+            .ok_or(error!(JupStableError::MathOverflow))?;
+        if redeemed_amount > self.max_redeem_amount {
             return err!(JupStableError::RedeemLimitExceeded);
         }
 
@@ -97,7 +109,7 @@ impl PeriodLimit {
             return;
         }
 
-        self.minted_amount += amount;
+        self.minted_amount = self.minted_amount.saturating_add(amount);
     }
 
     pub fn record_redeem(&mut self, amount: u64) {
@@ -105,8 +117,231 @@ impl PeriodLimit {
             return;
         }
 
-        self.redeemed_amount += amount;
+        self.redeemed_amount = self.redeemed_amount.saturating_add(amount);
     }
 
     pub fn reset(&mut self) { *self = Self::default(); }
+
+    /// Mint amount the window still allows at `current_time`, `u64::MAX` when it is disabled.
+    pub fn remaining_mint_amount(&self, current_time: i64) -> u64 {
+        let mut window = *self;
+        window.roll_window(current_time);
+        if window.duration_seconds == 0 {
+            return u64::MAX;
+        }
+
+        window.max_mint_amount.saturating_sub(window.minted_amount)
+    }
+
+    /// Redeem counterpart of [`PeriodLimit::remaining_mint_amount`].
+    pub fn remaining_redeem_amount(&self, current_time: i64) -> u64 {
+        let mut window = *self;
+        window.roll_window(current_time);
+        if window.duration_seconds == 0 {
+            return u64::MAX;
+        }
+
+        window
+            .max_redeem_amount
+            .saturating_sub(window.redeemed_amount)
+    }
+
+    fn log_rejection(&self, scope: &str, is_mint: bool, index: usize, amount: u64) {
+        let (kind, used, max) = if is_mint {
+            ("mint", self.minted_amount, self.max_mint_amount)
+        } else {
+            ("redeem", self.redeemed_amount, self.max_redeem_amount)
+        };
+        let window_end = self
+            .window_start
+            .saturating_add(i64::try_from(self.duration_seconds).unwrap_or(i64::MAX));
+        msg!(
+            "{} {} limit {}: requested {}, available {}, window {}..{}",
+            scope,
+            kind,
+            index,
+            amount,
+            max.saturating_sub(used),
+            self.window_start,
+            window_end
+        );
+    }
+}
+
+/// Limits a new vault or benefactor starts with in its first window, so one nobody configured
+/// isn't left unlimited.
+#[repr(C)]
+#[derive(Default, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+pub struct PeriodLimitTemplate {
+    /// Window duration in seconds (0 = no template)
+    pub duration_seconds: u64,
+    pub max_mint_amount: u64,
+    pub max_redeem_amount: u64,
+}
+
+unsafe impl Pod for PeriodLimitTemplate {}
+unsafe impl Zeroable for PeriodLimitTemplate {}
+
+impl PeriodLimitTemplate {
+    pub const MAX_SIZE: usize = 8 + 8 + 8;
+
+    pub fn new(
+        duration_seconds: u64,
+        max_mint_amount: u64,
+        max_redeem_amount: u64,
+    ) -> Result<Self> {
+        if duration_seconds == 0 {
+            return Ok(Self::default());
+        }
+
+        let window = PeriodLimit {
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+            ..Default::default()
+        };
+        require!(window.is_valid(), JupStableError::InvalidPeriodLimit);
+
+        Ok(PeriodLimitTemplate {
+            duration_seconds,
+            max_mint_amount,
+            max_redeem_amount,
+        })
+    }
+
+    pub fn apply(&self, window: &mut PeriodLimit, current_time: i64) -> Result<()> {
+        if self.duration_seconds == 0 {
+            return Ok(());
+        }
+
+        window.update(
+            self.duration_seconds,
+            self.max_mint_amount,
+            self.max_redeem_amount,
+            current_time,
+        )
+    }
+}
+
+/// Fails while an account a disabler role stopped at `disabled_at` is still within `cooldown`.
+pub fn check_reactivation_cooldown(
+    scope: &str,
+    disabled_at: i64,
+    cooldown_seconds: u64,
+    current_time: i64,
+) -> Result<()> {
+    if disabled_at == 0 {
+        return Ok(());
+    }
+
+    let unlocked_at =
+        disabled_at.saturating_add(i64::try_from(cooldown_seconds).unwrap_or(i64::MAX));
+    if current_time < unlocked_at {
+        msg!(
+            "{} disabled at {}, can be re-enabled from {}",
+            scope,
+            disabled_at,
+            unlocked_at
+        );
+        return err!(JupStableError::ReactivationCooldown);
+    }
+    Ok(())
+}
+
+/// Fails in strict mode when none of `windows` is configured, so an account nobody gave limits
+/// to is blocked rather than unlimited.
+pub fn check_period_limit_configured(
+    scope: &str,
+    windows: &[PeriodLimit],
+    strict_period_limits: bool,
+) -> Result<()> {
+    if !strict_period_limits || windows.iter().any(|window| window.duration_seconds != 0) {
+        return Ok(());
+    }
+
+    msg!("{} has no period limit configured", scope);
+    err!(JupStableError::PeriodLimitRequired)
+}
+
+/// Mint amount every window still allows at `current_time`, without touching them. Zero when
+/// `strict_period_limits` blocks an account that has no window configured.
+pub fn period_mint_capacity(
+    windows: &[PeriodLimit],
+    current_time: i64,
+    strict_period_limits: bool,
+) -> u64 {
+    if strict_period_limits && windows.iter().all(|window| window.duration_seconds == 0) {
+        return 0;
+    }
+
+    windows
+        .iter()
+        .map(|window| window.remaining_mint_amount(current_time))
+        .min()
+        .unwrap_or(u64::MAX)
+}
+
+/// Redeem counterpart of [`period_mint_capacity`].
+pub fn period_redeem_capacity(
+    windows: &[PeriodLimit],
+    current_time: i64,
+    strict_period_limits: bool,
+) -> u64 {
+    if strict_period_limits && windows.iter().all(|window| window.duration_seconds == 0) {
+        return 0;
+    }
+
+    windows
+        .iter()
+        .map(|window| window.remaining_redeem_amount(current_time))
+        .min()
+        .unwrap_or(u64::MAX)
+}
+
+/// Checks `amount` against the mint limit of every window as rolled at `current_time`, without
+/// touching them, logging the window that rejects it under `scope` so failed fills can be
+/// debugged from the transaction logs.
+pub fn peek_mint_limits(
+    scope: &str,
+    windows: &[PeriodLimit],
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    for (index, window) in windows.iter().enumerate() {
+        let mut window = *window;
+        window.roll_window(current_time);
+        if let Err(err) = window.check_mint_limit(amount) {
+            window.log_rejection(scope, true, index, amount);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Redeem counterpart of [`peek_mint_limits`].
+pub fn peek_redeem_limits(
+    scope: &str,
+    windows: &[PeriodLimit],
+    amount: u64,
+    current_time: i64,
+) -> Result<()> {
+    for (index, window) in windows.iter().enumerate() {
+        let mut window = *window;
+        window.roll_window(current_time);
+        if let Err(err) = window.check_redeem_limit(amount) {
+            window.log_rejection(scope, false, index, amount);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Rolls every window to `current_time`, before recording a mint or redeem checked with
+/// [`peek_mint_limits`] or [`peek_redeem_limits`] in them.
+pub fn roll_period_limits(windows: &mut [PeriodLimit], current_time: i64) {
+    for window in windows {
+        window.roll_window(current_time);
+    }
 }
@@ -1,22 +1,105 @@
-use std::mem::size_of;
+use std::mem::{offset_of, size_of};
 
 use anchor_lang::prelude::*;
 use static_assertions::const_assert_eq;
 
-use crate::{error::JupStableError, state::common::PeriodLimit};
+use crate::{
+    error::JupStableError,
+    state::common::{
+        check_period_limit_configured, peek_mint_limits, peek_redeem_limits, period_mint_capacity,
+        period_redeem_capacity, roll_period_limits, PeriodLimit, PeriodLimitTemplate,
+    },
+};
 
 const_assert_eq!(Config::MAX_SIZE, size_of::<Config>());
 const_assert_eq!(size_of::<Config>() % 8, 0);
 
+// Layout map of the deployed account. Fields never move; new ones are carved out of `reserved`.
+const_assert_eq!(size_of::<Config>(), 496);
+const_assert_eq!(offset_of!(Config, mint), 0);
+const_assert_eq!(offset_of!(Config, authority), 32);
+const_assert_eq!(offset_of!(Config, token_program), 64);
+const_assert_eq!(offset_of!(Config, period_limits), 96);
+const_assert_eq!(offset_of!(Config, peg_price_usd), 288);
+const_assert_eq!(offset_of!(Config, decimals), 296);
+const_assert_eq!(offset_of!(Config, is_mint_redeem_enabled), 297);
+const_assert_eq!(offset_of!(Config, authority_bump), 298);
+const_assert_eq!(offset_of!(Config, config_bump), 299);
+const_assert_eq!(offset_of!(Config, skip_restart_slot_check), 300);
+const_assert_eq!(offset_of!(Config, strict_period_limits), 301);
+const_assert_eq!(offset_of!(Config, supply_check_enabled), 302);
+const_assert_eq!(offset_of!(Config, pause_blocks_withdraw), 303);
+const_assert_eq!(offset_of!(Config, pending_upgrade_authority), 304);
+const_assert_eq!(offset_of!(Config, reactivation_cooldown_seconds), 336);
+const_assert_eq!(offset_of!(Config, benefactor_registry_pages), 344);
+const_assert_eq!(offset_of!(Config, rent_treasury), 352);
+const_assert_eq!(offset_of!(Config, default_vault_period_limit), 384);
+const_assert_eq!(offset_of!(Config, default_benefactor_period_limit), 408);
+const_assert_eq!(offset_of!(Config, tracked_supply), 432);
+const_assert_eq!(offset_of!(Config, peg_price_history), 440);
+const_assert_eq!(offset_of!(Config, peg_price_history_head), 472);
+const_assert_eq!(offset_of!(Config, peg_symbol), 480);
+const_assert_eq!(offset_of!(Config, instance_seeded), 488);
+const_assert_eq!(offset_of!(Config, reserved), 489);
+
+#[constant]
 pub const CONFIG_PREFIX: &[u8; 6] = b"config";
+#[constant]
 pub const AUTHORITY_PREFIX: &[u8; 9] = b"authority";
 pub const MAX_PERIOD_LIMIT: usize = 4;
 pub const PEG_PRICE_DECIMALS: u32 = 4;
+pub const MIN_LP_DECIMALS: u8 = 6;
+pub const MAX_LP_DECIMALS: u8 = 9;
+pub const PEG_PRICE_HISTORY_LEN: usize = 4;
+pub const PEG_SYMBOL_LEN: usize = 8;
+/// Currency a config without a `peg_symbol` is pegged to
+pub const DEFAULT_PEG_SYMBOL: &str = "USD";
+
+/// Fails unless `peg_price_usd` lies strictly between 0 and 2 USD.
+pub fn check_peg_price_usd(peg_price_usd: u64) -> Result<()> {
+    require!(peg_price_usd > 0, JupStableError::InvalidPegPriceUSD);
+    require!(
+        peg_price_usd < 2 * 10_u64.pow(PEG_PRICE_DECIMALS),
+        JupStableError::InvalidPegPriceUSD
+    );
+    Ok(())
+}
+
+/// Fails unless `peg_symbol` is a non-empty run of uppercase ASCII letters and digits, padded
+/// with zeros on the right.
+pub fn check_peg_symbol(peg_symbol: &[u8; PEG_SYMBOL_LEN]) -> Result<()> {
+    let len = peg_symbol.iter().take_while(|byte| **byte != 0).count();
+    require!(len > 0, JupStableError::InvalidPegSymbol);
+    require!(
+        peg_symbol[..len]
+            .iter()
+            .all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit()),
+        JupStableError::InvalidPegSymbol
+    );
+    require!(
+        peg_symbol[len..].iter().all(|byte| *byte == 0),
+        JupStableError::InvalidPegSymbol
+    );
+    Ok(())
+}
+
+/// Seed that scopes the PDAs of a stablecoin instance: empty for the instance the deployment was
+/// initialized with, so its addresses stay those derived before instances existed, and the LP mint
+/// for every instance created through `init_instance`.
+pub fn instance_seed(instance_mint: &Pubkey) -> &[u8] {
+    if *instance_mint == Pubkey::default() {
+        &[]
+    } else {
+        instance_mint.as_ref()
+    }
+}
 
 #[macro_export]
 macro_rules! authority_seeds {
-    ($bump:expr) => {
-        &[AUTHORITY_PREFIX, &[$bump]]
+    ($config:expr) => {
+        &[AUTHORITY_PREFIX, $config.instance_seed(), &[
+            $config.authority_bump
+        ]]
     };
 }
 
@@ -31,8 +114,46 @@ pub struct Config {
     pub is_mint_redeem_enabled: u8,
     pub authority_bump: u8,
     pub config_bump: u8,
-    pub _padding: [u8; 4],
-    pub reserved: [u8; 192],
+    /// Zero keeps the oracle restart-slot guard on, so configs created before the flag existed
+    /// keep validating feeds against `LastRestartSlot`.
+    pub skip_restart_slot_check: u8,
+    /// Non-zero blocks mints and redeems against a config, vault or benefactor without any
+    /// period limit window, instead of treating it as unlimited.
+    pub strict_period_limits: u8,
+    /// Non-zero once `sync_supply` seeded `tracked_supply`, enabling the supply consistency check
+    pub supply_check_enabled: u8,
+    /// Non-zero stops `withdraw` while mint and redeem are paused. Zero keeps operator withdrawals
+    /// available during a pause, as before the flag existed.
+    pub pause_blocks_withdraw: u8,
+    /// Upgrade authority an admin announced through `set_pending_upgrade_authority`, cleared once
+    /// `confirm_upgrade_authority` observed it on the program data account.
+    pub pending_upgrade_authority: Pubkey,
+    /// Time a vault or benefactor stopped by a disabler role stays locked before a manager role
+    /// may re-enable it.
+    pub reactivation_cooldown_seconds: u64,
+    /// Number of `BenefactorRegistry` pages created so far
+    pub benefactor_registry_pages: u32,
+    pub _padding1: [u8; 4],
+    /// Account closures must refund rent to, default letting the caller pick the receiver
+    pub rent_treasury: Pubkey,
+    /// Copied into the first window of every vault created
+    pub default_vault_period_limit: PeriodLimitTemplate,
+    /// Copied into the first window of every benefactor created
+    pub default_benefactor_period_limit: PeriodLimitTemplate,
+    /// jupUSD supply issued through mint and flash mint, net of redeem burns
+    pub tracked_supply: u64,
+    /// Ring buffer of the peg prices `set_peg_price_usd` replaced, zero for unused slots
+    pub peg_price_history: [u64; PEG_PRICE_HISTORY_LEN],
+    /// Slot of `peg_price_history` the next replaced peg price is written to
+    pub peg_price_history_head: u8,
+    pub _padding2: [u8; 7],
+    /// Currency the stablecoin is pegged to and `peg_price_usd` is quoted in, zero meaning
+    /// [`DEFAULT_PEG_SYMBOL`]
+    pub peg_symbol: [u8; PEG_SYMBOL_LEN],
+    /// Non-zero for configs created by `init_instance`, whose PDAs and those of their authority,
+    /// vaults and benefactors are seeded by `mint`. Zero for the config created by `init`.
+    pub instance_seeded: u8,
+    pub reserved: [u8; 7],
 }
 
 impl Default for Config {
@@ -47,55 +168,274 @@ impl Default for Config {
             is_mint_redeem_enabled: 0,
             authority_bump: 0,
             config_bump: 0,
-            _padding: [0; 4],
-            reserved: [0; 192],
+            skip_restart_slot_check: 0,
+            strict_period_limits: 0,
+            supply_check_enabled: 0,
+            pause_blocks_withdraw: 0,
+            pending_upgrade_authority: Pubkey::default(),
+            reactivation_cooldown_seconds: 0,
+            benefactor_registry_pages: 0,
+            _padding1: [0; 4],
+            rent_treasury: Pubkey::default(),
+            default_vault_period_limit: PeriodLimitTemplate::default(),
+            default_benefactor_period_limit: PeriodLimitTemplate::default(),
+            tracked_supply: 0,
+            peg_price_history: [0; PEG_PRICE_HISTORY_LEN],
+            peg_price_history_head: 0,
+            _padding2: [0; 7],
+            peg_symbol: [0; PEG_SYMBOL_LEN],
+            instance_seeded: 0,
+            reserved: [0; 7],
         }
     }
 }
 impl Config {
-    pub const MAX_SIZE: usize =
-        32 + 32 + 32 + PeriodLimit::MAX_SIZE * MAX_PERIOD_LIMIT + 8 + 1 + 1 + 1 + 1 + 4 + 192;
+    pub const MAX_SIZE: usize = 32
+        + 32
+        + 32
+        + PeriodLimit::MAX_SIZE * MAX_PERIOD_LIMIT
+        + 8
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 32
+        + 8
+        + 4
+        + 4
+        + 32
+        + PeriodLimitTemplate::MAX_SIZE * 2
+        + 8
+        + 8 * PEG_PRICE_HISTORY_LEN
+        + 1
+        + 7
+        + PEG_SYMBOL_LEN
+        + 1
+        + 7;
 
     pub fn is_mint_redeem_enabled(&self) -> bool { self.is_mint_redeem_enabled == 1 }
 
-    pub fn set_peg_price_usd(&mut self, peg_price_usd: u64) { self.peg_price_usd = peg_price_usd; }
+    /// Sets the peg price and pushes the replaced one into `peg_price_history`.
+    pub fn set_peg_price_usd(&mut self, peg_price_usd: u64) -> u64 {
+        let old_peg_price_usd = self.peg_price_usd;
+        let head = self.peg_price_history_head as usize % PEG_PRICE_HISTORY_LEN;
+        self.peg_price_history[head] = old_peg_price_usd;
+        self.peg_price_history_head = ((head + 1) % PEG_PRICE_HISTORY_LEN) as u8;
+        self.peg_price_usd = peg_price_usd;
+        old_peg_price_usd
+    }
+
+    /// Previous peg prices, most recent first.
+    pub fn peg_price_history(&self) -> Vec<u64> {
+        let head = self.peg_price_history_head as usize % PEG_PRICE_HISTORY_LEN;
+        (1..=PEG_PRICE_HISTORY_LEN)
+            .map(|i| {
+                self.peg_price_history[(head + PEG_PRICE_HISTORY_LEN - i) % PEG_PRICE_HISTORY_LEN]
+            })
+            .filter(|peg_price_usd| *peg_price_usd != 0)
+            .collect()
+    }
+
+    pub fn peg_symbol(&self) -> &str {
+        let len = self
+            .peg_symbol
+            .iter()
+            .take_while(|byte| **byte != 0)
+            .count();
+        match std::str::from_utf8(&self.peg_symbol[..len]) {
+            Ok(peg_symbol) if !peg_symbol.is_empty() => peg_symbol,
+            _ => DEFAULT_PEG_SYMBOL,
+        }
+    }
+
+    /// Oracles quote collateral in USD, so any other peg needs every vault to convert through a
+    /// quote oracle.
+    pub fn requires_quote_oracle(&self) -> bool { self.peg_symbol() != DEFAULT_PEG_SYMBOL }
+
+    pub fn set_peg_symbol(&mut self, peg_symbol: [u8; PEG_SYMBOL_LEN]) {
+        self.peg_symbol = peg_symbol;
+    }
+
+    /// Mint vaults and benefactors record to tie them to this config, default for the config
+    /// created by `init`.
+    pub fn instance_mint(&self) -> Pubkey {
+        if self.instance_seeded == 0 {
+            Pubkey::default()
+        } else {
+            self.mint
+        }
+    }
+
+    pub fn instance_seed(&self) -> &[u8] {
+        if self.instance_seeded == 0 {
+            &[]
+        } else {
+            self.mint.as_ref()
+        }
+    }
 
     pub fn update_mint_redeem_enabled(&mut self, is_mint_redeem_enabled: bool) {
         self.is_mint_redeem_enabled = if is_mint_redeem_enabled { 1 } else { 0 };
     }
 
-    pub fn can_mint(&mut self, amount: u64, current_time: i64) -> Result<()> {
+    pub fn is_restart_slot_check_enabled(&self) -> bool { self.skip_restart_slot_check == 0 }
+
+    pub fn update_restart_slot_check(&mut self, enabled: bool) {
+        self.skip_restart_slot_check = if enabled { 0 } else { 1 };
+    }
+
+    pub fn is_strict_period_limits(&self) -> bool { self.strict_period_limits == 1 }
+
+    pub fn update_strict_period_limits(&mut self, enabled: bool) {
+        self.strict_period_limits = if enabled { 1 } else { 0 };
+    }
+
+    pub fn is_pause_blocking_withdraw(&self) -> bool { self.pause_blocks_withdraw == 1 }
+
+    pub fn update_pause_blocks_withdraw(&mut self, enabled: bool) {
+        self.pause_blocks_withdraw = if enabled { 1 } else { 0 };
+    }
+
+    pub fn check_withdraw_allowed(&self) -> Result<()> {
+        require!(
+            self.is_mint_redeem_enabled() || !self.is_pause_blocking_withdraw(),
+            JupStableError::ProtocolPaused
+        );
+        Ok(())
+    }
+
+    pub fn set_reactivation_cooldown_seconds(&mut self, reactivation_cooldown_seconds: u64) {
+        self.reactivation_cooldown_seconds = reactivation_cooldown_seconds;
+    }
+
+    pub fn set_rent_treasury(&mut self, rent_treasury: Pubkey) {
+        self.rent_treasury = rent_treasury;
+    }
+
+    pub fn is_rent_receiver(&self, receiver: &Pubkey) -> bool {
+        self.rent_treasury == Pubkey::default() || self.rent_treasury == *receiver
+    }
+
+    pub fn set_pending_upgrade_authority(&mut self, pending_upgrade_authority: Pubkey) {
+        self.pending_upgrade_authority = pending_upgrade_authority;
+    }
+
+    /// Clears the pending upgrade authority once the program data account reports it.
+    pub fn confirm_upgrade_authority(
+        &mut self,
+        upgrade_authority: Option<Pubkey>,
+    ) -> Result<Pubkey> {
+        let pending = self.pending_upgrade_authority;
+        require!(
+            pending != Pubkey::default(),
+            JupStableError::NoPendingUpgradeAuthority
+        );
+        require!(
+            upgrade_authority == Some(pending),
+            JupStableError::UpgradeAuthorityMismatch
+        );
+
+        self.pending_upgrade_authority = Pubkey::default();
+        Ok(pending)
+    }
+
+    pub fn can_mint(&self, amount: u64, current_time: i64) -> Result<()> {
         if !self.is_mint_redeem_enabled() {
             return err!(JupStableError::ProtocolPaused);
         }
+        check_period_limit_configured(
+            "config",
+            &self.period_limits,
+            self.is_strict_period_limits(),
+        )?;
 
-        for window in &mut self.period_limits {
-            window.roll_window(current_time);
-            window.check_mint_limit(amount)?;
+        peek_mint_limits("config", &self.period_limits, amount, current_time)
+    }
+
+    /// `pause_exempt` lets a benefactor exempt from the global pause through while it is paused.
+    pub fn can_redeem(&self, amount: u64, current_time: i64, pause_exempt: bool) -> Result<()> {
+        if !self.is_mint_redeem_enabled() && !pause_exempt {
+            return err!(JupStableError::ProtocolPaused);
         }
+        check_period_limit_configured(
+            "config",
+            &self.period_limits,
+            self.is_strict_period_limits(),
+        )?;
 
-        Ok(())
+        peek_redeem_limits("config", &self.period_limits, amount, current_time)
     }
 
-    pub fn can_redeem(&mut self, amount: u64, current_time: i64) -> Result<()> {
+    /// Rolls the period windows a mint or redeem that passed `can_mint` or `can_redeem` is then
+    /// recorded in.
+    pub fn roll_period_limits(&mut self, current_time: i64) {
+        roll_period_limits(&mut self.period_limits, current_time);
+    }
+
+    /// Amount `can_mint` would still accept at `current_time`, zero while paused.
+    pub fn mint_capacity(&self, current_time: i64) -> u64 {
         if !self.is_mint_redeem_enabled() {
-            return err!(JupStableError::ProtocolPaused);
+            return 0;
         }
 
-        for window in &mut self.period_limits {
-            window.roll_window(current_time);
-            window.check_redeem_limit(amount)?;
+        period_mint_capacity(
+            &self.period_limits,
+            current_time,
+            self.is_strict_period_limits(),
+        )
+    }
+
+    /// Amount `can_redeem` would still accept at `current_time`.
+    pub fn redeem_capacity(&self, current_time: i64, pause_exempt: bool) -> u64 {
+        if !self.is_mint_redeem_enabled() && !pause_exempt {
+            return 0;
         }
 
-        Ok(())
+        period_redeem_capacity(
+            &self.period_limits,
+            current_time,
+            self.is_strict_period_limits(),
+        )
     }
 
     pub fn record_mint(&mut self, amount: u64) {
+        self.tracked_supply = self.tracked_supply.saturating_add(amount);
         for window in &mut self.period_limits {
             window.record_mint(amount);
         }
     }
 
+    pub fn record_burn(&mut self, amount: u64) {
+        self.tracked_supply = self.tracked_supply.saturating_sub(amount);
+    }
+
+    pub fn is_supply_check_enabled(&self) -> bool { self.supply_check_enabled == 1 }
+
+    /// Starts tracking from the current `supply`, for configs that minted before tracking existed.
+    pub fn sync_supply(&mut self, supply: u64) {
+        self.tracked_supply = supply;
+        self.supply_check_enabled = 1;
+    }
+
+    /// Fails when the LP mint holds more than the program issued, i.e. someone minted around it.
+    /// Holders burning their own jupUSD only lowers `supply`, so that direction is not flagged.
+    pub fn check_supply_consistency(&self, supply: u64) -> Result<()> {
+        if !self.is_supply_check_enabled() || supply <= self.tracked_supply {
+            return Ok(());
+        }
+
+        msg!(
+            "config: lp mint supply {}, tracked supply {}",
+            supply,
+            self.tracked_supply
+        );
+        err!(JupStableError::SupplyMismatch)
+    }
+
     pub fn record_redeem(&mut self, amount: u64) {
         for window in &mut self.period_limits {
             window.record_redeem(amount);
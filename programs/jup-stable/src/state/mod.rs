@@ -1,5 +1,9 @@
 pub mod benefactor;
+pub mod benefactor_registry;
+pub mod collateral_approval;
 pub mod common;
 pub mod config;
 pub mod operator;
+pub mod operator_log;
 pub mod vault;
+pub mod vault_registry;
@@ -1,4 +1,4 @@
-use std::mem::size_of;
+use std::mem::{offset_of, size_of};
 
 use anchor_lang::prelude::*;
 use bytemuck::{Pod, Zeroable};
@@ -8,6 +8,15 @@ use crate::error::JupStableError;
 
 const_assert_eq!(Operator::MAX_SIZE, size_of::<Operator>());
 
+// Deployed layout
+const_assert_eq!(size_of::<Operator>(), 176);
+const_assert_eq!(offset_of!(Operator, operator_authority), 0);
+const_assert_eq!(offset_of!(Operator, role), 32);
+const_assert_eq!(offset_of!(Operator, status), 40);
+const_assert_eq!(offset_of!(Operator, has_log), 41);
+const_assert_eq!(offset_of!(Operator, reserved), 48);
+
+#[constant]
 pub const OPERATOR_PREFIX: &[u8; 8] = b"operator";
 
 #[repr(u8)]
@@ -38,7 +47,9 @@ pub struct Operator {
     pub operator_authority: Pubkey,
     pub role: u64,
     pub status: OperatorStatus,
-    pub _padding0: [u8; 7],
+    /// Set once the operator created its `OperatorLog`
+    pub has_log: u8,
+    pub _padding0: [u8; 6],
     pub reserved: [u8; 128],
 }
 
@@ -48,14 +59,15 @@ impl Default for Operator {
             operator_authority: Pubkey::default(),
             role: 0,
             status: OperatorStatus::Disabled,
-            _padding0: [0; 7],
+            has_log: 0,
+            _padding0: [0; 6],
             reserved: [0; 128],
         }
     }
 }
 
 impl Operator {
-    pub const MAX_SIZE: usize = 32 + 8 + 1 + 7 + 128;
+    pub const MAX_SIZE: usize = 32 + 8 + 1 + 1 + 6 + 128;
 
     pub fn is(&self, role: OperatorRole) -> Result<()> {
         require!(
@@ -69,6 +81,18 @@ impl Operator {
         Ok(())
     }
 
+    /// Operators only grant roles they hold themselves, so no operator can hand out more
+    /// privileges than it has.
+    pub fn can_grant(&self, role: OperatorRole) -> Result<()> {
+        require!(
+            self.role & (1 << role as u64) != 0,
+            JupStableError::RoleNotHeld
+        );
+        Ok(())
+    }
+
+    pub fn has_log(&self) -> bool { self.has_log == 1 }
+
     pub fn set_role(&mut self, role: OperatorRole) { self.role |= 1 << role as u64; }
 
     pub fn clear_role(&mut self, role: OperatorRole) { self.role &= !(1 << role as u64); }
@@ -110,4 +134,19 @@ mod tests {
         operator.clear_role(OperatorRole::PeriodManager);
         assert!(operator.is(OperatorRole::PeriodManager).is_err());
     }
+
+    #[test]
+    fn test_operator_can_grant_held_roles_only() {
+        let mut operator = Operator {
+            status: OperatorStatus::Enabled,
+            ..Operator::default()
+        };
+        operator.set_role(OperatorRole::Admin);
+        operator.set_role(OperatorRole::VaultManager);
+
+        assert!(operator.can_grant(OperatorRole::Admin).is_ok());
+        assert!(operator.can_grant(OperatorRole::VaultManager).is_ok());
+        assert!(operator.can_grant(OperatorRole::PegManager).is_err());
+        assert!(operator.can_grant(OperatorRole::VaultDisabler).is_err());
+    }
 }
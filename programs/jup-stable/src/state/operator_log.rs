@@ -0,0 +1,136 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use static_assertions::const_assert_eq;
+
+use crate::error::JupStableError;
+
+const_assert_eq!(OperatorLog::MAX_SIZE, size_of::<OperatorLog>());
+const_assert_eq!(size_of::<OperatorLog>() % 8, 0);
+
+#[constant]
+pub const OPERATOR_LOG_PREFIX: &[u8; 12] = b"operator_log";
+pub const OPERATOR_LOG_LEN: usize = 32;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum OperatorAction {
+    ManageConfig,
+    CreateOperator,
+    DeleteOperator,
+    ManageOperator,
+    CreateBenefactor,
+    ManageBenefactor,
+    DeleteBenefactor,
+    CreateVault,
+    ManageVault,
+    Withdraw,
+    SetPendingUpgradeAuthority,
+    CreateVaultRegistry,
+    CreateBenefactorRegistryPage,
+    ApproveCollateral,
+    SyncSupply,
+    BurnTreasury,
+    InitInstance,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+pub struct OperatorLogEntry {
+    /// Account the action was applied to
+    pub target: Pubkey,
+    pub timestamp: i64,
+    /// `OperatorAction` discriminant
+    pub action: u8,
+    pub _padding: [u8; 7],
+}
+
+unsafe impl Pod for OperatorLogEntry {}
+unsafe impl Zeroable for OperatorLogEntry {}
+
+impl OperatorLogEntry {
+    pub const MAX_SIZE: usize = 32 + 8 + 1 + 7;
+}
+
+/// Ring buffer of the last `OPERATOR_LOG_LEN` privileged actions of an operator. Every privileged
+/// instruction an operator signs has to pass and append to it.
+#[account(zero_copy)]
+pub struct OperatorLog {
+    pub operator: Pubkey,
+    /// Number of actions recorded so far; the next entry goes to `count % OPERATOR_LOG_LEN`.
+    pub count: u64,
+    pub entries: [OperatorLogEntry; OPERATOR_LOG_LEN],
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    pub reserved: [u8; 64],
+}
+
+impl Default for OperatorLog {
+    fn default() -> Self {
+        OperatorLog {
+            operator: Pubkey::default(),
+            count: 0,
+            entries: [OperatorLogEntry::default(); OPERATOR_LOG_LEN],
+            bump: 0,
+            _padding: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl OperatorLog {
+    pub const MAX_SIZE: usize = 32 + 8 + OperatorLogEntry::MAX_SIZE * OPERATOR_LOG_LEN + 1 + 7 + 64;
+
+    pub fn push(&mut self, action: OperatorAction, target: Pubkey, timestamp: i64) {
+        let index = (self.count % OPERATOR_LOG_LEN as u64) as usize;
+        self.entries[index] = OperatorLogEntry {
+            target,
+            timestamp,
+            action: action as u8,
+            _padding: [0; 7],
+        };
+        self.count += 1;
+    }
+
+    /// Entries from oldest to newest.
+    pub fn entries(&self) -> impl Iterator<Item = &OperatorLogEntry> {
+        let len = self.count.min(OPERATOR_LOG_LEN as u64) as usize;
+        let start = ((self.count - len as u64) % OPERATOR_LOG_LEN as u64) as usize;
+        (0..len).map(move |i| &self.entries[(start + i) % OPERATOR_LOG_LEN])
+    }
+
+    /// Appends `action` to `log`. Every operator gets its log when it is created, and operators
+    /// that predate logs have to create theirs before they can act again.
+    pub fn record(
+        log: &Option<AccountLoader<OperatorLog>>,
+        action: OperatorAction,
+        target: Pubkey,
+    ) -> Result<()> {
+        let log = log.as_ref().ok_or(JupStableError::MissingOperatorLog)?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        log.load_mut()?.push(action, target, timestamp);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operator_log_wraps() {
+        let mut log = OperatorLog::default();
+        assert_eq!(log.entries().count(), 0);
+
+        let total = OPERATOR_LOG_LEN as i64 + 3;
+        for timestamp in 0..total {
+            log.push(OperatorAction::ManageVault, Pubkey::default(), timestamp);
+        }
+
+        let timestamps: Vec<i64> = log.entries().map(|entry| entry.timestamp).collect();
+        assert_eq!(timestamps.len(), OPERATOR_LOG_LEN);
+        assert_eq!(timestamps[0], 3);
+        assert_eq!(timestamps[OPERATOR_LOG_LEN - 1], total - 1);
+    }
+}
@@ -1,23 +1,71 @@
-use std::mem::size_of;
+use std::mem::{offset_of, size_of};
 
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 use bytemuck::{Pod, Zeroable};
 use rust_decimal::{prelude::ToPrimitive, Decimal};
 use static_assertions::const_assert_eq;
 
-use crate::{error::JupStableError, oracle::OraclePrice, state::common::PeriodLimit};
+use crate::{
+    error::JupStableError,
+    oracle::{OraclePrice, OracleQuote},
+    state::{
+        common::{
+            check_period_limit_configured, check_reactivation_cooldown, peek_mint_limits,
+            peek_redeem_limits, period_mint_capacity, period_redeem_capacity, roll_period_limits,
+            PeriodLimit,
+        },
+        config::instance_seed,
+    },
+};
 
 const_assert_eq!(Vault::MAX_SIZE, size_of::<Vault>());
 
+// Deployed layout, additions come out of `reserved` and must not shift these offsets
+const_assert_eq!(size_of::<Vault>(), 1288);
+const_assert_eq!(offset_of!(Vault, mint), 0);
+const_assert_eq!(offset_of!(Vault, custodian), 32);
+const_assert_eq!(offset_of!(Vault, token_account), 64);
+const_assert_eq!(offset_of!(Vault, token_program), 96);
+const_assert_eq!(offset_of!(Vault, stalesness_threshold), 128);
+const_assert_eq!(offset_of!(Vault, min_oracle_price_usd), 136);
+const_assert_eq!(offset_of!(Vault, max_oracle_price_usd), 144);
+const_assert_eq!(offset_of!(Vault, status), 152);
+const_assert_eq!(offset_of!(Vault, bump), 160);
+const_assert_eq!(offset_of!(Vault, decimals), 161);
+const_assert_eq!(offset_of!(Vault, oracles), 168);
+const_assert_eq!(offset_of!(Vault, period_limits), 776);
+const_assert_eq!(offset_of!(Vault, instance_mint), 968);
+const_assert_eq!(offset_of!(Vault, total_minted), 1000);
+const_assert_eq!(offset_of!(Vault, total_redeemed), 1016);
+const_assert_eq!(offset_of!(Vault, last_price), 1032);
+const_assert_eq!(offset_of!(Vault, last_price_ts), 1040);
+const_assert_eq!(offset_of!(Vault, last_spread_bps), 1048);
+const_assert_eq!(offset_of!(Vault, max_price_jump_bps), 1050);
+const_assert_eq!(offset_of!(Vault, confidence_factor_bps), 1052);
+const_assert_eq!(offset_of!(Vault, symmetric_oracle_bounds), 1054);
+const_assert_eq!(offset_of!(Vault, benefactor_optional), 1055);
+const_assert_eq!(offset_of!(Vault, max_net_outstanding), 1056);
+const_assert_eq!(offset_of!(Vault, disabled_at), 1064);
+const_assert_eq!(offset_of!(Vault, custodian_token_account), 1072);
+const_assert_eq!(offset_of!(Vault, max_mint_per_tx), 1104);
+const_assert_eq!(offset_of!(Vault, max_redeem_per_tx), 1112);
+const_assert_eq!(offset_of!(Vault, total_acknowledged), 1120);
+const_assert_eq!(offset_of!(Vault, last_acknowledged_ts), 1136);
+const_assert_eq!(offset_of!(Vault, quote_oracle), 1144);
+const_assert_eq!(offset_of!(Vault, price_jump_window_seconds), 1268);
+const_assert_eq!(offset_of!(Vault, reserved), 1272);
+
 pub const MAX_ORACLES: usize = 5;
 pub const MAX_PERIOD_LIMIT: usize = 4;
+#[constant]
 pub const VAULT_PREFIX: &[u8; 5] = b"vault";
 pub const ORACLE_PRICE_DECIMALS: u32 = 4;
 
 #[macro_export]
 macro_rules! vault_seeds {
-    ($mint:expr, $bump:expr) => {
-        &[VAULT_PREFIX, $mint.as_ref(), &[$bump]]
+    ($instance_seed:expr, $mint:expr, $bump:expr) => {
+        &[VAULT_PREFIX, $instance_seed, $mint.as_ref(), &[$bump]]
     };
 }
 
@@ -107,12 +155,54 @@ pub struct Vault {
 
     pub period_limits: [PeriodLimit; MAX_PERIOD_LIMIT],
 
-    pub reserved1: [u8; 32],
+    /// LP mint of the instance the vault was created in, default for the instance created by
+    /// `init`
+    pub instance_mint: Pubkey,
 
     pub total_minted: [u8; 16],
     pub total_redeemed: [u8; 16],
 
-    pub reserved: [u8; 256],
+    /// Oracle price of the last mint or redeem, with `ORACLE_PRICE_DECIMALS` decimals.
+    pub last_price: u64,
+    pub last_price_ts: i64,
+    /// Spread between the vault's oracles at `last_price_ts`.
+    pub last_spread_bps: u16,
+    /// Largest move allowed from `last_price` while it is fresh, zero disables the guard.
+    pub max_price_jump_bps: u16,
+    /// Share of the oracle confidence interval mints and redeems are priced against, in bps of
+    /// the interval. Zero prices at the oracle price.
+    pub confidence_factor_bps: u16,
+    /// Non-zero checks both oracle price bounds on mint and redeem, instead of only the min bound
+    /// on mint and the max bound on redeem.
+    pub symmetric_oracle_bounds: u8,
+    /// Non-zero lets any user mint and redeem through the vault's public benefactor, whose
+    /// authority is the vault address, on top of benefactors used by their own authority.
+    pub benefactor_optional: u8,
+    /// Cap on `total_minted - total_redeemed` against this collateral, zero meaning uncapped
+    pub max_net_outstanding: u64,
+    /// When a disabler role last stopped the vault, zero once re-enabled
+    pub disabled_at: i64,
+    /// Token account collateral is deposited to, default meaning the custodian's ATA
+    pub custodian_token_account: Pubkey,
+    /// Largest jupUSD amount a single mint may issue against this vault, zero meaning uncapped
+    pub max_mint_per_tx: u64,
+    /// Largest jupUSD amount a single redeem may burn against this vault, zero meaning uncapped
+    pub max_redeem_per_tx: u64,
+    /// Collateral the custodian confirmed receiving through `acknowledge_deposit`
+    pub total_acknowledged: [u8; 16],
+    /// When the custodian last acknowledged a deposit, zero if it never did
+    pub last_acknowledged_ts: i64,
+    /// Feed quoting USD in the peg currency, composed with the USD prices of `oracles`. Required
+    /// unless the stablecoin is pegged to USD, and empty otherwise. `min_oracle_price_usd` and
+    /// `max_oracle_price_usd` bound the composed price.
+    pub quote_oracle: OracleType,
+
+    pub _padding4: [u8; 3],
+    /// How long `last_price` bounds the next fill through `max_price_jump_bps`, zero meaning the
+    /// staleness threshold
+    pub price_jump_window_seconds: u32,
+
+    pub reserved: [u8; 16],
 }
 
 impl Default for Vault {
@@ -130,13 +220,30 @@ impl Default for Vault {
             bump: 0,
             decimals: 0,
             _padding2: [0; 6],
-            reserved1: [0; 32],
+            instance_mint: Pubkey::default(),
             oracles: [OracleType::Empty(Default::default()); MAX_ORACLES],
             _padding3: [0; 3],
             period_limits: [PeriodLimit::default(); MAX_PERIOD_LIMIT],
             total_minted: [0; 16],
             total_redeemed: [0; 16],
-            reserved: [0; 256],
+            last_price: 0,
+            last_price_ts: 0,
+            last_spread_bps: 0,
+            max_price_jump_bps: 0,
+            confidence_factor_bps: 0,
+            symmetric_oracle_bounds: 0,
+            benefactor_optional: 0,
+            max_net_outstanding: 0,
+            disabled_at: 0,
+            custodian_token_account: Pubkey::default(),
+            max_mint_per_tx: 0,
+            max_redeem_per_tx: 0,
+            total_acknowledged: [0; 16],
+            last_acknowledged_ts: 0,
+            quote_oracle: OracleType::Empty(Default::default()),
+            _padding4: [0; 3],
+            price_jump_window_seconds: 0,
+            reserved: [0; 16],
         }
     }
 }
@@ -155,10 +262,24 @@ impl Vault {
         6 + // _padding2
         OracleType::MAX_SIZE * MAX_ORACLES + // oracles array
         3 + // _padding3
-        32 + // reserved
+        32 + // instance_mint
         PeriodLimit::MAX_SIZE * MAX_PERIOD_LIMIT + // rate limit windows
         16 + 16 + // total stats
-        256;
+        8 + 8 + // last_price and last_price_ts
+        2 + 2 + // last_spread_bps and max_price_jump_bps
+        2 + // confidence_factor_bps
+        1 + // symmetric_oracle_bounds
+        1 + // benefactor_optional
+        8 + // max_net_outstanding
+        8 + // disabled_at
+        32 + // custodian_token_account
+        8 + 8 + // max_mint_per_tx and max_redeem_per_tx
+        16 + // total_acknowledged
+        8 + // last_acknowledged_ts
+        OracleType::MAX_SIZE + // quote_oracle
+        3 + // _padding4
+        4 + // price_jump_window_seconds
+        16;
 
     // reserved
 
@@ -178,6 +299,30 @@ impl Vault {
         Ok(())
     }
 
+    pub fn instance_seed(&self) -> &[u8] { instance_seed(&self.instance_mint) }
+
+    /// Token account mints deposit to and withdrawals pay out to.
+    pub fn resolve_custodian_token_account(&self) -> Pubkey {
+        if self.custodian_token_account != Pubkey::default() {
+            return self.custodian_token_account;
+        }
+        get_associated_token_address_with_program_id(
+            &self.custodian,
+            &self.mint,
+            &self.token_program,
+        )
+    }
+
+    pub fn set_custodian(&mut self, custodian: Pubkey) {
+        self.custodian = custodian;
+        // An override belongs to the previous custodian
+        self.custodian_token_account = Pubkey::default();
+    }
+
+    pub fn set_custodian_token_account(&mut self, custodian_token_account: Pubkey) {
+        self.custodian_token_account = custodian_token_account;
+    }
+
     pub fn set_min_oracle_price_usd(&mut self, min_oracle_price_usd: u64) {
         self.min_oracle_price_usd = min_oracle_price_usd;
     }
@@ -186,29 +331,145 @@ impl Vault {
         self.max_oracle_price_usd = max_oracle_price_usd;
     }
 
+    fn oracle_price_usd(oracle_price: &OraclePrice) -> Result<u64> {
+        let price = oracle_price.0 * Decimal::from(10_i64.pow(ORACLE_PRICE_DECIMALS));
+        Ok(price.to_u64().ok_or(JupStableError::MathOverflow)?)
+    }
+
     pub fn validate_oracle_price(&self, oracle_price: &OraclePrice, is_mint: bool) -> Result<()> {
-        let oracle_price_usd = (oracle_price.0 * Decimal::from(10_i64.pow(ORACLE_PRICE_DECIMALS)))
-            .to_u64()
-            .ok_or(JupStableError::MathOverflow)?;
-        if is_mint {
-        require!(
-                oracle_price_usd >= self.min_oracle_price_usd,
-                JupStableError::BadOracle
+        let oracle_price_usd = Self::oracle_price_usd(oracle_price)?;
+        let symmetric = self.is_symmetric_oracle_bounds();
+        if (is_mint || symmetric) && oracle_price_usd < self.min_oracle_price_usd {
+            msg!(
+                "vault: oracle price {}, min oracle price {}",
+                oracle_price_usd,
+                self.min_oracle_price_usd
             );
-        } else {
-            require!(
-                oracle_price_usd <= self.max_oracle_price_usd,
-                JupStableError::BadOracle
+            return err!(JupStableError::BadOracle);
+        }
+        if (!is_mint || symmetric) && oracle_price_usd > self.max_oracle_price_usd {
+            msg!(
+                "vault: oracle price {}, max oracle price {}",
+                oracle_price_usd,
+                self.max_oracle_price_usd
+            );
+            return err!(JupStableError::BadOracle);
+        }
+        Ok(())
+    }
+
+    /// Rejects a price that moved more than `max_price_jump_bps` away from the last executed one,
+    /// as long as that one is younger than the price jump window.
+    pub fn check_price_jump(&self, oracle_price: &OraclePrice, current_time: i64) -> Result<()> {
+        if self.max_price_jump_bps == 0 || self.last_price == 0 {
+            return Ok(());
+        }
+        let age = current_time.saturating_sub(self.last_price_ts);
+        if age > i64::try_from(self.price_jump_window())? {
+            return Ok(());
+        }
+
+        let oracle_price_usd = Self::oracle_price_usd(oracle_price)?;
+        let jump = u128::from(oracle_price_usd.abs_diff(self.last_price));
+        let jump_bps = jump * 10_000 / u128::from(self.last_price);
+        if jump_bps > u128::from(self.max_price_jump_bps) {
+            msg!(
+                "vault: oracle price {}, last price {}, jump {} bps, max jump {} bps",
+                oracle_price_usd,
+                self.last_price,
+                jump_bps,
+                self.max_price_jump_bps
             );
+            return err!(JupStableError::PriceJumpTooLarge);
         }
         Ok(())
     }
 
+    pub fn record_price(
+        &mut self,
+        oracle_price: &OraclePrice,
+        spread_bps: u16,
+        current_time: i64,
+    ) -> Result<()> {
+        self.last_price = Self::oracle_price_usd(oracle_price)?;
+        self.last_price_ts = current_time;
+        self.last_spread_bps = spread_bps;
+        Ok(())
+    }
+
+    /// Shades the quoted price against the user by `confidence_factor_bps` of its confidence
+    /// interval: down when minting, up when redeeming.
+    pub fn conservative_price(&self, quote: &OracleQuote, is_mint: bool) -> Result<OraclePrice> {
+        let adjustment =
+            quote.confidence * Decimal::from(self.confidence_factor_bps) / Decimal::from(10_000);
+        let price = if is_mint {
+            quote.price.0 - adjustment
+        } else {
+            quote.price.0 + adjustment
+        };
+        require!(price > Decimal::ZERO, JupStableError::BadOracle);
+        Ok(OraclePrice(price))
+    }
+
+    pub fn set_confidence_factor_bps(&mut self, confidence_factor_bps: u16) {
+        self.confidence_factor_bps = confidence_factor_bps;
+    }
+
+    pub fn is_symmetric_oracle_bounds(&self) -> bool { self.symmetric_oracle_bounds == 1 }
+
+    pub fn set_symmetric_oracle_bounds(&mut self, enabled: bool) {
+        self.symmetric_oracle_bounds = if enabled { 1 } else { 0 };
+    }
+
+    pub fn requires_benefactor(&self) -> bool { self.benefactor_optional == 0 }
+
+    pub fn set_requires_benefactor(&mut self, required: bool) {
+        self.benefactor_optional = if required { 0 } else { 1 };
+    }
+
+    pub fn set_max_net_outstanding(&mut self, max_net_outstanding: u64) {
+        self.max_net_outstanding = max_net_outstanding;
+    }
+
+    pub fn set_per_tx_limits(&mut self, max_mint_per_tx: u64, max_redeem_per_tx: u64) {
+        self.max_mint_per_tx = max_mint_per_tx;
+        self.max_redeem_per_tx = max_redeem_per_tx;
+    }
+
+    pub fn set_max_price_jump_bps(&mut self, max_price_jump_bps: u16) {
+        self.max_price_jump_bps = max_price_jump_bps;
+    }
+
+    pub fn price_jump_window(&self) -> u64 {
+        match self.price_jump_window_seconds {
+            0 => self.stalesness_threshold,
+            window => window.into(),
+        }
+    }
+
+    pub fn set_price_jump_window_seconds(&mut self, price_jump_window_seconds: u32) {
+        self.price_jump_window_seconds = price_jump_window_seconds;
+    }
+
     pub fn set_stalesness_threshold(&mut self, stalesness_threshold: u64) {
         self.stalesness_threshold = stalesness_threshold;
     }
 
-    pub fn set_status(&mut self, status: VaultStatus) { self.status = status; }
+    pub fn set_status(&mut self, status: VaultStatus) {
+        if status == VaultStatus::Enabled {
+            self.disabled_at = 0;
+        }
+        self.status = status;
+    }
+
+    pub fn disable(&mut self, current_time: i64) {
+        self.status = VaultStatus::Disabled;
+        self.disabled_at = current_time;
+    }
+
+    pub fn check_reactivation(&self, cooldown_seconds: u64, current_time: i64) -> Result<()> {
+        check_reactivation_cooldown("vault", self.disabled_at, cooldown_seconds, current_time)
+    }
 
     pub fn update_oracle(&mut self, index: usize, oracle: &OracleType) -> Result<()> {
         if index >= MAX_ORACLES {
@@ -220,6 +481,10 @@ impl Vault {
         Ok(())
     }
 
+    pub fn has_quote_oracle(&self) -> bool { !matches!(self.quote_oracle, OracleType::Empty(_)) }
+
+    pub fn update_quote_oracle(&mut self, oracle: &OracleType) { self.quote_oracle = *oracle; }
+
     pub fn update_period_limit(
         &mut self,
         index: usize,
@@ -252,25 +517,96 @@ impl Vault {
         Ok(())
     }
 
-    pub fn can_mint(&mut self, amount: u64, current_time: i64) -> Result<()> {
+    pub fn can_mint(
+        &self,
+        amount: u64,
+        current_time: i64,
+        strict_period_limits: bool,
+    ) -> Result<()> {
+        self.is_enabled()?;
+        check_per_tx_limit("mint", amount, self.max_mint_per_tx)?;
+        self.check_net_outstanding(amount)?;
+        check_period_limit_configured("vault", &self.period_limits, strict_period_limits)?;
+
+        peek_mint_limits("vault", &self.period_limits, amount, current_time)
+    }
+
+    pub fn can_redeem(
+        &self,
+        amount: u64,
+        current_time: i64,
+        strict_period_limits: bool,
+    ) -> Result<()> {
         self.is_enabled()?;
+        check_per_tx_limit("redeem", amount, self.max_redeem_per_tx)?;
+        check_period_limit_configured("vault", &self.period_limits, strict_period_limits)?;
 
-        for window in &mut self.period_limits {
-            window.roll_window(current_time);
-            window.check_mint_limit(amount)?;
+        peek_redeem_limits("vault", &self.period_limits, amount, current_time)
+    }
+
+    /// Rolls the period windows ahead of `record_mint` or `record_redeem`.
+    pub fn roll_period_limits(&mut self, current_time: i64) {
+        roll_period_limits(&mut self.period_limits, current_time);
+    }
+
+    /// Amount `can_mint` would still accept at `current_time`, including the per transaction and
+    /// net outstanding caps.
+    pub fn mint_capacity(&self, current_time: i64, strict_period_limits: bool) -> u64 {
+        if self.status != VaultStatus::Enabled {
+            return 0;
         }
 
-        Ok(())
+        let mut capacity =
+            period_mint_capacity(&self.period_limits, current_time, strict_period_limits);
+        if self.max_mint_per_tx != 0 {
+            capacity = capacity.min(self.max_mint_per_tx);
+        }
+        if self.max_net_outstanding != 0 {
+            let available =
+                (self.max_net_outstanding as u128).saturating_sub(self.net_outstanding());
+            capacity = capacity.min(available as u64);
+        }
+        capacity
     }
 
-    pub fn can_redeem(&mut self, amount: u64, current_time: i64) -> Result<()> {
-        self.is_enabled()?;
+    /// Amount `can_redeem` would still accept at `current_time`.
+    pub fn redeem_capacity(&self, current_time: i64, strict_period_limits: bool) -> u64 {
+        if self.status != VaultStatus::Enabled {
+            return 0;
+        }
 
-        for window in &mut self.period_limits {
-            window.roll_window(current_time);
-            window.check_redeem_limit(amount)?;
+        let capacity =
+            period_redeem_capacity(&self.period_limits, current_time, strict_period_limits);
+        if self.max_redeem_per_tx != 0 {
+            return capacity.min(self.max_redeem_per_tx);
         }
+        capacity
+    }
+
+    pub fn total_minted(&self) -> u128 { u128::from_le_bytes(self.total_minted) }
+
+    pub fn total_redeemed(&self) -> u128 { u128::from_le_bytes(self.total_redeemed) }
+
+    pub fn total_acknowledged(&self) -> u128 { u128::from_le_bytes(self.total_acknowledged) }
+
+    pub fn net_outstanding(&self) -> u128 {
+        self.total_minted().saturating_sub(self.total_redeemed())
+    }
 
+    fn check_net_outstanding(&self, amount: u64) -> Result<()> {
+        if self.max_net_outstanding == 0 {
+            return Ok(());
+        }
+
+        let available = (self.max_net_outstanding as u128).saturating_sub(self.net_outstanding());
+        if amount as u128 > available {
+            msg!(
+                "vault net outstanding limit: requested {}, available {}",
+                amount,
+                available
+            );
+            return err!(JupStableError::NetOutstandingLimitExceeded);
+        }
         Ok(())
     }
 
@@ -286,6 +622,13 @@ impl Vault {
         self.total_redeemed = fake_u128.to_le_bytes();
     }
 
+    pub fn record_acknowledged_deposit(&mut self, amount: u64, current_time: i64) {
+        let mut fake_u128 = u128::from_le_bytes(self.total_acknowledged);
+        fake_u128 += amount as u128;
+        self.total_acknowledged = fake_u128.to_le_bytes();
+        self.last_acknowledged_ts = current_time;
+    }
+
     pub fn record_mint(&mut self, amount: u64) {
         self.record_total_minted(amount);
 
@@ -302,3 +645,16 @@ impl Vault {
         }
     }
 }
+
+fn check_per_tx_limit(kind: &str, amount: u64, max_per_tx: u64) -> Result<()> {
+    if max_per_tx != 0 && amount > max_per_tx {
+        msg!(
+            "vault {} per tx limit: requested {}, max {}",
+            kind,
+            amount,
+            max_per_tx
+        );
+        return err!(JupStableError::PerTxLimitExceeded);
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+use crate::error::JupStableError;
+
+const_assert_eq!(VaultRegistry::MAX_SIZE, size_of::<VaultRegistry>());
+const_assert_eq!(size_of::<VaultRegistry>() % 8, 0);
+
+#[constant]
+pub const VAULT_REGISTRY_PREFIX: &[u8; 14] = b"vault_registry";
+pub const MAX_VAULTS: usize = 64;
+
+/// Mints of every vault created, in creation order, so clients can enumerate collaterals with a
+/// single account fetch.
+#[account(zero_copy)]
+pub struct VaultRegistry {
+    pub count: u64,
+    pub mints: [Pubkey; MAX_VAULTS],
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    pub reserved: [u8; 64],
+}
+
+impl Default for VaultRegistry {
+    fn default() -> Self {
+        VaultRegistry {
+            count: 0,
+            mints: [Pubkey::default(); MAX_VAULTS],
+            bump: 0,
+            _padding: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl VaultRegistry {
+    pub const MAX_SIZE: usize = 8 + 32 * MAX_VAULTS + 1 + 7 + 64;
+
+    pub fn mints(&self) -> &[Pubkey] { &self.mints[..self.count as usize] }
+
+    pub fn push(&mut self, mint: Pubkey) -> Result<()> {
+        require!(
+            !self.mints().contains(&mint),
+            JupStableError::VaultAlreadyRegistered
+        );
+        require!(
+            (self.count as usize) < MAX_VAULTS,
+            JupStableError::VaultRegistryFull
+        );
+
+        self.mints[self.count as usize] = mint;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_registry_push() {
+        let mut registry = VaultRegistry::default();
+        let mint = Pubkey::new_unique();
+
+        registry.push(mint).unwrap();
+        assert_eq!(registry.mints(), &[mint]);
+        assert!(registry.push(mint).is_err());
+
+        for _ in 1..MAX_VAULTS {
+            registry.push(Pubkey::new_unique()).unwrap();
+        }
+        assert!(registry.push(Pubkey::new_unique()).is_err());
+    }
+}
@@ -1,13 +1,19 @@
-use fixtures::test::TestFixture;
-use jup_stable::state::config::Config;
+use fixtures::{event::logged_events, test::TestFixture, utils::patch_program_data_account};
+use jup_stable::{event::PegPriceUpdatedEvent, state::config::Config};
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    bpf_loader_upgradeable::get_program_data_address, pubkey::Pubkey, signature::Keypair,
+    signer::Signer, transaction::Transaction,
+};
 
 use crate::common::{
-    derivation::find_config,
+    derivation::{find_config, find_operator},
     faciliter::setup_full_test_context,
     instructions::{
-        create_reset_config_period_limit_instruction, create_update_config_period_limit_instruction,
+        create_confirm_upgrade_authority_instruction, create_reset_config_period_limit_instruction,
+        create_set_peg_price_usd_instruction, create_set_pending_upgrade_authority_instruction,
+        create_update_config_period_limit_instruction,
+        SetPendingUpgradeAuthorityInstructionAccounts,
     },
 };
 
@@ -193,3 +199,117 @@ async fn update_config_period_limit_fails_when_not_admin() -> anyhow::Result<()>
 
     Ok(())
 }
+
+#[tokio::test]
+async fn set_peg_price_usd_emits_event_and_keeps_history() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+
+    let mut ctx = test_f.context.borrow_mut();
+    let last_blockhash = ctx.get_new_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[create_set_peg_price_usd_instruction(deployer, 10_050)],
+        Some(&deployer),
+        &[&test_f.deployer],
+        last_blockhash,
+    );
+    let outcome = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    drop(ctx);
+    outcome.result?;
+    let metadata = outcome.metadata.unwrap();
+
+    let events = logged_events::<PegPriceUpdatedEvent>(&metadata.log_messages);
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.operator, find_operator(&deployer));
+    assert_eq!(event.old_peg_price_usd, 10_000);
+    assert_eq!(event.new_peg_price_usd, 10_050);
+    assert!(event.timestamp > 0 && event.timestamp <= test_f.get_clock().await.unix_timestamp);
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.peg_price_usd, 10_050);
+    assert_eq!(config.peg_price_history(), vec![10_000]);
+
+    // Wraps around once more pegs were replaced than the history holds
+    for peg_price_usd in [10_100, 10_150, 10_200, 10_250] {
+        test_f
+            .send_ixs(
+                &[create_set_peg_price_usd_instruction(
+                    deployer,
+                    peg_price_usd,
+                )],
+                &[&test_f.deployer],
+            )
+            .await?;
+    }
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.peg_price_usd, 10_250);
+    assert_eq!(config.peg_price_history(), vec![
+        10_200, 10_150, 10_100, 10_050
+    ]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn upgrade_authority_handover_is_confirmed_against_program_data() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+    let program_data = get_program_data_address(&jup_stable::ID);
+    let new_upgrade_authority = Keypair::new().pubkey();
+
+    let result = test_f
+        .send_ixs(
+            &[create_confirm_upgrade_authority_instruction(program_data)],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err(), "Nothing is pending yet");
+
+    test_f
+        .send_ixs(
+            &[create_set_pending_upgrade_authority_instruction(
+                SetPendingUpgradeAuthorityInstructionAccounts {
+                    operator_authority: deployer,
+                    program_data,
+                },
+                new_upgrade_authority,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.pending_upgrade_authority, new_upgrade_authority);
+
+    // The program data still reports the deployer
+    let result = test_f
+        .send_ixs(
+            &[create_confirm_upgrade_authority_instruction(program_data)],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Confirmation should wait for the actual handover"
+    );
+
+    patch_program_data_account(&test_f, &jup_stable::ID, Some(new_upgrade_authority)).await;
+    test_f
+        .send_ixs(
+            &[create_confirm_upgrade_authority_instruction(program_data)],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.pending_upgrade_authority, Pubkey::default());
+
+    Ok(())
+}
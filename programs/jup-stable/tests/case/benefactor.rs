@@ -1,18 +1,27 @@
 use fixtures::test::TestFixture;
-use jup_stable::state::benefactor::{Benefactor, BenefactorStatus};
+use jup_stable::state::{
+    benefactor::{Benefactor, BenefactorStatus},
+    benefactor_registry::BenefactorRegistry,
+    config::Config,
+    vault::Vault,
+};
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
 
 use super::super::common::instructions::create_create_benefactor_instruction;
 use crate::common::{
     constants::USDC_MINT,
-    derivation::find_benefactor,
+    derivation::{find_benefactor, find_benefactor_registry, find_config, find_vault},
     faciliter::{create_benefactor, create_vault, setup_full_test_context},
     instructions::{
-        create_delete_benefactor_instruction, create_set_benefactor_status_instruction,
+        create_create_benefactor_registry_page_instruction, create_delete_benefactor_instruction,
+        create_set_benefactor_redeem_vault_hint_instruction,
+        create_set_benefactor_status_instruction,
+        create_set_default_benefactor_period_limit_instruction,
+        create_set_default_vault_period_limit_instruction, create_set_rent_treasury_instruction,
         create_update_benefactor_period_limit_instruction, create_update_fee_rates_instruction,
         CreateBenefactorInstructionAccounts, CreateBenefactorInstructionArgs,
-        DeleteBenefactorInstructionAccounts,
+        CreateBenefactorRegistryPageInstructionAccounts, DeleteBenefactorInstructionAccounts,
     },
 };
 
@@ -38,6 +47,7 @@ async fn create_benefactor_success() -> anyhow::Result<()> {
                 authority: deployer,
                 payer: deployer,
                 benefactor_authority: benefactor_authority.pubkey(),
+                registry_page: 0,
             },
             CreateBenefactorInstructionArgs {
                 mint_fee_rate,
@@ -336,6 +346,7 @@ async fn delete_benefactor_success() -> anyhow::Result<()> {
                     authority: deployer,
                     receiver: deployer,
                     benefactor: benefactor_pubkey,
+                    registry_page: 0,
                 },
             )],
             Some(&deployer),
@@ -351,3 +362,241 @@ async fn delete_benefactor_success() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn benefactor_registry_pages_list_benefactors() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let create_benefactor_ix = |benefactor_authority, registry_page| {
+        create_create_benefactor_instruction(
+            CreateBenefactorInstructionAccounts {
+                authority: deployer,
+                payer: deployer,
+                benefactor_authority,
+                registry_page,
+            },
+            CreateBenefactorInstructionArgs {
+                mint_fee_rate: 0,
+                redeem_fee_rate: 0,
+            },
+        )
+    };
+
+    let first = Keypair::new().pubkey();
+    let second = Keypair::new().pubkey();
+    test_f
+        .send_ixs(
+            &[
+                create_benefactor_ix(first, 0),
+                create_create_benefactor_registry_page_instruction(
+                    CreateBenefactorRegistryPageInstructionAccounts {
+                        authority: deployer,
+                        payer: deployer,
+                    },
+                    1,
+                    &[],
+                ),
+                create_benefactor_ix(second, 1),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.benefactor_registry_pages, 2);
+    let page: BenefactorRegistry = test_f
+        .load_and_deserialize(&find_benefactor_registry(0))
+        .await;
+    assert_eq!(page.authorities(), &[first]);
+    let page: BenefactorRegistry = test_f
+        .load_and_deserialize(&find_benefactor_registry(1))
+        .await;
+    assert_eq!(page.page, 1);
+    assert_eq!(page.authorities(), &[second]);
+
+    test_f
+        .send_ixs(
+            &[create_delete_benefactor_instruction(
+                DeleteBenefactorInstructionAccounts {
+                    authority: deployer,
+                    receiver: deployer,
+                    benefactor: find_benefactor(&first),
+                    registry_page: 0,
+                },
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let page: BenefactorRegistry = test_f
+        .load_and_deserialize(&find_benefactor_registry(0))
+        .await;
+    assert!(page.authorities().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn benefactor_closure_refunds_rent_treasury() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let benefactor_authority = Keypair::new();
+    let benefactor_pubkey =
+        create_benefactor(&test_f, &benefactor_authority.pubkey(), 100u16, 50u16).await?;
+    let rent = test_f.get_account(&benefactor_pubkey).await.lamports;
+
+    let rent_treasury = Keypair::new().pubkey();
+    test_f
+        .send_ixs(
+            &[create_set_rent_treasury_instruction(
+                deployer,
+                rent_treasury,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let delete_benefactor_ix = |receiver| {
+        create_delete_benefactor_instruction(DeleteBenefactorInstructionAccounts {
+            authority: deployer,
+            receiver,
+            benefactor: benefactor_pubkey,
+            registry_page: 0,
+        })
+    };
+    let result = test_f
+        .send_ixs(&[delete_benefactor_ix(deployer)], &[&test_f.deployer])
+        .await;
+    assert!(
+        result.is_err(),
+        "Closure should refund the rent treasury once one is set"
+    );
+
+    test_f
+        .send_ixs(&[delete_benefactor_ix(rent_treasury)], &[&test_f.deployer])
+        .await?;
+    assert_eq!(test_f.get_account(&rent_treasury).await.lamports, rent);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn default_period_limits_apply_to_new_accounts() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let result = test_f
+        .send_ixs(
+            &[create_set_default_vault_period_limit_instruction(
+                deployer, 1, 1_000, 1_000,
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Templates should be validated like any period limit"
+    );
+
+    test_f
+        .send_ixs(
+            &[
+                create_set_default_vault_period_limit_instruction(deployer, 3600, 1_000, 2_000),
+                create_set_default_benefactor_period_limit_instruction(
+                    deployer, 86400, 3_000, 4_000,
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    create_vault(&test_f, USDC_MINT).await?;
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    let window = vault.period_limits[0];
+    assert_eq!(window.duration_seconds, 3600);
+    assert_eq!(window.max_mint_amount, 1_000);
+    assert_eq!(window.max_redeem_amount, 2_000);
+
+    let benefactor_pubkey =
+        create_benefactor(&test_f, &Keypair::new().pubkey(), 100u16, 50u16).await?;
+    let benefactor: Benefactor = test_f.load_and_deserialize(&benefactor_pubkey).await;
+    let window = benefactor.period_limits[0];
+    assert_eq!(window.duration_seconds, 86400);
+    assert_eq!(window.max_mint_amount, 3_000);
+    assert_eq!(window.max_redeem_amount, 4_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_benefactor_redeem_vault_hint() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    create_vault(&test_f, USDC_MINT).await?;
+    let benefactor_authority = Keypair::new();
+    let benefactor =
+        create_benefactor(&test_f, &benefactor_authority.pubkey(), 100u16, 50u16).await?;
+
+    let benefactor_account: Benefactor = test_f.load_and_deserialize(&benefactor).await;
+    assert_eq!(benefactor_account.redeem_vault_hint(), None);
+
+    // Only a mint with a vault can be hinted
+    let result = test_f
+        .send_ixs(
+            &[create_set_benefactor_redeem_vault_hint_instruction(
+                test_f.deployer.pubkey(),
+                benefactor,
+                Pubkey::new_unique(),
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err(), "A mint without a vault should be rejected");
+
+    let mut ix = create_set_benefactor_redeem_vault_hint_instruction(
+        test_f.deployer.pubkey(),
+        benefactor,
+        USDC_MINT,
+    );
+    ix.accounts.pop();
+    let result = test_f.send_ixs(&[ix], &[&test_f.deployer]).await;
+    assert!(result.is_err(), "The vault account should be required");
+
+    test_f
+        .send_ixs(
+            &[create_set_benefactor_redeem_vault_hint_instruction(
+                test_f.deployer.pubkey(),
+                benefactor,
+                USDC_MINT,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let benefactor_account: Benefactor = test_f.load_and_deserialize(&benefactor).await;
+    assert_eq!(benefactor_account.redeem_vault_hint(), Some(USDC_MINT));
+
+    test_f
+        .send_ixs(
+            &[create_set_benefactor_redeem_vault_hint_instruction(
+                test_f.deployer.pubkey(),
+                benefactor,
+                Pubkey::default(),
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let benefactor_account: Benefactor = test_f.load_and_deserialize(&benefactor).await;
+    assert_eq!(
+        benefactor_account.redeem_vault_hint(),
+        None,
+        "Default should clear the hint"
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use fixtures::test::TestFixture;
 use jup_stable::state::{
+    common::PeriodLimitTemplate,
     config::Config,
     operator::{Operator, OperatorRole, OperatorStatus},
 };
@@ -31,12 +32,7 @@ async fn init_success() -> anyhow::Result<()> {
         token_program: spl_token::ID,
     };
 
-    let args = InitInstructionArgs {
-        decimals: JUPUSD_DECIMALS,
-        name: JUPUSD_NAME.to_string(),
-        symbol: JUPUSD_SYMBOL.to_string(),
-        uri: JUPUSD_URI.to_string(),
-    };
+    let args = init_args(JUPUSD_DECIMALS, 10_000);
 
     {
         let mut ctx = test_f.context.borrow_mut();
@@ -106,3 +102,99 @@ async fn init_success() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn init_applies_config_params() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let lp_mint = Keypair::new();
+
+    let template = PeriodLimitTemplate {
+        duration_seconds: 3600,
+        max_mint_amount: 1_000_000,
+        max_redeem_amount: 500_000,
+    };
+    let args = InitInstructionArgs {
+        is_mint_redeem_enabled: true,
+        default_vault_period_limit: template,
+        ..init_args(JUPUSD_DECIMALS, 9_990)
+    };
+    test_f
+        .send_ixs(
+            &[create_init_instruction(
+                init_accounts(&test_f, &lp_mint),
+                args,
+            )],
+            &[&test_f.deployer, &lp_mint],
+        )
+        .await?;
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.peg_price_usd, 9_990);
+    assert!(config.is_mint_redeem_enabled());
+    assert_eq!(config.default_vault_period_limit.duration_seconds, 3600);
+    assert_eq!(config.default_vault_period_limit.max_mint_amount, 1_000_000);
+    assert_eq!(config.default_vault_period_limit.max_redeem_amount, 500_000);
+    assert_eq!(config.default_benefactor_period_limit.duration_seconds, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn init_rejects_out_of_bounds_params() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+
+    for (args, reason) in [
+        (init_args(5, 10_000), "decimals below 6"),
+        (init_args(10, 10_000), "decimals above 9"),
+        (init_args(JUPUSD_DECIMALS, 0), "a zero peg price"),
+        (init_args(JUPUSD_DECIMALS, 20_000), "a peg price of 2 USD"),
+        (
+            InitInstructionArgs {
+                default_benefactor_period_limit: PeriodLimitTemplate {
+                    duration_seconds: 3600,
+                    max_mint_amount: 0,
+                    max_redeem_amount: 0,
+                },
+                ..init_args(JUPUSD_DECIMALS, 10_000)
+            },
+            "an invalid default period limit",
+        ),
+    ] {
+        let lp_mint = Keypair::new();
+        let result = test_f
+            .send_ixs(
+                &[create_init_instruction(
+                    init_accounts(&test_f, &lp_mint),
+                    args,
+                )],
+                &[&test_f.deployer, &lp_mint],
+            )
+            .await;
+        assert!(result.is_err(), "Init should reject {reason}");
+    }
+
+    Ok(())
+}
+
+fn init_accounts(test_f: &TestFixture, lp_mint: &Keypair) -> InitInstructionAccounts {
+    InitInstructionAccounts {
+        payer: test_f.deployer.pubkey(),
+        upgrade_authority: test_f.deployer.pubkey(),
+        program_data: get_program_data_address(&jup_stable::ID),
+        mint: lp_mint.pubkey(),
+        token_program: spl_token::ID,
+    }
+}
+
+fn init_args(decimals: u8, peg_price_usd: u64) -> InitInstructionArgs {
+    InitInstructionArgs {
+        decimals,
+        name: JUPUSD_NAME.to_string(),
+        symbol: JUPUSD_SYMBOL.to_string(),
+        uri: JUPUSD_URI.to_string(),
+        peg_price_usd,
+        is_mint_redeem_enabled: false,
+        default_vault_period_limit: PeriodLimitTemplate::default(),
+        default_benefactor_period_limit: PeriodLimitTemplate::default(),
+    }
+}
@@ -0,0 +1,401 @@
+use anchor_spl::token_interface::TokenAccount;
+use fixtures::{oracle::DovesPriceFeedBuilder, test::TestFixture};
+use jup_stable::{
+    instructions::{BenefactorManagementAction, OracleConfig, VaultManagementAction},
+    state::{
+        benefactor::{Benefactor, BenefactorStatus},
+        common::PeriodLimitTemplate,
+        config::Config,
+        vault::{Vault, VaultStatus},
+        vault_registry::VaultRegistry,
+    },
+};
+use solana_instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::{
+    constants::{USDC_DECIMALS, USDC_MINT},
+    derivation::{
+        find_authority, find_benefactor, find_config, find_instance_authority,
+        find_instance_benefactor, find_instance_config, find_instance_vault,
+        find_instance_vault_registry, find_instance_vault_token_account_with_program, find_vault,
+    },
+    faciliter::{create_associated_token_account, setup_full_test_context, setup_mint_with_oracle},
+    instructions::{
+        create_create_benefactor_instruction_for_instance,
+        create_create_vault_instruction_for_instance,
+        create_delete_benefactor_instruction_for_instance,
+        create_get_capacity_instruction_for_instance, create_init_instance_instruction,
+        create_manage_benefactor_instruction_for_instance,
+        create_manage_vault_instruction_for_instance, create_mint_instruction_for_instance,
+        create_redeem_instruction_for_instance,
+        create_set_benefactor_redeem_vault_hint_instruction_for_instance,
+        CreateBenefactorInstructionAccounts, CreateBenefactorInstructionArgs,
+        CreateVaultInstructionAccounts, DeleteBenefactorInstructionAccounts,
+        InitInstanceInstructionAccounts, InitInstructionArgs, ManageBenefactorInstructionAccounts,
+        ManageVaultInstructionAccounts, MintInstructionAccounts, RedeemInstructionAccounts,
+    },
+};
+
+/// Processes `ixs` and returns the transaction result along with its logs.
+async fn process_with_logs(
+    test_f: &TestFixture,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> anyhow::Result<(bool, Vec<String>)> {
+    let tx = test_f.tx().ixs(ixs).signers(signers).build().await?;
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    let logs = outcome
+        .metadata
+        .map(|metadata| metadata.log_messages)
+        .unwrap_or_default();
+    Ok((outcome.result.is_ok(), logs))
+}
+
+#[tokio::test]
+async fn second_instance_mints_in_its_own_space() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+
+    // The instance `init` created, with a USDC vault and a benefactor for the user
+    let feed = Pubkey::new_unique();
+    let params =
+        setup_mint_with_oracle(&test_f, &test_context, OracleConfig::Doves(feed), feed).await?;
+    let user = params.user.pubkey();
+
+    // A second stablecoin next to it
+    let lp_mint = Keypair::new();
+    let instance_mint = lp_mint.pubkey();
+    let instance = Some(&instance_mint);
+    test_f
+        .send_ixs(
+            &[create_init_instance_instruction(
+                InitInstanceInstructionAccounts {
+                    authority: deployer,
+                    payer: deployer,
+                    mint: instance_mint,
+                    token_program: spl_token::ID,
+                },
+                InitInstructionArgs {
+                    decimals: USDC_DECIMALS,
+                    name: "Jupiter EUR".to_string(),
+                    symbol: "jupEUR".to_string(),
+                    uri: String::new(),
+                    peg_price_usd: 10_000,
+                    is_mint_redeem_enabled: true,
+                    default_vault_period_limit: PeriodLimitTemplate::default(),
+                    default_benefactor_period_limit: PeriodLimitTemplate::default(),
+                },
+            )],
+            &[&test_f.deployer, &lp_mint],
+        )
+        .await?;
+
+    let config: Config = test_f
+        .load_and_deserialize(&find_instance_config(instance))
+        .await;
+    assert_eq!(config.mint, instance_mint);
+    assert_eq!(config.authority, find_instance_authority(instance));
+    assert_eq!(config.instance_mint(), instance_mint);
+    assert_ne!(find_instance_config(instance), find_config());
+    assert_ne!(find_instance_authority(instance), find_authority());
+    let primary_config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(primary_config.mint, test_context.lp_mint);
+    assert_eq!(primary_config.instance_mint(), Pubkey::default());
+
+    // Its own USDC vault, reusing the deployment-wide collateral approval, and its own benefactor
+    // for the same user
+    let custodian = params.custodian;
+    let manage_vault = |action| {
+        create_manage_vault_instruction_for_instance(
+            instance,
+            ManageVaultInstructionAccounts {
+                authority: deployer,
+                vault_mint: USDC_MINT,
+            },
+            action,
+        )
+    };
+    let benefactor = find_instance_benefactor(instance, &user);
+    test_f
+        .send_ixs(
+            &[
+                create_create_vault_instruction_for_instance(
+                    instance,
+                    CreateVaultInstructionAccounts {
+                        authority: deployer,
+                        payer: deployer,
+                        mint: USDC_MINT,
+                        token_program: spl_token::ID,
+                    },
+                ),
+                manage_vault(VaultManagementAction::SetCustodian {
+                    new_custodian: custodian,
+                }),
+                manage_vault(VaultManagementAction::UpdateOracle {
+                    index: 0,
+                    oracle: OracleConfig::Doves(feed),
+                }),
+                manage_vault(VaultManagementAction::SetStatus {
+                    status: VaultStatus::Enabled,
+                }),
+                create_create_benefactor_instruction_for_instance(
+                    instance,
+                    CreateBenefactorInstructionAccounts {
+                        authority: deployer,
+                        payer: deployer,
+                        benefactor_authority: user,
+                        registry_page: 0,
+                    },
+                    CreateBenefactorInstructionArgs {
+                        mint_fee_rate: 0,
+                        redeem_fee_rate: 0,
+                    },
+                ),
+                create_manage_benefactor_instruction_for_instance(
+                    instance,
+                    ManageBenefactorInstructionAccounts {
+                        authority: deployer,
+                        benefactor,
+                    },
+                    BenefactorManagementAction::SetStatus {
+                        status: BenefactorStatus::Active,
+                    },
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+    create_associated_token_account(&test_f, &user, &instance_mint).await?;
+
+    let vault_address = find_instance_vault(instance, &USDC_MINT);
+    assert_ne!(vault_address, find_vault(&USDC_MINT));
+    assert_ne!(benefactor, find_benefactor(&user));
+    let vault: Vault = test_f.load_and_deserialize(&vault_address).await;
+    assert_eq!(vault.instance_mint, instance_mint);
+    assert_eq!(
+        vault.token_account,
+        find_instance_vault_token_account_with_program(instance, &USDC_MINT, &spl_token::ID)
+    );
+    let benefactor_account: Benefactor = test_f.load_and_deserialize(&benefactor).await;
+    assert_eq!(benefactor_account.instance_mint, instance_mint);
+
+    // The redeem vault hint has to name a vault of the benefactor's own instance
+    let mut cross_instance = create_set_benefactor_redeem_vault_hint_instruction_for_instance(
+        instance, deployer, benefactor, USDC_MINT,
+    );
+    cross_instance.accounts.last_mut().unwrap().pubkey = find_vault(&USDC_MINT);
+    let (succeeded, logs) =
+        process_with_logs(&test_f, &[cross_instance], &[&test_f.deployer]).await?;
+    assert!(!succeeded);
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: InstanceMismatch.")));
+
+    test_f
+        .send_ixs(
+            &[
+                create_set_benefactor_redeem_vault_hint_instruction_for_instance(
+                    instance, deployer, benefactor, USDC_MINT,
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let benefactor_account: Benefactor = test_f.load_and_deserialize(&benefactor).await;
+    assert_eq!(benefactor_account.redeem_vault_hint(), Some(USDC_MINT));
+
+    // A config only manages the vaults of its own instance
+    let mut cross_instance = manage_vault(VaultManagementAction::SetStatus {
+        status: VaultStatus::Disabled,
+    });
+    for account in &mut cross_instance.accounts {
+        if account.pubkey == vault_address {
+            account.pubkey = find_vault(&USDC_MINT);
+        }
+    }
+    let (succeeded, logs) =
+        process_with_logs(&test_f, &[cross_instance], &[&test_f.deployer]).await?;
+    assert!(!succeeded);
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: ConstraintSeeds.")));
+    let vault_registry: VaultRegistry = test_f
+        .load_and_deserialize(&find_instance_vault_registry(instance))
+        .await;
+    assert_eq!(vault_registry.count, 1);
+    assert_eq!(vault_registry.mints[0], USDC_MINT);
+
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(&feed, DovesPriceFeedBuilder::new().timestamp(now).build())
+        .await;
+
+    let amount = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let mint_accounts = |benefactor| MintInstructionAccounts {
+        user,
+        benefactor,
+        custodian,
+        custodian_token_account: None,
+        vault_mint: USDC_MINT,
+        lp_mint: instance_mint,
+        vault_token_program: spl_token::ID,
+        lp_token_program: spl_token::ID,
+        remaining_accounts: vec![feed],
+    };
+
+    // The benefactor of the other instance is not accepted
+    let (succeeded, logs) = process_with_logs(
+        &test_f,
+        &[create_mint_instruction_for_instance(
+            instance,
+            amount,
+            0,
+            mint_accounts(params.benefactor),
+        )],
+        &[&params.user],
+    )
+    .await?;
+    assert!(!succeeded);
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: InstanceMismatch.")));
+
+    test_f
+        .send_ixs(
+            &[create_mint_instruction_for_instance(
+                instance,
+                amount,
+                0,
+                mint_accounts(benefactor),
+            )],
+            &[&params.user],
+        )
+        .await?;
+
+    let user_lp: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(&user, &instance_mint))
+        .await;
+    assert_eq!(user_lp.amount, amount);
+    let vault: Vault = test_f.load_and_deserialize(&vault_address).await;
+    assert_eq!(vault.total_minted(), u128::from(amount));
+    let primary_vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert_eq!(primary_vault.total_minted(), 0);
+
+    // Capacity is only reported for the vault and benefactor of the config's instance
+    let (succeeded, logs) = process_with_logs(
+        &test_f,
+        &[create_get_capacity_instruction_for_instance(
+            instance,
+            USDC_MINT,
+            params.benefactor,
+        )],
+        &[&test_f.deployer],
+    )
+    .await?;
+    assert!(!succeeded);
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: InstanceMismatch.")));
+    let mut cross_instance =
+        create_get_capacity_instruction_for_instance(instance, USDC_MINT, benefactor);
+    for account in &mut cross_instance.accounts {
+        if account.pubkey == vault_address {
+            account.pubkey = find_vault(&USDC_MINT);
+        }
+    }
+    let (succeeded, logs) =
+        process_with_logs(&test_f, &[cross_instance], &[&test_f.deployer]).await?;
+    assert!(!succeeded);
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: ConstraintSeeds.")));
+    let (succeeded, _) = process_with_logs(
+        &test_f,
+        &[create_get_capacity_instruction_for_instance(
+            instance, USDC_MINT, benefactor,
+        )],
+        &[&test_f.deployer],
+    )
+    .await?;
+    assert!(succeeded);
+
+    // Redeems pay out of the instance's own vault token account
+    let vault_token_account =
+        find_instance_vault_token_account_with_program(instance, &USDC_MINT, &spl_token::ID);
+    test_f.mint_tokens(&vault_token_account, amount).await;
+    test_f
+        .send_ixs(
+            &[create_redeem_instruction_for_instance(
+                instance,
+                amount / 2,
+                0,
+                RedeemInstructionAccounts {
+                    user,
+                    benefactor,
+                    vault_mint: USDC_MINT,
+                    lp_mint: instance_mint,
+                    vault_token_program: spl_token::ID,
+                    lp_token_program: spl_token::ID,
+                    remaining_accounts: vec![feed],
+                },
+            )],
+            &[&params.user],
+        )
+        .await?;
+
+    let vault_collateral: TokenAccount = test_f.load_and_deserialize(&vault_token_account).await;
+    assert_eq!(vault_collateral.amount, amount - amount / 2);
+    let user_lp: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(&user, &instance_mint))
+        .await;
+    assert_eq!(user_lp.amount, amount - amount / 2);
+
+    // Deleting the benefactor goes by its own instance's config and rent treasury
+    let delete_accounts = || DeleteBenefactorInstructionAccounts {
+        authority: deployer,
+        receiver: deployer,
+        benefactor,
+        registry_page: 0,
+    };
+    let mut cross_instance =
+        create_delete_benefactor_instruction_for_instance(instance, delete_accounts());
+    for account in &mut cross_instance.accounts {
+        if account.pubkey == find_instance_config(instance) {
+            account.pubkey = find_config();
+        }
+    }
+    let (succeeded, logs) =
+        process_with_logs(&test_f, &[cross_instance], &[&test_f.deployer]).await?;
+    assert!(!succeeded);
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: ConstraintSeeds.")));
+
+    test_f
+        .send_ixs(
+            &[create_delete_benefactor_instruction_for_instance(
+                instance,
+                delete_accounts(),
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    assert!(test_f
+        .context
+        .borrow_mut()
+        .banks_client
+        .get_account(benefactor)
+        .await?
+        .is_none());
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! Decodes dumps of accounts written with the deployed layout, so fields carved out of `reserved`
+//! have to keep reading zero, i.e. their pre-existing behaviour, on accounts that predate them.
+
+use fixtures::utils::decode_zero_copy;
+use jup_stable::state::{
+    benefactor::{Benefactor, BenefactorStatus, MAX_RELAYERS},
+    common::PeriodLimit,
+    config::Config,
+    operator::{Operator, OperatorRole, OperatorStatus},
+    vault::{OracleType, Vault, VaultStatus},
+};
+use solana_sdk::pubkey::Pubkey;
+
+fn assert_deployed_period_limit(window: &PeriodLimit) {
+    assert_eq!(window.duration_seconds, 3600);
+    assert_eq!(window.max_mint_amount, 1_000_000);
+    assert_eq!(window.max_redeem_amount, 500_000);
+    assert_eq!(window.minted_amount, 250_000);
+    assert_eq!(window.redeemed_amount, 125_000);
+    assert_eq!(window.window_start, 1_700_000_000);
+}
+
+#[test]
+fn deployed_config_layout() {
+    let config: Config = decode_zero_copy(include_bytes!("../fixtures/layout/config.bin"));
+
+    assert_eq!(config.mint, Pubkey::new_from_array([1; 32]));
+    assert_eq!(config.authority, Pubkey::new_from_array([2; 32]));
+    assert_eq!(config.token_program, Pubkey::new_from_array([3; 32]));
+    assert_deployed_period_limit(&config.period_limits[0]);
+    assert_eq!(config.peg_price_usd, 10_000);
+    assert_eq!(config.decimals, 6);
+    assert!(config.is_mint_redeem_enabled());
+    assert_eq!(config.authority_bump, 254);
+    assert_eq!(config.config_bump, 253);
+
+    assert!(config.is_restart_slot_check_enabled());
+    assert!(!config.is_strict_period_limits());
+    assert!(!config.is_supply_check_enabled());
+    assert!(!config.is_pause_blocking_withdraw());
+    assert_eq!(config.pending_upgrade_authority, Pubkey::default());
+    assert_eq!(config.reactivation_cooldown_seconds, 0);
+    assert_eq!(config.benefactor_registry_pages, 0);
+    assert_eq!(config.rent_treasury, Pubkey::default());
+    assert_eq!(config.default_vault_period_limit.duration_seconds, 0);
+    assert_eq!(config.default_benefactor_period_limit.duration_seconds, 0);
+    assert_eq!(config.tracked_supply, 0);
+    assert!(config.peg_price_history().is_empty());
+    assert_eq!(config.peg_symbol(), "USD");
+    assert_eq!(config.instance_mint(), Pubkey::default());
+    assert!(config.instance_seed().is_empty());
+}
+
+#[test]
+fn deployed_vault_layout() {
+    let vault: Vault = decode_zero_copy(include_bytes!("../fixtures/layout/vault.bin"));
+
+    assert_eq!(vault.mint, Pubkey::new_from_array([4; 32]));
+    assert_eq!(vault.custodian, Pubkey::new_from_array([5; 32]));
+    assert_eq!(vault.token_account, Pubkey::new_from_array([6; 32]));
+    assert_eq!(vault.token_program, Pubkey::new_from_array([7; 32]));
+    assert_eq!(vault.stalesness_threshold, 60);
+    assert_eq!(vault.min_oracle_price_usd, 9_500);
+    assert_eq!(vault.max_oracle_price_usd, 10_500);
+    assert!(vault.status == VaultStatus::Enabled);
+    assert_eq!(vault.bump, 252);
+    assert_eq!(vault.decimals, 6);
+    match vault.oracles[0] {
+        OracleType::Pyth(oracle) => {
+            assert_eq!(oracle.feed_id, [8; 32]);
+            assert_eq!(oracle.account, Pubkey::new_from_array([9; 32]));
+        },
+        _ => panic!("first oracle should be Pyth"),
+    }
+    assert!(matches!(vault.oracles[1], OracleType::Empty(_)));
+    assert_deployed_period_limit(&vault.period_limits[0]);
+    assert_eq!(vault.total_minted(), 5_000_000);
+    assert_eq!(vault.total_redeemed(), 2_000_000);
+
+    assert_eq!(vault.last_price, 0);
+    assert_eq!(vault.last_price_ts, 0);
+    assert_eq!(vault.max_price_jump_bps, 0);
+    assert_eq!(vault.price_jump_window(), vault.stalesness_threshold);
+    assert_eq!(vault.confidence_factor_bps, 0);
+    assert!(!vault.is_symmetric_oracle_bounds());
+    assert!(vault.requires_benefactor());
+    assert_eq!(vault.max_net_outstanding, 0);
+    assert_eq!(vault.disabled_at, 0);
+    assert_eq!(vault.custodian_token_account, Pubkey::default());
+    assert_eq!(vault.max_mint_per_tx, 0);
+    assert_eq!(vault.max_redeem_per_tx, 0);
+    assert_eq!(vault.total_acknowledged(), 0);
+    assert_eq!(vault.last_acknowledged_ts, 0);
+    assert!(!vault.has_quote_oracle());
+    assert_eq!(vault.instance_mint, Pubkey::default());
+}
+
+#[test]
+fn deployed_benefactor_layout() {
+    let benefactor: Benefactor =
+        decode_zero_copy(include_bytes!("../fixtures/layout/benefactor.bin"));
+
+    assert_eq!(benefactor.authority, Pubkey::new_from_array([10; 32]));
+    assert!(benefactor.status == BenefactorStatus::Active);
+    assert_eq!(benefactor.mint_fee_rate, 25);
+    assert_eq!(benefactor.redeem_fee_rate, 50);
+    assert_deployed_period_limit(&benefactor.period_limits[0]);
+    assert_eq!(benefactor.total_minted(), 3_000_000);
+    assert_eq!(benefactor.total_redeemed(), 1_000_000);
+
+    assert!(!benefactor.is_exempt_from_global_pause());
+    assert_eq!(benefactor.max_net_minted, 0);
+    assert_eq!(benefactor.disabled_at, 0);
+    assert_eq!(benefactor.integrator_program, Pubkey::default());
+    assert_eq!(benefactor.flash_mint_vault, Pubkey::default());
+    assert_eq!(benefactor.flash_mint_debt, 0);
+    assert_eq!(benefactor.redeem_vault_hint(), None);
+    assert_eq!(benefactor.instance_mint, Pubkey::default());
+    assert_eq!(benefactor.relayers, [Pubkey::default(); MAX_RELAYERS]);
+}
+
+#[test]
+fn deployed_operator_layout() {
+    let operator: Operator = decode_zero_copy(include_bytes!("../fixtures/layout/operator.bin"));
+
+    assert_eq!(
+        operator.operator_authority,
+        Pubkey::new_from_array([11; 32])
+    );
+    assert!(operator.status == OperatorStatus::Enabled);
+    assert!(operator.is(OperatorRole::Admin).is_ok());
+    assert!(operator.is(OperatorRole::CollateralManager).is_ok());
+    assert!(operator.is(OperatorRole::PeriodManager).is_err());
+
+    assert!(!operator.has_log());
+}
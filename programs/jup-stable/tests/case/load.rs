@@ -0,0 +1,297 @@
+use std::collections::HashSet;
+
+use fixtures::{
+    oracle::{DovesPriceFeedBuilder, PythPriceUpdateBuilder, SwitchboardPullFeedBuilder},
+    test::TestFixture,
+};
+use jup_stable::{
+    instructions::OracleConfig,
+    state::{
+        benefactor::Benefactor,
+        benefactor_registry::{BenefactorRegistry, BENEFACTORS_PER_PAGE},
+        config::Config,
+        vault::{OracleType, Vault, MAX_ORACLES},
+        vault_registry::{VaultRegistry, MAX_VAULTS},
+    },
+};
+use solana_instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::{
+    entrypoint::MAX_PERMITTED_DATA_INCREASE, pubkey::Pubkey, signature::Keypair, signer::Signer,
+};
+
+use crate::common::{
+    constants::{USDC_DECIMALS, USDC_FEED_ID, USDC_MINT},
+    derivation::{
+        find_authority, find_benefactor, find_benefactor_registry, find_config, find_operator,
+        find_vault, find_vault_registry, find_vault_token_account,
+    },
+    faciliter::{create_vault, setup_full_test_context, setup_mint_with_oracle},
+    instructions::{
+        create_create_benefactor_instruction, create_create_benefactor_registry_page_instruction,
+        create_mint_instruction, create_redeem_instruction, create_update_vault_oracle_instruction,
+        CreateBenefactorInstructionAccounts, CreateBenefactorInstructionArgs,
+        CreateBenefactorRegistryPageInstructionAccounts, MintInstructionAccounts,
+        RedeemInstructionAccounts,
+    },
+};
+
+/// Vaults created next to the USDC vault, on top of what production is expected to run.
+const LOAD_VAULTS: usize = 48;
+/// Benefactors created next to the USDC one, spread over several registry pages.
+const LOAD_BENEFACTORS: usize = 320;
+const BENEFACTORS_PER_TX: usize = 4;
+/// Compute units a transaction gets without a compute budget instruction.
+const DEFAULT_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// Processes `ixs` and returns the compute units they consumed, failing with the logs when the
+/// transaction does.
+async fn compute_units(
+    test_f: &TestFixture,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> anyhow::Result<u64> {
+    let tx = test_f.tx().ixs(ixs).signers(signers).build().await?;
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    let metadata = outcome
+        .metadata
+        .ok_or_else(|| anyhow::anyhow!("missing transaction metadata"))?;
+    if let Err(err) = outcome.result {
+        anyhow::bail!("{err}\n{}", metadata.log_messages.join("\n"));
+    }
+
+    Ok(metadata.compute_units_consumed)
+}
+
+#[tokio::test]
+async fn production_scale_vaults_and_benefactors() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    // Keep the clock slot above the staleness window so the switchboard reader does not underflow
+    test_f.warp_to_slot(1_000);
+    let deployer = test_f.deployer.pubkey();
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    // A USDC vault priced by every oracle slot, and its benefactor
+    let pyth_accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let switchboard_account = Pubkey::new_unique();
+    let doves_accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        OracleConfig::Pyth(USDC_FEED_ID, pyth_accounts[0]),
+        pyth_accounts[0],
+    )
+    .await?;
+    let extra_oracles = [
+        OracleConfig::Pyth(USDC_FEED_ID, pyth_accounts[1]),
+        OracleConfig::SwitchboardOnDemand(switchboard_account),
+        OracleConfig::Doves(doves_accounts[0]),
+        OracleConfig::Doves(doves_accounts[1]),
+    ];
+    let update_oracles = extra_oracles
+        .into_iter()
+        .enumerate()
+        .map(|(index, oracle)| {
+            create_update_vault_oracle_instruction(deployer, USDC_MINT, index as u8 + 1, oracle)
+        })
+        .collect::<Vec<_>>();
+    test_f
+        .send_ixs(&update_oracles, &[&test_f.deployer])
+        .await?;
+
+    let clock = test_f.get_clock().await;
+    for account in pyth_accounts {
+        test_f
+            .set_account(
+                &account,
+                PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                    .publish_time(clock.unix_timestamp)
+                    .build(),
+            )
+            .await;
+    }
+    test_f
+        .set_account(
+            &switchboard_account,
+            SwitchboardPullFeedBuilder::new()
+                .last_update_slot(clock.slot)
+                .last_update_timestamp(clock.unix_timestamp)
+                .build(),
+        )
+        .await;
+    for account in doves_accounts {
+        test_f
+            .set_account(
+                &account,
+                DovesPriceFeedBuilder::new()
+                    .timestamp(clock.unix_timestamp)
+                    .build(),
+            )
+            .await;
+    }
+    params.remaining_accounts = vec![
+        pyth_accounts[0],
+        pyth_accounts[1],
+        switchboard_account,
+        doves_accounts[0],
+        doves_accounts[1],
+    ];
+
+    let mut vault_mints = vec![USDC_MINT];
+    for _ in 0..LOAD_VAULTS {
+        let mint = test_f.create_spl_mint(USDC_DECIMALS).await;
+        create_vault(&test_f, mint).await?;
+        vault_mints.push(mint);
+    }
+
+    // The USDC benefactor already sits on page 0
+    let mut benefactor_authorities = vec![params.user.pubkey()];
+    let load_authorities = (0..LOAD_BENEFACTORS)
+        .map(|_| Keypair::new().pubkey())
+        .collect::<Vec<_>>();
+    for batch in load_authorities.chunks(BENEFACTORS_PER_TX) {
+        let mut ixs = vec![];
+        for authority in batch {
+            let registered = benefactor_authorities.len();
+            let registry_page = (registered / BENEFACTORS_PER_PAGE) as u32;
+            if registered % BENEFACTORS_PER_PAGE == 0 {
+                ixs.push(create_create_benefactor_registry_page_instruction(
+                    CreateBenefactorRegistryPageInstructionAccounts {
+                        authority: deployer,
+                        payer: deployer,
+                    },
+                    registry_page,
+                    &[],
+                ));
+            }
+            ixs.push(create_create_benefactor_instruction(
+                CreateBenefactorInstructionAccounts {
+                    authority: deployer,
+                    payer: deployer,
+                    benefactor_authority: *authority,
+                    registry_page,
+                },
+                CreateBenefactorInstructionArgs {
+                    mint_fee_rate: 0,
+                    redeem_fee_rate: 0,
+                },
+            ));
+            benefactor_authorities.push(*authority);
+        }
+        test_f.send_ixs(&ixs, &[&test_f.deployer]).await?;
+    }
+
+    // Every program address is distinct
+    let registry_pages = benefactor_authorities.len().div_ceil(BENEFACTORS_PER_PAGE) as u32;
+    let mut addresses = vec![
+        find_config(),
+        find_authority(),
+        find_operator(&deployer),
+        find_vault_registry(),
+    ];
+    addresses.extend((0..registry_pages).map(find_benefactor_registry));
+    addresses.extend(vault_mints.iter().map(find_vault));
+    addresses.extend(vault_mints.iter().map(find_vault_token_account));
+    addresses.extend(benefactor_authorities.iter().map(find_benefactor));
+    let unique = addresses.iter().collect::<HashSet<_>>();
+    assert_eq!(unique.len(), addresses.len(), "program addresses collide");
+
+    // The registries list everything that was created
+    let vault_registry: VaultRegistry = test_f.load_and_deserialize(&find_vault_registry()).await;
+    assert!(vault_mints.len() <= MAX_VAULTS);
+    assert_eq!(vault_registry.count as usize, vault_mints.len());
+    assert_eq!(
+        &vault_registry.mints[..vault_mints.len()],
+        vault_mints.as_slice()
+    );
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.benefactor_registry_pages, registry_pages);
+    let mut registered = vec![];
+    for page in 0..registry_pages {
+        let registry: BenefactorRegistry = test_f
+            .load_and_deserialize(&find_benefactor_registry(page))
+            .await;
+        registered.extend_from_slice(registry.authorities());
+    }
+    assert_eq!(registered, benefactor_authorities);
+
+    // Accounts are sized as declared, and small enough to be created from inside a program
+    for (address, size) in [
+        (find_config(), Config::MAX_SIZE),
+        (find_vault_registry(), VaultRegistry::MAX_SIZE),
+        (find_benefactor_registry(0), BenefactorRegistry::MAX_SIZE),
+        (find_vault(&USDC_MINT), Vault::MAX_SIZE),
+        (find_benefactor(&params.user.pubkey()), Benefactor::MAX_SIZE),
+    ] {
+        assert_eq!(test_f.get_account(&address).await.data.len(), 8 + size);
+        assert!(8 + size <= MAX_PERMITTED_DATA_INCREASE);
+    }
+
+    // Mint and redeem read all five oracles within the default compute budget
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert!(vault
+        .oracles
+        .iter()
+        .all(|oracle| !matches!(oracle, OracleType::Empty(_))));
+
+    let amount = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let mint_units = compute_units(
+        &test_f,
+        &[create_mint_instruction(
+            amount,
+            0,
+            MintInstructionAccounts {
+                user: params.user.pubkey(),
+                benefactor: params.benefactor,
+                custodian: params.custodian,
+                custodian_token_account: None,
+                vault_mint: USDC_MINT,
+                lp_mint: params.lp_mint,
+                vault_token_program: spl_token::ID,
+                lp_token_program: spl_token::ID,
+                remaining_accounts: params.remaining_accounts.clone(),
+            },
+        )],
+        &[&params.user],
+    )
+    .await?;
+
+    test_f
+        .mint_tokens(&find_vault_token_account(&USDC_MINT), amount)
+        .await;
+    let redeem_units = compute_units(
+        &test_f,
+        &[create_redeem_instruction(
+            amount / 2,
+            0,
+            RedeemInstructionAccounts {
+                user: params.user.pubkey(),
+                benefactor: params.benefactor,
+                vault_mint: USDC_MINT,
+                lp_mint: params.lp_mint,
+                vault_token_program: spl_token::ID,
+                lp_token_program: spl_token::ID,
+                remaining_accounts: params.remaining_accounts.clone(),
+            },
+        )],
+        &[&params.user],
+    )
+    .await?;
+
+    println!(
+        "{} vaults, {} benefactors, {MAX_ORACLES} oracles: mint {mint_units} CU, redeem \
+         {redeem_units} CU",
+        vault_mints.len(),
+        benefactor_authorities.len(),
+    );
+    assert!(mint_units < DEFAULT_COMPUTE_UNIT_LIMIT);
+    assert!(redeem_units < DEFAULT_COMPUTE_UNIT_LIMIT);
+
+    Ok(())
+}
@@ -1,6 +1,14 @@
 mod admin;
 mod benefactor;
+mod idl;
 mod init;
+mod instance;
+mod layout;
+mod load;
 mod operator;
+mod oracle;
+mod relay;
+mod scenario;
+mod token2022;
 mod user;
 mod vault;
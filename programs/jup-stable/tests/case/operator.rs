@@ -1,17 +1,23 @@
 use fixtures::test::TestFixture;
 use jup_stable::{
     instructions::OperatorManagementAction,
-    state::operator::{Operator, OperatorRole, OperatorStatus},
+    state::{
+        operator::{Operator, OperatorRole, OperatorStatus},
+        operator_log::{OperatorAction, OperatorLog},
+    },
 };
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    instruction::AccountMeta, signature::Keypair, signer::Signer, transaction::Transaction,
+};
 
 use crate::common::{
-    derivation::find_operator,
+    derivation::{find_operator, find_operator_log},
     faciliter::setup_full_test_context,
     instructions::{
-        create_create_operator_instruction, create_delete_operator_instruction,
-        create_manage_operator_instruction, CreateOperatorInstructionAccounts,
+        create_create_operator_instruction, create_create_operator_log_instruction,
+        create_delete_operator_instruction, create_manage_operator_instruction,
+        CreateOperatorInstructionAccounts, CreateOperatorLogInstructionAccounts,
         DeleteOperatorInstructionAccounts, ManageOperatorInstructionAccounts,
     },
 };
@@ -359,7 +365,7 @@ async fn delete_operator_success() -> anyhow::Result<()> {
     {
         let accounts = DeleteOperatorInstructionAccounts {
             operator_authority: deployer,
-            payer: deployer,
+            receiver: deployer,
             deleted_operator: find_operator(&operator_authority.pubkey()),
         };
 
@@ -383,3 +389,184 @@ async fn delete_operator_success() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn operator_log_records_privileged_actions() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    // `init` creates the log of the genesis operator
+    let deployer = test_f.deployer.pubkey();
+    let deployer_log = find_operator_log(&find_operator(&deployer));
+    let operator: Operator = test_f.load_and_deserialize(&find_operator(&deployer)).await;
+    assert!(operator.has_log());
+
+    let new_operator_authority = Keypair::new().pubkey();
+    let create_operator_ix = create_create_operator_instruction(
+        CreateOperatorInstructionAccounts {
+            operator_authority: deployer,
+            payer: deployer,
+            new_operator_authority,
+        },
+        OperatorRole::PegManager,
+    );
+
+    // The log can't be skipped
+    let mut without_log_ix = create_operator_ix.clone();
+    for meta in without_log_ix.accounts.iter_mut() {
+        if meta.pubkey == deployer_log {
+            *meta = AccountMeta::new_readonly(jup_stable::id(), false);
+        }
+    }
+    let tx = test_f
+        .tx()
+        .ixs(&[without_log_ix])
+        .signers(&[&test_f.deployer])
+        .build()
+        .await?;
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    assert!(outcome.result.is_err());
+    assert!(outcome
+        .metadata
+        .unwrap()
+        .log_messages
+        .iter()
+        .any(|log| log.contains("Error Code: MissingOperatorLog.")));
+
+    test_f
+        .send_ixs(&[create_operator_ix], &[&test_f.deployer])
+        .await?;
+
+    let operator_log: OperatorLog = test_f.load_and_deserialize(&deployer_log).await;
+    let entries: Vec<_> = operator_log.entries().collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, OperatorAction::CreateOperator as u8);
+    assert_eq!(entries[0].target, find_operator(&new_operator_authority));
+    assert!(entries[0].timestamp > 0);
+
+    // The new operator starts out with its own, empty log
+    let new_operator = find_operator(&new_operator_authority);
+    let new_operator_state: Operator = test_f.load_and_deserialize(&new_operator).await;
+    assert!(new_operator_state.has_log());
+    let new_operator_log: OperatorLog = test_f
+        .load_and_deserialize(&find_operator_log(&new_operator))
+        .await;
+    assert_eq!(new_operator_log.operator, new_operator);
+    assert_eq!(new_operator_log.entries().count(), 0);
+
+    // The genesis operator already has its log, so there is nothing left to create
+    let result = test_f
+        .send_ixs(
+            &[create_create_operator_log_instruction(
+                CreateOperatorLogInstructionAccounts {
+                    operator_authority: deployer,
+                    payer: deployer,
+                },
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn admin_only_grants_roles_it_holds() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let deployer = test_f.deployer.pubkey();
+    let admin = Keypair::new();
+    test_f
+        .send_ixs(
+            &[create_create_operator_instruction(
+                CreateOperatorInstructionAccounts {
+                    operator_authority: deployer,
+                    payer: deployer,
+                    new_operator_authority: admin.pubkey(),
+                },
+                OperatorRole::Admin,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let create_operator_ix = |new_operator_authority, role| {
+        create_create_operator_instruction(
+            CreateOperatorInstructionAccounts {
+                operator_authority: admin.pubkey(),
+                payer: deployer,
+                new_operator_authority,
+            },
+            role,
+        )
+    };
+
+    let result = test_f
+        .send_ixs(
+            &[create_operator_ix(
+                Keypair::new().pubkey(),
+                OperatorRole::PegManager,
+            )],
+            &[&test_f.deployer, &admin],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Admin should not grant a role it does not hold"
+    );
+
+    let new_admin = Keypair::new().pubkey();
+    test_f
+        .send_ixs(&[create_operator_ix(new_admin, OperatorRole::Admin)], &[
+            &test_f.deployer,
+            &admin,
+        ])
+        .await?;
+
+    let manage_ix = |action| {
+        create_manage_operator_instruction(
+            ManageOperatorInstructionAccounts {
+                operator_authority: admin.pubkey(),
+                managed_operator: find_operator(&new_admin),
+            },
+            action,
+        )
+    };
+    let result = test_f
+        .send_ixs(
+            &[manage_ix(OperatorManagementAction::SetRole {
+                role: OperatorRole::VaultManager,
+            })],
+            &[&test_f.deployer, &admin],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Admin should not grant a role it does not hold"
+    );
+
+    // Roles it lacks can still be cleared
+    test_f
+        .send_ixs(
+            &[manage_ix(OperatorManagementAction::ClearRole {
+                role: OperatorRole::VaultManager,
+            })],
+            &[&test_f.deployer, &admin],
+        )
+        .await?;
+
+    let operator: Operator = test_f
+        .load_and_deserialize(&find_operator(&new_admin))
+        .await;
+    assert!(operator.is(OperatorRole::Admin).is_ok());
+    assert!(operator.is(OperatorRole::VaultManager).is_err());
+
+    Ok(())
+}
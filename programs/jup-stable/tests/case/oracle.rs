@@ -0,0 +1,850 @@
+use anchor_spl::token_interface::TokenAccount;
+use fixtures::{
+    event::assert_event,
+    oracle::{DovesPriceFeedBuilder, PythPriceUpdateBuilder, SwitchboardPullFeedBuilder},
+    test::TestFixture,
+};
+use jup_stable::{
+    instructions::{MintV1Event, OracleConfig, RedeemV1Event},
+    state::{config::Config, vault::Vault},
+};
+use solana_program_test::*;
+use solana_sdk::{account::Account, pubkey::Pubkey, signer::Signer, system_program};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::common::{
+    constants::{
+        JUPUSD_DECIMALS, USDC_DECIMALS, USDC_FEED_ID, USDC_MINT, USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    },
+    derivation::{find_config, find_vault},
+    faciliter::{
+        mint_stablecoin, mint_stablecoin_with_events, mint_stablecoin_with_logs,
+        redeem_stablecoin_with_events, setup_full_test_context, setup_mint_with_oracle,
+    },
+    instructions::{
+        create_set_confidence_factor_instruction, create_set_max_price_jump_instruction,
+        create_set_peg_symbol_instruction, create_set_price_jump_window_instruction,
+        create_set_symmetric_oracle_bounds_instruction,
+        create_update_restart_slot_check_instruction, create_update_vault_oracle_instruction,
+        create_update_vault_quote_oracle_instruction,
+    },
+};
+
+#[tokio::test]
+async fn mint_with_synthetic_pyth_price() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+
+    let publish_time = test_f.get_clock().await.unix_timestamp;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    // Negative price
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .price(-100_000_000)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(result.is_err(), "Mint should fail with a negative price");
+
+    // Confidence interval wider than 2% of the price
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .conf(2_000_000)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail with a wide confidence interval"
+    );
+
+    // Depegged below the vault min oracle price
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .price(40_000_000)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(result.is_err(), "Mint should fail with a depegged price");
+
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mint_with_synthetic_switchboard_feed() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    // Keep the clock slot above the staleness window so the feed reader does not underflow
+    test_f.warp_to_slot(1_000);
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let feed = Pubkey::new_unique();
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        OracleConfig::SwitchboardOnDemand(feed),
+        feed,
+    )
+    .await?;
+
+    let clock = test_f.get_clock().await;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    // Standard deviation wider than 2% of the price
+    test_f
+        .set_account(
+            &feed,
+            SwitchboardPullFeedBuilder::new()
+                .std_dev(2 * 10_i128.pow(16))
+                .last_update_slot(clock.slot)
+                .last_update_timestamp(clock.unix_timestamp)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail with a wide standard deviation"
+    );
+
+    // Stale update timestamp
+    test_f
+        .set_account(
+            &feed,
+            SwitchboardPullFeedBuilder::new()
+                .last_update_slot(clock.slot)
+                .last_update_timestamp(clock.unix_timestamp - 3_600)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(result.is_err(), "Mint should fail with a stale feed");
+
+    test_f
+        .set_account(
+            &feed,
+            SwitchboardPullFeedBuilder::new()
+                .last_update_slot(clock.slot)
+                .last_update_timestamp(clock.unix_timestamp)
+                .build(),
+        )
+        .await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mint_with_synthetic_doves_feed() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let feed = Pubkey::new_unique();
+    let params =
+        setup_mint_with_oracle(&test_f, &test_context, OracleConfig::Doves(feed), feed).await?;
+
+    let now = test_f.get_clock().await.unix_timestamp;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    // Zero price
+    test_f
+        .set_account(
+            &feed,
+            DovesPriceFeedBuilder::new().price(0).timestamp(now).build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(result.is_err(), "Mint should fail with a zero price");
+
+    // Published outside the vault staleness threshold
+    test_f
+        .set_account(
+            &feed,
+            DovesPriceFeedBuilder::new().timestamp(now - 3_600).build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(result.is_err(), "Mint should fail with a stale price");
+
+    test_f
+        .set_account(&feed, DovesPriceFeedBuilder::new().timestamp(now).build())
+        .await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quote_oracle_prices_mint_in_peg_currency() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+
+    let feed = Pubkey::new_unique();
+    let mut params =
+        setup_mint_with_oracle(&test_f, &test_context, OracleConfig::Doves(feed), feed).await?;
+
+    // Only uppercase letters and digits make a symbol
+    let result = test_f
+        .send_ixs(
+            &[create_set_peg_symbol_instruction(
+                deployer,
+                *b"eur\0\0\0\0\0",
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err(), "Lowercase peg symbol should be rejected");
+
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(&feed, DovesPriceFeedBuilder::new().timestamp(now).build())
+        .await;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    // A EUR stablecoin can't price its USD collateral without a quote oracle
+    test_f
+        .send_ixs(
+            &[create_set_peg_symbol_instruction(
+                deployer,
+                *b"EUR\0\0\0\0\0",
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.peg_symbol(), "EUR");
+    assert!(config.requires_quote_oracle());
+
+    let (result, logs) = mint_stablecoin_with_logs(&test_f, &params, amount_in, 0).await?;
+    assert!(result.is_err());
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: MissingQuoteOracle.")));
+
+    // Converted through a USD/EUR feed at 0.92
+    let quote_feed = Pubkey::new_unique();
+    test_f
+        .send_ixs(
+            &[create_update_vault_quote_oracle_instruction(
+                deployer,
+                USDC_MINT,
+                OracleConfig::Doves(quote_feed),
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert!(vault.has_quote_oracle());
+
+    test_f
+        .set_account(
+            &quote_feed,
+            DovesPriceFeedBuilder::new()
+                .price(92_000_000)
+                .timestamp(now)
+                .build(),
+        )
+        .await;
+
+    // The quote feed's account follows the collateral oracles'
+    let (result, logs) = mint_stablecoin_with_logs(&test_f, &params, amount_in, 0).await?;
+    assert!(result.is_err());
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: MissingOracleAccounts.")));
+
+    params.remaining_accounts = vec![feed, quote_feed];
+    let mint_event = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+    assert_eq!(mint_event.oracle_price, 920_000);
+    assert_eq!(
+        mint_event.mint_amount,
+        92 * 10_u64.pow(JUPUSD_DECIMALS.into())
+    );
+
+    // Clearing the quote oracle stops the vault again while the peg isn't USD
+    test_f
+        .send_ixs(
+            &[create_update_vault_quote_oracle_instruction(
+                deployer,
+                USDC_MINT,
+                OracleConfig::None,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    params.remaining_accounts = vec![feed];
+    let (result, logs) = mint_stablecoin_with_logs(&test_f, &params, amount_in, 0).await?;
+    assert!(result.is_err());
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: MissingQuoteOracle.")));
+
+    // Back on a USD peg, the oracles price the collateral directly
+    test_f
+        .send_ixs(
+            &[create_set_peg_symbol_instruction(
+                deployer,
+                *b"USD\0\0\0\0\0",
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let mint_event = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+    assert_eq!(mint_event.oracle_price, 1_000_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn depeg_and_recovery_branch_from_snapshot() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+
+    let publish_time = test_f.get_clock().await.unix_timestamp;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &params.user.pubkey(),
+        &params.lp_mint,
+        &spl_token::ID,
+    );
+
+    let snapshot = test_f.snapshot().await;
+
+    // Depeg branch
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .price(40_000_000)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(result.is_err(), "Mint should fail with a depegged price");
+
+    // Recovery branch
+    test_f.restore(&snapshot).await;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+    let user_lp_account: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert!(user_lp_account.amount > 0);
+
+    // Rolling back undoes the mint
+    test_f.restore(&snapshot).await;
+    let user_lp_account: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp_account.amount, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restart_slot_check_applies_to_pyth() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+
+    let publish_time = test_f.get_clock().await.unix_timestamp;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    // Posted at the last restart slot
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .posted_slot(0)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail with a price posted before the last restart"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_update_restart_slot_check_instruction(
+                test_f.deployer.pubkey(),
+                false,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mint_records_last_price_and_rejects_jumps() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .send_ixs(
+            &[create_set_max_price_jump_instruction(
+                test_f.deployer.pubkey(),
+                USDC_MINT,
+                100,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let clock = test_f.get_clock().await;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(clock.unix_timestamp)
+                .build(),
+        )
+        .await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert_eq!(vault.last_price, 10_000);
+    assert!(vault.last_price_ts >= clock.unix_timestamp);
+    assert_eq!(vault.last_spread_bps, 0);
+
+    // 3% below the last executed price
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .price(97_000_000)
+                .publish_time(clock.unix_timestamp)
+                .build(),
+        )
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(result.is_err(), "Mint should fail on a price spike");
+
+    // Within the allowed move
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .price(99_500_000)
+                .publish_time(clock.unix_timestamp)
+                .build(),
+        )
+        .await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert_eq!(vault.last_price, 9_950);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn price_jump_guard_expires_after_its_window() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    let window_seconds = 60;
+    test_f
+        .send_ixs(
+            &[
+                create_set_max_price_jump_instruction(test_f.deployer.pubkey(), USDC_MINT, 100),
+                create_set_price_jump_window_instruction(
+                    test_f.deployer.pubkey(),
+                    USDC_MINT,
+                    window_seconds,
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert_eq!(vault.price_jump_window(), u64::from(window_seconds));
+    assert!(vault.price_jump_window() < vault.stalesness_threshold);
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let set_price = |price, publish_time| {
+        test_f.set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .price(price)
+                .publish_time(publish_time)
+                .build(),
+        )
+    };
+
+    let now = test_f.get_clock().await.unix_timestamp;
+    set_price(100_000_000, now).await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    // 3% below the previous fill, right after it
+    set_price(97_000_000, now).await;
+    let (result, logs) = mint_stablecoin_with_logs(&test_f, &params, amount_in, 0).await?;
+    assert!(result.is_err());
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: PriceJumpTooLarge.")));
+
+    // The same price is accepted once the window has passed, although the previous fill is still
+    // younger than the staleness threshold
+    test_f.advance_time(i64::from(window_seconds) + 1).await;
+    let now = test_f.get_clock().await.unix_timestamp;
+    set_price(97_000_000, now).await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert_eq!(vault.last_price, 9_700);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn confidence_factor_shades_mint_and_redeem_prices() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .send_ixs(
+            &[create_set_confidence_factor_instruction(
+                test_f.deployer.pubkey(),
+                USDC_MINT,
+                10_000,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    // $1.00 with a $0.01 confidence interval
+    let publish_time = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .conf(1_000_000)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let mint_event = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+    assert_eq!(mint_event.oracle_price, 990_000);
+
+    let redeem_event = assert_event::<RedeemV1Event>(
+        &redeem_stablecoin_with_events(&test_f, &params, mint_event.mint_amount, 0).await?,
+    );
+    assert_eq!(redeem_event.oracle_price, 1_010_000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn symmetric_oracle_bounds_check_max_price_on_mint() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .send_ixs(
+            &[create_set_symmetric_oracle_bounds_instruction(
+                test_f.deployer.pubkey(),
+                USDC_MINT,
+                true,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert!(vault.is_symmetric_oracle_bounds());
+
+    // Above the vault max oracle price, which mints normally ignore
+    let publish_time = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .price(150_000_000)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail above the max oracle price with symmetric bounds"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_set_symmetric_oracle_bounds_instruction(
+                test_f.deployer.pubkey(),
+                USDC_MINT,
+                false,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+struct OracleFailureCase {
+    name: &'static str,
+    oracles: Vec<OracleConfig>,
+    feeds: Vec<(Pubkey, Account)>,
+    remaining_accounts: Vec<Pubkey>,
+    error: &'static str,
+}
+
+#[tokio::test]
+async fn oracle_failures_reject_mint_with_typed_errors() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    // Keep the clock slot above the staleness window so the switchboard reader does not underflow
+    test_f.warp_to_slot(1_000);
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let mut params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+
+    let clock = test_f.get_clock().await;
+    let now = clock.unix_timestamp;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let switchboard_feed = Pubkey::new_unique();
+    let doves_feed = Pubkey::new_unique();
+    let other_pyth_account = Pubkey::new_unique();
+    let fresh_pyth = PythPriceUpdateBuilder::new(USDC_FEED_ID).publish_time(now);
+
+    let cases = vec![
+        OracleFailureCase {
+            name: "stale pyth",
+            oracles: vec![USDC_ORACLE_CONFIG],
+            feeds: vec![(
+                USDC_PRICE_ACCOUNT,
+                PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                    .publish_time(now - 3_600)
+                    .build(),
+            )],
+            remaining_accounts: vec![USDC_PRICE_ACCOUNT],
+            error: "PriceTooOld",
+        },
+        OracleFailureCase {
+            name: "wide pyth confidence",
+            oracles: vec![USDC_ORACLE_CONFIG],
+            feeds: vec![(
+                USDC_PRICE_ACCOUNT,
+                PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                    .conf(2_000_000)
+                    .publish_time(now)
+                    .build(),
+            )],
+            remaining_accounts: vec![USDC_PRICE_ACCOUNT],
+            error: "PriceConfidenceTooWide",
+        },
+        OracleFailureCase {
+            name: "zero switchboard price",
+            oracles: vec![OracleConfig::SwitchboardOnDemand(switchboard_feed)],
+            feeds: vec![(
+                switchboard_feed,
+                SwitchboardPullFeedBuilder::new()
+                    .value(0)
+                    .last_update_slot(clock.slot)
+                    .last_update_timestamp(now)
+                    .build(),
+            )],
+            remaining_accounts: vec![switchboard_feed],
+            error: "BadOracle",
+        },
+        OracleFailureCase {
+            name: "stale doves",
+            oracles: vec![OracleConfig::Doves(doves_feed)],
+            feeds: vec![(
+                doves_feed,
+                DovesPriceFeedBuilder::new().timestamp(now - 3_600).build(),
+            )],
+            remaining_accounts: vec![doves_feed],
+            error: "BadOracle",
+        },
+        OracleFailureCase {
+            name: "mismatched oracle account",
+            oracles: vec![USDC_ORACLE_CONFIG],
+            feeds: vec![
+                (USDC_PRICE_ACCOUNT, fresh_pyth.build()),
+                (other_pyth_account, fresh_pyth.build()),
+            ],
+            remaining_accounts: vec![other_pyth_account],
+            error: "BadOracle",
+        },
+        OracleFailureCase {
+            name: "wrong owner program",
+            oracles: vec![USDC_ORACLE_CONFIG],
+            feeds: vec![(USDC_PRICE_ACCOUNT, Account {
+                owner: system_program::ID,
+                ..fresh_pyth.build()
+            })],
+            remaining_accounts: vec![USDC_PRICE_ACCOUNT],
+            error: "BadOracle",
+        },
+        OracleFailureCase {
+            name: "missing remaining accounts",
+            oracles: vec![USDC_ORACLE_CONFIG],
+            feeds: vec![(USDC_PRICE_ACCOUNT, fresh_pyth.build())],
+            remaining_accounts: vec![],
+            error: "MissingOracleAccounts",
+        },
+        OracleFailureCase {
+            name: "cross-oracle spread",
+            oracles: vec![USDC_ORACLE_CONFIG, OracleConfig::Doves(doves_feed)],
+            feeds: vec![
+                (USDC_PRICE_ACCOUNT, fresh_pyth.build()),
+                // 3% above the pyth price, past the 2% spread allowed between oracles
+                (
+                    doves_feed,
+                    DovesPriceFeedBuilder::new()
+                        .price(103_000_000)
+                        .timestamp(now)
+                        .build(),
+                ),
+            ],
+            remaining_accounts: vec![USDC_PRICE_ACCOUNT, doves_feed],
+            error: "PriceConfidenceTooWide",
+        },
+    ];
+
+    let snapshot = test_f.snapshot().await;
+
+    for case in cases {
+        test_f.restore(&snapshot).await;
+
+        let update_oracles = case
+            .oracles
+            .into_iter()
+            .enumerate()
+            .map(|(index, oracle)| {
+                create_update_vault_oracle_instruction(
+                    test_f.deployer.pubkey(),
+                    USDC_MINT,
+                    index as u8,
+                    oracle,
+                )
+            })
+            .collect::<Vec<_>>();
+        test_f
+            .send_ixs(&update_oracles, &[&test_f.deployer])
+            .await?;
+        for (address, account) in case.feeds {
+            test_f.set_account(&address, account).await;
+        }
+
+        params.remaining_accounts = case.remaining_accounts;
+        let (result, logs) = mint_stablecoin_with_logs(&test_f, &params, amount_in, 0).await?;
+
+        assert!(result.is_err(), "{}: mint should fail", case.name);
+        let code = format!("Error Code: {}.", case.error);
+        assert!(
+            logs.iter().any(|log| log.contains(&code)),
+            "{}: expected {code} in logs {logs:?}",
+            case.name
+        );
+    }
+
+    Ok(())
+}
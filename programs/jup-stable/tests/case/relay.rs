@@ -0,0 +1,176 @@
+use anchor_spl::token_interface::TokenAccount;
+use fixtures::{oracle::DovesPriceFeedBuilder, test::TestFixture};
+use jup_stable::{instructions::OracleConfig, state::benefactor::Benefactor};
+use solana_instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::{
+    constants::{USDC_DECIMALS, USDC_MINT},
+    derivation::{find_authority, find_vault_token_account},
+    faciliter::{setup_full_test_context, setup_mint_with_oracle},
+    instructions::{
+        create_relayed_mint_instruction, create_relayed_redeem_instruction,
+        create_set_benefactor_relayer_instruction, RelayedMintInstructionAccounts,
+        RelayedRedeemInstructionAccounts,
+    },
+};
+
+/// Processes `ixs` and returns the transaction result along with its logs.
+async fn process_with_logs(
+    test_f: &TestFixture,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> anyhow::Result<(bool, Vec<String>)> {
+    let tx = test_f.tx().ixs(ixs).signers(signers).build().await?;
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    let logs = outcome
+        .metadata
+        .map(|metadata| metadata.log_messages)
+        .unwrap_or_default();
+    Ok((outcome.result.is_ok(), logs))
+}
+
+#[tokio::test]
+async fn relayer_mints_and_redeems_for_benefactor() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+
+    let feed = Pubkey::new_unique();
+    let params =
+        setup_mint_with_oracle(&test_f, &test_context, OracleConfig::Doves(feed), feed).await?;
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(&feed, DovesPriceFeedBuilder::new().timestamp(now).build())
+        .await;
+    let user = params.user.pubkey();
+    let user_collateral_ata = get_associated_token_address(&user, &USDC_MINT);
+    let user_lp_ata = get_associated_token_address(&user, &params.lp_mint);
+
+    let relayer = Keypair::new();
+    test_f.fund_account(&relayer.pubkey()).await;
+    test_f
+        .send_ixs(
+            &[create_set_benefactor_relayer_instruction(
+                deployer,
+                params.benefactor,
+                1,
+                relayer.pubkey(),
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let benefactor: Benefactor = test_f.load_and_deserialize(&params.benefactor).await;
+    assert!(benefactor.is_relayer(&relayer.pubkey()));
+
+    // The user only ever signs the approvals, the relayer pays for and submits the rest
+    let amount = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .send_ixs(
+            &[
+                spl_token::instruction::approve(
+                    &spl_token::ID,
+                    &user_collateral_ata,
+                    &find_authority(),
+                    &user,
+                    &[],
+                    amount,
+                )?,
+                spl_token::instruction::approve(
+                    &spl_token::ID,
+                    &user_lp_ata,
+                    &find_authority(),
+                    &user,
+                    &[],
+                    amount,
+                )?,
+            ],
+            &[&params.user],
+        )
+        .await?;
+
+    let relayed_mint = |relayer| {
+        create_relayed_mint_instruction(amount, 0, RelayedMintInstructionAccounts {
+            relayer,
+            user,
+            benefactor: params.benefactor,
+            custodian: params.custodian,
+            custodian_token_account: None,
+            vault_mint: USDC_MINT,
+            lp_mint: params.lp_mint,
+            vault_token_program: spl_token::ID,
+            lp_token_program: spl_token::ID,
+            remaining_accounts: params.remaining_accounts.clone(),
+        })
+    };
+
+    // A key the benefactor did not designate cannot relay
+    let stranger = Keypair::new();
+    test_f.fund_account(&stranger.pubkey()).await;
+    let (succeeded, logs) =
+        process_with_logs(&test_f, &[relayed_mint(stranger.pubkey())], &[&stranger]).await?;
+    assert!(!succeeded);
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: InvalidRelayer.")));
+
+    let user_collateral: TokenAccount = test_f.load_and_deserialize(&user_collateral_ata).await;
+    test_f
+        .send_ixs(&[relayed_mint(relayer.pubkey())], &[&relayer])
+        .await?;
+
+    let user_collateral_after: TokenAccount =
+        test_f.load_and_deserialize(&user_collateral_ata).await;
+    assert_eq!(
+        user_collateral_after.amount,
+        user_collateral.amount - amount
+    );
+    assert_eq!(user_collateral_after.delegated_amount, 0);
+    let user_lp: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp.amount, amount);
+
+    // The approval is spent, so the relayer cannot pull more collateral
+    let (succeeded, _) =
+        process_with_logs(&test_f, &[relayed_mint(relayer.pubkey())], &[&relayer]).await?;
+    assert!(!succeeded);
+
+    test_f
+        .mint_tokens(&find_vault_token_account(&USDC_MINT), amount)
+        .await;
+    test_f
+        .send_ixs(
+            &[create_relayed_redeem_instruction(
+                amount,
+                0,
+                RelayedRedeemInstructionAccounts {
+                    relayer: relayer.pubkey(),
+                    user,
+                    benefactor: params.benefactor,
+                    vault_mint: USDC_MINT,
+                    lp_mint: params.lp_mint,
+                    vault_token_program: spl_token::ID,
+                    lp_token_program: spl_token::ID,
+                    remaining_accounts: params.remaining_accounts.clone(),
+                },
+            )],
+            &[&relayer],
+        )
+        .await?;
+
+    let user_lp: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp.amount, 0);
+    let user_collateral: TokenAccount = test_f.load_and_deserialize(&user_collateral_ata).await;
+    assert_eq!(
+        user_collateral.amount,
+        user_collateral_after.amount + amount
+    );
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::{fs, path::Path};
+
+use fixtures::{
+    scenario::{run_scenario, Scenario},
+    test::TestFixture,
+};
+use solana_program_test::*;
+
+#[tokio::test]
+async fn declarative_scenarios() -> anyhow::Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scenarios");
+    let mut paths: Vec<_> = fs::read_dir(&dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+    paths.sort();
+    assert!(!paths.is_empty(), "no scenarios in {}", dir.display());
+
+    for path in paths {
+        let scenario = Scenario::from_file(&path)?;
+        let test_f = TestFixture::new().await;
+        run_scenario(&test_f, &scenario).await?;
+    }
+
+    Ok(())
+}
+
+/// Seeds replayed on every run. Set `SCENARIO_SEED` to run a single other seed instead, e.g. one
+/// printed by a failing run.
+const RANDOM_SCENARIO_SEEDS: [u64; 3] = [1, 0x5eed, 0xdead_beef];
+const RANDOM_SCENARIO_STEPS: usize = 300;
+
+#[tokio::test]
+async fn randomized_scenarios() -> anyhow::Result<()> {
+    let seeds = match std::env::var("SCENARIO_SEED") {
+        Ok(seed) => vec![seed.parse()?],
+        Err(_) => RANDOM_SCENARIO_SEEDS.to_vec(),
+    };
+
+    for seed in seeds {
+        println!("randomized scenario seed {seed}");
+        let scenario = Scenario::random(seed, RANDOM_SCENARIO_STEPS);
+        let test_f = TestFixture::new().await;
+        run_scenario(&test_f, &scenario).await?;
+    }
+
+    Ok(())
+}
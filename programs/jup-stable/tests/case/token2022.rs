@@ -0,0 +1,156 @@
+use anchor_spl::token_interface::TokenAccount;
+use fixtures::{
+    oracle::DovesPriceFeedBuilder,
+    test::{MintExtension, TestFixture},
+};
+use jup_stable::{instructions::OracleConfig, state::vault::Vault};
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::common::{
+    constants::USDC_DECIMALS,
+    derivation::{find_vault, find_vault_token_account_with_program},
+    faciliter::{
+        mint_stablecoin, mint_stablecoin_with_logs, redeem_stablecoin, setup_full_test_context,
+        setup_mint_with_collateral,
+    },
+    instructions::{create_withdraw_instruction, WithdrawInstructionAccounts},
+};
+
+const TRANSFER_FEE_BPS: u16 = 100;
+
+#[tokio::test]
+async fn token2022_collateral_mint_redeem_withdraw() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let mint = test_f.create_token2022_mint(USDC_DECIMALS, &[]).await;
+    let feed = Pubkey::new_unique();
+    let params = setup_mint_with_collateral(
+        &test_f,
+        &test_context,
+        mint,
+        spl_token_2022::ID,
+        OracleConfig::Doves(feed),
+        feed,
+    )
+    .await?;
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(&feed, DovesPriceFeedBuilder::new().timestamp(now).build())
+        .await;
+
+    let user_collateral_ata = get_associated_token_address_with_program_id(
+        &params.user.pubkey(),
+        &mint,
+        &spl_token_2022::ID,
+    );
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &params.user.pubkey(),
+        &params.lp_mint,
+        &spl_token::ID,
+    );
+    let custodian_ata =
+        get_associated_token_address_with_program_id(&params.custodian, &mint, &spl_token_2022::ID);
+    let vault_token_account = find_vault_token_account_with_program(&mint, &spl_token_2022::ID);
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&mint)).await;
+    assert_eq!(vault.token_program, spl_token_2022::ID);
+    assert_eq!(vault.token_account, vault_token_account);
+
+    let user_balance: TokenAccount = test_f.load_and_deserialize(&user_collateral_ata).await;
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    let user_collateral: TokenAccount = test_f.load_and_deserialize(&user_collateral_ata).await;
+    assert_eq!(user_collateral.amount, user_balance.amount - amount_in);
+    let custodian_collateral: TokenAccount = test_f.load_and_deserialize(&custodian_ata).await;
+    assert_eq!(custodian_collateral.amount, amount_in);
+    let user_lp: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp.amount, amount_in);
+
+    let redeem_amount = user_lp.amount / 2;
+    test_f
+        .mint_tokens(&vault_token_account, 2 * amount_in)
+        .await;
+    redeem_stablecoin(&test_f, &params, redeem_amount, 0).await?;
+
+    let vault_collateral: TokenAccount = test_f.load_and_deserialize(&vault_token_account).await;
+    assert_eq!(vault_collateral.amount, 2 * amount_in - redeem_amount);
+    let user_collateral_after: TokenAccount =
+        test_f.load_and_deserialize(&user_collateral_ata).await;
+    assert_eq!(
+        user_collateral_after.amount,
+        user_collateral.amount + redeem_amount
+    );
+    let user_lp: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp.amount, amount_in - redeem_amount);
+
+    let withdraw_amount = vault_collateral.amount;
+    test_f
+        .send_ixs(
+            &[create_withdraw_instruction(
+                WithdrawInstructionAccounts {
+                    operator_authority: test_f.deployer.pubkey(),
+                    custodian: params.custodian,
+                    custodian_token_account: None,
+                    vault_mint: mint,
+                    vault_token_program: spl_token_2022::ID,
+                    memo_program: None,
+                },
+                withdraw_amount,
+                None,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let vault_collateral: TokenAccount = test_f.load_and_deserialize(&vault_token_account).await;
+    assert_eq!(vault_collateral.amount, 0);
+    let custodian_collateral_after: TokenAccount =
+        test_f.load_and_deserialize(&custodian_ata).await;
+    assert_eq!(
+        custodian_collateral_after.amount,
+        custodian_collateral.amount + withdraw_amount
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token2022_transfer_fee_collateral_mint_rejected() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let mint = test_f
+        .create_token2022_mint(USDC_DECIMALS, &[MintExtension::TransferFee {
+            transfer_fee_basis_points: TRANSFER_FEE_BPS,
+            maximum_fee: u64::MAX,
+        }])
+        .await;
+    let feed = Pubkey::new_unique();
+    let params = setup_mint_with_collateral(
+        &test_f,
+        &test_context,
+        mint,
+        spl_token_2022::ID,
+        OracleConfig::Doves(feed),
+        feed,
+    )
+    .await?;
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(&feed, DovesPriceFeedBuilder::new().timestamp(now).build())
+        .await;
+
+    // The custodian must receive the full deposit, which a withheld transfer fee never allows
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let (result, logs) = mint_stablecoin_with_logs(&test_f, &params, amount_in, 0).await?;
+    assert!(result.is_err());
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("Error Code: InsufficientAmount.")));
+
+    Ok(())
+}
@@ -1,19 +1,42 @@
-use anchor_spl::token_interface::TokenAccount;
-use fixtures::test::TestFixture;
-use jup_stable::state::{benefactor::Benefactor, config::Config, vault::Vault};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use fixtures::{
+    event::{assert_event, assert_no_event},
+    oracle::PythPriceUpdateBuilder,
+    test::{PeriodLimitAccount, TestFixture},
+};
+use jup_stable::{
+    instructions::{MintV0Event, MintV1Event, RedeemV0Event, RedeemV1Event},
+    state::{benefactor::Benefactor, config::Config, vault::Vault},
+};
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 
 use crate::common::{
     constants::{
-        JUPUSD_DECIMALS, USDC_DECIMALS, USDC_MINT, USDC_ORACLE_CONFIG, USDC_PRICE_ACCOUNT,
+        JUPUSD_DECIMALS, USDC_DECIMALS, USDC_FEED_ID, USDC_MINT, USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
     },
     derivation::{find_config, find_vault, find_vault_token_account},
     faciliter::{
         create_active_benefactor, create_associated_token_account, create_vault_with_oracle,
-        mint_stablecoin, redeem_stablecoin, refresh_pyth_feed, set_period_limit,
-        setup_full_test_context, MintRedeemParams, PeriodLimitArgs, PeriodLimitTarget,
+        dry_run_mint, dry_run_redeem, get_capacity, mint_stablecoin, mint_stablecoin_with_events,
+        mint_stablecoin_with_logs, redeem_stablecoin, redeem_stablecoin_with_events,
+        refresh_pyth_feed, set_period_limit, setup_full_test_context, setup_mint_with_oracle,
+        MintRedeemParams, PeriodLimitArgs, PeriodLimitTarget,
+    },
+    instructions::{
+        create_assert_supply_consistency_instruction, create_burn_treasury_instruction,
+        create_mint_instruction, create_redeem_instruction,
+        create_reset_vault_period_limit_instruction,
+        create_set_benefactor_exempt_from_global_pause_instruction,
+        create_set_benefactor_max_net_minted_instruction,
+        create_set_custodian_token_account_instruction, create_set_max_net_outstanding_instruction,
+        create_set_per_tx_limits_instruction, create_set_requires_benefactor_instruction,
+        create_sync_supply_instruction, create_update_fee_rates_instruction,
+        create_update_pause_flag_instruction, create_update_strict_period_limits_instruction,
+        create_withdraw_instruction, BurnTreasuryInstructionAccounts, MintInstructionAccounts,
+        RedeemInstructionAccounts, WithdrawInstructionAccounts,
     },
 };
 
@@ -121,12 +144,12 @@ async fn mint_redeem_success() -> anyhow::Result<()> {
         "Vault period limit should be updated"
     );
     assert_eq!(
-        u128::from_le_bytes(vault.total_minted),
+        vault.total_minted(),
         u128::from(user_lp_mint_account.amount),
         "Vault total minted should be updated"
     );
     assert_eq!(
-        u128::from_le_bytes(vault.total_redeemed),
+        vault.total_redeemed(),
         0,
         "Vault total redeemed should be 0"
     );
@@ -137,12 +160,12 @@ async fn mint_redeem_success() -> anyhow::Result<()> {
         "Benefactor period limit should be updated"
     );
     assert_eq!(
-        u128::from_le_bytes(benefactor.total_minted),
+        benefactor.total_minted(),
         u128::from(user_lp_mint_account.amount),
         "Benefactor total minted should be updated"
     );
     assert_eq!(
-        u128::from_le_bytes(benefactor.total_redeemed),
+        benefactor.total_redeemed(),
         0,
         "Benefactor total redeemed should be 0"
     );
@@ -188,7 +211,7 @@ async fn mint_redeem_success() -> anyhow::Result<()> {
         "Vault period limit should be updated"
     );
     assert_eq!(
-        u128::from_le_bytes(vault.total_redeemed),
+        vault.total_redeemed(),
         u128::from(net_redeem_amount),
         "Vault total redeemed should be updated"
     );
@@ -199,12 +222,12 @@ async fn mint_redeem_success() -> anyhow::Result<()> {
         "Benefactor period limit should be updated"
     );
     assert_eq!(
-        u128::from_le_bytes(benefactor.total_minted),
+        benefactor.total_minted(),
         u128::from(redeem_amount),
         "Benefactor total minted should be updated"
     );
     assert_eq!(
-        u128::from_le_bytes(benefactor.total_redeemed),
+        benefactor.total_redeemed(),
         u128::from(net_redeem_amount),
         "Benefactor total redeemed should be updated"
     );
@@ -263,7 +286,12 @@ async fn mint_redeem_with_benefactor_fees_success() -> anyhow::Result<()> {
         remaining_accounts: vec![USDC_PRICE_ACCOUNT],
     };
 
-    mint_stablecoin(&test_f, &accounts, amount_in, min_amount_out).await?;
+    let mint_event = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &accounts, amount_in, min_amount_out).await?,
+    );
+    assert_eq!(mint_event.fee_amount, amount_in / 100);
+    assert_eq!(mint_event.fee_rate_bps, mint_fee_rate);
+    assert_eq!(mint_event.net_amount, amount_in - mint_event.fee_amount);
 
     let user_collateral_account: TokenAccount =
         test_f.load_and_deserialize(&user_collateral_ata).await;
@@ -293,7 +321,12 @@ async fn mint_redeem_with_benefactor_fees_success() -> anyhow::Result<()> {
     test_f
         .mint_tokens(&find_vault_token_account(&mint), redeem_amount)
         .await;
-    redeem_stablecoin(&test_f, &accounts, redeem_amount, redeem_amount_out).await?;
+    let redeem_event = assert_event::<RedeemV1Event>(
+        &redeem_stablecoin_with_events(&test_f, &accounts, redeem_amount, redeem_amount_out)
+            .await?,
+    );
+    assert_eq!(redeem_event.fee_amount, redeem_amount.div_ceil(100));
+    assert_eq!(redeem_event.fee_rate_bps, redeem_fee_rate);
 
     Ok(())
 }
@@ -349,12 +382,19 @@ async fn mint_outside_of_period_limit_fail() -> anyhow::Result<()> {
         remaining_accounts: vec![USDC_PRICE_ACCOUNT],
     };
 
-    let result = mint_stablecoin(&test_f, &accounts, mint_amount, 0).await;
+    let (result, logs) = mint_stablecoin_with_logs(&test_f, &accounts, mint_amount, 0).await?;
 
     assert!(
         result.is_err(),
         "Transaction should fail when minting outside of period limit"
     );
+    assert!(
+        logs.iter().any(|log| {
+            log.contains("benefactor mint limit 0: requested")
+                && log.contains(&format!("available {max_mint_amount}"))
+        }),
+        "rejection should log the requested and available amounts"
+    );
 
     set_period_limit(&test_f, vec![
         PeriodLimitArgs {
@@ -543,3 +583,1348 @@ async fn redeem_outside_of_period_limit_fail() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn mint_redeem_emit_events() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+
+    let publish_time = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(publish_time)
+                .build(),
+        )
+        .await;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let events = mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?;
+    let mint_event = assert_event::<MintV1Event>(&events);
+    assert_eq!(mint_event.amount, amount_in);
+    assert_eq!(mint_event.net_amount, amount_in);
+    assert_eq!(mint_event.oracle_price, 1_000_000);
+    assert_eq!(mint_event.fee_amount, 0);
+    assert_no_event::<MintV0Event>(&events);
+
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &params.user.pubkey(),
+        &params.lp_mint,
+        &spl_token::ID,
+    );
+    let user_lp_account: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp_account.amount, mint_event.mint_amount);
+
+    let events = redeem_stablecoin_with_events(&test_f, &params, user_lp_account.amount, 0).await?;
+    let redeem_event = assert_event::<RedeemV1Event>(&events);
+    assert_eq!(redeem_event.amount, user_lp_account.amount);
+    assert_eq!(redeem_event.oracle_price, 1_000_000);
+    assert_no_event::<RedeemV0Event>(&events);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn period_limit_windows_roll() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+
+    let targets = [
+        PeriodLimitAccount::Config(find_config()),
+        PeriodLimitAccount::Vault(find_vault(&params.vault_mint)),
+        PeriodLimitAccount::Benefactor(params.benefactor),
+    ];
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+    let first = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+    for target in &targets {
+        let limit = test_f.load_period_limit(target, 0).await;
+        assert_eq!(limit.minted_amount, first.mint_amount);
+    }
+
+    // A mint after the window elapses only counts against the fresh window
+    let stale = test_f.advance_past_window(&targets[0], 0).await;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+    let second = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in / 2, 0).await?,
+    );
+    for target in &targets {
+        let limit = test_f.assert_window_rolled(target, 0, &stale).await;
+        assert_eq!(limit.minted_amount, second.mint_amount);
+        assert_eq!(limit.redeemed_amount, 0);
+    }
+
+    // Same for redeems
+    let stale = test_f.advance_past_window(&targets[0], 0).await;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+    let redeem = assert_event::<RedeemV1Event>(
+        &redeem_stablecoin_with_events(&test_f, &params, second.mint_amount, 0).await?,
+    );
+    for target in &targets {
+        let limit = test_f.assert_window_rolled(target, 0, &stale).await;
+        assert_eq!(limit.minted_amount, 0);
+        assert_eq!(limit.redeemed_amount, redeem.net_amount);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn period_limit_windows_survive_clock_regression() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+
+    let targets = [
+        PeriodLimitAccount::Config(find_config()),
+        PeriodLimitAccount::Vault(find_vault(&params.vault_mint)),
+        PeriodLimitAccount::Benefactor(params.benefactor),
+    ];
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+    let first = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+    let mut windows = vec![];
+    for target in &targets {
+        windows.push(test_f.load_period_limit(target, 0).await);
+    }
+
+    // A clock earlier than the window start keeps accruing into the open window
+    let earliest_start = windows
+        .iter()
+        .map(|window| window.window_start)
+        .min()
+        .unwrap();
+    test_f.set_time(earliest_start - 3600);
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+    let second = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in / 2, 0).await?,
+    );
+    for (target, window) in targets.iter().zip(&windows) {
+        let limit = test_f.load_period_limit(target, 0).await;
+        assert_eq!(limit.window_start, window.window_start);
+        assert_eq!(limit.minted_amount, first.mint_amount + second.mint_amount);
+    }
+
+    // Once the clock catches up past the original window end, the window rolls as usual
+    let stale = test_f.advance_past_window(&targets[0], 0).await;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+    let third = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in / 2, 0).await?,
+    );
+    for target in &targets {
+        let limit = test_f.assert_window_rolled(target, 0, &stale).await;
+        assert_eq!(limit.minted_amount, third.mint_amount);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mint_with_vault_total_near_overflow() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+
+    let vault = find_vault(&params.vault_mint);
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+
+    // Total minted already saturated, recording another mint must abort
+    test_f
+        .mutate_zero_copy::<Vault>(&vault, |vault| {
+            vault.total_minted = u128::MAX.to_le_bytes();
+        })
+        .await;
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail when the vault total would overflow"
+    );
+
+    // Exactly enough headroom left for this mint
+    test_f
+        .mutate_zero_copy::<Vault>(&vault, |vault| {
+            vault.total_minted = (u128::MAX - amount_in as u128).to_le_bytes();
+        })
+        .await;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    let vault: Vault = test_f.load_and_deserialize(&vault).await;
+    assert_eq!(vault.total_minted(), u128::MAX);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn benefactor_net_minted_is_capped() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let max_net_minted = 150 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    test_f
+        .send_ixs(
+            &[create_set_benefactor_max_net_minted_instruction(
+                test_f.deployer.pubkey(),
+                params.benefactor,
+                max_net_minted,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let minted = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail once the benefactor's net minted amount would exceed the cap"
+    );
+
+    // Redeeming frees up room regardless of the period windows
+    test_f
+        .mint_tokens(
+            &find_vault_token_account(&params.vault_mint),
+            minted.mint_amount,
+        )
+        .await;
+    redeem_stablecoin(&test_f, &params, minted.mint_amount, 0).await?;
+
+    let benefactor: Benefactor = test_f.load_and_deserialize(&params.benefactor).await;
+    assert_eq!(benefactor.net_minted(), 0);
+
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn capacity_reports_the_tightest_limit() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let max_amount = 1_000_000 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    let capacity = get_capacity(&test_f, params.vault_mint, params.benefactor).await?;
+    assert_eq!(capacity.mint_amount, max_amount);
+    assert_eq!(capacity.redeem_amount, max_amount);
+
+    let max_net_minted = 150 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    test_f
+        .send_ixs(
+            &[create_set_benefactor_max_net_minted_instruction(
+                test_f.deployer.pubkey(),
+                params.benefactor,
+                max_net_minted,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let minted = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+
+    let capacity = get_capacity(&test_f, params.vault_mint, params.benefactor).await?;
+    assert_eq!(
+        capacity.mint_amount,
+        max_net_minted - minted.mint_amount,
+        "The net minted cap should be the binding mint limit"
+    );
+    assert_eq!(capacity.redeem_amount, max_amount);
+
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Minting past the reported capacity should fail"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_update_pause_flag_instruction(
+                test_f.deployer.pubkey(),
+                false,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let capacity = get_capacity(&test_f, params.vault_mint, params.benefactor).await?;
+    assert_eq!(
+        capacity.mint_amount, 0,
+        "Nothing can be minted while paused"
+    );
+    assert_eq!(
+        capacity.redeem_amount, 0,
+        "Nothing can be redeemed while paused"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn strict_period_limits_block_unconfigured_vault() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    test_f
+        .send_ixs(
+            &[create_update_strict_period_limits_instruction(
+                test_f.deployer.pubkey(),
+                true,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(config.is_strict_period_limits());
+
+    let amount_in = 10 * 10_u64.pow(USDC_DECIMALS.into());
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    test_f
+        .send_ixs(
+            &[create_reset_vault_period_limit_instruction(
+                test_f.deployer.pubkey(),
+                params.vault_mint,
+                0,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail in strict mode when the vault has no period limit"
+    );
+
+    // Without strict mode an unconfigured vault is unlimited again
+    test_f
+        .send_ixs(
+            &[create_update_strict_period_limits_instruction(
+                test_f.deployer.pubkey(),
+                false,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vault_per_tx_limits_cap_single_mint_and_redeem() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let max_mint_per_tx = 50 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    let max_redeem_per_tx = 20 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    test_f
+        .send_ixs(
+            &[create_set_per_tx_limits_instruction(
+                test_f.deployer.pubkey(),
+                params.vault_mint,
+                max_mint_per_tx,
+                max_redeem_per_tx,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let result = mint_stablecoin(&test_f, &params, 100 * 10_u64.pow(USDC_DECIMALS.into()), 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail above the vault per tx limit"
+    );
+
+    let minted = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, 50 * 10_u64.pow(USDC_DECIMALS.into()), 0)
+            .await?,
+    );
+    assert!(minted.mint_amount <= max_mint_per_tx);
+
+    test_f
+        .mint_tokens(
+            &find_vault_token_account(&params.vault_mint),
+            minted.mint_amount,
+        )
+        .await;
+    let result = redeem_stablecoin(&test_f, &params, max_redeem_per_tx + 1, 0).await;
+    assert!(
+        result.is_err(),
+        "Redeem should fail above the vault per tx limit"
+    );
+    redeem_stablecoin(&test_f, &params, max_redeem_per_tx, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pause_exempt_benefactor_redeems_during_global_pause() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let minted = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+    test_f
+        .mint_tokens(
+            &find_vault_token_account(&params.vault_mint),
+            minted.mint_amount,
+        )
+        .await;
+
+    test_f
+        .send_ixs(
+            &[create_update_pause_flag_instruction(
+                test_f.deployer.pubkey(),
+                false,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let redeem_amount = minted.mint_amount / 2;
+    let result = redeem_stablecoin(&test_f, &params, redeem_amount, 0).await;
+    assert!(result.is_err(), "Redeem should fail during a global pause");
+
+    test_f
+        .send_ixs(
+            &[create_set_benefactor_exempt_from_global_pause_instruction(
+                test_f.deployer.pubkey(),
+                params.benefactor,
+                true,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let benefactor: Benefactor = test_f.load_and_deserialize(&params.benefactor).await;
+    assert!(benefactor.is_exempt_from_global_pause());
+
+    redeem_stablecoin(&test_f, &params, redeem_amount, 0).await?;
+
+    // The exemption only covers redeems
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should still fail during a global pause"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vault_net_outstanding_is_capped() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let set_max_net_outstanding = |max_net_outstanding| {
+        create_set_max_net_outstanding_instruction(
+            test_f.deployer.pubkey(),
+            params.vault_mint,
+            max_net_outstanding,
+        )
+    };
+    let max_net_outstanding = 150 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    test_f
+        .send_ixs(&[set_max_net_outstanding(max_net_outstanding)], &[
+            &test_f.deployer
+        ])
+        .await?;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail once the vault's net outstanding amount would exceed the cap"
+    );
+
+    // Zero lifts the cap
+    test_f
+        .send_ixs(&[set_max_net_outstanding(0)], &[&test_f.deployer])
+        .await?;
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    let vault: Vault = test_f
+        .load_and_deserialize(&find_vault(&params.vault_mint))
+        .await;
+    assert_eq!(vault.net_outstanding(), 2 * amount_in as u128);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn custodian_token_account_override_receives_deposits() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+
+    let custodian_token_account = test_f
+        .create_spl_token_account(&params.vault_mint, &params.custodian)
+        .await?;
+    test_f
+        .send_ixs(
+            &[create_set_custodian_token_account_instruction(
+                test_f.deployer.pubkey(),
+                params.vault_mint,
+                custodian_token_account,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should reject the custodian ATA once the vault overrides it"
+    );
+
+    test_f
+        .tx()
+        .ix(create_mint_instruction(
+            amount_in,
+            0,
+            MintInstructionAccounts {
+                user: params.user.pubkey(),
+                benefactor: params.benefactor,
+                custodian: params.custodian,
+                custodian_token_account: Some(custodian_token_account),
+                vault_mint: params.vault_mint,
+                lp_mint: params.lp_mint,
+                vault_token_program: spl_token::ID,
+                lp_token_program: spl_token::ID,
+                remaining_accounts: params.remaining_accounts.clone(),
+            },
+        ))
+        .signer(&params.user)
+        .send()
+        .await?;
+
+    let deposit: TokenAccount = test_f.load_and_deserialize(&custodian_token_account).await;
+    assert_eq!(
+        deposit.amount, amount_in,
+        "Collateral should land in the override"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn external_lp_mint_blocks_mint_and_redeem() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let amount_in = 10 * 10_u64.pow(USDC_DECIMALS.into());
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    test_f
+        .send_ixs(
+            &[create_sync_supply_instruction(
+                test_f.deployer.pubkey(),
+                params.lp_mint,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let lp_mint: Mint = test_f.load_and_deserialize(&params.lp_mint).await;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(config.is_supply_check_enabled());
+    assert_eq!(config.tracked_supply, lp_mint.supply);
+
+    // Supply issued through the program keeps the books balanced
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+    test_f
+        .send_ixs(
+            &[create_assert_supply_consistency_instruction(params.lp_mint)],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(config.is_mint_redeem_enabled());
+
+    // Simulate jupUSD minted outside of the program
+    let inflated_supply = lp_mint.supply * 3;
+    test_f
+        .patch_account(params.lp_mint, 36, &inflated_supply.to_le_bytes())
+        .await;
+
+    let result = mint_stablecoin(&test_f, &params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Mint should fail when the LP mint supply exceeds the tracked supply"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_assert_supply_consistency_instruction(params.lp_mint)],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(
+        !config.is_mint_redeem_enabled(),
+        "A supply mismatch should pause mint and redeem"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn burn_treasury_lowers_tracked_supply() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let amount_in = 10 * 10_u64.pow(USDC_DECIMALS.into());
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+    test_f
+        .send_ixs(
+            &[create_sync_supply_instruction(
+                test_f.deployer.pubkey(),
+                params.lp_mint,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    // Move the minted jupUSD into a treasury account held by the operator
+    let deployer = test_f.deployer.pubkey();
+    create_associated_token_account(&test_f, &deployer, &params.lp_mint).await?;
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &params.user.pubkey(),
+        &params.lp_mint,
+        &spl_token::ID,
+    );
+    let treasury_ata =
+        get_associated_token_address_with_program_id(&deployer, &params.lp_mint, &spl_token::ID);
+    let minted: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    test_f
+        .send_ixs(
+            &[spl_token::instruction::transfer(
+                &spl_token::ID,
+                &user_lp_ata,
+                &treasury_ata,
+                &params.user.pubkey(),
+                &[],
+                minted.amount,
+            )?],
+            &[&params.user],
+        )
+        .await?;
+
+    let burn_amount = minted.amount / 2;
+    let burn_treasury_ix = |amount| {
+        create_burn_treasury_instruction(
+            BurnTreasuryInstructionAccounts {
+                operator_authority: deployer,
+                treasury_token_account: treasury_ata,
+                lp_mint: params.lp_mint,
+                lp_token_program: spl_token::ID,
+            },
+            amount,
+        )
+    };
+    test_f
+        .send_ixs(&[burn_treasury_ix(burn_amount)], &[&test_f.deployer])
+        .await?;
+
+    let lp_mint: Mint = test_f.load_and_deserialize(&params.lp_mint).await;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    let treasury: TokenAccount = test_f.load_and_deserialize(&treasury_ata).await;
+    assert_eq!(lp_mint.supply, minted.amount - burn_amount);
+    assert_eq!(config.tracked_supply, lp_mint.supply);
+    assert_eq!(treasury.amount, minted.amount - burn_amount);
+
+    let result = test_f
+        .send_ixs(&[burn_treasury_ix(minted.amount)], &[&test_f.deployer])
+        .await;
+    assert!(
+        result.is_err(),
+        "Burn should fail when the treasury holds less than the amount"
+    );
+
+    // The books stay balanced, so minting keeps working
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run_quotes_without_side_effects() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    test_f
+        .set_account(
+            &USDC_PRICE_ACCOUNT,
+            PythPriceUpdateBuilder::new(USDC_FEED_ID)
+                .publish_time(test_f.get_clock().await.unix_timestamp)
+                .build(),
+        )
+        .await;
+
+    let user_collateral_ata = get_associated_token_address_with_program_id(
+        &params.user.pubkey(),
+        &params.vault_mint,
+        &spl_token::ID,
+    );
+    let user_lp_ata = get_associated_token_address_with_program_id(
+        &params.user.pubkey(),
+        &params.lp_mint,
+        &spl_token::ID,
+    );
+    let collateral_before: TokenAccount = test_f.load_and_deserialize(&user_collateral_ata).await;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let mint_quote = dry_run_mint(&test_f, &params, amount_in, 0).await?;
+    assert_eq!(mint_quote.amount, amount_in);
+
+    let collateral_after: TokenAccount = test_f.load_and_deserialize(&user_collateral_ata).await;
+    assert_eq!(collateral_after.amount, collateral_before.amount);
+    let vault: Vault = test_f
+        .load_and_deserialize(&find_vault(&params.vault_mint))
+        .await;
+    assert_eq!(vault.total_minted(), 0, "A dry run should not mint");
+    let benefactor: Benefactor = test_f.load_and_deserialize(&params.benefactor).await;
+    assert_eq!(benefactor.total_minted(), 0);
+
+    let result = dry_run_mint(&test_f, &params, amount_in, mint_quote.mint_amount + 1).await;
+    assert!(
+        result.is_err(),
+        "A dry run should enforce the same slippage bound as the mint"
+    );
+
+    let minted = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &params, amount_in, 0).await?,
+    );
+    assert_eq!(minted.net_amount, mint_quote.net_amount);
+    assert_eq!(minted.fee_amount, mint_quote.fee_amount);
+    assert_eq!(minted.fee_rate_bps, mint_quote.fee_rate_bps);
+    assert_eq!(minted.mint_amount, mint_quote.mint_amount);
+
+    // A dry run past the end of the period windows leaves them as they were
+    let targets = [
+        PeriodLimitAccount::Config(find_config()),
+        PeriodLimitAccount::Vault(find_vault(&params.vault_mint)),
+        PeriodLimitAccount::Benefactor(params.benefactor),
+    ];
+    let mut windows = Vec::new();
+    for target in &targets {
+        windows.push(test_f.load_period_limit(target, 0).await);
+    }
+    test_f.advance_past_window(&targets[0], 0).await;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+
+    let redeem_quote = dry_run_redeem(&test_f, &params, minted.mint_amount, 0).await?;
+    for (target, before) in targets.iter().zip(&windows) {
+        let limit = test_f.load_period_limit(target, 0).await;
+        assert_eq!(
+            limit.window_start, before.window_start,
+            "A dry run should not roll"
+        );
+        assert_eq!(limit.minted_amount, before.minted_amount);
+    }
+    let user_lp_account: TokenAccount = test_f.load_and_deserialize(&user_lp_ata).await;
+    assert_eq!(user_lp_account.amount, minted.mint_amount);
+    let vault: Vault = test_f
+        .load_and_deserialize(&find_vault(&params.vault_mint))
+        .await;
+    assert_eq!(vault.total_redeemed(), 0, "A dry run should not redeem");
+
+    let redeemed = assert_event::<RedeemV1Event>(
+        &redeem_stablecoin_with_events(&test_f, &params, minted.mint_amount, 0).await?,
+    );
+    assert_eq!(redeemed.net_amount, redeem_quote.net_amount);
+    assert_eq!(redeemed.fee_amount, redeem_quote.fee_amount);
+    assert_eq!(redeemed.fee_rate_bps, redeem_quote.fee_rate_bps);
+    assert_eq!(redeemed.redeem_amount, redeem_quote.redeem_amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_run_quotes_follow_fee_rate_updates() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+
+    test_f
+        .send_ixs(
+            &[create_update_fee_rates_instruction(
+                test_f.deployer.pubkey(),
+                params.benefactor,
+                200,
+                150,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let mint_quote = dry_run_mint(&test_f, &params, amount_in, 0).await?;
+    assert_eq!(mint_quote.fee_rate_bps, 200);
+    assert_eq!(mint_quote.fee_amount, amount_in * 200 / 10_000);
+    assert_eq!(mint_quote.net_amount, amount_in - mint_quote.fee_amount);
+
+    mint_stablecoin(&test_f, &params, amount_in, 0).await?;
+    let redeem_quote = dry_run_redeem(&test_f, &params, mint_quote.mint_amount, 0).await?;
+    assert_eq!(redeem_quote.fee_rate_bps, 150);
+    assert_eq!(
+        redeem_quote.fee_amount,
+        (mint_quote.mint_amount * 150).div_ceil(10_000)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vault_without_benefactor_requirement_is_open_to_any_user() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+
+    let params = setup_mint_with_oracle(
+        &test_f,
+        &test_context,
+        USDC_ORACLE_CONFIG,
+        USDC_PRICE_ACCOUNT,
+    )
+    .await?;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+
+    // A user that is not the benefactor's authority
+    let outsider = Keypair::new();
+    test_f.fund_account(&outsider.pubkey()).await;
+    create_associated_token_account(&test_f, &outsider.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &outsider.pubkey(), &params.lp_mint).await?;
+    test_f
+        .mint_tokens(
+            &get_associated_token_address_with_program_id(
+                &outsider.pubkey(),
+                &USDC_MINT,
+                &spl_token::ID,
+            ),
+            1_000 * 10_u64.pow(USDC_DECIMALS.into()),
+        )
+        .await;
+    let outsider_params = MintRedeemParams {
+        user: outsider,
+        benefactor: params.benefactor,
+        custodian: params.custodian,
+        vault_mint: params.vault_mint,
+        lp_mint: params.lp_mint,
+        vault_token_program: None,
+        lp_token_program: None,
+        remaining_accounts: params.remaining_accounts.clone(),
+    };
+
+    let amount_in = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    let result = mint_stablecoin(&test_f, &outsider_params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Vaults require the benefactor's authority by default"
+    );
+
+    let set_requires_benefactor = |required| {
+        create_set_requires_benefactor_instruction(test_f.deployer.pubkey(), USDC_MINT, required)
+    };
+    test_f
+        .send_ixs(&[set_requires_benefactor(false)], &[&test_f.deployer])
+        .await?;
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    assert!(!vault.requires_benefactor());
+
+    let result = mint_stablecoin(&test_f, &outsider_params, amount_in, 0).await;
+    assert!(
+        result.is_err(),
+        "Open vaults still keep other benefactors to their own authority"
+    );
+
+    // The public benefactor is the one whose authority is the vault itself
+    let public_benefactor =
+        create_active_benefactor(&test_f, &find_vault(&USDC_MINT), 0u16, 0u16).await?;
+    set_period_limit(&test_f, vec![PeriodLimitArgs {
+        target: PeriodLimitTarget::Benefactor(public_benefactor),
+        index: 0,
+        duration_seconds: 3600,
+        max_mint_amount: u64::MAX,
+        max_redeem_amount: u64::MAX,
+    }])
+    .await?;
+    let outsider_params = MintRedeemParams {
+        benefactor: public_benefactor,
+        ..outsider_params
+    };
+
+    let minted = assert_event::<MintV1Event>(
+        &mint_stablecoin_with_events(&test_f, &outsider_params, amount_in, 0).await?,
+    );
+    let benefactor: Benefactor = test_f.load_and_deserialize(&public_benefactor).await;
+    assert_eq!(
+        benefactor.total_minted(),
+        minted.mint_amount as u128,
+        "The public benefactor accounts for the mint"
+    );
+    redeem_stablecoin(&test_f, &outsider_params, minted.mint_amount / 2, 0).await?;
+
+    test_f
+        .send_ixs(&[set_requires_benefactor(true)], &[&test_f.deployer])
+        .await?;
+    let result = redeem_stablecoin(&test_f, &outsider_params, minted.mint_amount / 2, 0).await;
+    assert!(
+        result.is_err(),
+        "Restoring the requirement should gate redeems again"
+    );
+
+    Ok(())
+}
+
+/// Sums what `owners` hold of `mint`, skipping owners without an account.
+async fn total_balance(test_f: &TestFixture, owners: &[Pubkey], mint: &Pubkey) -> u64 {
+    let mut total = 0;
+    for owner in owners {
+        let account: TokenAccount = test_f
+            .load_and_deserialize(&get_associated_token_address_with_program_id(
+                owner,
+                mint,
+                &spl_token::ID,
+            ))
+            .await;
+        total += account.amount;
+    }
+    total
+}
+
+/// Checks jupUSD and collateral accounting after a batch of concurrent transactions. Nothing
+/// advances the clock between batches, so every limit is still in its first window.
+async fn assert_contended_vault_consistent(
+    test_f: &TestFixture,
+    lp_mint: &Pubkey,
+    users: &[Pubkey],
+    benefactors: &[Pubkey],
+    custodian: &Pubkey,
+    collateral_total: u64,
+) {
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&USDC_MINT)).await;
+    let window = vault.period_limits[0];
+    assert!(window.minted_amount <= window.max_mint_amount);
+    assert!(window.redeemed_amount <= window.max_redeem_amount);
+    assert_eq!(u128::from(window.minted_amount), vault.total_minted());
+    assert_eq!(u128::from(window.redeemed_amount), vault.total_redeemed());
+
+    let mut benefactors_minted = 0;
+    let mut benefactors_redeemed = 0;
+    for benefactor in benefactors {
+        let benefactor: Benefactor = test_f.load_and_deserialize(benefactor).await;
+        benefactors_minted += benefactor.total_minted();
+        benefactors_redeemed += benefactor.total_redeemed();
+    }
+    assert_eq!(benefactors_minted, vault.total_minted());
+    assert_eq!(benefactors_redeemed, vault.total_redeemed());
+
+    let supply = test_f.load_and_deserialize::<Mint>(lp_mint).await.supply;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.tracked_supply, supply);
+    assert_eq!(u128::from(supply), vault.net_outstanding());
+    assert_eq!(total_balance(test_f, users, lp_mint).await, supply);
+
+    let vault_token_account: TokenAccount = test_f
+        .load_and_deserialize(&find_vault_token_account(&USDC_MINT))
+        .await;
+    let collateral = total_balance(test_f, users, &USDC_MINT).await
+        + total_balance(test_f, &[*custodian], &USDC_MINT).await
+        + vault_token_account.amount;
+    assert_eq!(collateral, collateral_total, "Collateral is conserved");
+}
+
+#[tokio::test]
+async fn same_slot_contention_keeps_limits_and_totals_consistent() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let test_context = setup_full_test_context(&test_f).await?;
+    let lp_mint = test_context.lp_mint;
+    let deployer = test_f.deployer.pubkey();
+
+    let custodian = Keypair::new().pubkey();
+    create_vault_with_oracle(&test_f, USDC_MINT, custodian, USDC_ORACLE_CONFIG).await?;
+    create_associated_token_account(&test_f, &custodian, &USDC_MINT).await?;
+    test_f
+        .replicate_account_from_mainnet(&USDC_PRICE_ACCOUNT)
+        .await?;
+    refresh_pyth_feed(&test_f, USDC_PRICE_ACCOUNT).await?;
+
+    let unit = 10_u64.pow(USDC_DECIMALS.into());
+    let generous = 1_000_000 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    let mut limits = vec![
+        PeriodLimitArgs {
+            target: PeriodLimitTarget::Vault(USDC_MINT),
+            index: 0,
+            duration_seconds: 3600,
+            max_mint_amount: 500 * unit,
+            max_redeem_amount: 150 * unit,
+        },
+        PeriodLimitArgs {
+            target: PeriodLimitTarget::Config,
+            index: 0,
+            duration_seconds: 3600,
+            max_mint_amount: generous,
+            max_redeem_amount: generous,
+        },
+    ];
+
+    let user_balance = 200 * unit;
+    let vault_liquidity = 1_000 * unit;
+    let mut users = vec![];
+    let mut benefactors = vec![];
+    for _ in 0..8 {
+        let user = Keypair::new();
+        test_f.fund_account(&user.pubkey()).await;
+        let benefactor = create_active_benefactor(&test_f, &user.pubkey(), 0, 0).await?;
+        limits.push(PeriodLimitArgs {
+            target: PeriodLimitTarget::Benefactor(benefactor),
+            index: 0,
+            duration_seconds: 3600,
+            max_mint_amount: generous,
+            max_redeem_amount: generous,
+        });
+        create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+        create_associated_token_account(&test_f, &user.pubkey(), &lp_mint).await?;
+        test_f
+            .mint_tokens(
+                &get_associated_token_address_with_program_id(
+                    &user.pubkey(),
+                    &USDC_MINT,
+                    &spl_token::ID,
+                ),
+                user_balance,
+            )
+            .await;
+        users.push(user);
+        benefactors.push(benefactor);
+    }
+    set_period_limit(&test_f, limits).await?;
+    test_f
+        .mint_tokens(&find_vault_token_account(&USDC_MINT), vault_liquidity)
+        .await;
+
+    let user_keys: Vec<Pubkey> = users.iter().map(|user| user.pubkey()).collect();
+    let collateral_total = user_balance * users.len() as u64 + vault_liquidity;
+    let mint_ix = |user: &Keypair, benefactor: Pubkey, amount: u64| {
+        create_mint_instruction(amount, 0, MintInstructionAccounts {
+            user: user.pubkey(),
+            benefactor,
+            custodian,
+            custodian_token_account: None,
+            vault_mint: USDC_MINT,
+            lp_mint,
+            vault_token_program: spl_token::ID,
+            lp_token_program: spl_token::ID,
+            remaining_accounts: vec![USDC_PRICE_ACCOUNT],
+        })
+    };
+    let redeem_ix = |user: &Keypair, benefactor: Pubkey, amount: u64| {
+        create_redeem_instruction(amount, 0, RedeemInstructionAccounts {
+            user: user.pubkey(),
+            benefactor,
+            vault_mint: USDC_MINT,
+            lp_mint,
+            vault_token_program: spl_token::ID,
+            lp_token_program: spl_token::ID,
+            remaining_accounts: vec![USDC_PRICE_ACCOUNT],
+        })
+    };
+    let withdraw_ix = |amount: u64| {
+        create_withdraw_instruction(
+            WithdrawInstructionAccounts {
+                operator_authority: deployer,
+                custodian,
+                custodian_token_account: None,
+                vault_mint: USDC_MINT,
+                vault_token_program: spl_token::ID,
+                memo_program: None,
+            },
+            amount,
+            None,
+        )
+    };
+
+    // Every user mints against the same vault in one slot, more than its window allows
+    let blockhash = test_f.get_latest_blockhash().await;
+    let mut txs = vec![];
+    for (user, benefactor) in users.iter().zip(&benefactors) {
+        txs.push(
+            test_f
+                .tx()
+                .ix(mint_ix(user, *benefactor, 100 * unit))
+                .signer(user)
+                .blockhash(blockhash)
+                .build()
+                .await?,
+        );
+    }
+    let outcomes = test_f.process_concurrently(txs).await;
+    let minted = outcomes.iter().filter(|outcome| outcome.is_ok()).count();
+    assert!(minted > 0, "Mints within the vault window should land");
+    assert!(
+        minted < users.len(),
+        "Mints past the vault window should fail"
+    );
+    assert_contended_vault_consistent(
+        &test_f,
+        &lp_mint,
+        &user_keys,
+        &benefactors,
+        &custodian,
+        collateral_total,
+    )
+    .await;
+
+    // Holders redeem past the redeem window while the rest keep minting and the operator
+    // withdraws from the same vault
+    let blockhash = test_f.get_latest_blockhash().await;
+    let mut txs = vec![];
+    for ((user, benefactor), outcome) in users.iter().zip(&benefactors).zip(&outcomes) {
+        let ix = match outcome {
+            Ok(()) => redeem_ix(user, *benefactor, 50 * unit),
+            Err(_) => mint_ix(user, *benefactor, 90 * unit),
+        };
+        txs.push(
+            test_f
+                .tx()
+                .ix(ix)
+                .signer(user)
+                .blockhash(blockhash)
+                .build()
+                .await?,
+        );
+    }
+    for amount in [10 * unit, 20 * unit] {
+        txs.push(
+            test_f
+                .tx()
+                .ix(withdraw_ix(amount))
+                .signer(&test_f.deployer)
+                .blockhash(blockhash)
+                .build()
+                .await?,
+        );
+    }
+    let outcomes = test_f.process_concurrently(txs).await;
+    assert!(
+        outcomes[users.len()..]
+            .iter()
+            .all(|outcome| outcome.is_ok()),
+        "Withdrawals are not limited by the windows"
+    );
+    assert_contended_vault_consistent(
+        &test_f,
+        &lp_mint,
+        &user_keys,
+        &benefactors,
+        &custodian,
+        collateral_total,
+    )
+    .await;
+
+    Ok(())
+}
@@ -1,6 +1,14 @@
 use anchor_spl::token::TokenAccount;
-use fixtures::test::TestFixture;
-use jup_stable::state::vault::{Vault, VaultStatus};
+use fixtures::{event::logged_events, test::TestFixture};
+use jup_stable::{
+    event::{DepositAcknowledgedEvent, WithdrawEvent},
+    state::{
+        collateral_approval::CollateralApproval,
+        operator::OperatorRole,
+        vault::{Vault, VaultStatus},
+        vault_registry::VaultRegistry,
+    },
+};
 use solana_program_test::*;
 use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
 use spl_associated_token_account::get_associated_token_address_with_program_id;
@@ -8,17 +16,27 @@ use switchboard_on_demand::Pubkey;
 
 use crate::common::{
     constants::{USDC_DECIMALS, USDC_FEED_ID, USDC_MINT, USDC_ORACLE_CONFIG},
-    derivation::{find_vault, find_vault_token_account},
+    derivation::{
+        find_collateral_approval, find_operator, find_vault, find_vault_registry,
+        find_vault_token_account,
+    },
     faciliter::{
         create_associated_token_account, create_vault, create_vault_with_oracle,
         setup_full_test_context,
     },
     instructions::{
+        create_acknowledge_deposit_instruction, create_approve_collateral_instruction,
+        create_create_operator_instruction, create_create_vault_instruction,
+        create_create_vault_registry_instruction, create_disable_vault_instruction,
         create_reset_vault_period_limit_instruction, create_set_custodian_instruction,
         create_set_max_oracle_price_instruction, create_set_min_oracle_price_instruction,
-        create_set_stalesness_threshold_instruction, create_set_vault_status_instruction,
-        create_update_vault_oracle_instruction, create_update_vault_period_limit_instruction,
-        create_withdraw_instruction, WithdrawInstructionAccounts,
+        create_set_reactivation_cooldown_instruction, create_set_stalesness_threshold_instruction,
+        create_set_vault_status_instruction, create_update_pause_blocks_withdraw_instruction,
+        create_update_pause_flag_instruction, create_update_vault_oracle_instruction,
+        create_update_vault_period_limit_instruction, create_withdraw_instruction,
+        ApproveCollateralInstructionAccounts, CreateOperatorInstructionAccounts,
+        CreateVaultInstructionAccounts, CreateVaultRegistryInstructionAccounts,
+        WithdrawInstructionAccounts,
     },
 };
 
@@ -74,17 +92,160 @@ async fn create_vault_success() -> anyhow::Result<()> {
         "Vault should have the correct decimals"
     );
     assert_eq!(
-        vault_account.total_minted, [0; 16],
+        vault_account.total_minted(),
+        0,
         "Vault should have the correct total minted"
     );
     assert_eq!(
-        vault_account.total_redeemed, [0; 16],
+        vault_account.total_redeemed(),
+        0,
         "Vault should have the correct total redeemed"
     );
 
     Ok(())
 }
 
+#[tokio::test]
+async fn create_vault_requires_collateral_approval() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let vault_manager = Keypair::new();
+    test_f
+        .send_ixs(
+            &[create_create_operator_instruction(
+                CreateOperatorInstructionAccounts {
+                    operator_authority: deployer,
+                    payer: deployer,
+                    new_operator_authority: vault_manager.pubkey(),
+                },
+                OperatorRole::VaultManager,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let mint = USDC_MINT;
+    let create_vault_ix = create_create_vault_instruction(CreateVaultInstructionAccounts {
+        authority: vault_manager.pubkey(),
+        payer: deployer,
+        mint,
+        token_program: spl_token::ID,
+    });
+    let result = test_f
+        .send_ixs(&[create_vault_ix.clone()], &[
+            &test_f.deployer,
+            &vault_manager,
+        ])
+        .await;
+    assert!(
+        result.is_err(),
+        "Vault creation should fail for a collateral that was not approved"
+    );
+
+    let result = test_f
+        .send_ixs(
+            &[create_approve_collateral_instruction(
+                ApproveCollateralInstructionAccounts {
+                    authority: vault_manager.pubkey(),
+                    payer: deployer,
+                    mint,
+                },
+            )],
+            &[&test_f.deployer, &vault_manager],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Only an admin should be able to approve a collateral"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_approve_collateral_instruction(
+                ApproveCollateralInstructionAccounts {
+                    authority: deployer,
+                    payer: deployer,
+                    mint,
+                },
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let approval: CollateralApproval = test_f
+        .load_and_deserialize(&find_collateral_approval(&mint))
+        .await;
+    assert_eq!(approval.mint, mint);
+    assert_eq!(approval.approved_by, find_operator(&deployer));
+
+    test_f
+        .send_ixs(&[create_vault_ix], &[&test_f.deployer, &vault_manager])
+        .await?;
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&mint)).await;
+    assert_eq!(vault.mint, mint);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_vault_rejects_unsupported_decimals() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let mint = test_f.create_spl_mint(13).await;
+    let result = create_vault(&test_f, mint).await;
+    assert!(
+        result.is_err(),
+        "Vault creation should fail for a collateral with too many decimals"
+    );
+
+    let mint = test_f.create_spl_mint(12).await;
+    create_vault(&test_f, mint).await?;
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&mint)).await;
+    assert_eq!(vault.decimals, 12);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vault_registry_lists_created_vaults() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let registry: VaultRegistry = test_f.load_and_deserialize(&find_vault_registry()).await;
+    assert!(registry.mints().is_empty());
+
+    let mint = USDC_MINT;
+    create_vault(&test_f, mint).await?;
+
+    let registry: VaultRegistry = test_f.load_and_deserialize(&find_vault_registry()).await;
+    assert_eq!(registry.mints(), &[mint]);
+
+    // Deployments initialized before the registry existed backfill it from their vaults
+    test_f
+        .set_account(&find_vault_registry(), Default::default())
+        .await;
+    test_f
+        .send_ixs(
+            &[create_create_vault_registry_instruction(
+                CreateVaultRegistryInstructionAccounts {
+                    authority: deployer,
+                    payer: deployer,
+                },
+                &[mint],
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let registry: VaultRegistry = test_f.load_and_deserialize(&find_vault_registry()).await;
+    assert_eq!(registry.mints(), &[mint]);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn set_vault_status_success() -> anyhow::Result<()> {
     let test_f = TestFixture::new().await;
@@ -143,6 +304,47 @@ async fn set_vault_status_success() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn disabled_vault_reactivation_waits_for_cooldown() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let mint = USDC_MINT;
+    create_vault_with_oracle(&test_f, mint, deployer, USDC_ORACLE_CONFIG).await?;
+
+    let cooldown_seconds = 3600;
+    test_f
+        .send_ixs(
+            &[
+                create_set_reactivation_cooldown_instruction(deployer, cooldown_seconds),
+                create_disable_vault_instruction(deployer, mint),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&mint)).await;
+    assert_eq!(vault.status, VaultStatus::Disabled);
+    assert!(vault.disabled_at > 0);
+
+    let enable_ix = || create_set_vault_status_instruction(deployer, mint, VaultStatus::Enabled);
+    let result = test_f.send_ixs(&[enable_ix()], &[&test_f.deployer]).await;
+    assert!(
+        result.is_err(),
+        "Vault should stay disabled during the cooldown"
+    );
+
+    test_f.advance_time(cooldown_seconds as i64).await;
+    test_f.send_ixs(&[enable_ix()], &[&test_f.deployer]).await?;
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&mint)).await;
+    assert_eq!(vault.status, VaultStatus::Enabled);
+    assert_eq!(vault.disabled_at, 0);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn set_custodian_success() -> anyhow::Result<()> {
     let test_f = TestFixture::new().await;
@@ -527,10 +729,13 @@ async fn withdraw_collateral_success() -> anyhow::Result<()> {
             WithdrawInstructionAccounts {
                 operator_authority: deployer,
                 custodian: custodian.pubkey(),
+                custodian_token_account: None,
                 vault_mint: mint,
                 vault_token_program: spl_token::ID,
+                memo_program: None,
             },
             amount,
+            None,
         )],
         Some(&deployer),
         &[&test_f.deployer],
@@ -550,3 +755,210 @@ async fn withdraw_collateral_success() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn withdraw_during_pause_follows_config_policy() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let mint = USDC_MINT;
+    let custodian: Keypair = Keypair::new();
+    create_vault_with_oracle(&test_f, mint, custodian.pubkey(), USDC_ORACLE_CONFIG).await?;
+    create_associated_token_account(&test_f, &custodian.pubkey(), &mint).await?;
+
+    let amount = 1000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(&find_vault_token_account(&mint), 2 * amount)
+        .await;
+
+    let withdraw_ix = || {
+        create_withdraw_instruction(
+            WithdrawInstructionAccounts {
+                operator_authority: deployer,
+                custodian: custodian.pubkey(),
+                custodian_token_account: None,
+                vault_mint: mint,
+                vault_token_program: spl_token::ID,
+                memo_program: None,
+            },
+            amount,
+            None,
+        )
+    };
+
+    test_f
+        .send_ixs(&[create_update_pause_flag_instruction(deployer, false)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+
+    // By default operators keep withdrawing while mint and redeem are paused
+    test_f
+        .send_ixs(&[withdraw_ix()], &[&test_f.deployer])
+        .await?;
+
+    test_f
+        .send_ixs(
+            &[create_update_pause_blocks_withdraw_instruction(
+                deployer, true,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let result = test_f.send_ixs(&[withdraw_ix()], &[&test_f.deployer]).await;
+    assert!(
+        result.is_err(),
+        "Withdraw should be blocked during a pause once the policy is enabled"
+    );
+
+    test_f
+        .send_ixs(&[create_update_pause_flag_instruction(deployer, true)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+    test_f
+        .send_ixs(&[withdraw_ix()], &[&test_f.deployer])
+        .await?;
+
+    let custodian_token_account: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address_with_program_id(
+            &custodian.pubkey(),
+            &mint,
+            &spl_token::ID,
+        ))
+        .await;
+    assert_eq!(custodian_token_account.amount, 2 * amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdraw_collateral_with_reference_memo() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let mint = USDC_MINT;
+    let custodian: Keypair = Keypair::new();
+    create_vault_with_oracle(&test_f, mint, custodian.pubkey(), USDC_ORACLE_CONFIG).await?;
+    create_associated_token_account(&test_f, &custodian.pubkey(), &mint).await?;
+
+    let amount = 1000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(&find_vault_token_account(&mint), amount)
+        .await;
+
+    let reference = [7u8; 32];
+    let mut ctx = test_f.context.borrow_mut();
+    let last_blockhash = ctx.get_new_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[create_withdraw_instruction(
+            WithdrawInstructionAccounts {
+                operator_authority: deployer,
+                custodian: custodian.pubkey(),
+                custodian_token_account: None,
+                vault_mint: mint,
+                vault_token_program: spl_token::ID,
+                memo_program: Some(anchor_spl::memo::ID),
+            },
+            amount,
+            Some(reference),
+        )],
+        Some(&deployer),
+        &[&test_f.deployer],
+        last_blockhash,
+    );
+    let outcome = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    drop(ctx);
+    outcome.result?;
+    let metadata = outcome.metadata.unwrap();
+
+    let memo = "07".repeat(32);
+    assert!(
+        metadata
+            .log_messages
+            .iter()
+            .any(|log| log.contains(&format!("Memo (len 64): \"{memo}\""))),
+        "withdraw should log the reference as a memo"
+    );
+
+    let events = logged_events::<WithdrawEvent>(&metadata.log_messages);
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.operator, find_operator(&deployer));
+    assert_eq!(
+        event.destination,
+        get_associated_token_address_with_program_id(&custodian.pubkey(), &mint, &spl_token::ID)
+    );
+    assert_eq!(event.amount, amount);
+    assert_eq!(event.vault_balance, 0);
+    assert_eq!(event.reference, Some(reference));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn custodian_acknowledges_deposits() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let deployer = test_f.deployer.pubkey();
+    let _test_context = setup_full_test_context(&test_f).await?;
+
+    let mint = USDC_MINT;
+    let custodian: Keypair = Keypair::new();
+    create_vault_with_oracle(&test_f, mint, custodian.pubkey(), USDC_ORACLE_CONFIG).await?;
+
+    let amount = 1000 * 10_u64.pow(USDC_DECIMALS.into());
+    let reference = [3u8; 32];
+    let mut ctx = test_f.context.borrow_mut();
+    let last_blockhash = ctx.get_new_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_acknowledge_deposit_instruction(custodian.pubkey(), mint, reference, amount),
+            create_acknowledge_deposit_instruction(custodian.pubkey(), mint, [4u8; 32], amount),
+        ],
+        Some(&deployer),
+        &[&test_f.deployer, &custodian],
+        last_blockhash,
+    );
+    let outcome = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    drop(ctx);
+    outcome.result?;
+    let metadata = outcome.metadata.unwrap();
+
+    let events = logged_events::<DepositAcknowledgedEvent>(&metadata.log_messages);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].vault, find_vault(&mint));
+    assert_eq!(events[0].custodian, custodian.pubkey());
+    assert_eq!(events[0].reference, reference);
+    assert_eq!(events[0].amount, amount);
+
+    let vault: Vault = test_f.load_and_deserialize(&find_vault(&mint)).await;
+    assert_eq!(vault.total_acknowledged(), 2 * amount as u128);
+    assert_eq!(vault.last_acknowledged_ts, events[1].timestamp);
+
+    // Only the vault's custodian can acknowledge
+    let impostor = Keypair::new();
+    let mut ctx = test_f.context.borrow_mut();
+    let last_blockhash = ctx.get_new_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[create_acknowledge_deposit_instruction(
+            impostor.pubkey(),
+            mint,
+            reference,
+            amount,
+        )],
+        Some(&deployer),
+        &[&test_f.deployer, &impostor],
+        last_blockhash,
+    );
+    assert!(ctx.banks_client.process_transaction(tx).await.is_err());
+
+    Ok(())
+}
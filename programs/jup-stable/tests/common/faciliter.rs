@@ -1,24 +1,38 @@
-use anchor_lang::AnchorSerialize;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
 use anyhow::Result;
-use fixtures::test::TestFixture;
-use jup_stable::state::{benefactor::BenefactorStatus, vault::VaultStatus};
+use fixtures::{
+    event::{process_transaction_with_events, CpiEvents},
+    test::TestFixture,
+};
+use jup_stable::{
+    instructions::{Capacity, MintQuote, RedeemQuote},
+    state::{benefactor::BenefactorStatus, common::PeriodLimitTemplate, vault::VaultStatus},
+};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 use solana_instruction::Instruction;
 use solana_sdk::{
-    bpf_loader_upgradeable::get_program_data_address, pubkey::Pubkey, signature::Keypair,
-    signer::Signer, transaction::Transaction,
+    bpf_loader_upgradeable::get_program_data_address,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
 };
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 
 use crate::common::{
-    constants::{JUPUSD_DECIMALS, JUPUSD_NAME, JUPUSD_SYMBOL, JUPUSD_URI, USDC_MINT},
+    constants::{
+        JUPUSD_DECIMALS, JUPUSD_NAME, JUPUSD_SYMBOL, JUPUSD_URI, USDC_DECIMALS, USDC_MINT,
+    },
     derivation::find_benefactor,
     instructions::{
-        create_create_benefactor_instruction, create_create_vault_instruction,
-        create_init_instruction, create_mint_instruction, create_redeem_instruction,
+        create_approve_collateral_instruction, create_create_benefactor_instruction,
+        create_create_vault_instruction, create_get_capacity_instruction, create_init_instruction,
+        create_mint_dry_run_instruction, create_mint_instruction,
+        create_redeem_dry_run_instruction, create_redeem_instruction,
         create_set_benefactor_status_instruction, create_set_custodian_instruction,
         create_set_vault_status_instruction, create_update_benefactor_period_limit_instruction,
-        create_update_config_period_limit_instruction, create_update_pause_flag_instruction,
-        create_update_vault_oracle_instruction, create_update_vault_period_limit_instruction,
+        create_update_config_period_limit_instruction, create_update_vault_oracle_instruction,
+        create_update_vault_period_limit_instruction, ApproveCollateralInstructionAccounts,
         CreateBenefactorInstructionAccounts, CreateBenefactorInstructionArgs,
         CreateVaultInstructionAccounts, InitInstructionAccounts, InitInstructionArgs,
         MintInstructionAccounts, RedeemInstructionAccounts,
@@ -42,21 +56,18 @@ pub async fn init_program(test_f: &TestFixture, mint: &Keypair) -> Result<()> {
         name: JUPUSD_NAME.to_string(),
         symbol: JUPUSD_SYMBOL.to_string(),
         uri: JUPUSD_URI.to_string(),
+        peg_price_usd: 10_000,
+        is_mint_redeem_enabled: true,
+        default_vault_period_limit: PeriodLimitTemplate::default(),
+        default_benefactor_period_limit: PeriodLimitTemplate::default(),
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[
-            create_init_instruction(accounts, args),
-            create_update_pause_flag_instruction(test_f.deployer.pubkey(), true),
-        ],
-        Some(&payer),
-        &[&test_f.deployer, mint],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(&[create_init_instruction(accounts, args)], &[
+            &test_f.deployer,
+            mint,
+        ])
+        .await?;
 
     Ok(())
 }
@@ -75,16 +86,19 @@ pub async fn create_vault(test_f: &TestFixture, vault_mint: Pubkey) -> Result<()
         token_program: spl_token::ID,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[create_create_vault_instruction(accounts)],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(
+            &[
+                create_approve_collateral_instruction(ApproveCollateralInstructionAccounts {
+                    authority: payer,
+                    payer,
+                    mint: vault_mint,
+                }),
+                create_create_vault_instruction(accounts),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
 
     Ok(())
 }
@@ -94,6 +108,16 @@ pub async fn create_vault_with_oracle(
     vault_mint: Pubkey,
     custodian: Pubkey,
     oracle: jup_stable::instructions::OracleConfig,
+) -> Result<()> {
+    create_vault_with_oracle_and_program(test_f, vault_mint, spl_token::ID, custodian, oracle).await
+}
+
+pub async fn create_vault_with_oracle_and_program(
+    test_f: &TestFixture,
+    vault_mint: Pubkey,
+    token_program: Pubkey,
+    custodian: Pubkey,
+    oracle: jup_stable::instructions::OracleConfig,
 ) -> Result<()> {
     let payer = test_f.deployer.pubkey();
 
@@ -101,24 +125,25 @@ pub async fn create_vault_with_oracle(
         authority: payer,
         payer,
         mint: vault_mint,
-        token_program: spl_token::ID,
+        token_program,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[
-            create_create_vault_instruction(accounts),
-            create_set_custodian_instruction(payer, vault_mint, custodian),
-            create_update_vault_oracle_instruction(payer, vault_mint, 0, oracle),
-            create_set_vault_status_instruction(payer, vault_mint, VaultStatus::Enabled),
-        ],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-    ctx.banks_client.process_transaction(tx).await?;
-    drop(ctx);
+    test_f
+        .send_ixs(
+            &[
+                create_approve_collateral_instruction(ApproveCollateralInstructionAccounts {
+                    authority: payer,
+                    payer,
+                    mint: vault_mint,
+                }),
+                create_create_vault_instruction(accounts),
+                create_set_custodian_instruction(payer, vault_mint, custodian),
+                create_update_vault_oracle_instruction(payer, vault_mint, 0, oracle),
+                create_set_vault_status_instruction(payer, vault_mint, VaultStatus::Enabled),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
 
     Ok(())
 }
@@ -142,25 +167,28 @@ pub async fn create_associated_token_account(
     owner: &Pubkey,
     mint: &Pubkey,
 ) -> Result<()> {
-    let payer = test_f.deployer.pubkey();
-
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[
-            spl_associated_token_account::instruction::create_associated_token_account(
-                &test_f.deployer.pubkey(),
-                owner,
-                mint,
-                &spl_token::ID,
-            ),
-        ],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
+    create_associated_token_account_with_program(test_f, owner, mint, &spl_token::ID).await
+}
 
-    ctx.banks_client.process_transaction(tx).await?;
+pub async fn create_associated_token_account_with_program(
+    test_f: &TestFixture,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<()> {
+    test_f
+        .send_ixs(
+            &[
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &test_f.deployer.pubkey(),
+                    owner,
+                    mint,
+                    token_program,
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
 
     Ok(())
 }
@@ -177,6 +205,7 @@ pub async fn create_benefactor(
         authority: payer,
         payer,
         benefactor_authority: *benefactor_authority,
+        registry_page: 0,
     };
 
     let args = CreateBenefactorInstructionArgs {
@@ -184,16 +213,11 @@ pub async fn create_benefactor(
         redeem_fee_rate,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[create_create_benefactor_instruction(accounts, args)],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(&[create_create_benefactor_instruction(accounts, args)], &[
+            &test_f.deployer,
+        ])
+        .await?;
 
     Ok(find_benefactor(benefactor_authority))
 }
@@ -210,6 +234,7 @@ pub async fn create_active_benefactor(
         authority: payer,
         payer,
         benefactor_authority: *benefactor_authority,
+        registry_page: 0,
     };
 
     let args = CreateBenefactorInstructionArgs {
@@ -219,19 +244,19 @@ pub async fn create_active_benefactor(
 
     let benefactor = find_benefactor(benefactor_authority);
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[
-            create_create_benefactor_instruction(accounts, args),
-            create_set_benefactor_status_instruction(payer, benefactor, BenefactorStatus::Active),
-        ],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(
+            &[
+                create_create_benefactor_instruction(accounts, args),
+                create_set_benefactor_status_instruction(
+                    payer,
+                    benefactor,
+                    BenefactorStatus::Active,
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
 
     Ok(benefactor)
 }
@@ -282,15 +307,7 @@ pub async fn set_period_limit(test_f: &TestFixture, args: Vec<PeriodLimitArgs>)
         })
         .collect::<Vec<Instruction>>();
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &instructions,
-        Some(&test_f.deployer.pubkey()),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f.send_ixs(&instructions, &[&test_f.deployer]).await?;
     Ok(())
 }
 
@@ -305,43 +322,88 @@ pub struct MintRedeemParams {
     pub remaining_accounts: Vec<Pubkey>,
 }
 
-pub async fn mint_stablecoin(
-    test_f: &TestFixture,
-    params: &MintRedeemParams,
-    amount: u64,
-    min_amount_out: u64,
-) -> Result<()> {
-    let accounts = MintInstructionAccounts {
+fn mint_accounts(params: &MintRedeemParams) -> MintInstructionAccounts {
+    MintInstructionAccounts {
         user: params.user.pubkey(),
         benefactor: params.benefactor,
         custodian: params.custodian,
+        custodian_token_account: None,
         vault_mint: params.vault_mint,
         lp_mint: params.lp_mint,
         vault_token_program: params.vault_token_program.unwrap_or(spl_token::ID),
         lp_token_program: params.lp_token_program.unwrap_or(spl_token::ID),
         remaining_accounts: params.remaining_accounts.clone(),
-    };
+    }
+}
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[create_mint_instruction(amount, min_amount_out, accounts)],
-        Some(&params.user.pubkey()),
-        &[&params.user],
-        last_blockhash,
-    );
+async fn mint_transaction(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<Transaction> {
+    test_f
+        .tx()
+        .ix(create_mint_instruction(
+            amount,
+            min_amount_out,
+            mint_accounts(params),
+        ))
+        .signer(&params.user)
+        .build()
+        .await
+}
 
-    ctx.banks_client.process_transaction(tx).await?;
+pub async fn mint_stablecoin(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    let tx = mint_transaction(test_f, params, amount, min_amount_out).await?;
+    test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction(tx)
+        .await?;
     Ok(())
 }
 
-pub async fn redeem_stablecoin(
+/// Processes the mint and returns its outcome together with the program logs, which are kept
+/// even when the transaction fails.
+pub async fn mint_stablecoin_with_logs(
     test_f: &TestFixture,
     params: &MintRedeemParams,
     amount: u64,
     min_amount_out: u64,
-) -> Result<()> {
-    let accounts = RedeemInstructionAccounts {
+) -> Result<(std::result::Result<(), TransactionError>, Vec<String>)> {
+    let tx = mint_transaction(test_f, params, amount, min_amount_out).await?;
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    let logs = outcome
+        .metadata
+        .map(|metadata| metadata.log_messages)
+        .unwrap_or_default();
+    Ok((outcome.result, logs))
+}
+
+pub async fn mint_stablecoin_with_events(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<CpiEvents> {
+    let tx = mint_transaction(test_f, params, amount, min_amount_out).await?;
+    process_transaction_with_events(test_f, tx).await
+}
+
+fn redeem_accounts(params: &MintRedeemParams) -> RedeemInstructionAccounts {
+    RedeemInstructionAccounts {
         user: params.user.pubkey(),
         benefactor: params.benefactor,
         vault_mint: params.vault_mint,
@@ -349,19 +411,149 @@ pub async fn redeem_stablecoin(
         vault_token_program: params.vault_token_program.unwrap_or(spl_token::ID),
         lp_token_program: params.lp_token_program.unwrap_or(spl_token::ID),
         remaining_accounts: params.remaining_accounts.clone(),
-    };
+    }
+}
+
+async fn redeem_transaction(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<Transaction> {
+    test_f
+        .tx()
+        .ix(create_redeem_instruction(
+            amount,
+            min_amount_out,
+            redeem_accounts(params),
+        ))
+        .signer(&params.user)
+        .build()
+        .await
+}
+
+pub async fn redeem_stablecoin(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    let tx = redeem_transaction(test_f, params, amount, min_amount_out).await?;
+    test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction(tx)
+        .await?;
+    Ok(())
+}
+
+pub async fn redeem_stablecoin_with_events(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<CpiEvents> {
+    let tx = redeem_transaction(test_f, params, amount, min_amount_out).await?;
+    process_transaction_with_events(test_f, tx).await
+}
+
+/// Processes a dry-run mint, which has to go through without simulation, and decodes the quote
+/// it returns.
+pub async fn dry_run_mint(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<MintQuote> {
+    let tx = test_f
+        .tx()
+        .ix(create_mint_dry_run_instruction(
+            amount,
+            min_amount_out,
+            mint_accounts(params),
+        ))
+        .signer(&params.user)
+        .build()
+        .await?;
+    process_returning(test_f, tx).await
+}
+
+/// Processes a dry-run redeem and decodes the quote it returns.
+pub async fn dry_run_redeem(
+    test_f: &TestFixture,
+    params: &MintRedeemParams,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<RedeemQuote> {
+    let tx = test_f
+        .tx()
+        .ix(create_redeem_dry_run_instruction(
+            amount,
+            min_amount_out,
+            redeem_accounts(params),
+        ))
+        .signer(&params.user)
+        .build()
+        .await?;
+    process_returning(test_f, tx).await
+}
+
+async fn process_returning<T: AnchorDeserialize>(
+    test_f: &TestFixture,
+    tx: Transaction,
+) -> Result<T> {
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    outcome.result?;
+    let return_data = outcome
+        .metadata
+        .and_then(|metadata| metadata.return_data)
+        .ok_or_else(|| anyhow::anyhow!("Missing return data"))?;
+
+    Ok(T::try_from_slice(&return_data.data)?)
+}
+
+/// Simulates `get_capacity` for `benefactor` against the vault of `vault_mint`.
+pub async fn get_capacity(
+    test_f: &TestFixture,
+    vault_mint: Pubkey,
+    benefactor: Pubkey,
+) -> Result<Capacity> {
+    simulate_view(
+        test_f,
+        create_get_capacity_instruction(vault_mint, benefactor),
+    )
+    .await
+}
+
+/// Simulates the view instruction `ix` and decodes its return data.
+async fn simulate_view<T: AnchorDeserialize>(test_f: &TestFixture, ix: Instruction) -> Result<T> {
+    let payer = test_f.deployer.pubkey();
 
     let mut ctx = test_f.context.borrow_mut();
     let last_blockhash = ctx.get_new_latest_blockhash().await?;
     let tx = Transaction::new_signed_with_payer(
-        &[create_redeem_instruction(amount, min_amount_out, accounts)],
-        Some(&params.user.pubkey()),
-        &[&params.user],
+        &[ix],
+        Some(&payer),
+        &[&test_f.deployer],
         last_blockhash,
     );
 
-    ctx.banks_client.process_transaction(tx).await?;
-    Ok(())
+    let simulation = ctx.banks_client.simulate_transaction(tx).await?;
+    simulation
+        .result
+        .ok_or_else(|| anyhow::anyhow!("Missing simulation result"))??;
+    let return_data = simulation
+        .simulation_details
+        .and_then(|details| details.return_data)
+        .ok_or_else(|| anyhow::anyhow!("Missing return data"))?;
+
+    Ok(T::try_from_slice(&return_data.data)?)
 }
 
 pub async fn setup_full_test_context(test_f: &TestFixture) -> Result<TestContext> {
@@ -374,3 +566,86 @@ pub async fn setup_full_test_context(test_f: &TestFixture) -> Result<TestContext
         lp_mint: lp_mint.pubkey(),
     })
 }
+
+/// Sets up an enabled USDC vault priced by `oracle`, an active benefactor with generous period
+/// limits, and a funded user, returning the params for minting against `oracle_account`.
+pub async fn setup_mint_with_oracle(
+    test_f: &TestFixture,
+    test_context: &TestContext,
+    oracle: jup_stable::instructions::OracleConfig,
+    oracle_account: Pubkey,
+) -> Result<MintRedeemParams> {
+    setup_mint_with_collateral(
+        test_f,
+        test_context,
+        USDC_MINT,
+        spl_token::ID,
+        oracle,
+        oracle_account,
+    )
+    .await
+}
+
+/// Same as [`setup_mint_with_oracle`] for a `mint` owned by `token_program`, which has to have
+/// the USDC decimals.
+pub async fn setup_mint_with_collateral(
+    test_f: &TestFixture,
+    test_context: &TestContext,
+    mint: Pubkey,
+    token_program: Pubkey,
+    oracle: jup_stable::instructions::OracleConfig,
+    oracle_account: Pubkey,
+) -> Result<MintRedeemParams> {
+    let custodian = Keypair::new().pubkey();
+    create_vault_with_oracle_and_program(test_f, mint, token_program, custodian, oracle).await?;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let benefactor = create_active_benefactor(test_f, &user.pubkey(), 0u16, 0u16).await?;
+
+    let max_amount = 1_000_000 * 10_u64.pow(JUPUSD_DECIMALS.into());
+    set_period_limit(
+        test_f,
+        [
+            PeriodLimitTarget::Benefactor(benefactor),
+            PeriodLimitTarget::Vault(mint),
+            PeriodLimitTarget::Config,
+        ]
+        .into_iter()
+        .map(|target| PeriodLimitArgs {
+            target,
+            index: 0,
+            duration_seconds: 3600u64,
+            max_mint_amount: max_amount,
+            max_redeem_amount: max_amount,
+        })
+        .collect(),
+    )
+    .await?;
+
+    create_associated_token_account_with_program(test_f, &user.pubkey(), &mint, &token_program)
+        .await?;
+    create_associated_token_account(test_f, &user.pubkey(), &test_context.lp_mint).await?;
+    create_associated_token_account_with_program(test_f, &custodian, &mint, &token_program).await?;
+
+    let user_collateral_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &mint, &token_program);
+    test_f
+        .mint_tokens(
+            &user_collateral_ata,
+            1_000 * 10_u64.pow(USDC_DECIMALS.into()),
+        )
+        .await;
+
+    Ok(MintRedeemParams {
+        user,
+        benefactor,
+        custodian,
+        vault_mint: mint,
+        lp_mint: test_context.lp_mint,
+        vault_token_program: Some(token_program),
+        lp_token_program: None,
+        remaining_accounts: vec![oracle_account],
+    })
+}
@@ -0,0 +1,157 @@
+#![allow(unexpected_cfgs)]
+
+//! Onboards holders of non-collateral tokens: swaps the input token into a jup-stable vault's
+//! collateral through Jupiter, then mints jupUSD with exactly the amount the swap produced.
+//!
+//! The router never signs for anything: the Jupiter route runs with the user's signature and the
+//! mint is a plain CPI into jup-stable, which validates every account itself.
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use jup_stable::program::JupStable;
+
+declare_id!("DcxQt7R6w2j8r4x4KKTqMPMFvf8ihqzudMjNRZxwSKcE");
+
+pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+#[program]
+pub mod jupusd_router {
+    use super::*;
+
+    /// The first `route_accounts_len` remaining accounts are the Jupiter route accounts, in the
+    /// order `swap_data` expects them; the rest are the oracle accounts of the jup-stable vault.
+    pub fn swap_and_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapAndMint<'info>>,
+        route_accounts_len: u8,
+        swap_data: Vec<u8>,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        let route_accounts_len = route_accounts_len as usize;
+        require!(
+            ctx.remaining_accounts.len() >= route_accounts_len,
+            RouterError::InvalidRouteAccounts
+        );
+        let (route_accounts, oracle_accounts) = ctx.remaining_accounts.split_at(route_accounts_len);
+
+        let balance_before = ctx.accounts.user_collateral_token_account.amount;
+
+        let swap_ix = Instruction {
+            program_id: JUPITER_PROGRAM_ID,
+            accounts: route_accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: swap_data,
+        };
+        let mut swap_account_infos = route_accounts.to_vec();
+        swap_account_infos.push(ctx.accounts.jupiter_program.to_account_info());
+        invoke(&swap_ix, &swap_account_infos)?;
+
+        ctx.accounts.user_collateral_token_account.reload()?;
+        let swapped_amount = ctx
+            .accounts
+            .user_collateral_token_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(RouterError::CollateralBalanceDecreased)?;
+        require!(swapped_amount > 0, RouterError::NothingSwapped);
+
+        jup_stable::cpi::mint(
+            ctx.accounts
+                .mint_ctx()
+                .with_remaining_accounts(oracle_accounts.to_vec()),
+            swapped_amount,
+            min_amount_out,
+            false,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct SwapAndMint<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = vault_mint,
+        token::authority = user,
+    )]
+    pub user_collateral_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub vault_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: the route program is pinned to Jupiter
+    #[account(address = JUPITER_PROGRAM_ID @ RouterError::InvalidSwapProgram)]
+    pub jupiter_program: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub user_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub custodian: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub custodian_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub lp_token_program: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub vault_token_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+}
+
+impl<'info> SwapAndMint<'info> {
+    fn mint_ctx(&self) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Mint<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Mint {
+            user: self.user.to_account_info(),
+            user_collateral_token_account: self.user_collateral_token_account.to_account_info(),
+            user_lp_token_account: self.user_lp_token_account.to_account_info(),
+            config: self.config.to_account_info(),
+            authority: self.authority.to_account_info(),
+            lp_mint: self.lp_mint.to_account_info(),
+            vault: self.vault.to_account_info(),
+            vault_mint: self.vault_mint.to_account_info(),
+            custodian: self.custodian.to_account_info(),
+            custodian_token_account: self.custodian_token_account.to_account_info(),
+            benefactor: self.benefactor.to_account_info(),
+            lp_token_program: self.lp_token_program.to_account_info(),
+            vault_token_program: self.vault_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        CpiContext::new(self.jup_stable_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[error_code]
+pub enum RouterError {
+    #[msg("Invalid Swap Program")]
+    InvalidSwapProgram,
+    #[msg("Invalid Route Accounts")]
+    InvalidRouteAccounts,
+    #[msg("Collateral Balance Decreased")]
+    CollateralBalanceDecreased,
+    #[msg("Nothing Swapped")]
+    NothingSwapped,
+}
@@ -0,0 +1,71 @@
+use fixtures::test::TestFixture;
+use solana_program_test::*;
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl_associated_token_account::instruction::create_associated_token_account;
+
+use crate::common::instructions::{create_swap_and_mint_instruction, SwapAndMintAccounts};
+
+async fn setup() -> anyhow::Result<(TestFixture, Keypair, SwapAndMintAccounts)> {
+    let test_f = TestFixture::new_with_programs(&[("jupusd_router", jupusd_router::ID)]).await;
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let vault_mint = test_f.create_spl_mint(6).await;
+    let lp_mint = test_f.create_spl_mint(6).await;
+    test_f
+        .send_ixs(
+            &[create_associated_token_account(
+                &user.pubkey(),
+                &user.pubkey(),
+                &vault_mint,
+                &spl_token::ID,
+            )],
+            &[&user],
+        )
+        .await?;
+
+    let accounts =
+        SwapAndMintAccounts::new(user.pubkey(), vault_mint, lp_mint, Pubkey::new_unique());
+    Ok((test_f, user, accounts))
+}
+
+#[tokio::test]
+async fn swap_and_mint_rejects_non_jupiter_swap_program() -> anyhow::Result<()> {
+    let (test_f, user, mut accounts) = setup().await?;
+    accounts.jupiter_program = spl_token::ID;
+
+    let ix = create_swap_and_mint_instruction(&accounts, vec![], 0, vec![], 0);
+    let result = test_f.send_ixs(&[ix], &[&user]).await;
+    assert!(
+        result.is_err(),
+        "Routing through a program other than Jupiter should fail"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_and_mint_rejects_missing_route_accounts() -> anyhow::Result<()> {
+    let (test_f, user, accounts) = setup().await?;
+
+    // Claims three route accounts while only one route account and the oracle follow
+    let route_accounts = vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)];
+    let result = test_f
+        .send_ixs(
+            &[create_swap_and_mint_instruction(
+                &accounts,
+                route_accounts,
+                3,
+                vec![],
+                0,
+            )],
+            &[&user],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Route accounts beyond the remaining accounts should be rejected"
+    );
+
+    Ok(())
+}
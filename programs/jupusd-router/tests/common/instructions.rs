@@ -0,0 +1,80 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use jupusd_client::jup_stable::derivation as stable_pda;
+use jupusd_router::JUPITER_PROGRAM_ID;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+pub struct SwapAndMintAccounts {
+    pub user: Pubkey,
+    pub benefactor: Pubkey,
+    pub custodian: Pubkey,
+    pub vault_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub oracle: Pubkey,
+    pub jupiter_program: Pubkey,
+}
+
+impl SwapAndMintAccounts {
+    pub fn new(user: Pubkey, vault_mint: Pubkey, lp_mint: Pubkey, oracle: Pubkey) -> Self {
+        SwapAndMintAccounts {
+            user,
+            benefactor: stable_pda::find_benefactor(&user),
+            custodian: Pubkey::new_unique(),
+            vault_mint,
+            lp_mint,
+            oracle,
+            jupiter_program: JUPITER_PROGRAM_ID,
+        }
+    }
+}
+
+pub fn create_swap_and_mint_instruction(
+    accounts: &SwapAndMintAccounts,
+    route_accounts: Vec<AccountMeta>,
+    route_accounts_len: u8,
+    swap_data: Vec<u8>,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut metas = jupusd_router::accounts::SwapAndMint {
+        user: accounts.user,
+        user_collateral_token_account: get_associated_token_address(
+            &accounts.user,
+            &accounts.vault_mint,
+        ),
+        vault_mint: accounts.vault_mint,
+        jupiter_program: accounts.jupiter_program,
+        user_lp_token_account: get_associated_token_address(&accounts.user, &accounts.lp_mint),
+        config: stable_pda::find_config(),
+        authority: stable_pda::find_authority(),
+        lp_mint: accounts.lp_mint,
+        vault: stable_pda::find_vault(&accounts.vault_mint),
+        custodian: accounts.custodian,
+        custodian_token_account: get_associated_token_address(
+            &accounts.custodian,
+            &accounts.vault_mint,
+        ),
+        benefactor: accounts.benefactor,
+        lp_token_program: spl_token::ID,
+        vault_token_program: spl_token::ID,
+        system_program: system_program::ID,
+        jup_stable_event_authority: stable_pda::find_event_authority(),
+        jup_stable_program: jup_stable::ID,
+    }
+    .to_account_metas(None);
+    metas.extend(route_accounts);
+    metas.push(AccountMeta::new_readonly(accounts.oracle, false));
+
+    Instruction {
+        program_id: jupusd_router::ID,
+        accounts: metas,
+        data: jupusd_router::instruction::SwapAndMint {
+            route_accounts_len,
+            swap_data,
+            min_amount_out,
+        }
+        .data(),
+    }
+}
@@ -0,0 +1,2 @@
+mod case;
+mod common;
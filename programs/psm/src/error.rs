@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 #[error_code]
 pub enum PSmError {
-    #[msg("")]
+    #[msg("Unspecified Error")]
     SomeError,
     #[msg("Admin Array Full")]
     AdminArrayFull,
@@ -38,4 +38,46 @@ pub enum PSmError {
     MathOverflow,
     #[msg("No Admin Left")]
     NoAdminLeft,
+    #[msg("Settlement Retired")]
+    SettlementRetired,
+    #[msg("Settlement Not Retired")]
+    SettlementNotRetired,
+    #[msg("Invalid Reserve")]
+    InvalidReserve,
+    #[msg("Pool Has Reserve")]
+    PoolHasReserve,
+    #[msg("Pool Not Empty")]
+    PoolNotEmpty,
+    #[msg("Below Min Redeem Amount")]
+    BelowMinRedeemAmount,
+    #[msg("Invalid Custodian")]
+    InvalidCustodian,
+    #[msg("Pauser Array Full")]
+    PauserArrayFull,
+    #[msg("Withdrawal Timelocked")]
+    WithdrawalTimelocked,
+    #[msg("Withdrawal Not Ready")]
+    WithdrawalNotReady,
+    #[msg("Inexact Amount")]
+    InexactAmount,
+    #[msg("Slippage Tolerance Exceeded")]
+    SlippageToleranceExceeded,
+    #[msg("Allowed Mint List Full")]
+    AllowedMintListFull,
+    #[msg("Mint Not Allowed")]
+    MintNotAllowed,
+    #[msg("Invalid Period Limit")]
+    InvalidPeriodLimit,
+    #[msg("Supply Limit Exceeded")]
+    SupplyLimitExceeded,
+    #[msg("Withdrawal Destination List Full")]
+    WithdrawalDestinationListFull,
+    #[msg("Withdrawal Destination Not Approved")]
+    WithdrawalDestinationNotApproved,
+    #[msg("Unsupported Version")]
+    UnsupportedVersion,
+    #[msg("Unsupported Mint Extension")]
+    UnsupportedMintExtension,
+    #[msg("Fee Exempt List Full")]
+    FeeExemptListFull,
 }
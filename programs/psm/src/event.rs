@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Emitted by `supply`, `supply_from_treasury`, `mint_and_supply` and `replenish` for the
+/// redemption tokens they add to `pool`. `admin` is the signer, the jup-stable operator for
+/// `supply_from_treasury`. `window_supplied_amount` is what the pool's supply window counted so
+/// far, `amount` included.
+#[event]
+pub struct SupplyEvent {
+    pub pool: Pubkey,
+    pub label: [u8; 32],
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub window_supplied_amount: u64,
+    pub total_supplied: u128,
+}
+
+/// Emitted by `cancel_withdrawal` for the pending withdrawal of `pool` it drops. `signer` is the
+/// pauser or admin that cancelled it.
+#[event]
+pub struct WithdrawalCancelledEvent {
+    pub pool: Pubkey,
+    pub signer: Pubkey,
+    pub proposer: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
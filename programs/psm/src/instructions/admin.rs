@@ -18,11 +18,38 @@ pub enum ConfigManagementAction {
     AddAdmin { admin: Pubkey },
     RemoveAdmin { admin: Pubkey },
     UpdatePauseFlag { is_paused: bool },
+    AddPauser { pauser: Pubkey },
+    RemovePauser { pauser: Pubkey },
+    SetWithdrawalDelay { withdrawal_delay: u64 },
+    SetGuardian { guardian: Pubkey },
+    SetPoolCreationFee { pool_creation_fee: u64 },
+    SetPermissionlessPoolCreation { enabled: bool },
 }
 
 pub fn manage_config(ctx: Context<ManageConfig>, action: ConfigManagementAction) -> Result<()> {
     let mut config = ctx.accounts.config.load_mut()?;
 
+    apply_config_action(&mut config, action)
+}
+
+/// Applies every action in order within a single instruction, so a multisig proposal either lands
+/// as a whole or not at all.
+pub fn manage_config_batch(
+    ctx: Context<ManageConfig>,
+    actions: Vec<ConfigManagementAction>,
+) -> Result<()> {
+    require!(!actions.is_empty(), PSmError::BadInput);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+
+    for action in actions {
+        apply_config_action(&mut config, action)?;
+    }
+
+    Ok(())
+}
+
+fn apply_config_action(config: &mut Config, action: ConfigManagementAction) -> Result<()> {
     match action {
         ConfigManagementAction::AddAdmin { admin } => {
             require!(admin != Pubkey::default(), PSmError::SomeError);
@@ -36,7 +63,52 @@ pub fn manage_config(ctx: Context<ManageConfig>, action: ConfigManagementAction)
         ConfigManagementAction::UpdatePauseFlag { is_paused } => {
             config.update_pause_flag(is_paused)?;
         },
+        ConfigManagementAction::AddPauser { pauser } => {
+            require!(pauser != Pubkey::default(), PSmError::SomeError);
+            require!(!config.is_pauser(&pauser), PSmError::DuplicateRessources);
+            config.add_pauser(&pauser)?;
+        },
+        ConfigManagementAction::RemovePauser { pauser } => {
+            config.remove_pauser(&pauser)?;
+        },
+        ConfigManagementAction::SetWithdrawalDelay { withdrawal_delay } => {
+            config.set_withdrawal_delay(withdrawal_delay, Clock::get()?.unix_timestamp)?;
+        },
+        ConfigManagementAction::SetGuardian { guardian } => {
+            config.set_guardian(guardian);
+        },
+        ConfigManagementAction::SetPoolCreationFee { pool_creation_fee } => {
+            // The treasury holds no data, its first deposit has to make it rent exempt
+            require!(
+                pool_creation_fee == 0 || pool_creation_fee >= Rent::get()?.minimum_balance(0),
+                PSmError::BadInput
+            );
+            config.set_pool_creation_fee(pool_creation_fee);
+        },
+        ConfigManagementAction::SetPermissionlessPoolCreation { enabled } => {
+            config.set_permissionless_pool_creation(enabled);
+        },
     }
 
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct GuardianPause<'info> {
+    pub guardian: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.load()?.is_guardian(guardian.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+}
+
+/// Kill-switch for the guardian key: pauses the whole protocol across all pools. Only admins can
+/// unpause through `manage_config`.
+pub fn guardian_pause(ctx: Context<GuardianPause>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+
+    config.update_pause_flag(true)?;
+
+    Ok(())
+}
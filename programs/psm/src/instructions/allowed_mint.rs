@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::PSmError,
+    state::{
+        allowed_mint::{AllowedMintList, ALLOWED_MINT_LIST_PREFIX},
+        config::Config,
+    },
+};
+
+#[derive(Accounts)]
+pub struct CreateAllowedMintList<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AllowedMintList::MAX_SIZE,
+        seeds = [ALLOWED_MINT_LIST_PREFIX],
+        bump
+    )]
+    pub allowed_mint_list: AccountLoader<'info, AllowedMintList>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_allowed_mint_list(ctx: Context<CreateAllowedMintList>) -> Result<()> {
+    let mut allowed_mint_list = ctx.accounts.allowed_mint_list.load_init()?;
+
+    allowed_mint_list.bump = ctx.bumps.allowed_mint_list;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ManageAllowedMintList<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [ALLOWED_MINT_LIST_PREFIX],
+        bump = allowed_mint_list.load()?.bump,
+    )]
+    pub allowed_mint_list: AccountLoader<'info, AllowedMintList>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum AllowedMintListAction {
+    AddMint { mint: Pubkey },
+    RemoveMint { mint: Pubkey },
+}
+
+/// Removing a mint only stops new pools from being created with it, existing pools are untouched.
+pub fn manage_allowed_mint_list(
+    ctx: Context<ManageAllowedMintList>,
+    action: AllowedMintListAction,
+) -> Result<()> {
+    let mut allowed_mint_list = ctx.accounts.allowed_mint_list.load_mut()?;
+
+    match action {
+        AllowedMintListAction::AddMint { mint } => {
+            require!(mint != Pubkey::default(), PSmError::SomeError);
+            require!(
+                !allowed_mint_list.is_allowed(&mint),
+                PSmError::DuplicateRessources
+            );
+            allowed_mint_list.add(&mint)?;
+        },
+        AllowedMintListAction::RemoveMint { mint } => {
+            allowed_mint_list.remove(&mint)?;
+        },
+    }
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::PSmError,
+    state::{
+        config::Config,
+        fee_exempt::{FeeExemptList, FEE_EXEMPT_LIST_PREFIX},
+        pool::Pool,
+    },
+};
+
+#[derive(Accounts)]
+pub struct CreateFeeExemptList<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + FeeExemptList::MAX_SIZE,
+        seeds = [FEE_EXEMPT_LIST_PREFIX, pool.key().as_ref()],
+        bump
+    )]
+    pub fee_exempt_list: AccountLoader<'info, FeeExemptList>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_fee_exempt_list(ctx: Context<CreateFeeExemptList>) -> Result<()> {
+    let mut fee_exempt_list = ctx.accounts.fee_exempt_list.load_init()?;
+
+    fee_exempt_list.pool = ctx.accounts.pool.key();
+    fee_exempt_list.bump = ctx.bumps.fee_exempt_list;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ManageFeeExemptList<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [FEE_EXEMPT_LIST_PREFIX, pool.key().as_ref()],
+        bump = fee_exempt_list.load()?.bump,
+        has_one = pool,
+    )]
+    pub fee_exempt_list: AccountLoader<'info, FeeExemptList>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum FeeExemptListAction {
+    AddAddress { address: Pubkey },
+    RemoveAddress { address: Pubkey },
+}
+
+/// Addresses on a pool's exempt list, e.g. market makers and protocol-owned accounts, redeem
+/// without the pool's utilization fee. The haircut still applies to them.
+pub fn manage_fee_exempt_list(
+    ctx: Context<ManageFeeExemptList>,
+    action: FeeExemptListAction,
+) -> Result<()> {
+    let mut fee_exempt_list = ctx.accounts.fee_exempt_list.load_mut()?;
+
+    match action {
+        FeeExemptListAction::AddAddress { address } => {
+            require!(address != Pubkey::default(), PSmError::SomeError);
+            require!(
+                !fee_exempt_list.is_exempt(&address),
+                PSmError::DuplicateRessources
+            );
+            fee_exempt_list.add(&address)?;
+        },
+        FeeExemptListAction::RemoveAddress { address } => {
+            fee_exempt_list.remove(&address)?;
+        },
+    }
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::{
     program::Psm,
-    state::config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
+    state::config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX, CONFIG_VERSION},
 };
 
 #[derive(Accounts)]
@@ -43,6 +43,7 @@ pub fn init(ctx: Context<Init>) -> Result<()> {
     config.authority = ctx.accounts.authority.key();
     config.config_bump = ctx.bumps.config;
     config.authority_bump = ctx.bumps.authority;
+    config.version = CONFIG_VERSION;
 
     Ok(())
 }
@@ -0,0 +1,300 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use super::{check_mint_extensions, check_pool_creator};
+use crate::{
+    authority_seeds,
+    error::PSmError,
+    state::{
+        allowed_mint::{AllowedMintList, ALLOWED_MINT_LIST_PREFIX},
+        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX, TREASURY_PREFIX},
+        pool::{
+            Pool, PoolStatus, POOL_PREFIX, POOL_REDEMPTION_TOKEN_ACCOUNT_PREFIX,
+            POOL_SETTLEMENT_TOKEN_ACCOUNT_PREFIX, POOL_VERSION,
+        },
+    },
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: lamport-only account, checked with seeds constraint
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub redemption_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub new_settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = redemption_mint,
+        has_one = settlement_mint,
+        has_one = redemption_token_account,
+        has_one = settlement_token_account,
+        has_one = redemption_token_program,
+        has_one = settlement_token_program,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(mut)]
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::MAX_SIZE,
+        seeds = [POOL_PREFIX, redemption_mint.key().as_ref(), new_settlement_mint.key().as_ref()],
+        bump
+    )]
+    pub new_pool: AccountLoader<'info, Pool>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POOL_REDEMPTION_TOKEN_ACCOUNT_PREFIX, new_pool.key().as_ref()],
+        token::authority = authority,
+        token::mint = redemption_mint,
+        token::token_program = redemption_token_program,
+        bump
+    )]
+    pub new_redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POOL_SETTLEMENT_TOKEN_ACCOUNT_PREFIX, new_pool.key().as_ref()],
+        token::authority = authority,
+        token::mint = new_settlement_mint,
+        token::token_program = new_settlement_token_program,
+        bump
+    )]
+    pub new_settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub redemption_token_program: Interface<'info, TokenInterface>,
+    pub settlement_token_program: Interface<'info, TokenInterface>,
+    pub new_settlement_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// Same role as in `create_pool`
+    #[account(
+        seeds = [ALLOWED_MINT_LIST_PREFIX],
+        bump = allowed_mint_list.load()?.bump,
+    )]
+    pub allowed_mint_list: Option<AccountLoader<'info, AllowedMintList>>,
+}
+
+/// Disables the pool and moves its redemption liquidity into a successor pool for
+/// `new_settlement_mint`, created Disabled with the old pool's settings so it still has to go
+/// through `activate_pool`. The settlement balance has to be withdrawn through the usual
+/// withdrawal path first.
+pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    let redemption_amount = ctx.accounts.redemption_token_account.amount;
+    require!(
+        ctx.accounts.settlement_token_account.amount == 0,
+        PSmError::PoolNotEmpty
+    );
+    check_mint_extensions(&ctx.accounts.new_settlement_mint)?;
+
+    let authority_bump = {
+        let config = ctx.accounts.config.load()?;
+        require!(!config.is_paused(), PSmError::ProtocolPaused);
+        check_pool_creator(
+            &config,
+            ctx.accounts.admin.key,
+            ctx.accounts.allowed_mint_list.as_ref(),
+            &ctx.accounts.redemption_mint.key(),
+            &ctx.accounts.new_settlement_mint.key(),
+        )?;
+
+        config.authority_bump
+    };
+    let now = Clock::get()?.unix_timestamp;
+
+    {
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        require!(!pool.has_reserve(), PSmError::PoolHasReserve);
+
+        let mut new_pool = ctx.accounts.new_pool.load_init()?;
+
+        new_pool.redemption_mint = ctx.accounts.redemption_mint.key();
+        new_pool.settlement_mint = ctx.accounts.new_settlement_mint.key();
+        new_pool.redemption_token_account = ctx.accounts.new_redemption_token_account.key();
+        new_pool.settlement_token_account = ctx.accounts.new_settlement_token_account.key();
+        new_pool.redemption_token_program = ctx.accounts.redemption_token_program.key();
+        new_pool.settlement_token_program = ctx.accounts.new_settlement_token_program.key();
+        new_pool.status = PoolStatus::Disabled;
+        new_pool.redemption_token_decimals = ctx.accounts.redemption_mint.decimals;
+        new_pool.settlement_token_decimals = ctx.accounts.new_settlement_mint.decimals;
+        new_pool.bump = ctx.bumps.new_pool;
+        new_pool.version = POOL_VERSION;
+
+        require!(
+            new_pool
+                .redemption_token_decimals
+                .abs_diff(new_pool.settlement_token_decimals)
+                <= 19,
+            PSmError::MathOverflow
+        );
+
+        // The minimum is counted in settlement units, which may change scale with the mint
+        new_pool.min_redeem_amount = rescale_amount(
+            pool.min_redeem_amount,
+            pool.settlement_token_decimals,
+            new_pool.settlement_token_decimals,
+        )?;
+        new_pool.retire_settlement = pool.retire_settlement;
+        new_pool.rounding_mode = pool.rounding_mode;
+        new_pool.haircut_bps = pool.haircut_bps;
+        new_pool.custodian = pool.custodian;
+        new_pool.pending_custodian = pool.pending_custodian;
+        new_pool.pending_custodian_at = pool.pending_custodian_at;
+        new_pool.admin = pool.admin;
+        new_pool.label = pool.label;
+        new_pool.set_supply_period_limit(
+            pool.supply_period_limit.duration_seconds,
+            pool.supply_period_limit.max_mint_amount,
+            now,
+        )?;
+
+        new_pool.record_supply(redemption_amount);
+
+        pool.set_status(PoolStatus::Disabled);
+    }
+
+    {
+        let mut config = ctx.accounts.config.load_mut()?;
+        let fee = config.pool_creation_fee;
+        if fee > 0 {
+            transfer(ctx.accounts.pay_creation_fee(), fee)?;
+            config.record_pool_creation_fee(fee);
+        }
+    }
+
+    if redemption_amount > 0 {
+        transfer_checked(
+            ctx.accounts
+                .migrate_redemption_tokens()
+                .with_signer(&[authority_seeds!(authority_bump)]),
+            redemption_amount,
+            ctx.accounts.redemption_mint.decimals,
+        )?;
+    }
+
+    emit_cpi!(PoolMigratedEvent {
+        pool: ctx.accounts.pool.key(),
+        new_pool: ctx.accounts.new_pool.key(),
+        settlement_mint: ctx.accounts.settlement_mint.key(),
+        new_settlement_mint: ctx.accounts.new_settlement_mint.key(),
+        redemption_amount,
+    });
+
+    Ok(())
+}
+
+fn rescale_amount(amount: u64, from_decimals: u8, to_decimals: u8) -> Result<u64> {
+    let amount = if to_decimals >= from_decimals {
+        10_u64
+            .checked_pow((to_decimals - from_decimals).into())
+            .and_then(|scale| amount.checked_mul(scale))
+    } else {
+        10_u64
+            .checked_pow((from_decimals - to_decimals).into())
+            .map(|scale| amount / scale)
+    };
+
+    Ok(amount.ok_or(PSmError::MathOverflow)?)
+}
+
+impl<'info> MigratePool<'info> {
+    fn migrate_redemption_tokens(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.redemption_token_account.to_account_info(),
+            mint: self.redemption_mint.to_account_info(),
+            to: self.new_redemption_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.redemption_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    fn pay_creation_fee(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.payer.to_account_info(),
+            to: self.treasury.to_account_info(),
+        };
+        let cpi_program = self.system_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+#[event]
+pub struct PoolMigratedEvent {
+    pub pool: Pubkey,
+    pub new_pool: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub new_settlement_mint: Pubkey,
+    pub redemption_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Grown to the current size before anything reads it, so the admin check is in the handler
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX],
+        bump,
+        realloc = 8 + Config::MAX_SIZE,
+        realloc::payer = payer,
+        realloc::zero = true,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(mut)]
+    pub pool: Option<AccountLoader<'info, Pool>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Brings the config, and `pool` when passed, up to the current layout versions. A config created
+/// before `reserved` is reallocated to the current size, `payer` covering the extra rent; until
+/// then no other instruction can load it, so this has to run right after the upgrade.
+pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+    {
+        let mut config = ctx.accounts.config.load_mut()?;
+        require!(
+            config.is_admin(ctx.accounts.admin.key),
+            PSmError::NotAuthorized
+        );
+        config.migrate()?;
+    }
+
+    if let Some(pool) = &ctx.accounts.pool {
+        pool.load_mut()?.migrate()?;
+    }
+
+    Ok(())
+}
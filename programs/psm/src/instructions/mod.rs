@@ -1,9 +1,23 @@
 pub use admin::*;
+pub use allowed_mint::*;
+pub use fee_exempt::*;
 pub use init::*;
+pub use migrate::*;
 pub use pool::*;
+pub use reserve::*;
+pub use treasury::*;
 pub use user::*;
+pub use withdrawal::*;
+pub use withdrawal_destination::*;
 
 mod admin;
+mod allowed_mint;
+mod fee_exempt;
 mod init;
+mod migrate;
 mod pool;
+mod reserve;
+mod treasury;
 mod user;
+mod withdrawal;
+mod withdrawal_destination;
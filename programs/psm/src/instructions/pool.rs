@@ -1,22 +1,37 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
 };
+use jup_stable::{
+    program::JupStable,
+    state::operator::{Operator, OperatorRole},
+};
+use spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+};
 
+use super::check_withdrawal_destination;
 use crate::{
     authority_seeds,
     error::PSmError,
+    event::SupplyEvent,
     state::{
-        config::{Config, AUTHORITY_PREFIX},
+        allowed_mint::{AllowedMintList, ALLOWED_MINT_LIST_PREFIX},
+        config::{Config, AUTHORITY_PREFIX, TREASURY_PREFIX},
         pool::{
-            Pool, PoolStatus, POOL_PREFIX, POOL_REDEMPTION_TOKEN_ACCOUNT_PREFIX,
-            POOL_SETTLEMENT_TOKEN_ACCOUNT_PREFIX,
+            Pool, PoolStatus, RoundingMode, POOL_PREFIX, POOL_REDEMPTION_TOKEN_ACCOUNT_PREFIX,
+            POOL_SETTLEMENT_TOKEN_ACCOUNT_PREFIX, POOL_VERSION,
         },
+        withdrawal_destination::{WithdrawalDestinationList, WITHDRAWAL_DESTINATION_LIST_PREFIX},
     },
 };
 
 #[derive(Accounts)]
 pub struct CreatePool<'info> {
+    /// An admin, or anyone while permissionless pool creation is enabled
     pub admin: Signer<'info>,
     #[account(mut)]
     pub payer: Signer<'info>,
@@ -25,12 +40,19 @@ pub struct CreatePool<'info> {
     pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
-        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+        mut,
         constraint = config.load()?.authority == authority.key() @ PSmError::InvalidAuthority,
     )]
     pub config: AccountLoader<'info, Config>,
     /// CHECK: checked with constraint
     pub authority: UncheckedAccount<'info>,
+    /// CHECK: lamport-only account, checked with seeds constraint
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
 
     #[account(
         init,
@@ -66,9 +88,21 @@ pub struct CreatePool<'info> {
     pub redemption_token_program: Interface<'info, TokenInterface>,
     pub settlement_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    /// Required when a non-admin creates the pool
+    #[account(
+        seeds = [ALLOWED_MINT_LIST_PREFIX],
+        bump = allowed_mint_list.load()?.bump,
+    )]
+    pub allowed_mint_list: Option<AccountLoader<'info, AllowedMintList>>,
 }
 
+/// Charges the config's pool creation fee, if any, from the payer into the treasury.
 pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
+    ctx.accounts.check_creator()?;
+    check_mint_extensions(&ctx.accounts.redemption_mint)?;
+    check_mint_extensions(&ctx.accounts.settlement_mint)?;
+
     let mut pool = ctx.accounts.pool.load_init()?;
 
     pool.redemption_mint = ctx.accounts.redemption_mint.key();
@@ -81,6 +115,7 @@ pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
     pool.redemption_token_decimals = ctx.accounts.redemption_mint.decimals;
     pool.settlement_token_decimals = ctx.accounts.settlement_mint.decimals;
     pool.bump = ctx.bumps.pool;
+    pool.version = POOL_VERSION;
 
     require!(
         pool.redemption_token_decimals
@@ -89,6 +124,80 @@ pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
         PSmError::MathOverflow
     );
 
+    let mut config = ctx.accounts.config.load_mut()?;
+    let fee = config.pool_creation_fee;
+    if fee > 0 {
+        transfer(ctx.accounts.pay_creation_fee(), fee)?;
+        config.record_pool_creation_fee(fee);
+    }
+
+    Ok(())
+}
+
+impl<'info> CreatePool<'info> {
+    fn check_creator(&self) -> Result<()> {
+        check_pool_creator(
+            &self.config.load()?,
+            self.admin.key,
+            self.allowed_mint_list.as_ref(),
+            &self.redemption_mint.key(),
+            &self.settlement_mint.key(),
+        )
+    }
+
+    fn pay_creation_fee(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.payer.to_account_info(),
+            to: self.treasury.to_account_info(),
+        };
+        let cpi_program = self.system_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+/// Admins create any pool. Anyone else needs permissionless pool creation enabled and both
+/// mints on the allowed mint list.
+pub(crate) fn check_pool_creator(
+    config: &Config,
+    creator: &Pubkey,
+    allowed_mint_list: Option<&AccountLoader<AllowedMintList>>,
+    redemption_mint: &Pubkey,
+    settlement_mint: &Pubkey,
+) -> Result<()> {
+    if config.is_admin(creator) {
+        return Ok(());
+    }
+    require!(
+        config.is_permissionless_pool_creation(),
+        PSmError::NotAuthorized
+    );
+
+    let allowed_mint_list = allowed_mint_list.ok_or(PSmError::MintNotAllowed)?.load()?;
+    require!(
+        allowed_mint_list.is_allowed(redemption_mint)
+            && allowed_mint_list.is_allowed(settlement_mint),
+        PSmError::MintNotAllowed
+    );
+
+    Ok(())
+}
+
+/// Pool accounting assumes a transfer moves its full amount, which a Token-2022 transfer fee
+/// breaks. Mints carrying the extension are refused even at a zero rate, since the fee authority
+/// can raise it later.
+pub(crate) fn check_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    require!(
+        mint.get_extension::<TransferFeeConfig>().is_err(),
+        PSmError::UnsupportedMintExtension
+    );
+
     Ok(())
 }
 
@@ -98,7 +207,7 @@ pub struct ManagePool<'info> {
     pub admin: Signer<'info>,
 
     #[account(
-        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+        constraint = config.load()?.is_admin(admin.key) || config.load()?.is_pauser(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
     )]
     pub config: AccountLoader<'info, Config>,
 
@@ -108,21 +217,242 @@ pub struct ManagePool<'info> {
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub enum PoolManagementAction {
-    SetStatus { status: PoolStatus },
+    SetStatus {
+        status: PoolStatus,
+    },
+    SetRetireSettlement {
+        retire_settlement: bool,
+    },
+    SetMinRedeemAmount {
+        min_redeem_amount: u64,
+    },
+    SetCustodian {
+        custodian: Pubkey,
+    },
+    SetRoundingMode {
+        rounding_mode: RoundingMode,
+    },
+    SetAdmin {
+        admin: Pubkey,
+    },
+    SetHaircut {
+        haircut_bps: u16,
+    },
+    SetSupplyPeriodLimit {
+        duration_seconds: u64,
+        max_supply_amount: u64,
+    },
+    SetLabel {
+        label: [u8; 32],
+    },
+    /// Redemption fee scaling with pool utilization, a zero `max_fee_bps` turning it off
+    SetUtilizationFee {
+        min_fee_bps: u16,
+        max_fee_bps: u16,
+    },
+}
+
+impl PoolManagementAction {
+    /// Pausing a pool is the only action available to pausers; everything else, including
+    /// re-enabling a pool, stays with admins.
+    pub fn is_pauser_action(&self) -> bool {
+        matches!(self, PoolManagementAction::SetStatus {
+            status: PoolStatus::Paused
+        })
+    }
+
+    /// Pool admins only get the actions that can't move or withhold funds. The custodian, the
+    /// haircut, retiring settlement, the supply limit and the pool admin itself stay with global
+    /// admins.
+    pub fn is_pool_admin_action(&self) -> bool {
+        matches!(
+            self,
+            PoolManagementAction::SetLabel { .. }
+                | PoolManagementAction::SetStatus { .. }
+                | PoolManagementAction::SetRoundingMode { .. }
+                | PoolManagementAction::SetMinRedeemAmount { .. }
+        )
+    }
+}
+
+fn can_apply(config: &Config, pool: &Pool, signer: &Pubkey, action: &PoolManagementAction) -> bool {
+    config.is_admin(signer)
+        || (pool.is_admin(signer) && action.is_pool_admin_action())
+        || action.is_pauser_action()
 }
 
 pub fn manage_pool(ctx: Context<ManagePool>, action: PoolManagementAction) -> Result<()> {
+    let config = ctx.accounts.config.load()?;
     let mut pool = ctx.accounts.pool.load_mut()?;
+    require!(
+        can_apply(&config, &pool, ctx.accounts.admin.key, &action),
+        PSmError::NotAuthorized
+    );
+
+    apply_pool_action(&config, &mut pool, action)
+}
+
+/// Applies every action in order within a single instruction. Pausers can only batch pause
+/// actions.
+pub fn manage_pool_batch(
+    ctx: Context<ManagePool>,
+    actions: Vec<PoolManagementAction>,
+) -> Result<()> {
+    require!(!actions.is_empty(), PSmError::BadInput);
 
+    let config = ctx.accounts.config.load()?;
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    require!(
+        actions
+            .iter()
+            .all(|action| can_apply(&config, &pool, ctx.accounts.admin.key, action)),
+        PSmError::NotAuthorized
+    );
+
+    for action in actions {
+        apply_pool_action(&config, &mut pool, action)?;
+    }
+
+    Ok(())
+}
+
+fn apply_pool_action(config: &Config, pool: &mut Pool, action: PoolManagementAction) -> Result<()> {
     match action {
         PoolManagementAction::SetStatus { status } => {
+            if status == PoolStatus::Active {
+                msg!("pools are activated through activate_pool");
+                return err!(PSmError::BadInput);
+            }
+
             pool.set_status(status);
         },
+        PoolManagementAction::SetRetireSettlement { retire_settlement } => {
+            pool.set_retire_settlement(retire_settlement);
+        },
+        PoolManagementAction::SetMinRedeemAmount { min_redeem_amount } => {
+            pool.set_min_redeem_amount(min_redeem_amount);
+        },
+        PoolManagementAction::SetCustodian { custodian } => {
+            require!(custodian != Pubkey::default(), PSmError::InvalidCustodian);
+
+            // The custodian receives permissionless sweeps, so changing it waits out the
+            // withdrawal delay like any other withdrawal, and never less than a day
+            let now = Clock::get()?.unix_timestamp;
+            pool.set_custodian(custodian, config.custodian_executable_at(now)?, now);
+        },
+        PoolManagementAction::SetRoundingMode { rounding_mode } => {
+            pool.set_rounding_mode(rounding_mode);
+        },
+        PoolManagementAction::SetAdmin { admin } => {
+            pool.set_admin(admin);
+        },
+        PoolManagementAction::SetHaircut { haircut_bps } => {
+            require!(haircut_bps < 10_000, PSmError::BadInput);
+
+            pool.set_haircut_bps(haircut_bps);
+        },
+        PoolManagementAction::SetSupplyPeriodLimit {
+            duration_seconds,
+            max_supply_amount,
+        } => {
+            pool.set_supply_period_limit(
+                duration_seconds,
+                max_supply_amount,
+                Clock::get()?.unix_timestamp,
+            )?;
+        },
+        PoolManagementAction::SetLabel { label } => {
+            pool.set_label(label)?;
+        },
+        PoolManagementAction::SetUtilizationFee {
+            min_fee_bps,
+            max_fee_bps,
+        } => {
+            pool.set_utilization_fee(min_fee_bps, max_fee_bps)?;
+        },
     }
 
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct ActivatePool<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with has_one
+    pub authority: UncheckedAccount<'info>,
+
+    pub redemption_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_PREFIX, redemption_mint.key().as_ref(), settlement_mint.key().as_ref()],
+        bump = pool.load()?.bump,
+        has_one = redemption_mint,
+        has_one = settlement_mint,
+        has_one = redemption_token_account @ PSmError::InvalidRedemptionTokenAccount,
+        has_one = settlement_token_account @ PSmError::InvalidSettlementTokenAccount,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Sets a disabled or paused pool Active once its token accounts, decimals and supply limit check
+/// out. This is the only way to activate a pool, `SetStatus` can only pause or disable it.
+pub fn activate_pool(ctx: Context<ActivatePool>) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+
+    let redemption_token_account = &ctx.accounts.redemption_token_account;
+    require!(
+        redemption_token_account.mint == pool.redemption_mint
+            && redemption_token_account.owner == authority,
+        PSmError::InvalidRedemptionTokenAccount
+    );
+    let settlement_token_account = &ctx.accounts.settlement_token_account;
+    require!(
+        settlement_token_account.mint == pool.settlement_mint
+            && settlement_token_account.owner == authority,
+        PSmError::InvalidSettlementTokenAccount
+    );
+    require!(
+        *redemption_token_account.to_account_info().owner == pool.redemption_token_program
+            && *settlement_token_account.to_account_info().owner == pool.settlement_token_program,
+        PSmError::InvalidTokenProgram
+    );
+
+    require!(
+        ctx.accounts.redemption_mint.decimals == pool.redemption_token_decimals,
+        PSmError::InvalidRedemptionMint
+    );
+    require!(
+        ctx.accounts.settlement_mint.decimals == pool.settlement_token_decimals,
+        PSmError::InvalidSettlementMint
+    );
+    require!(
+        pool.redemption_token_decimals
+            .abs_diff(pool.settlement_token_decimals)
+            <= 19,
+        PSmError::MathOverflow
+    );
+
+    require!(
+        pool.supply_period_limit.duration_seconds != 0,
+        PSmError::InvalidPeriodLimit
+    );
+
+    pool.set_status(PoolStatus::Active);
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct Supply<'info> {
     #[account(mut)]
@@ -135,7 +465,7 @@ pub struct Supply<'info> {
     pub admin_redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
     )]
     pub config: AccountLoader<'info, Config>,
     #[account(mut)]
@@ -163,6 +493,16 @@ pub fn supply(ctx: Context<Supply>, amount: u64) -> Result<()> {
     require!(!config.is_paused(), PSmError::ProtocolPaused);
     pool.can_supply()?;
     pool.record_supply(amount);
+    pool.record_supply_in_window(amount, Clock::get()?.unix_timestamp)?;
+
+    emit!(SupplyEvent {
+        pool: ctx.accounts.pool.key(),
+        label: pool.label,
+        admin: ctx.accounts.admin.key(),
+        amount,
+        window_supplied_amount: pool.supply_period_limit.minted_amount,
+        total_supplied: pool.total_supplied(),
+    });
 
     transfer_checked(
         ctx.accounts.deposit_redemption_tokens(),
@@ -186,6 +526,87 @@ impl<'info> Supply<'info> {
     }
 }
 
+#[derive(Accounts)]
+pub struct SupplyFromTreasury<'info> {
+    pub operator_authority: Signer<'info>,
+    #[account(
+        has_one = operator_authority @ PSmError::NotAuthorized,
+    )]
+    pub operator: AccountLoader<'info, Operator>,
+    #[account(
+        mut,
+        token::mint = redemption_mint,
+        token::authority = operator_authority,
+        token::token_program = redemption_token_program,
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub config: AccountLoader<'info, Config>,
+    pub redemption_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = redemption_mint,
+        has_one = redemption_token_account,
+        has_one = redemption_token_program,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(mut)]
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub redemption_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Moves protocol-held jupUSD, e.g. collected fees, from a jup-stable collateral manager's
+/// treasury token account into the pool's redemption liquidity. It is the treasury jup-stable
+/// `burn_treasury` burns from, so the operator can put fees to work defending the peg instead of
+/// burning them. Counted as supplied like `supply`.
+pub fn supply_from_treasury(ctx: Context<SupplyFromTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, PSmError::ZeroAmount);
+
+    ctx.accounts
+        .operator
+        .load()?
+        .is(OperatorRole::CollateralManager)?;
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let config = ctx.accounts.config.load()?;
+
+    require!(!config.is_paused(), PSmError::ProtocolPaused);
+    pool.can_supply()?;
+    pool.record_supply(amount);
+    pool.record_supply_in_window(amount, Clock::get()?.unix_timestamp)?;
+
+    emit!(SupplyEvent {
+        pool: ctx.accounts.pool.key(),
+        label: pool.label,
+        admin: ctx.accounts.operator_authority.key(),
+        amount,
+        window_supplied_amount: pool.supply_period_limit.minted_amount,
+        total_supplied: pool.total_supplied(),
+    });
+
+    transfer_checked(
+        ctx.accounts.deposit_treasury_tokens(),
+        amount,
+        ctx.accounts.redemption_mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> SupplyFromTreasury<'info> {
+    fn deposit_treasury_tokens(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.treasury_token_account.to_account_info(),
+            mint: self.redemption_mint.to_account_info(),
+            to: self.redemption_token_account.to_account_info(),
+            authority: self.operator_authority.to_account_info(),
+        };
+        let cpi_program = self.redemption_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     #[account(mut)]
@@ -198,7 +619,7 @@ pub struct Withdraw<'info> {
     pub admin_settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
-        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
     )]
     pub config: AccountLoader<'info, Config>,
     /// CHECK: checked with constraint
@@ -217,6 +638,13 @@ pub struct Withdraw<'info> {
     pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     pub settlement_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    /// Required once the config restricts withdrawal destinations
+    #[account(
+        seeds = [WITHDRAWAL_DESTINATION_LIST_PREFIX],
+        bump = withdrawal_destination_list.load()?.bump,
+    )]
+    pub withdrawal_destination_list: Option<AccountLoader<'info, WithdrawalDestinationList>>,
 }
 
 pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
@@ -230,6 +658,15 @@ pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     let config = ctx.accounts.config.load()?;
 
     require!(!config.is_paused(), PSmError::ProtocolPaused);
+    require!(
+        !config.is_withdrawal_timelocked(Clock::get()?.unix_timestamp),
+        PSmError::WithdrawalTimelocked
+    );
+    check_withdrawal_destination(
+        &config,
+        ctx.accounts.withdrawal_destination_list.as_ref(),
+        ctx.accounts.admin.key,
+    )?;
     pool.can_withdraw()?;
     pool.record_withdraw(amount);
 
@@ -256,3 +693,536 @@ impl<'info> Withdraw<'info> {
         CpiContext::new(cpi_program, cpi_accounts)
     }
 }
+
+#[derive(Accounts)]
+pub struct SweepSettlement<'info> {
+    /// CHECK: checked against the pool custodian in the handler
+    pub custodian: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = settlement_mint,
+        associated_token::authority = custodian,
+        associated_token::token_program = settlement_token_program,
+    )]
+    pub custodian_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(has_one = authority)]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    pub authority: UncheckedAccount<'info>,
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = settlement_mint,
+        has_one = settlement_token_account,
+        has_one = settlement_token_program,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(mut)]
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub settlement_token_program: Interface<'info, TokenInterface>,
+
+    /// Required once the config restricts withdrawal destinations
+    #[account(
+        seeds = [WITHDRAWAL_DESTINATION_LIST_PREFIX],
+        bump = withdrawal_destination_list.load()?.bump,
+    )]
+    pub withdrawal_destination_list: Option<AccountLoader<'info, WithdrawalDestinationList>>,
+}
+
+/// Permissionless crank forwarding the pool's whole settlement balance to its custodian, so the
+/// hot pool only holds what was settled since the last sweep. A new custodian only receives sweeps
+/// once its change has waited out `MIN_CUSTODIAN_DELAY_SECONDS`.
+pub fn sweep_settlement(ctx: Context<SweepSettlement>) -> Result<()> {
+    let amount = ctx.accounts.settlement_token_account.amount;
+    require!(amount > 0, PSmError::ZeroAmount);
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let config = ctx.accounts.config.load()?;
+
+    require!(!config.is_paused(), PSmError::ProtocolPaused);
+    pool.apply_pending_custodian(Clock::get()?.unix_timestamp);
+    require!(
+        pool.has_custodian() && pool.custodian == ctx.accounts.custodian.key(),
+        PSmError::InvalidCustodian
+    );
+    check_withdrawal_destination(
+        &config,
+        ctx.accounts.withdrawal_destination_list.as_ref(),
+        ctx.accounts.custodian.key,
+    )?;
+    pool.can_withdraw()?;
+    pool.record_withdraw(amount);
+
+    transfer_checked(
+        ctx.accounts
+            .sweep_settlement_tokens()
+            .with_signer(&[authority_seeds!(config.authority_bump)]),
+        amount,
+        ctx.accounts.settlement_mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> SweepSettlement<'info> {
+    fn sweep_settlement_tokens(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.settlement_token_account.to_account_info(),
+            mint: self.settlement_mint.to_account_info(),
+            to: self.custodian_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.settlement_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct RetireSettlement<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub redemption_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = redemption_mint,
+        has_one = settlement_mint,
+        has_one = redemption_token_account,
+        has_one = settlement_token_account,
+        has_one = redemption_token_program,
+        has_one = settlement_token_program,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(mut)]
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_vault_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+
+    pub redemption_token_program: Interface<'info, TokenInterface>,
+    pub settlement_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Burns settled jupUSD through jup-stable `redeem`, with the PSM authority acting as the
+/// benefactor. The released collateral is credited to the pool's redemption side, so the jupUSD
+/// supply and jup-stable's vault totals stay in sync with what the PSM holds.
+/// Oracle accounts for the jup-stable vault are passed as remaining_accounts.
+pub fn retire_settlement<'info>(
+    ctx: Context<'_, '_, '_, 'info, RetireSettlement<'info>>,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require!(amount > 0, PSmError::ZeroAmount);
+    require!(
+        ctx.accounts.settlement_token_account.amount >= amount,
+        PSmError::InsufficientPoolBalance
+    );
+
+    let authority_bump = {
+        let pool = ctx.accounts.pool.load()?;
+        let config = ctx.accounts.config.load()?;
+
+        require!(!config.is_paused(), PSmError::ProtocolPaused);
+        pool.can_retire()?;
+
+        config.authority_bump
+    };
+
+    let amount_before = ctx.accounts.redemption_token_account.amount;
+    jup_stable::cpi::redeem(
+        ctx.accounts
+            .redeem_settlement_tokens()
+            .with_signer(&[authority_seeds!(authority_bump)])
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        amount,
+        min_amount_out,
+        false,
+    )?;
+    ctx.accounts.redemption_token_account.reload()?;
+    let amount_after = ctx.accounts.redemption_token_account.amount;
+    let min_amount_after = amount_before
+        .checked_add(min_amount_out)
+        .ok_or(PSmError::MathOverflow)?;
+    require!(
+        amount_after >= min_amount_after,
+        PSmError::InsufficientAmount
+    );
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.record_retire(amount);
+
+    Ok(())
+}
+
+impl<'info> RetireSettlement<'info> {
+    fn redeem_settlement_tokens(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Redeem<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Redeem {
+            user: self.authority.to_account_info(),
+            user_lp_token_account: self.settlement_token_account.to_account_info(),
+            user_collateral_token_account: self.redemption_token_account.to_account_info(),
+            config: self.jup_stable_config.to_account_info(),
+            authority: self.jup_stable_authority.to_account_info(),
+            lp_mint: self.settlement_mint.to_account_info(),
+            vault: self.jup_stable_vault.to_account_info(),
+            vault_token_account: self.jup_stable_vault_token_account.to_account_info(),
+            vault_mint: self.redemption_mint.to_account_info(),
+            benefactor: self.jup_stable_benefactor.to_account_info(),
+            lp_token_program: self.settlement_token_program.to_account_info(),
+            vault_token_program: self.redemption_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        let cpi_program = self.jup_stable_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Replenish<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub redemption_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = redemption_mint,
+        has_one = settlement_mint,
+        has_one = redemption_token_account,
+        has_one = settlement_token_account,
+        has_one = redemption_token_program,
+        has_one = settlement_token_program,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(mut)]
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_custodian: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_custodian_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+
+    pub redemption_token_program: Interface<'info, TokenInterface>,
+    pub settlement_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mints jupUSD through jup-stable `mint` using the pool's settlement tokens as collateral, with
+/// the PSM authority acting as the benefactor, and keeps the minted jupUSD on the redemption side.
+/// Oracle accounts for the jup-stable vault are passed as remaining_accounts.
+pub fn replenish<'info>(
+    ctx: Context<'_, '_, '_, 'info, Replenish<'info>>,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require!(amount > 0, PSmError::ZeroAmount);
+    require!(
+        ctx.accounts.settlement_token_account.amount >= amount,
+        PSmError::InsufficientPoolBalance
+    );
+
+    let authority_bump = {
+        let pool = ctx.accounts.pool.load()?;
+        let config = ctx.accounts.config.load()?;
+
+        require!(!config.is_paused(), PSmError::ProtocolPaused);
+        pool.can_supply()?;
+
+        config.authority_bump
+    };
+
+    let amount_before = ctx.accounts.redemption_token_account.amount;
+    jup_stable::cpi::mint(
+        ctx.accounts
+            .mint_redemption_tokens()
+            .with_signer(&[authority_seeds!(authority_bump)])
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        amount,
+        min_amount_out,
+        false,
+    )?;
+    ctx.accounts.redemption_token_account.reload()?;
+    let amount_after = ctx.accounts.redemption_token_account.amount;
+    let minted_amount = amount_after
+        .checked_sub(amount_before)
+        .ok_or(PSmError::MathOverflow)?;
+    require!(
+        minted_amount >= min_amount_out,
+        PSmError::InsufficientAmount
+    );
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.record_withdraw(amount);
+    pool.record_supply(minted_amount);
+    pool.record_supply_in_window(minted_amount, Clock::get()?.unix_timestamp)?;
+
+    emit!(SupplyEvent {
+        pool: ctx.accounts.pool.key(),
+        label: pool.label,
+        admin: ctx.accounts.admin.key(),
+        amount: minted_amount,
+        window_supplied_amount: pool.supply_period_limit.minted_amount,
+        total_supplied: pool.total_supplied(),
+    });
+
+    Ok(())
+}
+
+impl<'info> Replenish<'info> {
+    fn mint_redemption_tokens(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Mint<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Mint {
+            user: self.authority.to_account_info(),
+            user_collateral_token_account: self.settlement_token_account.to_account_info(),
+            user_lp_token_account: self.redemption_token_account.to_account_info(),
+            config: self.jup_stable_config.to_account_info(),
+            authority: self.jup_stable_authority.to_account_info(),
+            lp_mint: self.redemption_mint.to_account_info(),
+            vault: self.jup_stable_vault.to_account_info(),
+            vault_mint: self.settlement_mint.to_account_info(),
+            custodian: self.jup_stable_custodian.to_account_info(),
+            custodian_token_account: self.jup_stable_custodian_token_account.to_account_info(),
+            benefactor: self.jup_stable_benefactor.to_account_info(),
+            lp_token_program: self.redemption_token_program.to_account_info(),
+            vault_token_program: self.settlement_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        let cpi_program = self.jup_stable_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct MintAndSupply<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = settlement_mint,
+        token::authority = admin,
+    )]
+    pub admin_settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub redemption_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = redemption_mint,
+        has_one = settlement_mint,
+        has_one = redemption_token_account,
+        has_one = settlement_token_account,
+        has_one = redemption_token_program,
+        has_one = settlement_token_program,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(mut)]
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_config: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_authority: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_vault: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_custodian: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_custodian_token_account: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    #[account(mut)]
+    pub jup_stable_benefactor: UncheckedAccount<'info>,
+    /// CHECK: checked by jup-stable
+    pub jup_stable_event_authority: UncheckedAccount<'info>,
+    pub jup_stable_program: Program<'info, JupStable>,
+
+    pub redemption_token_program: Interface<'info, TokenInterface>,
+    pub settlement_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Takes `amount` collateral from the admin and mints jupUSD with it through jup-stable `mint`,
+/// with the PSM authority acting as the benefactor. The jupUSD lands directly in the pool's
+/// redemption token account and is recorded as supplied, so it never passes through the admin.
+/// Oracle accounts for the jup-stable vault are passed as remaining_accounts.
+pub fn mint_and_supply<'info>(
+    ctx: Context<'_, '_, '_, 'info, MintAndSupply<'info>>,
+    amount: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require!(amount > 0, PSmError::ZeroAmount);
+
+    let authority_bump = {
+        let pool = ctx.accounts.pool.load()?;
+        let config = ctx.accounts.config.load()?;
+
+        require!(!config.is_paused(), PSmError::ProtocolPaused);
+        pool.can_supply()?;
+
+        config.authority_bump
+    };
+
+    // Only mint with what actually arrived, in case the collateral mint charges transfer fees
+    let settlement_before = ctx.accounts.settlement_token_account.amount;
+    transfer_checked(
+        ctx.accounts.deposit_collateral(),
+        amount,
+        ctx.accounts.settlement_mint.decimals,
+    )?;
+    ctx.accounts.settlement_token_account.reload()?;
+    let collateral_amount = ctx
+        .accounts
+        .settlement_token_account
+        .amount
+        .checked_sub(settlement_before)
+        .ok_or(PSmError::MathOverflow)?;
+
+    let amount_before = ctx.accounts.redemption_token_account.amount;
+    jup_stable::cpi::mint(
+        ctx.accounts
+            .mint_redemption_tokens()
+            .with_signer(&[authority_seeds!(authority_bump)])
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        collateral_amount,
+        min_amount_out,
+        false,
+    )?;
+    ctx.accounts.redemption_token_account.reload()?;
+    let amount_after = ctx.accounts.redemption_token_account.amount;
+    let minted_amount = amount_after
+        .checked_sub(amount_before)
+        .ok_or(PSmError::MathOverflow)?;
+    require!(
+        minted_amount >= min_amount_out,
+        PSmError::InsufficientAmount
+    );
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    pool.record_supply(minted_amount);
+    pool.record_supply_in_window(minted_amount, Clock::get()?.unix_timestamp)?;
+
+    emit!(SupplyEvent {
+        pool: ctx.accounts.pool.key(),
+        label: pool.label,
+        admin: ctx.accounts.admin.key(),
+        amount: minted_amount,
+        window_supplied_amount: pool.supply_period_limit.minted_amount,
+        total_supplied: pool.total_supplied(),
+    });
+
+    Ok(())
+}
+
+impl<'info> MintAndSupply<'info> {
+    fn deposit_collateral(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.admin_settlement_token_account.to_account_info(),
+            mint: self.settlement_mint.to_account_info(),
+            to: self.settlement_token_account.to_account_info(),
+            authority: self.admin.to_account_info(),
+        };
+        let cpi_program = self.settlement_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    fn mint_redemption_tokens(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, jup_stable::cpi::accounts::Mint<'info>> {
+        let cpi_accounts = jup_stable::cpi::accounts::Mint {
+            user: self.authority.to_account_info(),
+            user_collateral_token_account: self.settlement_token_account.to_account_info(),
+            user_lp_token_account: self.redemption_token_account.to_account_info(),
+            config: self.jup_stable_config.to_account_info(),
+            authority: self.jup_stable_authority.to_account_info(),
+            lp_mint: self.redemption_mint.to_account_info(),
+            vault: self.jup_stable_vault.to_account_info(),
+            vault_mint: self.settlement_mint.to_account_info(),
+            custodian: self.jup_stable_custodian.to_account_info(),
+            custodian_token_account: self.jup_stable_custodian_token_account.to_account_info(),
+            benefactor: self.jup_stable_benefactor.to_account_info(),
+            lp_token_program: self.redemption_token_program.to_account_info(),
+            vault_token_program: self.settlement_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.jup_stable_event_authority.to_account_info(),
+            program: self.jup_stable_program.to_account_info(),
+        };
+        let cpi_program = self.jup_stable_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
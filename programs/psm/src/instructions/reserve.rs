@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::PSmError,
+    state::{
+        config::{Config, CONFIG_PREFIX},
+        pool::{Pool, POOL_REDEMPTION_TOKEN_ACCOUNT_PREFIX},
+        reserve::{Reserve, RESERVE_PREFIX, RESERVE_REDEMPTION_TOKEN_ACCOUNT_PREFIX},
+    },
+};
+
+#[derive(Accounts)]
+pub struct CreateReserve<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub redemption_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Reserve::MAX_SIZE,
+        seeds = [RESERVE_PREFIX, redemption_mint.key().as_ref()],
+        bump
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [RESERVE_REDEMPTION_TOKEN_ACCOUNT_PREFIX, reserve.key().as_ref()],
+        token::authority = authority,
+        token::mint = redemption_mint,
+        token::token_program = redemption_token_program,
+        bump
+    )]
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub redemption_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_reserve(ctx: Context<CreateReserve>) -> Result<()> {
+    let mut reserve = ctx.accounts.reserve.load_init()?;
+
+    reserve.redemption_mint = ctx.accounts.redemption_mint.key();
+    reserve.redemption_token_account = ctx.accounts.redemption_token_account.key();
+    reserve.redemption_token_program = ctx.accounts.redemption_token_program.key();
+    reserve.redemption_token_decimals = ctx.accounts.redemption_mint.decimals;
+    reserve.bump = ctx.bumps.reserve;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AttachReserve<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX],
+        bump = config.load()?.config_bump,
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with has_one
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = redemption_token_account,
+        constraint = pool.load()?.redemption_mint == reserve.load()?.redemption_mint @ PSmError::InvalidReserve,
+        constraint = pool.load()?.redemption_token_program == reserve.load()?.redemption_token_program @ PSmError::InvalidReserve,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [RESERVE_PREFIX, reserve.load()?.redemption_mint.as_ref()],
+        bump = reserve.load()?.bump,
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+}
+
+/// Points the pool's redemption leg at the shared reserve token account, so every pool attached
+/// to the reserve supplies and redeems against the same liquidity.
+pub fn attach_reserve(ctx: Context<AttachReserve>) -> Result<()> {
+    require!(
+        ctx.accounts.redemption_token_account.amount == 0,
+        PSmError::PoolNotEmpty
+    );
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut reserve = ctx.accounts.reserve.load_mut()?;
+
+    require!(!pool.has_reserve(), PSmError::PoolHasReserve);
+
+    pool.reserve = ctx.accounts.reserve.key();
+    pool.redemption_token_account = reserve.redemption_token_account;
+    reserve.num_pools = reserve
+        .num_pools
+        .checked_add(1)
+        .ok_or(PSmError::MathOverflow)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DetachReserve<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX],
+        bump = config.load()?.config_bump,
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with has_one
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(
+        seeds = [POOL_REDEMPTION_TOKEN_ACCOUNT_PREFIX, pool.key().as_ref()],
+        bump
+    )]
+    pub pool_redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [RESERVE_PREFIX, reserve.load()?.redemption_mint.as_ref()],
+        bump = reserve.load()?.bump,
+        constraint = pool.load()?.reserve == reserve.key() @ PSmError::InvalidReserve,
+    )]
+    pub reserve: AccountLoader<'info, Reserve>,
+}
+
+/// Restores the pool's own redemption token account, leaving the reserve liquidity untouched.
+pub fn detach_reserve(ctx: Context<DetachReserve>) -> Result<()> {
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let mut reserve = ctx.accounts.reserve.load_mut()?;
+
+    pool.reserve = Pubkey::default();
+    pool.redemption_token_account = ctx.accounts.pool_redemption_token_account.key();
+    reserve.num_pools = reserve
+        .num_pools
+        .checked_sub(1)
+        .ok_or(PSmError::MathOverflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    error::PSmError,
+    state::config::{Config, TREASURY_PREFIX},
+};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: lamport-only account, checked with seeds constraint
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX],
+        bump
+    )]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: only receives lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves collected pool creation fees out of the treasury. The treasury has to end up either empty
+/// or still rent exempt.
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, PSmError::ZeroAmount);
+
+    let remaining = ctx
+        .accounts
+        .treasury
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(PSmError::InsufficientAmount)?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        PSmError::InsufficientAmount
+    );
+
+    transfer(
+        ctx.accounts
+            .withdraw_lamports()
+            .with_signer(&[&[TREASURY_PREFIX, &[ctx.bumps.treasury]]]),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> WithdrawTreasury<'info> {
+    fn withdraw_lamports(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.treasury.to_account_info(),
+            to: self.destination.to_account_info(),
+        };
+        let cpi_program = self.system_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
@@ -1,5 +1,3 @@
-use std::cmp::Ordering;
-
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
@@ -8,9 +6,11 @@ use anchor_spl::token_interface::{
 use crate::{
     authority_seeds,
     error::PSmError,
+    math::{apply_fee, apply_haircut, compute_redeem_amount},
     state::{
-        config::{Config, AUTHORITY_PREFIX},
-        pool::Pool,
+        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
+        fee_exempt::{FeeExemptList, FEE_EXEMPT_LIST_PREFIX},
+        pool::{Pool, PoolView, POOL_PREFIX},
     },
 };
 
@@ -32,10 +32,16 @@ pub struct Redeem<'info> {
     pub user_settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
+        seeds = [CONFIG_PREFIX],
+        bump = config.load()?.config_bump,
         has_one = authority
     )]
     pub config: AccountLoader<'info, Config>,
     /// CHECK: checked with constraint
+    #[account(
+        seeds = [AUTHORITY_PREFIX],
+        bump = config.load()?.authority_bump,
+    )]
     pub authority: UncheckedAccount<'info>,
     #[account(mut)]
     pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
@@ -44,6 +50,8 @@ pub struct Redeem<'info> {
 
     #[account(
         mut,
+        seeds = [POOL_PREFIX, redemption_mint.key().as_ref(), settlement_mint.key().as_ref()],
+        bump = pool.load()?.bump,
         has_one = redemption_mint,
         has_one = settlement_mint,
         has_one = redemption_token_account,
@@ -60,32 +68,58 @@ pub struct Redeem<'info> {
     pub redemption_token_program: Interface<'info, TokenInterface>,
     pub settlement_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    /// Only needed by users on the pool's fee exempt list
+    #[account(
+        seeds = [FEE_EXEMPT_LIST_PREFIX, pool.key().as_ref()],
+        bump = fee_exempt_list.load()?.bump,
+    )]
+    pub fee_exempt_list: Option<AccountLoader<'info, FeeExemptList>>,
 }
 
-pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
+pub fn redeem(ctx: Context<Redeem>, amount: u64, min_amount_out: u64) -> Result<()> {
     require!(amount > 0, PSmError::ZeroAmount);
-    let normalized_amount: u64 = normalize_amount(
-        amount.into(),
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let config = ctx.accounts.config.load()?;
+
+    require!(!config.is_paused(), PSmError::ProtocolPaused);
+    pool.can_redeem(amount)?;
+
+    let (settlement_amount, normalized_amount) = compute_redeem_amount(
+        amount,
         ctx.accounts.settlement_mint.decimals,
         ctx.accounts.redemption_mint.decimals,
-    )?
-    .try_into()?;
-    require!(normalized_amount > 0, PSmError::ZeroAmount);
+        pool.rounding_mode,
+    )?;
+    let is_fee_exempt = match &ctx.accounts.fee_exempt_list {
+        Some(fee_exempt_list) => fee_exempt_list.load()?.is_exempt(ctx.accounts.user.key),
+        None => false,
+    };
+    let fee_bps = if is_fee_exempt {
+        0
+    } else {
+        pool.utilization_fee_bps(
+            ctx.accounts.settlement_token_account.amount,
+            settlement_amount,
+            ctx.accounts.redemption_token_account.amount,
+        )?
+    };
+    let (amount_out, _) = apply_fee(apply_haircut(normalized_amount, pool.haircut_bps)?, fee_bps)?;
     require!(
-        ctx.accounts.redemption_token_account.amount >= normalized_amount,
+        amount_out >= min_amount_out,
+        PSmError::SlippageToleranceExceeded
+    );
+    require!(
+        ctx.accounts.redemption_token_account.amount >= amount_out,
         PSmError::InsufficientPoolBalance
     );
 
-    let mut pool = ctx.accounts.pool.load_mut()?;
-    let config = ctx.accounts.config.load()?;
-
-    require!(!config.is_paused(), PSmError::ProtocolPaused);
-    pool.can_redeem()?;
-    pool.record_redeem(amount);
+    pool.record_redeem(settlement_amount);
 
     transfer_checked(
         ctx.accounts.deposit_settlement_tokens(),
-        amount,
+        settlement_amount,
         ctx.accounts.settlement_mint.decimals,
     )?;
 
@@ -93,7 +127,7 @@ pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
         ctx.accounts
             .claim_redemption_tokens()
             .with_signer(&[authority_seeds!(config.authority_bump)]),
-        normalized_amount,
+        amount_out,
         ctx.accounts.redemption_mint.decimals,
     )?;
 
@@ -124,18 +158,56 @@ impl<'info> Redeem<'info> {
     }
 }
 
-fn normalize_amount(amount: u128, decimals: u8, target_decimals: u8) -> Result<u128> {
-    match decimals.cmp(&target_decimals) {
-        Ordering::Equal => Ok(amount),
-        Ordering::Less => {
-            let diff = target_decimals - decimals;
-            require!(diff <= 19, PSmError::MathOverflow);
-            Ok(amount * 10u128.pow(diff.into()))
-        },
-        Ordering::Greater => {
-            let diff = decimals - target_decimals;
-            require!(diff <= 19, PSmError::MathOverflow);
-            Ok(amount / 10u128.pow(diff.into()))
-        },
-    }
+#[derive(Accounts)]
+pub struct QuoteRedeem<'info> {
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        has_one = redemption_token_account,
+        has_one = settlement_token_account,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    pub redemption_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Quotes what a user off the pool's fee exempt list gets.
+pub fn quote_redeem(ctx: Context<QuoteRedeem>, amount: u64) -> Result<u64> {
+    require!(amount > 0, PSmError::ZeroAmount);
+
+    let pool = ctx.accounts.pool.load()?;
+    let config = ctx.accounts.config.load()?;
+
+    require!(!config.is_paused(), PSmError::ProtocolPaused);
+    pool.can_redeem(amount)?;
+
+    let (settlement_amount, normalized_amount) = compute_redeem_amount(
+        amount,
+        pool.settlement_token_decimals,
+        pool.redemption_token_decimals,
+        pool.rounding_mode,
+    )?;
+    let fee_bps = pool.utilization_fee_bps(
+        ctx.accounts.settlement_token_account.amount,
+        settlement_amount,
+        ctx.accounts.redemption_token_account.amount,
+    )?;
+    let (amount_out, _) = apply_fee(apply_haircut(normalized_amount, pool.haircut_bps)?, fee_bps)?;
+    require!(
+        ctx.accounts.redemption_token_account.amount >= amount_out,
+        PSmError::InsufficientPoolBalance
+    );
+
+    Ok(amount_out)
+}
+
+#[derive(Accounts)]
+pub struct ViewPool<'info> {
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+pub fn view_pool(ctx: Context<ViewPool>) -> Result<PoolView> {
+    let pool = ctx.accounts.pool.load()?;
+
+    Ok(PoolView::from(&*pool))
 }
@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use super::check_withdrawal_destination;
+use crate::{
+    authority_seeds,
+    error::PSmError,
+    event::WithdrawalCancelledEvent,
+    state::{
+        config::{Config, AUTHORITY_PREFIX, CONFIG_PREFIX},
+        pool::Pool,
+        withdrawal::{PendingWithdrawal, PENDING_WITHDRAWAL_PREFIX},
+        withdrawal_destination::{WithdrawalDestinationList, WITHDRAWAL_DESTINATION_LIST_PREFIX},
+    },
+};
+
+#[derive(Accounts)]
+pub struct ProposeWithdrawal<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        constraint = destination.mint == pool.load()?.settlement_mint @ PSmError::InvalidSettlementMint,
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PendingWithdrawal::MAX_SIZE,
+        seeds = [PENDING_WITHDRAWAL_PREFIX, pool.key().as_ref()],
+        bump
+    )]
+    pub pending_withdrawal: AccountLoader<'info, PendingWithdrawal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Queues a settlement withdrawal that can only be executed once the config withdrawal delay has
+/// elapsed. Only one withdrawal can be pending per pool.
+pub fn propose_withdrawal(ctx: Context<ProposeWithdrawal>, amount: u64) -> Result<()> {
+    require!(amount > 0, PSmError::ZeroAmount);
+
+    let pool = ctx.accounts.pool.load()?;
+    let config = ctx.accounts.config.load()?;
+
+    pool.can_withdraw()?;
+
+    let mut pending_withdrawal = ctx.accounts.pending_withdrawal.load_init()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    pending_withdrawal.pool = ctx.accounts.pool.key();
+    pending_withdrawal.proposer = ctx.accounts.admin.key();
+    pending_withdrawal.destination = ctx.accounts.destination.key();
+    pending_withdrawal.amount = amount;
+    pending_withdrawal.executable_at = config.withdrawal_executable_at(now)?;
+    pending_withdrawal.bump = ctx.bumps.pending_withdrawal;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawal<'info> {
+    pub admin: Signer<'info>,
+    /// CHECK: checked with constraint on pending_withdrawal
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        has_one = authority,
+        constraint = config.load()?.is_admin(admin.key) || pool.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+    /// CHECK: checked with constraint
+    pub authority: UncheckedAccount<'info>,
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = settlement_mint,
+        has_one = settlement_token_account,
+        has_one = settlement_token_program,
+    )]
+    pub pool: AccountLoader<'info, Pool>,
+    #[account(mut)]
+    pub settlement_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = proposer,
+        has_one = pool,
+        has_one = proposer,
+        has_one = destination,
+    )]
+    pub pending_withdrawal: AccountLoader<'info, PendingWithdrawal>,
+
+    pub settlement_token_program: Interface<'info, TokenInterface>,
+
+    /// Required once the config restricts withdrawal destinations
+    #[account(
+        seeds = [WITHDRAWAL_DESTINATION_LIST_PREFIX],
+        bump = withdrawal_destination_list.load()?.bump,
+    )]
+    pub withdrawal_destination_list: Option<AccountLoader<'info, WithdrawalDestinationList>>,
+}
+
+pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+    let amount = {
+        let pending_withdrawal = ctx.accounts.pending_withdrawal.load()?;
+        require!(
+            pending_withdrawal.is_executable(Clock::get()?.unix_timestamp),
+            PSmError::WithdrawalNotReady
+        );
+        pending_withdrawal.amount
+    };
+    require!(
+        ctx.accounts.settlement_token_account.amount >= amount,
+        PSmError::InsufficientPoolBalance
+    );
+
+    let mut pool = ctx.accounts.pool.load_mut()?;
+    let config = ctx.accounts.config.load()?;
+
+    require!(!config.is_paused(), PSmError::ProtocolPaused);
+    check_withdrawal_destination(
+        &config,
+        ctx.accounts.withdrawal_destination_list.as_ref(),
+        &ctx.accounts.destination.owner,
+    )?;
+    pool.can_withdraw()?;
+    pool.record_withdraw(amount);
+
+    transfer_checked(
+        ctx.accounts
+            .withdraw_settlement_tokens()
+            .with_signer(&[authority_seeds!(config.authority_bump)]),
+        amount,
+        ctx.accounts.settlement_mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> ExecuteWithdrawal<'info> {
+    fn withdraw_settlement_tokens(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_accounts = TransferChecked {
+            from: self.settlement_token_account.to_account_info(),
+            mint: self.settlement_mint.to_account_info(),
+            to: self.destination.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_program = self.settlement_token_program.to_account_info();
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    pub signer: Signer<'info>,
+    /// CHECK: checked with constraint on pending_withdrawal
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX],
+        bump = config.load()?.config_bump,
+        constraint = config.load()?.is_pauser(signer.key) || config.load()?.is_admin(signer.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [PENDING_WITHDRAWAL_PREFIX, pool.key().as_ref()],
+        bump = pending_withdrawal.load()?.bump,
+        has_one = pool,
+        has_one = proposer,
+    )]
+    pub pending_withdrawal: AccountLoader<'info, PendingWithdrawal>,
+}
+
+/// Drops a pending withdrawal. Pausers can cancel so a suspicious withdrawal can be stopped
+/// during the delay without holding admin rights.
+pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> Result<()> {
+    let pending_withdrawal = ctx.accounts.pending_withdrawal.load()?;
+
+    emit!(WithdrawalCancelledEvent {
+        pool: pending_withdrawal.pool,
+        signer: ctx.accounts.signer.key(),
+        proposer: pending_withdrawal.proposer,
+        destination: pending_withdrawal.destination,
+        amount: pending_withdrawal.amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::PSmError,
+    state::{
+        config::Config,
+        withdrawal_destination::{WithdrawalDestinationList, WITHDRAWAL_DESTINATION_LIST_PREFIX},
+    },
+};
+
+#[derive(Accounts)]
+pub struct CreateWithdrawalDestinationList<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = config.load()?.is_admin(admin.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + WithdrawalDestinationList::MAX_SIZE,
+        seeds = [WITHDRAWAL_DESTINATION_LIST_PREFIX],
+        bump
+    )]
+    pub withdrawal_destination_list: AccountLoader<'info, WithdrawalDestinationList>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creating the list turns the restriction on for good: from then on settlement funds can only
+/// leave pools to owners on it.
+pub fn create_withdrawal_destination_list(
+    ctx: Context<CreateWithdrawalDestinationList>,
+) -> Result<()> {
+    let mut withdrawal_destination_list = ctx.accounts.withdrawal_destination_list.load_init()?;
+    withdrawal_destination_list.bump = ctx.bumps.withdrawal_destination_list;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.enable_withdrawal_destination_restriction();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ManageWithdrawalDestinationList<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        constraint = config.load()?.is_admin(signer.key) || config.load()?.is_pauser(signer.key) @ PSmError::NotAuthorized,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [WITHDRAWAL_DESTINATION_LIST_PREFIX],
+        bump = withdrawal_destination_list.load()?.bump,
+    )]
+    pub withdrawal_destination_list: AccountLoader<'info, WithdrawalDestinationList>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum WithdrawalDestinationListAction {
+    AddOwner { owner: Pubkey },
+    RemoveOwner { owner: Pubkey },
+}
+
+/// Added owners are only approved once the config withdrawal delay has elapsed, so pausers can
+/// remove one a compromised admin key slipped in before it receives anything.
+pub fn manage_withdrawal_destination_list(
+    ctx: Context<ManageWithdrawalDestinationList>,
+    action: WithdrawalDestinationListAction,
+) -> Result<()> {
+    let config = ctx.accounts.config.load()?;
+    let mut withdrawal_destination_list = ctx.accounts.withdrawal_destination_list.load_mut()?;
+
+    match action {
+        WithdrawalDestinationListAction::AddOwner { owner } => {
+            require!(
+                config.is_admin(ctx.accounts.signer.key),
+                PSmError::NotAuthorized
+            );
+            require!(owner != Pubkey::default(), PSmError::SomeError);
+            require!(
+                !withdrawal_destination_list.contains(&owner),
+                PSmError::DuplicateRessources
+            );
+
+            let active_from = config.withdrawal_executable_at(Clock::get()?.unix_timestamp)?;
+            withdrawal_destination_list.add(&owner, active_from)?;
+        },
+        WithdrawalDestinationListAction::RemoveOwner { owner } => {
+            withdrawal_destination_list.remove(&owner)?;
+        },
+    }
+
+    Ok(())
+}
+
+/// Fails when the config restricts withdrawal destinations and `owner` isn't approved on the
+/// list, which then has to be passed.
+pub(crate) fn check_withdrawal_destination(
+    config: &Config,
+    withdrawal_destination_list: Option<&AccountLoader<WithdrawalDestinationList>>,
+    owner: &Pubkey,
+) -> Result<()> {
+    if !config.restricts_withdrawal_destinations() {
+        return Ok(());
+    }
+
+    let withdrawal_destination_list = withdrawal_destination_list
+        .ok_or(PSmError::WithdrawalDestinationNotApproved)?
+        .load()?;
+    require!(
+        withdrawal_destination_list.is_approved(owner, Clock::get()?.unix_timestamp),
+        PSmError::WithdrawalDestinationNotApproved
+    );
+
+    Ok(())
+}
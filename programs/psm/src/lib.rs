@@ -3,12 +3,17 @@
 use anchor_lang::prelude::*;
 
 pub mod error;
+pub mod event;
 pub mod instructions;
+pub mod math;
 pub mod state;
 
 declare_id!("GFU42W56UJ4ZyJL8beMWjtiz3LhbxXMBbHinft6Jc5SC");
 
-use crate::instructions::{ConfigManagementAction, *};
+use crate::{
+    instructions::{ConfigManagementAction, *},
+    state::pool::PoolView,
+};
 
 #[program]
 pub mod psm {
@@ -24,6 +29,19 @@ pub mod psm {
         Ok(())
     }
 
+    pub fn manage_config_batch(
+        ctx: Context<ManageConfig>,
+        actions: Vec<ConfigManagementAction>,
+    ) -> Result<()> {
+        instructions::manage_config_batch(ctx, actions)?;
+        Ok(())
+    }
+
+    pub fn guardian_pause(ctx: Context<GuardianPause>) -> Result<()> {
+        instructions::guardian_pause(ctx)?;
+        Ok(())
+    }
+
     pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
         instructions::create_pool(ctx)?;
         Ok(())
@@ -34,13 +52,107 @@ pub mod psm {
         Ok(())
     }
 
+    pub fn manage_pool_batch(
+        ctx: Context<ManagePool>,
+        actions: Vec<PoolManagementAction>,
+    ) -> Result<()> {
+        instructions::manage_pool_batch(ctx, actions)?;
+        Ok(())
+    }
+
+    pub fn activate_pool(ctx: Context<ActivatePool>) -> Result<()> {
+        instructions::activate_pool(ctx)?;
+        Ok(())
+    }
+
+    pub fn create_fee_exempt_list(ctx: Context<CreateFeeExemptList>) -> Result<()> {
+        instructions::create_fee_exempt_list(ctx)?;
+        Ok(())
+    }
+
+    pub fn manage_fee_exempt_list(
+        ctx: Context<ManageFeeExemptList>,
+        action: FeeExemptListAction,
+    ) -> Result<()> {
+        instructions::manage_fee_exempt_list(ctx, action)?;
+        Ok(())
+    }
+
+    pub fn create_allowed_mint_list(ctx: Context<CreateAllowedMintList>) -> Result<()> {
+        instructions::create_allowed_mint_list(ctx)?;
+        Ok(())
+    }
+
+    pub fn manage_allowed_mint_list(
+        ctx: Context<ManageAllowedMintList>,
+        action: AllowedMintListAction,
+    ) -> Result<()> {
+        instructions::manage_allowed_mint_list(ctx, action)?;
+        Ok(())
+    }
+
+    pub fn create_withdrawal_destination_list(
+        ctx: Context<CreateWithdrawalDestinationList>,
+    ) -> Result<()> {
+        instructions::create_withdrawal_destination_list(ctx)?;
+        Ok(())
+    }
+
+    pub fn manage_withdrawal_destination_list(
+        ctx: Context<ManageWithdrawalDestinationList>,
+        action: WithdrawalDestinationListAction,
+    ) -> Result<()> {
+        instructions::manage_withdrawal_destination_list(ctx, action)?;
+        Ok(())
+    }
+
+    pub fn sweep_settlement(ctx: Context<SweepSettlement>) -> Result<()> {
+        instructions::sweep_settlement(ctx)?;
+        Ok(())
+    }
+
+    pub fn create_reserve(ctx: Context<CreateReserve>) -> Result<()> {
+        instructions::create_reserve(ctx)?;
+        Ok(())
+    }
+
+    pub fn attach_reserve(ctx: Context<AttachReserve>) -> Result<()> {
+        instructions::attach_reserve(ctx)?;
+        Ok(())
+    }
+
+    pub fn detach_reserve(ctx: Context<DetachReserve>) -> Result<()> {
+        instructions::detach_reserve(ctx)?;
+        Ok(())
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool(ctx)?;
+        Ok(())
+    }
+
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        instructions::migrate(ctx)?;
+        Ok(())
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)?;
+        Ok(())
+    }
+
     pub fn supply(ctx: Context<Supply>, amount: u64) -> Result<()> {
         instructions::supply(ctx, amount)?;
         Ok(())
     }
 
-    pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
-        instructions::redeem(ctx, amount)?;
+    pub fn supply_from_treasury(ctx: Context<SupplyFromTreasury>, amount: u64) -> Result<()> {
+        instructions::supply_from_treasury(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn redeem(ctx: Context<Redeem>, amount: u64, min_amount_out: u64) -> Result<()> {
+        instructions::redeem(ctx, amount, min_amount_out)?;
         Ok(())
     }
 
@@ -48,4 +160,49 @@ pub mod psm {
         instructions::withdraw(ctx, amount)?;
         Ok(())
     }
+
+    pub fn propose_withdrawal(ctx: Context<ProposeWithdrawal>, amount: u64) -> Result<()> {
+        instructions::propose_withdrawal(ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+        instructions::execute_withdrawal(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> Result<()> {
+        instructions::cancel_withdrawal(ctx)?;
+        Ok(())
+    }
+
+    pub fn retire_settlement<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetireSettlement<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::retire_settlement(ctx, amount, min_amount_out)
+    }
+
+    pub fn replenish<'info>(
+        ctx: Context<'_, '_, '_, 'info, Replenish<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::replenish(ctx, amount, min_amount_out)
+    }
+
+    pub fn mint_and_supply<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintAndSupply<'info>>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::mint_and_supply(ctx, amount, min_amount_out)
+    }
+
+    pub fn quote_redeem(ctx: Context<QuoteRedeem>, amount: u64) -> Result<u64> {
+        instructions::quote_redeem(ctx, amount)
+    }
+
+    pub fn view_pool(ctx: Context<ViewPool>) -> Result<PoolView> { instructions::view_pool(ctx) }
 }
@@ -0,0 +1,206 @@
+use std::cmp::Ordering;
+
+use anchor_lang::prelude::*;
+
+use crate::{error::PSmError, state::pool::RoundingMode};
+
+/// Returns the settlement amount taken from the user and the redemption amount credited for it.
+pub fn compute_redeem_amount(
+    amount: u64,
+    settlement_decimals: u8,
+    redemption_decimals: u8,
+    rounding_mode: RoundingMode,
+) -> Result<(u64, u64)> {
+    let normalized_amount: u64 =
+        normalize_amount(amount.into(), settlement_decimals, redemption_decimals)?.try_into()?;
+    require!(normalized_amount > 0, PSmError::ZeroAmount);
+
+    let credited_amount: u64 = normalize_amount(
+        normalized_amount.into(),
+        redemption_decimals,
+        settlement_decimals,
+    )?
+    .try_into()?;
+
+    let settlement_amount = match rounding_mode {
+        RoundingMode::Floor => amount,
+        RoundingMode::Refund => credited_amount,
+        RoundingMode::Exact => {
+            require!(credited_amount == amount, PSmError::InexactAmount);
+            amount
+        },
+    };
+
+    Ok((settlement_amount, normalized_amount))
+}
+
+/// Takes `haircut_bps` off a redemption output, rounding down.
+pub fn apply_haircut(amount: u64, haircut_bps: u16) -> Result<u64> {
+    require!(haircut_bps < 10_000, PSmError::BadInput);
+
+    let amount_out = (amount as u128)
+        .checked_mul((10_000 - haircut_bps).into())
+        .ok_or(error!(PSmError::MathOverflow))?
+        / 10_000;
+    let amount_out: u64 = amount_out.try_into()?;
+    require!(amount_out > 0, PSmError::ZeroAmount);
+
+    Ok(amount_out)
+}
+
+/// Scales a fee linearly from `min_fee_bps` with nothing used to `max_fee_bps` once `used`
+/// reaches `capacity`.
+pub fn compute_utilization_fee_bps(
+    used: u128,
+    capacity: u128,
+    min_fee_bps: u16,
+    max_fee_bps: u16,
+) -> Result<u16> {
+    require!(min_fee_bps <= max_fee_bps, PSmError::BadInput);
+    if used >= capacity {
+        return Ok(max_fee_bps);
+    }
+
+    let fee_range = used
+        .checked_mul((max_fee_bps - min_fee_bps).into())
+        .ok_or(error!(PSmError::MathOverflow))?
+        / capacity;
+
+    Ok(min_fee_bps + u16::try_from(fee_range)?)
+}
+
+/// Takes `fee_bps` off a redemption output, rounding the fee up. Returns the output and the fee.
+pub fn apply_fee(amount: u64, fee_bps: u16) -> Result<(u64, u64)> {
+    require!(fee_bps < 10_000, PSmError::BadInput);
+
+    let fee_amount = (amount as u128)
+        .checked_mul(fee_bps.into())
+        .ok_or(error!(PSmError::MathOverflow))?
+        .div_ceil(10_000);
+    let fee_amount: u64 = fee_amount.try_into()?;
+    let amount_out = amount - fee_amount;
+    require!(amount_out > 0, PSmError::ZeroAmount);
+
+    Ok((amount_out, fee_amount))
+}
+
+pub fn normalize_amount(amount: u128, decimals: u8, target_decimals: u8) -> Result<u128> {
+    match decimals.cmp(&target_decimals) {
+        Ordering::Equal => Ok(amount),
+        Ordering::Less => {
+            let diff = target_decimals - decimals;
+            require!(diff <= 19, PSmError::MathOverflow);
+            amount
+                .checked_mul(10u128.pow(diff.into()))
+                .ok_or(error!(PSmError::MathOverflow))
+        },
+        Ordering::Greater => {
+            let diff = decimals - target_decimals;
+            require!(diff <= 19, PSmError::MathOverflow);
+            Ok(amount / 10u128.pow(diff.into()))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn rounding_mode() -> impl Strategy<Value = RoundingMode> {
+        prop_oneof![
+            Just(RoundingMode::Floor),
+            Just(RoundingMode::Refund),
+            Just(RoundingMode::Exact),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_never_creates_value(
+            amount in any::<u64>(),
+            decimals in 0u8..=19,
+            target_decimals in 0u8..=19,
+        ) {
+            let normalized = normalize_amount(amount.into(), decimals, target_decimals).unwrap();
+            let round_trip = normalize_amount(normalized, target_decimals, decimals).unwrap();
+            prop_assert!(round_trip <= amount.into());
+        }
+
+        #[test]
+        fn normalize_amount_is_monotonic(
+            a in any::<u64>(),
+            b in any::<u64>(),
+            decimals in 0u8..=19,
+            target_decimals in 0u8..=19,
+        ) {
+            let (low, high) = (a.min(b), a.max(b));
+            let low_out = normalize_amount(low.into(), decimals, target_decimals).unwrap();
+            let high_out = normalize_amount(high.into(), decimals, target_decimals).unwrap();
+            prop_assert!(low_out <= high_out);
+        }
+
+        #[test]
+        fn redeem_never_credits_more_than_taken(
+            amount in any::<u64>(),
+            settlement_decimals in 0u8..=12,
+            redemption_decimals in 0u8..=12,
+            rounding_mode in rounding_mode(),
+        ) {
+            if let Ok((settlement_amount, normalized_amount)) = compute_redeem_amount(
+                amount,
+                settlement_decimals,
+                redemption_decimals,
+                rounding_mode,
+            ) {
+                prop_assert!(settlement_amount <= amount);
+                let credited_value = normalize_amount(
+                    normalized_amount.into(),
+                    redemption_decimals,
+                    settlement_decimals,
+                )
+                .unwrap();
+                prop_assert!(credited_value <= settlement_amount.into());
+            }
+        }
+
+        #[test]
+        fn haircut_never_increases_amount(amount in 1u64.., haircut_bps in 0u16..10_000) {
+            if let Ok(amount_out) = apply_haircut(amount, haircut_bps) {
+                prop_assert!(amount_out <= amount);
+                if haircut_bps == 0 {
+                    prop_assert_eq!(amount_out, amount);
+                }
+            }
+        }
+
+        #[test]
+        fn utilization_fee_stays_in_range(
+            used in any::<u64>(),
+            capacity in any::<u64>(),
+            min_fee_bps in 0u16..10_000,
+            max_fee_bps in 0u16..10_000,
+        ) {
+            let (min_fee_bps, max_fee_bps) = (min_fee_bps.min(max_fee_bps), min_fee_bps.max(max_fee_bps));
+            let fee_bps =
+                compute_utilization_fee_bps(used.into(), capacity.into(), min_fee_bps, max_fee_bps)
+                    .unwrap();
+            prop_assert!((min_fee_bps..=max_fee_bps).contains(&fee_bps));
+            if let Ok((amount_out, fee_amount)) = apply_fee(capacity, fee_bps) {
+                prop_assert_eq!(amount_out + fee_amount, capacity);
+            }
+        }
+
+        #[test]
+        fn arbitrary_inputs_do_not_panic(
+            amount in any::<u128>(),
+            decimals in any::<u8>(),
+            target_decimals in any::<u8>(),
+            rounding_mode in rounding_mode(),
+        ) {
+            let _ = normalize_amount(amount, decimals, target_decimals);
+            let _ = compute_redeem_amount(amount as u64, decimals, target_decimals, rounding_mode);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+use crate::error::PSmError;
+
+const_assert_eq!(AllowedMintList::MAX_SIZE, size_of::<AllowedMintList>());
+
+#[constant]
+pub const ALLOWED_MINT_LIST_PREFIX: &[u8; 17] = b"allowed_mint_list";
+pub const MAX_ALLOWED_MINTS: usize = 32;
+
+/// Mints anyone may create a pool between while permissionless pool creation is enabled.
+#[account(zero_copy)]
+pub struct AllowedMintList {
+    pub mints: [Pubkey; MAX_ALLOWED_MINTS],
+
+    pub bump: u8,
+    pub _padding1: [u8; 7],
+
+    pub reserved: [u8; 64],
+}
+
+impl Default for AllowedMintList {
+    fn default() -> Self {
+        AllowedMintList {
+            mints: [Pubkey::default(); MAX_ALLOWED_MINTS],
+            bump: 0,
+            _padding1: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl AllowedMintList {
+    pub const MAX_SIZE: usize = 32 * MAX_ALLOWED_MINTS + // mints
+        1 + // bump
+        7 + // _padding1
+        64;
+
+    pub fn is_allowed(&self, mint: &Pubkey) -> bool {
+        *mint != Pubkey::default() && self.mints.contains(mint)
+    }
+
+    pub fn add(&mut self, mint: &Pubkey) -> Result<()> {
+        for i in 0..MAX_ALLOWED_MINTS {
+            if self.mints[i] == Pubkey::default() {
+                self.mints[i] = *mint;
+                return Ok(());
+            }
+        }
+        err!(PSmError::AllowedMintListFull)
+    }
+
+    pub fn remove(&mut self, mint: &Pubkey) -> Result<()> {
+        for i in 0..MAX_ALLOWED_MINTS {
+            if &self.mints[i] == mint {
+                self.mints[i] = Pubkey::default();
+                return Ok(());
+            }
+        }
+        err!(PSmError::SomeError)
+    }
+}
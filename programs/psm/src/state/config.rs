@@ -1,4 +1,4 @@
-use std::mem::size_of;
+use std::mem::{offset_of, size_of};
 
 use anchor_lang::prelude::*;
 use static_assertions::const_assert_eq;
@@ -8,10 +8,40 @@ use crate::error::PSmError;
 const_assert_eq!(Config::MAX_SIZE, size_of::<Config>());
 const_assert_eq!(size_of::<Config>() % 8, 0);
 
+// Layout map of the deployed account. Fields never move; new ones are carved out of `reserved`,
+// which `migrate` reallocates onto configs created before it existed.
+const_assert_eq!(size_of::<Config>(), 808);
+const_assert_eq!(offset_of!(Config, admins), 0);
+const_assert_eq!(offset_of!(Config, authority), 320);
+const_assert_eq!(offset_of!(Config, is_paused), 352);
+const_assert_eq!(offset_of!(Config, authority_bump), 353);
+const_assert_eq!(offset_of!(Config, config_bump), 354);
+const_assert_eq!(offset_of!(Config, permissionless_pool_creation), 355);
+const_assert_eq!(offset_of!(Config, restrict_withdrawal_destinations), 356);
+const_assert_eq!(offset_of!(Config, version), 357);
+const_assert_eq!(offset_of!(Config, withdrawal_delay), 360);
+const_assert_eq!(offset_of!(Config, pausers), 368);
+const_assert_eq!(offset_of!(Config, guardian), 496);
+const_assert_eq!(offset_of!(Config, pool_creation_fee), 528);
+const_assert_eq!(offset_of!(Config, total_pool_creation_fees), 536);
+const_assert_eq!(offset_of!(Config, withdrawal_delay_locked_until), 544);
+const_assert_eq!(offset_of!(Config, reserved), 552);
+
+#[constant]
 pub const CONFIG_PREFIX: &[u8; 6] = b"config";
+#[constant]
 pub const AUTHORITY_PREFIX: &[u8; 9] = b"authority";
+#[constant]
+pub const TREASURY_PREFIX: &[u8; 8] = b"treasury";
+/// Layout version `init` writes and `migrate` brings an older config up to
+#[constant]
+pub const CONFIG_VERSION: u8 = 2;
 pub const MAX_ADMINS: usize = 10;
 pub const MAX_PERIOD_LIMIT: usize = 4;
+pub const MAX_PAUSERS: usize = 4;
+/// Shortest time a custodian change waits before taking over, whatever the withdrawal delay
+#[constant]
+pub const MIN_CUSTODIAN_DELAY_SECONDS: i64 = 86_400;
 
 #[macro_export]
 macro_rules! config_seeds {
@@ -34,12 +64,61 @@ pub struct Config {
     pub is_paused: u8,
     pub authority_bump: u8,
     pub config_bump: u8,
-    pub _padding: [u8; 5],
-    pub reserved: [u8; 192],
+    /// Non-zero lets anyone create a pool between two mints of the allowed mint list
+    pub permissionless_pool_creation: u8,
+    /// Non-zero once the withdrawal destination list exists; settlement funds can then only leave
+    /// to owners on it. Never cleared.
+    pub restrict_withdrawal_destinations: u8,
+    /// Layout version, zero for a config initialized before versioning
+    pub version: u8,
+    pub _padding: [u8; 2],
+    pub withdrawal_delay: u64,
+    pub pausers: [Pubkey; MAX_PAUSERS],
+    pub guardian: Pubkey,
+    /// Lamports `create_pool` charges its payer into the treasury, zero meaning free
+    pub pool_creation_fee: u64,
+    /// Lamports `create_pool` collected into the treasury so far
+    pub total_pool_creation_fees: u64,
+    /// Lowering `withdrawal_delay` keeps the old one in force until this timestamp, so a decrease
+    /// can't be used to skip the timelock
+    pub withdrawal_delay_locked_until: i64,
+    pub reserved: [u8; 256],
 }
 
 impl Config {
-    pub const MAX_SIZE: usize = 32 * MAX_ADMINS + 32 + 1 + 1 + 1 + 5 + 192;
+    pub const MAX_SIZE: usize = 32 * MAX_ADMINS
+        + 32
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 1
+        + 2
+        + 8
+        + 32 * MAX_PAUSERS
+        + 32
+        + 8
+        + 8
+        + 8
+        + 256;
+
+    /// Steps the layout up to `CONFIG_VERSION`, a no-op once there.
+    pub fn migrate(&mut self) -> Result<()> {
+        require!(self.version <= CONFIG_VERSION, PSmError::UnsupportedVersion);
+        while self.version < CONFIG_VERSION {
+            match self.version {
+                // 0 -> 1: fields added so far were carved out of zeroed padding
+                0 => {},
+                // 1 -> 2: the account was reallocated to add a zeroed `reserved`
+                1 => {},
+                _ => return err!(PSmError::UnsupportedVersion),
+            }
+            self.version += 1;
+        }
+
+        Ok(())
+    }
 
     pub fn is_admin(&self, pubkey: &Pubkey) -> bool {
         for i in 0..MAX_ADMINS {
@@ -80,6 +159,99 @@ impl Config {
         count
     }
 
+    pub fn is_pauser(&self, pubkey: &Pubkey) -> bool {
+        for i in 0..MAX_PAUSERS {
+            if &self.pausers[i] == pubkey {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn add_pauser(&mut self, pubkey: &Pubkey) -> Result<()> {
+        for i in 0..MAX_PAUSERS {
+            if self.pausers[i] == Pubkey::default() {
+                self.pausers[i] = *pubkey;
+                return Ok(());
+            }
+        }
+        err!(PSmError::PauserArrayFull)
+    }
+
+    pub fn remove_pauser(&mut self, pubkey: &Pubkey) -> Result<()> {
+        for i in 0..MAX_PAUSERS {
+            if &self.pausers[i] == pubkey {
+                self.pausers[i] = Pubkey::default();
+                return Ok(());
+            }
+        }
+        err!(PSmError::SomeError)
+    }
+
+    pub fn is_guardian(&self, pubkey: &Pubkey) -> bool {
+        self.guardian != Pubkey::default() && &self.guardian == pubkey
+    }
+
+    pub fn set_guardian(&mut self, guardian: Pubkey) { self.guardian = guardian; }
+
+    pub fn set_pool_creation_fee(&mut self, pool_creation_fee: u64) {
+        self.pool_creation_fee = pool_creation_fee;
+    }
+
+    pub fn record_pool_creation_fee(&mut self, fee: u64) {
+        self.total_pool_creation_fees = self.total_pool_creation_fees.saturating_add(fee);
+    }
+
+    pub fn is_permissionless_pool_creation(&self) -> bool { self.permissionless_pool_creation == 1 }
+
+    pub fn set_permissionless_pool_creation(&mut self, enabled: bool) {
+        self.permissionless_pool_creation = if enabled { 1 } else { 0 };
+    }
+
+    pub fn restricts_withdrawal_destinations(&self) -> bool {
+        self.restrict_withdrawal_destinations == 1
+    }
+
+    pub fn enable_withdrawal_destination_restriction(&mut self) {
+        self.restrict_withdrawal_destinations = 1;
+    }
+
+    pub fn is_withdrawal_timelocked(&self, current_time: i64) -> bool {
+        self.withdrawal_delay > 0 || current_time < self.withdrawal_delay_locked_until
+    }
+
+    /// Earliest time something queued now behind the withdrawal delay can take effect.
+    pub fn withdrawal_executable_at(&self, current_time: i64) -> Result<i64> {
+        let executable_at = current_time
+            .checked_add(self.withdrawal_delay.try_into()?)
+            .ok_or(PSmError::MathOverflow)?;
+
+        Ok(executable_at.max(self.withdrawal_delay_locked_until))
+    }
+
+    /// Earliest time a custodian queued now takes over. Sweeps to the custodian need no
+    /// signature, so it waits at least `MIN_CUSTODIAN_DELAY_SECONDS` even without a withdrawal
+    /// delay.
+    pub fn custodian_executable_at(&self, current_time: i64) -> Result<i64> {
+        let min_executable_at = current_time
+            .checked_add(MIN_CUSTODIAN_DELAY_SECONDS)
+            .ok_or(PSmError::MathOverflow)?;
+
+        Ok(self
+            .withdrawal_executable_at(current_time)?
+            .max(min_executable_at))
+    }
+
+    /// A decrease only applies to what is queued after the current delay has run out.
+    pub fn set_withdrawal_delay(&mut self, withdrawal_delay: u64, current_time: i64) -> Result<()> {
+        if withdrawal_delay < self.withdrawal_delay {
+            self.withdrawal_delay_locked_until = self.withdrawal_executable_at(current_time)?;
+        }
+        self.withdrawal_delay = withdrawal_delay;
+
+        Ok(())
+    }
+
     pub fn is_paused(&self) -> bool { self.is_paused == 1 }
 
     pub fn update_pause_flag(&mut self, is_paused: bool) -> Result<()> {
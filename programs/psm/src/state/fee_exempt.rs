@@ -0,0 +1,68 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+use crate::error::PSmError;
+
+const_assert_eq!(FeeExemptList::MAX_SIZE, size_of::<FeeExemptList>());
+
+#[constant]
+pub const FEE_EXEMPT_LIST_PREFIX: &[u8; 15] = b"fee_exempt_list";
+pub const MAX_FEE_EXEMPT_ADDRESSES: usize = 16;
+
+/// Users of a pool that redeem without its utilization fee.
+#[account(zero_copy)]
+pub struct FeeExemptList {
+    pub pool: Pubkey,
+    pub addresses: [Pubkey; MAX_FEE_EXEMPT_ADDRESSES],
+
+    pub bump: u8,
+    pub _padding1: [u8; 7],
+
+    pub reserved: [u8; 64],
+}
+
+impl Default for FeeExemptList {
+    fn default() -> Self {
+        FeeExemptList {
+            pool: Pubkey::default(),
+            addresses: [Pubkey::default(); MAX_FEE_EXEMPT_ADDRESSES],
+            bump: 0,
+            _padding1: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl FeeExemptList {
+    pub const MAX_SIZE: usize = 32 + // pool
+        32 * MAX_FEE_EXEMPT_ADDRESSES + // addresses
+        1 + // bump
+        7 + // _padding1
+        64;
+
+    pub fn is_exempt(&self, pubkey: &Pubkey) -> bool {
+        *pubkey != Pubkey::default() && self.addresses.contains(pubkey)
+    }
+
+    pub fn add(&mut self, pubkey: &Pubkey) -> Result<()> {
+        for i in 0..MAX_FEE_EXEMPT_ADDRESSES {
+            if self.addresses[i] == Pubkey::default() {
+                self.addresses[i] = *pubkey;
+                return Ok(());
+            }
+        }
+        err!(PSmError::FeeExemptListFull)
+    }
+
+    pub fn remove(&mut self, pubkey: &Pubkey) -> Result<()> {
+        for i in 0..MAX_FEE_EXEMPT_ADDRESSES {
+            if &self.addresses[i] == pubkey {
+                self.addresses[i] = Pubkey::default();
+                return Ok(());
+            }
+        }
+        err!(PSmError::SomeError)
+    }
+}
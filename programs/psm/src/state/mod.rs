@@ -1,2 +1,7 @@
+pub mod allowed_mint;
 pub mod config;
+pub mod fee_exempt;
 pub mod pool;
+pub mod reserve;
+pub mod withdrawal;
+pub mod withdrawal_destination;
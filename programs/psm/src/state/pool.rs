@@ -1,15 +1,57 @@
-use std::mem::size_of;
+use std::mem::{offset_of, size_of};
 
 use anchor_lang::prelude::*;
 use bytemuck::{Pod, Zeroable};
+use jup_stable::state::common::{PeriodLimit, MAX_DURATION_SECONDS, MIN_DURATION_SECONDS};
 use static_assertions::const_assert_eq;
 
-use crate::error::PSmError;
+use crate::{
+    error::PSmError,
+    math::{compute_utilization_fee_bps, normalize_amount},
+};
 
 const_assert_eq!(Pool::MAX_SIZE, size_of::<Pool>());
 
+// Deployed layout, new fields come out of `reserved`
+const_assert_eq!(size_of::<Pool>(), 520);
+const_assert_eq!(offset_of!(Pool, redemption_mint), 0);
+const_assert_eq!(offset_of!(Pool, settlement_mint), 32);
+const_assert_eq!(offset_of!(Pool, redemption_token_account), 64);
+const_assert_eq!(offset_of!(Pool, settlement_token_account), 96);
+const_assert_eq!(offset_of!(Pool, redemption_token_program), 128);
+const_assert_eq!(offset_of!(Pool, settlement_token_program), 160);
+const_assert_eq!(offset_of!(Pool, redemption_token_decimals), 192);
+const_assert_eq!(offset_of!(Pool, settlement_token_decimals), 193);
+const_assert_eq!(offset_of!(Pool, status), 200);
+const_assert_eq!(offset_of!(Pool, bump), 208);
+const_assert_eq!(offset_of!(Pool, total_redeemed), 216);
+const_assert_eq!(offset_of!(Pool, total_supplied), 232);
+const_assert_eq!(offset_of!(Pool, total_withdrawn), 248);
+const_assert_eq!(offset_of!(Pool, total_retired), 264);
+const_assert_eq!(offset_of!(Pool, retire_settlement), 280);
+const_assert_eq!(offset_of!(Pool, rounding_mode), 281);
+const_assert_eq!(offset_of!(Pool, haircut_bps), 282);
+const_assert_eq!(offset_of!(Pool, version), 284);
+const_assert_eq!(offset_of!(Pool, reserve), 288);
+const_assert_eq!(offset_of!(Pool, min_redeem_amount), 320);
+const_assert_eq!(offset_of!(Pool, custodian), 328);
+const_assert_eq!(offset_of!(Pool, admin), 360);
+const_assert_eq!(offset_of!(Pool, supply_period_limit), 392);
+const_assert_eq!(offset_of!(Pool, label), 440);
+const_assert_eq!(offset_of!(Pool, pending_custodian), 472);
+const_assert_eq!(offset_of!(Pool, pending_custodian_at), 504);
+const_assert_eq!(offset_of!(Pool, min_fee_bps), 512);
+const_assert_eq!(offset_of!(Pool, max_fee_bps), 514);
+const_assert_eq!(offset_of!(Pool, reserved), 516);
+
+/// Layout version `create_pool` writes and `migrate` brings older pools up to
+#[constant]
+pub const POOL_VERSION: u8 = 1;
+#[constant]
 pub const POOL_PREFIX: &[u8; 4] = b"pool";
+#[constant]
 pub const POOL_REDEMPTION_TOKEN_ACCOUNT_PREFIX: &[u8; 29] = b"pool_redemption_token_account";
+#[constant]
 pub const POOL_SETTLEMENT_TOKEN_ACCOUNT_PREFIX: &[u8; 29] = b"pool_settlement_token_account";
 
 #[macro_export]
@@ -30,6 +72,21 @@ pub enum PoolStatus {
 unsafe impl Pod for PoolStatus {}
 unsafe impl Zeroable for PoolStatus {}
 
+/// How redemptions handle settlement amounts that don't convert exactly into redemption units.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum RoundingMode {
+    /// Round down and keep the un-creditable remainder in the pool.
+    Floor,
+    /// Round down and only take the settlement amount that is actually credited.
+    Refund,
+    /// Reject amounts that don't convert exactly.
+    Exact,
+}
+
+unsafe impl Pod for RoundingMode {}
+unsafe impl Zeroable for RoundingMode {}
+
 #[account(zero_copy)]
 pub struct Pool {
     pub redemption_mint: Pubkey,
@@ -52,8 +109,42 @@ pub struct Pool {
     pub total_redeemed: [u8; 16],
     pub total_supplied: [u8; 16],
     pub total_withdrawn: [u8; 16],
+    pub total_retired: [u8; 16],
+
+    pub retire_settlement: u8,
+    pub rounding_mode: RoundingMode,
+    /// Taken off the redemption output, independent of rounding
+    pub haircut_bps: u16,
+    /// Layout version, zero for pools created before versioning
+    pub version: u8,
+    pub _padding4: [u8; 3],
+
+    pub reserve: Pubkey,
+
+    pub min_redeem_amount: u64,
+
+    pub custodian: Pubkey,
+
+    /// Manages this pool alongside the global admins, default meaning none
+    pub admin: Pubkey,
+
+    /// Caps what `supply`, `mint_and_supply` and `replenish` add per window, counted on the mint
+    /// side
+    pub supply_period_limit: PeriodLimit,
 
-    pub reserved: [u8; 256],
+    /// Display name for dashboards and CLIs, UTF-8 padded with zeros
+    pub label: [u8; 32],
+
+    /// Custodian taking over once `pending_custodian_at` is reached, default meaning none
+    pub pending_custodian: Pubkey,
+    pub pending_custodian_at: i64,
+
+    /// Redemption fee while the pool holds no settlement, see `utilization_fee_bps`
+    pub min_fee_bps: u16,
+    /// Redemption fee once the redemption liquidity is drained, zero meaning no fee
+    pub max_fee_bps: u16,
+
+    pub reserved: [u8; 4],
 }
 
 impl Default for Pool {
@@ -75,7 +166,23 @@ impl Default for Pool {
             total_redeemed: [0; 16],
             total_supplied: [0; 16],
             total_withdrawn: [0; 16],
-            reserved: [0; 256],
+            total_retired: [0; 16],
+            retire_settlement: 0,
+            rounding_mode: RoundingMode::Floor,
+            haircut_bps: 0,
+            version: 0,
+            _padding4: [0; 3],
+            reserve: Pubkey::default(),
+            min_redeem_amount: 0,
+            custodian: Pubkey::default(),
+            admin: Pubkey::default(),
+            supply_period_limit: PeriodLimit::default(),
+            label: [0; 32],
+            pending_custodian: Pubkey::default(),
+            pending_custodian_at: 0,
+            min_fee_bps: 0,
+            max_fee_bps: 0,
+            reserved: [0; 4],
         }
     }
 }
@@ -97,12 +204,209 @@ impl Pool {
         16 + // total_redeemed
         16 + // total_supplied
         16 + // total_withdrawn
-        256;
+        16 + // total_retired
+        1 + // retire_settlement
+        1 + // rounding_mode (enum)
+        2 + // haircut_bps
+        1 + // version
+        3 + // _padding4
+        32 + // reserve
+        8 + // min_redeem_amount
+        32 + // custodian
+        32 + // admin
+        PeriodLimit::MAX_SIZE + // supply_period_limit
+        32 + // label
+        32 + // pending_custodian
+        8 + // pending_custodian_at
+        2 + // min_fee_bps
+        2 + // max_fee_bps
+        4;
 
     pub fn is_active(&self) -> bool { self.status == PoolStatus::Active }
 
+    /// Steps the layout up to `POOL_VERSION`, a no-op once there.
+    pub fn migrate(&mut self) -> Result<()> {
+        require!(self.version <= POOL_VERSION, PSmError::UnsupportedVersion);
+        while self.version < POOL_VERSION {
+            match self.version {
+                // 0 -> 1: fields added so far were carved out of zeroed padding and `reserved`
+                0 => {},
+                _ => return err!(PSmError::UnsupportedVersion),
+            }
+            self.version += 1;
+        }
+
+        Ok(())
+    }
+
     pub fn set_status(&mut self, status: PoolStatus) { self.status = status; }
 
+    pub fn has_reserve(&self) -> bool { self.reserve != Pubkey::default() }
+
+    pub fn set_min_redeem_amount(&mut self, min_redeem_amount: u64) {
+        self.min_redeem_amount = min_redeem_amount;
+    }
+
+    pub fn has_custodian(&self) -> bool { self.custodian != Pubkey::default() }
+
+    /// Queues `custodian` to take over at `effective_at`, replacing any change still pending.
+    /// Takes over right away when `effective_at` has already passed.
+    pub fn set_custodian(&mut self, custodian: Pubkey, effective_at: i64, current_time: i64) {
+        if effective_at <= current_time {
+            self.custodian = custodian;
+            self.pending_custodian = Pubkey::default();
+            self.pending_custodian_at = 0;
+        } else {
+            self.pending_custodian = custodian;
+            self.pending_custodian_at = effective_at;
+        }
+    }
+
+    /// Hands over to the pending custodian once its change is due.
+    pub fn apply_pending_custodian(&mut self, current_time: i64) {
+        if self.pending_custodian != Pubkey::default() && self.pending_custodian_at <= current_time
+        {
+            self.custodian = self.pending_custodian;
+            self.pending_custodian = Pubkey::default();
+            self.pending_custodian_at = 0;
+        }
+    }
+
+    pub fn is_admin(&self, pubkey: &Pubkey) -> bool {
+        self.admin != Pubkey::default() && &self.admin == pubkey
+    }
+
+    pub fn set_admin(&mut self, admin: Pubkey) { self.admin = admin; }
+
+    pub fn set_rounding_mode(&mut self, rounding_mode: RoundingMode) {
+        self.rounding_mode = rounding_mode;
+    }
+
+    /// A zero `duration_seconds` lifts the limit.
+    pub fn set_supply_period_limit(
+        &mut self,
+        duration_seconds: u64,
+        max_supply_amount: u64,
+        current_time: i64,
+    ) -> Result<()> {
+        if duration_seconds == 0 {
+            self.supply_period_limit.reset();
+            return Ok(());
+        }
+
+        require!(
+            (MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(&duration_seconds)
+                && max_supply_amount > 0,
+            PSmError::InvalidPeriodLimit
+        );
+        self.supply_period_limit = PeriodLimit {
+            duration_seconds,
+            max_mint_amount: max_supply_amount,
+            window_start: current_time,
+            ..Default::default()
+        };
+
+        Ok(())
+    }
+
+    /// Counts `amount` against the supply window, rolling it first.
+    pub fn record_supply_in_window(&mut self, amount: u64, current_time: i64) -> Result<()> {
+        self.supply_period_limit.roll_window(current_time);
+        let available = self.supply_period_limit.remaining_mint_amount(current_time);
+        if amount > available {
+            msg!(
+                "pool supply limit: requested {}, available {}",
+                amount,
+                available
+            );
+            return err!(PSmError::SupplyLimitExceeded);
+        }
+        self.supply_period_limit.record_mint(amount);
+
+        Ok(())
+    }
+
+    /// The label up to its zero padding, `None` when it isn't valid UTF-8.
+    pub fn label(&self) -> Option<&str> {
+        let len = self
+            .label
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(self.label.len());
+        std::str::from_utf8(&self.label[..len]).ok()
+    }
+
+    pub fn set_label(&mut self, label: [u8; 32]) -> Result<()> {
+        self.label = label;
+        require!(self.label().is_some(), PSmError::BadInput);
+
+        Ok(())
+    }
+
+    pub fn set_haircut_bps(&mut self, haircut_bps: u16) { self.haircut_bps = haircut_bps; }
+
+    pub fn has_utilization_fee(&self) -> bool { self.max_fee_bps > 0 }
+
+    /// A zero `max_fee_bps` turns the fee off.
+    pub fn set_utilization_fee(&mut self, min_fee_bps: u16, max_fee_bps: u16) -> Result<()> {
+        require!(
+            min_fee_bps <= max_fee_bps && max_fee_bps < 10_000,
+            PSmError::BadInput
+        );
+        self.min_fee_bps = min_fee_bps;
+        self.max_fee_bps = max_fee_bps;
+
+        Ok(())
+    }
+
+    /// Fee on a redemption paying `settlement_amount` into a pool holding `settlement_balance`
+    /// with `redemption_balance` left to pay out. It scales from `min_fee_bps` to `max_fee_bps`
+    /// with utilization after the redemption: the settlement held against the most the pool can
+    /// hold, which is what it holds plus what its redemption liquidity can still buy.
+    pub fn utilization_fee_bps(
+        &self,
+        settlement_balance: u64,
+        settlement_amount: u64,
+        redemption_balance: u64,
+    ) -> Result<u16> {
+        if !self.has_utilization_fee() {
+            return Ok(0);
+        }
+
+        let settlement_held = normalize_amount(
+            settlement_balance.into(),
+            self.settlement_token_decimals,
+            self.redemption_token_decimals,
+        )?;
+        let settlement_in = normalize_amount(
+            settlement_amount.into(),
+            self.settlement_token_decimals,
+            self.redemption_token_decimals,
+        )?;
+        let capacity = settlement_held
+            .checked_add(redemption_balance.into())
+            .ok_or(PSmError::MathOverflow)?;
+        let used = settlement_held
+            .checked_add(settlement_in)
+            .ok_or(PSmError::MathOverflow)?;
+
+        compute_utilization_fee_bps(used, capacity, self.min_fee_bps, self.max_fee_bps)
+    }
+
+    pub fn is_retiring_settlement(&self) -> bool { self.retire_settlement == 1 }
+
+    pub fn set_retire_settlement(&mut self, retire_settlement: bool) {
+        self.retire_settlement = if retire_settlement { 1 } else { 0 };
+    }
+
+    pub fn total_redeemed(&self) -> u128 { u128::from_le_bytes(self.total_redeemed) }
+
+    pub fn total_supplied(&self) -> u128 { u128::from_le_bytes(self.total_supplied) }
+
+    pub fn total_withdrawn(&self) -> u128 { u128::from_le_bytes(self.total_withdrawn) }
+
+    pub fn total_retired(&self) -> u128 { u128::from_le_bytes(self.total_retired) }
+
     pub fn record_total_redeemed(&mut self, amount: u64) {
         let mut fake_u128 = u128::from_le_bytes(self.total_redeemed);
         fake_u128 += amount as u128;
@@ -121,29 +425,57 @@ impl Pool {
         self.total_withdrawn = fake_u128.to_le_bytes();
     }
 
+    pub fn record_total_retired(&mut self, amount: u64) {
+        let mut fake_u128 = u128::from_le_bytes(self.total_retired);
+        fake_u128 += amount as u128;
+        self.total_retired = fake_u128.to_le_bytes();
+    }
+
     pub fn record_withdraw(&mut self, amount: u64) { self.record_total_withdrawn(amount); }
 
     pub fn record_redeem(&mut self, amount: u64) { self.record_total_redeemed(amount); }
 
     pub fn record_supply(&mut self, amount: u64) { self.record_total_supplied(amount); }
 
-    pub fn can_redeem(&mut self) -> Result<bool> {
+    pub fn record_retire(&mut self, amount: u64) { self.record_total_retired(amount); }
+
+    pub fn can_redeem(&self, amount: u64) -> Result<bool> {
         if !self.is_active() {
             return err!(PSmError::PoolNotActive);
         }
 
+        if amount < self.min_redeem_amount {
+            return err!(PSmError::BelowMinRedeemAmount);
+        }
+
         Ok(true)
     }
 
-    pub fn can_withdraw(&mut self) -> Result<bool> {
+    pub fn can_withdraw(&self) -> Result<bool> {
         if !self.is_active() {
             return err!(PSmError::PoolNotActive);
         }
 
+        if self.is_retiring_settlement() {
+            return err!(PSmError::SettlementRetired);
+        }
+
+        Ok(true)
+    }
+
+    pub fn can_retire(&self) -> Result<bool> {
+        if !self.is_active() {
+            return err!(PSmError::PoolNotActive);
+        }
+
+        if !self.is_retiring_settlement() {
+            return err!(PSmError::SettlementNotRetired);
+        }
+
         Ok(true)
     }
 
-    pub fn can_supply(&mut self) -> Result<bool> {
+    pub fn can_supply(&self) -> Result<bool> {
         if !self.is_active() {
             return err!(PSmError::PoolNotActive);
         }
@@ -151,3 +483,65 @@ impl Pool {
         Ok(true)
     }
 }
+
+/// Decoded pool state returned by the `view_pool` instruction.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct PoolView {
+    pub redemption_mint: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub redemption_token_account: Pubkey,
+    pub settlement_token_account: Pubkey,
+    pub redemption_token_decimals: u8,
+    pub settlement_token_decimals: u8,
+    pub status: PoolStatus,
+    pub rounding_mode: RoundingMode,
+    pub haircut_bps: u16,
+    pub min_fee_bps: u16,
+    pub max_fee_bps: u16,
+    pub version: u8,
+    pub retire_settlement: bool,
+    pub min_redeem_amount: u64,
+    pub reserve: Pubkey,
+    pub custodian: Pubkey,
+    pub pending_custodian: Pubkey,
+    pub pending_custodian_at: i64,
+    pub admin: Pubkey,
+    pub supply_period_limit: PeriodLimit,
+    pub label: String,
+    pub total_redeemed: u128,
+    pub total_supplied: u128,
+    pub total_withdrawn: u128,
+    pub total_retired: u128,
+}
+
+impl From<&Pool> for PoolView {
+    fn from(pool: &Pool) -> Self {
+        PoolView {
+            redemption_mint: pool.redemption_mint,
+            settlement_mint: pool.settlement_mint,
+            redemption_token_account: pool.redemption_token_account,
+            settlement_token_account: pool.settlement_token_account,
+            redemption_token_decimals: pool.redemption_token_decimals,
+            settlement_token_decimals: pool.settlement_token_decimals,
+            status: pool.status,
+            rounding_mode: pool.rounding_mode,
+            haircut_bps: pool.haircut_bps,
+            min_fee_bps: pool.min_fee_bps,
+            max_fee_bps: pool.max_fee_bps,
+            version: pool.version,
+            retire_settlement: pool.is_retiring_settlement(),
+            min_redeem_amount: pool.min_redeem_amount,
+            reserve: pool.reserve,
+            custodian: pool.custodian,
+            pending_custodian: pool.pending_custodian,
+            pending_custodian_at: pool.pending_custodian_at,
+            admin: pool.admin,
+            supply_period_limit: pool.supply_period_limit,
+            label: pool.label().unwrap_or_default().to_string(),
+            total_redeemed: pool.total_redeemed(),
+            total_supplied: pool.total_supplied(),
+            total_withdrawn: pool.total_withdrawn(),
+            total_retired: pool.total_retired(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+const_assert_eq!(Reserve::MAX_SIZE, size_of::<Reserve>());
+
+#[constant]
+pub const RESERVE_PREFIX: &[u8; 7] = b"reserve";
+#[constant]
+pub const RESERVE_REDEMPTION_TOKEN_ACCOUNT_PREFIX: &[u8; 32] = b"reserve_redemption_token_account";
+
+#[account(zero_copy)]
+pub struct Reserve {
+    pub redemption_mint: Pubkey,
+    pub redemption_token_account: Pubkey,
+    pub redemption_token_program: Pubkey,
+
+    pub redemption_token_decimals: u8,
+    pub bump: u8,
+    pub _padding1: [u8; 6],
+
+    pub num_pools: u64,
+
+    pub reserved: [u8; 256],
+}
+
+impl Default for Reserve {
+    fn default() -> Self {
+        Reserve {
+            redemption_mint: Pubkey::default(),
+            redemption_token_account: Pubkey::default(),
+            redemption_token_program: Pubkey::default(),
+            redemption_token_decimals: 0,
+            bump: 0,
+            _padding1: [0; 6],
+            num_pools: 0,
+            reserved: [0; 256],
+        }
+    }
+}
+
+impl Reserve {
+    pub const MAX_SIZE: usize = 32 + // redemption_mint
+        32 + // redemption_token_account
+        32 + // redemption_token_program
+        1 + // redemption_token_decimals
+        1 + // bump
+        6 + // _padding1
+        8 + // num_pools
+        256;
+}
@@ -0,0 +1,52 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use static_assertions::const_assert_eq;
+
+const_assert_eq!(PendingWithdrawal::MAX_SIZE, size_of::<PendingWithdrawal>());
+
+#[constant]
+pub const PENDING_WITHDRAWAL_PREFIX: &[u8; 18] = b"pending_withdrawal";
+
+#[account(zero_copy)]
+pub struct PendingWithdrawal {
+    pub pool: Pubkey,
+    pub proposer: Pubkey,
+    pub destination: Pubkey,
+
+    pub amount: u64,
+    pub executable_at: i64,
+
+    pub bump: u8,
+    pub _padding1: [u8; 7],
+
+    pub reserved: [u8; 64],
+}
+
+impl Default for PendingWithdrawal {
+    fn default() -> Self {
+        PendingWithdrawal {
+            pool: Pubkey::default(),
+            proposer: Pubkey::default(),
+            destination: Pubkey::default(),
+            amount: 0,
+            executable_at: 0,
+            bump: 0,
+            _padding1: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl PendingWithdrawal {
+    pub const MAX_SIZE: usize = 32 + // pool
+        32 + // proposer
+        32 + // destination
+        8 + // amount
+        8 + // executable_at
+        1 + // bump
+        7 + // _padding1
+        64;
+
+    pub fn is_executable(&self, now: i64) -> bool { now >= self.executable_at }
+}
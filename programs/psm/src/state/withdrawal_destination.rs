@@ -0,0 +1,95 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use static_assertions::const_assert_eq;
+
+use crate::error::PSmError;
+
+const_assert_eq!(
+    WithdrawalDestinationList::MAX_SIZE,
+    size_of::<WithdrawalDestinationList>()
+);
+
+#[constant]
+pub const WITHDRAWAL_DESTINATION_LIST_PREFIX: &[u8; 27] = b"withdrawal_destination_list";
+pub const MAX_WITHDRAWAL_DESTINATIONS: usize = 16;
+
+#[repr(C)]
+#[derive(Default, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+pub struct WithdrawalDestination {
+    /// Owner of the token accounts settlement funds may be sent to
+    pub owner: Pubkey,
+    /// Approval only applies from this timestamp, one withdrawal delay after it was added
+    pub active_from: i64,
+}
+
+unsafe impl Pod for WithdrawalDestination {}
+unsafe impl Zeroable for WithdrawalDestination {}
+
+/// Owners settlement funds may leave a pool to once the config restricts withdrawal destinations.
+#[account(zero_copy)]
+pub struct WithdrawalDestinationList {
+    pub destinations: [WithdrawalDestination; MAX_WITHDRAWAL_DESTINATIONS],
+
+    pub bump: u8,
+    pub _padding1: [u8; 7],
+
+    pub reserved: [u8; 64],
+}
+
+impl Default for WithdrawalDestinationList {
+    fn default() -> Self {
+        WithdrawalDestinationList {
+            destinations: [WithdrawalDestination::default(); MAX_WITHDRAWAL_DESTINATIONS],
+            bump: 0,
+            _padding1: [0; 7],
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl WithdrawalDestinationList {
+    pub const MAX_SIZE: usize = (32 + 8) * MAX_WITHDRAWAL_DESTINATIONS + // destinations
+        1 + // bump
+        7 + // _padding1
+        64;
+
+    pub fn contains(&self, owner: &Pubkey) -> bool {
+        *owner != Pubkey::default()
+            && self
+                .destinations
+                .iter()
+                .any(|destination| destination.owner == *owner)
+    }
+
+    pub fn is_approved(&self, owner: &Pubkey, current_time: i64) -> bool {
+        *owner != Pubkey::default()
+            && self.destinations.iter().any(|destination| {
+                destination.owner == *owner && current_time >= destination.active_from
+            })
+    }
+
+    pub fn add(&mut self, owner: &Pubkey, active_from: i64) -> Result<()> {
+        for i in 0..MAX_WITHDRAWAL_DESTINATIONS {
+            if self.destinations[i].owner == Pubkey::default() {
+                self.destinations[i] = WithdrawalDestination {
+                    owner: *owner,
+                    active_from,
+                };
+                return Ok(());
+            }
+        }
+        err!(PSmError::WithdrawalDestinationListFull)
+    }
+
+    pub fn remove(&mut self, owner: &Pubkey) -> Result<()> {
+        for i in 0..MAX_WITHDRAWAL_DESTINATIONS {
+            if &self.destinations[i].owner == owner {
+                self.destinations[i] = WithdrawalDestination::default();
+                return Ok(());
+            }
+        }
+        err!(PSmError::SomeError)
+    }
+}
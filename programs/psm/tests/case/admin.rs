@@ -7,8 +7,10 @@ use crate::common::{
     derivation::find_config,
     faciliter::init_program,
     instructions::{
-        create_add_admin_instruction, create_remove_admin_instruction,
-        create_update_pause_flag_instruction,
+        create_add_admin_instruction, create_guardian_pause_instruction,
+        create_manage_config_batch_instruction, create_remove_admin_instruction,
+        create_set_guardian_instruction, create_update_pause_flag_instruction,
+        ManageConfigInstructionAccounts,
     },
 };
 
@@ -171,3 +173,131 @@ async fn add_duplicate_admin_fails() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn manage_config_batch_success() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    init_program(&test_f).await?;
+
+    let payer = test_f.deployer.pubkey();
+    let new_admin = Keypair::new();
+    let withdrawal_delay = 3600;
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_manage_config_batch_instruction(
+                ManageConfigInstructionAccounts { admin: payer },
+                vec![
+                    psm::instructions::ConfigManagementAction::AddAdmin {
+                        admin: new_admin.pubkey(),
+                    },
+                    psm::instructions::ConfigManagementAction::UpdatePauseFlag { is_paused: true },
+                    psm::instructions::ConfigManagementAction::SetWithdrawalDelay {
+                        withdrawal_delay,
+                    },
+                ],
+            )],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(
+        config.is_admin(&new_admin.pubkey()),
+        "New admin should be added"
+    );
+    assert!(config.is_paused(), "Protocol should be paused");
+    assert_eq!(
+        config.withdrawal_delay, withdrawal_delay,
+        "Withdrawal delay should be set"
+    );
+
+    // A failing action reverts the whole batch
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_manage_config_batch_instruction(
+                ManageConfigInstructionAccounts { admin: payer },
+                vec![
+                    psm::instructions::ConfigManagementAction::UpdatePauseFlag { is_paused: false },
+                    psm::instructions::ConfigManagementAction::AddAdmin {
+                        admin: new_admin.pubkey(),
+                    },
+                ],
+            )],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+
+        let result = ctx.banks_client.process_transaction(tx).await;
+        assert!(result.is_err(), "Batch with a duplicate admin should fail");
+    }
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(config.is_paused(), "Protocol should still be paused");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn guardian_can_only_pause() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    init_program(&test_f).await?;
+
+    let payer = test_f.deployer.pubkey();
+    let guardian = Keypair::new();
+    test_f.fund_account(&guardian.pubkey()).await;
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_set_guardian_instruction(payer, guardian.pubkey())],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_guardian_pause_instruction(guardian.pubkey())],
+            Some(&guardian.pubkey()),
+            &[&guardian],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(config.is_paused(), "Guardian should pause the protocol");
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_update_pause_flag_instruction(
+                guardian.pubkey(),
+                false,
+            )],
+            Some(&guardian.pubkey()),
+            &[&guardian],
+            last_blockhash,
+        );
+        let result = ctx.banks_client.process_transaction(tx).await;
+        assert!(result.is_err(), "Guardian should not be able to unpause");
+    }
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert!(config.is_paused(), "Protocol should still be paused");
+
+    Ok(())
+}
@@ -0,0 +1,368 @@
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use fixtures::test::TestFixture;
+use jupusd_client::jup_stable::derivation::find_vault_token_account;
+use solana_instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::{
+    constants::{USDC_DECIMALS, USDC_MINT},
+    derivation::{
+        find_pool, find_pool_redemption_token_account, find_pool_settlement_token_account,
+    },
+    faciliter::{
+        create_active_pool, create_associated_token_account, mint_jupusd,
+        setup_jup_stable_test_context, view_pool,
+    },
+    instructions::{
+        create_mint_and_supply_instruction, create_replenish_instruction,
+        create_retire_settlement_instruction, create_set_retire_settlement_instruction,
+        create_supply_from_treasury_instruction, create_update_pause_flag_instruction,
+        MintAndSupplyInstructionAccounts, ReplenishInstructionAccounts,
+        RetireSettlementInstructionAccounts, SupplyFromTreasuryInstructionAccounts,
+    },
+};
+
+/// Sends `ix` signed by `signer` and asserts it fails with the PSM error `code`.
+async fn assert_fails_with(
+    test_f: &TestFixture,
+    ix: Instruction,
+    signer: &Keypair,
+    code: &str,
+) -> anyhow::Result<()> {
+    let tx = test_f.tx().ixs(&[ix]).signers(&[signer]).build().await?;
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    assert!(outcome.result.is_err());
+    let error = format!("Error Code: {code}.");
+    assert!(outcome
+        .metadata
+        .map(|metadata| metadata.log_messages)
+        .unwrap_or_default()
+        .iter()
+        .any(|log| log.contains(&error)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn retire_settlement_redeems_jupusd_into_the_redemption_side() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let stable_context = setup_jup_stable_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+    let lp_mint = stable_context.lp_mint;
+
+    // Users pay jupUSD into this pool for USDC, and the admin retires what they paid
+    create_active_pool(&test_f, USDC_MINT, lp_mint).await?;
+    test_f
+        .send_ixs(
+            &[create_set_retire_settlement_instruction(
+                deployer, USDC_MINT, lp_mint, true,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let amount = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    mint_jupusd(&test_f, &stable_context, amount).await?;
+    let pool = find_pool(&USDC_MINT, &lp_mint);
+    let settlement_token_account = find_pool_settlement_token_account(&pool);
+    let redemption_token_account = find_pool_redemption_token_account(&pool);
+    test_f
+        .send_ixs(
+            &[spl_token::instruction::transfer(
+                &spl_token::ID,
+                &get_associated_token_address(&stable_context.user.pubkey(), &lp_mint),
+                &settlement_token_account,
+                &stable_context.user.pubkey(),
+                &[],
+                amount,
+            )?],
+            &[&stable_context.user],
+        )
+        .await?;
+    // The minted collateral went to the custodian, jup-stable redeems from its vault
+    test_f
+        .mint_tokens(&find_vault_token_account(&USDC_MINT), amount)
+        .await;
+
+    let retire_ix = |min_amount_out| {
+        create_retire_settlement_instruction(
+            RetireSettlementInstructionAccounts {
+                admin: deployer,
+                redemption_mint: USDC_MINT,
+                settlement_mint: lp_mint,
+                redemption_token_program: spl_token::ID,
+                settlement_token_program: spl_token::ID,
+                remaining_accounts: vec![stable_context.oracle],
+            },
+            amount,
+            min_amount_out,
+        )
+    };
+
+    // Asking for more than the 1:1 redemption pays out fails and retires nothing
+    let result = test_f
+        .send_ixs(&[retire_ix(amount + 1)], &[&test_f.deployer])
+        .await;
+    assert!(result.is_err(), "Retiring below min_amount_out should fail");
+    let settlement: TokenAccount = test_f.load_and_deserialize(&settlement_token_account).await;
+    assert_eq!(settlement.amount, amount);
+
+    let supply_before = test_f.load_and_deserialize::<Mint>(&lp_mint).await.supply;
+    test_f
+        .send_ixs(&[retire_ix(amount)], &[&test_f.deployer])
+        .await?;
+
+    let settlement: TokenAccount = test_f.load_and_deserialize(&settlement_token_account).await;
+    assert_eq!(settlement.amount, 0);
+    let redemption: TokenAccount = test_f.load_and_deserialize(&redemption_token_account).await;
+    assert_eq!(redemption.amount, amount);
+    let supply_after = test_f.load_and_deserialize::<Mint>(&lp_mint).await.supply;
+    assert_eq!(supply_after, supply_before - amount);
+
+    let pool_view = view_pool(&test_f, USDC_MINT, lp_mint).await?;
+    assert_eq!(pool_view.total_retired, amount as u128);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn replenish_mints_jupusd_from_the_settlement_side() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let stable_context = setup_jup_stable_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+    let lp_mint = stable_context.lp_mint;
+
+    // Users pay USDC into this pool for jupUSD, and the admin turns their USDC into more jupUSD
+    create_active_pool(&test_f, lp_mint, USDC_MINT).await?;
+    let pool = find_pool(&lp_mint, &USDC_MINT);
+    let settlement_token_account = find_pool_settlement_token_account(&pool);
+    let redemption_token_account = find_pool_redemption_token_account(&pool);
+    let amount = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f.mint_tokens(&settlement_token_account, amount).await;
+
+    let replenish_ix = |admin| {
+        create_replenish_instruction(
+            ReplenishInstructionAccounts {
+                admin,
+                redemption_mint: lp_mint,
+                settlement_mint: USDC_MINT,
+                redemption_token_program: spl_token::ID,
+                settlement_token_program: spl_token::ID,
+                jup_stable_custodian: stable_context.custodian,
+                remaining_accounts: vec![stable_context.oracle],
+            },
+            amount,
+            amount,
+        )
+    };
+
+    // Only admins replenish
+    let stranger = Keypair::new();
+    test_f.fund_account(&stranger.pubkey()).await;
+    assert_fails_with(
+        &test_f,
+        replenish_ix(stranger.pubkey()),
+        &stranger,
+        "NotAuthorized",
+    )
+    .await?;
+
+    // Nor while the protocol is paused
+    test_f
+        .send_ixs(&[create_update_pause_flag_instruction(deployer, true)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+    assert_fails_with(
+        &test_f,
+        replenish_ix(deployer),
+        &test_f.deployer,
+        "ProtocolPaused",
+    )
+    .await?;
+    test_f
+        .send_ixs(&[create_update_pause_flag_instruction(deployer, false)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+
+    let supply_before = test_f.load_and_deserialize::<Mint>(&lp_mint).await.supply;
+    test_f
+        .send_ixs(&[replenish_ix(deployer)], &[&test_f.deployer])
+        .await?;
+
+    // The USDC moved to the jup-stable custodian and the minted jupUSD to the redemption side
+    let settlement: TokenAccount = test_f.load_and_deserialize(&settlement_token_account).await;
+    assert_eq!(settlement.amount, 0);
+    let redemption: TokenAccount = test_f.load_and_deserialize(&redemption_token_account).await;
+    assert_eq!(redemption.amount, amount);
+    let custodian: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &stable_context.custodian,
+            &USDC_MINT,
+        ))
+        .await;
+    assert_eq!(custodian.amount, amount);
+    let supply_after = test_f.load_and_deserialize::<Mint>(&lp_mint).await.supply;
+    assert_eq!(supply_after, supply_before + amount);
+
+    let pool_view = view_pool(&test_f, lp_mint, USDC_MINT).await?;
+    assert_eq!(pool_view.total_withdrawn, amount as u128);
+    assert_eq!(pool_view.total_supplied, amount as u128);
+    assert_eq!(pool_view.supply_period_limit.minted_amount, amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mint_and_supply_mints_jupusd_into_the_redemption_side() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let stable_context = setup_jup_stable_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+    let lp_mint = stable_context.lp_mint;
+
+    create_active_pool(&test_f, lp_mint, USDC_MINT).await?;
+    let pool = find_pool(&lp_mint, &USDC_MINT);
+    let settlement_token_account = find_pool_settlement_token_account(&pool);
+    let redemption_token_account = find_pool_redemption_token_account(&pool);
+
+    let amount = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    create_associated_token_account(&test_f, &deployer, &USDC_MINT).await?;
+    let admin_collateral = get_associated_token_address(&deployer, &USDC_MINT);
+    test_f.mint_tokens(&admin_collateral, 2 * amount).await;
+
+    let mint_and_supply_ix = || {
+        create_mint_and_supply_instruction(
+            MintAndSupplyInstructionAccounts {
+                admin: deployer,
+                redemption_mint: lp_mint,
+                settlement_mint: USDC_MINT,
+                redemption_token_program: spl_token::ID,
+                settlement_token_program: spl_token::ID,
+                jup_stable_custodian: stable_context.custodian,
+                remaining_accounts: vec![stable_context.oracle],
+            },
+            amount,
+            amount,
+        )
+    };
+
+    // Nothing is minted while the protocol is paused
+    test_f
+        .send_ixs(&[create_update_pause_flag_instruction(deployer, true)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+    assert_fails_with(
+        &test_f,
+        mint_and_supply_ix(),
+        &test_f.deployer,
+        "ProtocolPaused",
+    )
+    .await?;
+    test_f
+        .send_ixs(&[create_update_pause_flag_instruction(deployer, false)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+
+    let supply_before = test_f.load_and_deserialize::<Mint>(&lp_mint).await.supply;
+    test_f
+        .send_ixs(&[mint_and_supply_ix()], &[&test_f.deployer])
+        .await?;
+
+    // The admin's USDC passed through the settlement side to the jup-stable custodian
+    let admin: TokenAccount = test_f.load_and_deserialize(&admin_collateral).await;
+    assert_eq!(admin.amount, amount);
+    let settlement: TokenAccount = test_f.load_and_deserialize(&settlement_token_account).await;
+    assert_eq!(settlement.amount, 0);
+    let custodian: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address(
+            &stable_context.custodian,
+            &USDC_MINT,
+        ))
+        .await;
+    assert_eq!(custodian.amount, amount);
+
+    // The jupUSD minted for it landed on the redemption side and counts as supplied
+    let redemption: TokenAccount = test_f.load_and_deserialize(&redemption_token_account).await;
+    assert_eq!(redemption.amount, amount);
+    let supply_after = test_f.load_and_deserialize::<Mint>(&lp_mint).await.supply;
+    assert_eq!(supply_after, supply_before + amount);
+
+    let pool_view = view_pool(&test_f, lp_mint, USDC_MINT).await?;
+    assert_eq!(pool_view.total_supplied, amount as u128);
+    assert_eq!(pool_view.total_withdrawn, 0);
+    assert_eq!(pool_view.supply_period_limit.minted_amount, amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn supply_from_treasury_moves_operator_jupusd_into_the_pool() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let stable_context = setup_jup_stable_test_context(&test_f).await?;
+    let deployer = test_f.deployer.pubkey();
+    let lp_mint = stable_context.lp_mint;
+
+    create_active_pool(&test_f, lp_mint, USDC_MINT).await?;
+    let pool = find_pool(&lp_mint, &USDC_MINT);
+    let redemption_token_account = find_pool_redemption_token_account(&pool);
+
+    // The deployer is a jup-stable operator holding every role, the user isn't an operator
+    let amount = 100 * 10_u64.pow(USDC_DECIMALS.into());
+    create_associated_token_account(&test_f, &deployer, &lp_mint).await?;
+    let treasury_token_account = get_associated_token_address(&deployer, &lp_mint);
+    test_f.mint_tokens(&treasury_token_account, amount).await;
+    let user = &stable_context.user;
+    let user_token_account = get_associated_token_address(&user.pubkey(), &lp_mint);
+    test_f.mint_tokens(&user_token_account, amount).await;
+
+    let supply_from_treasury_ix = |operator_authority, treasury_token_account| {
+        create_supply_from_treasury_instruction(
+            SupplyFromTreasuryInstructionAccounts {
+                operator_authority,
+                treasury_token_account,
+                redemption_mint: lp_mint,
+                settlement_mint: USDC_MINT,
+                redemption_token_program: spl_token::ID,
+            },
+            amount,
+        )
+    };
+
+    let result = test_f
+        .send_ixs(
+            &[supply_from_treasury_ix(user.pubkey(), user_token_account)],
+            &[user],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Only jup-stable operators supply from a treasury"
+    );
+
+    test_f
+        .send_ixs(
+            &[supply_from_treasury_ix(deployer, treasury_token_account)],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let treasury: TokenAccount = test_f.load_and_deserialize(&treasury_token_account).await;
+    assert_eq!(treasury.amount, 0);
+    let redemption: TokenAccount = test_f.load_and_deserialize(&redemption_token_account).await;
+    assert_eq!(redemption.amount, amount);
+
+    let pool_view = view_pool(&test_f, lp_mint, USDC_MINT).await?;
+    assert_eq!(pool_view.total_supplied, amount as u128);
+    assert_eq!(pool_view.supply_period_limit.minted_amount, amount);
+
+    Ok(())
+}
@@ -0,0 +1,8 @@
+use fixtures::idl::check_idl_layouts;
+
+/// Needs the anchor CLI, so it runs in its own CI job with `--ignored`
+#[test]
+#[ignore]
+fn idl_layouts_match_snapshot() -> anyhow::Result<()> {
+    check_idl_layouts(env!("CARGO_MANIFEST_DIR"))
+}
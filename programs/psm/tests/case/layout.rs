@@ -0,0 +1,104 @@
+//! Decodes a config and a pool dumped with the deployed layout, so fields carved out of `reserved`
+//! have to keep reading zero on accounts that predate them.
+
+use fixtures::utils::decode_zero_copy;
+use psm::state::{
+    config::{Config, CONFIG_VERSION},
+    pool::{Pool, PoolStatus, RoundingMode, POOL_VERSION},
+};
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn deployed_pool_layout() {
+    let pool: Pool = decode_zero_copy(include_bytes!("../fixtures/layout/pool.bin"));
+
+    assert_eq!(pool.redemption_mint, Pubkey::new_from_array([12; 32]));
+    assert_eq!(pool.settlement_mint, Pubkey::new_from_array([13; 32]));
+    assert_eq!(
+        pool.redemption_token_account,
+        Pubkey::new_from_array([14; 32])
+    );
+    assert_eq!(
+        pool.settlement_token_account,
+        Pubkey::new_from_array([15; 32])
+    );
+    assert_eq!(
+        pool.redemption_token_program,
+        Pubkey::new_from_array([16; 32])
+    );
+    assert_eq!(
+        pool.settlement_token_program,
+        Pubkey::new_from_array([17; 32])
+    );
+    assert_eq!(pool.redemption_token_decimals, 6);
+    assert_eq!(pool.settlement_token_decimals, 6);
+    assert_eq!(pool.status, PoolStatus::Active);
+    assert_eq!(pool.bump, 251);
+    assert_eq!(pool.total_redeemed(), 7_000_000);
+    assert_eq!(pool.total_supplied(), 6_000_000);
+    assert_eq!(pool.total_withdrawn(), 1_000_000);
+
+    assert_eq!(pool.total_retired(), 0);
+    assert!(!pool.is_retiring_settlement());
+    assert_eq!(pool.rounding_mode, RoundingMode::Floor);
+    assert_eq!(pool.haircut_bps, 0);
+    assert_eq!(pool.version, 0);
+    assert_eq!(pool.reserve, Pubkey::default());
+    assert_eq!(pool.min_redeem_amount, 0);
+    assert_eq!(pool.custodian, Pubkey::default());
+    assert_eq!(pool.admin, Pubkey::default());
+    assert_eq!(pool.supply_period_limit.duration_seconds, 0);
+    assert_eq!(pool.supply_period_limit.max_mint_amount, 0);
+    assert_eq!(pool.supply_period_limit.minted_amount, 0);
+    assert_eq!(pool.label, [0; 32]);
+    assert_eq!(pool.pending_custodian, Pubkey::default());
+    assert_eq!(pool.pending_custodian_at, 0);
+    assert_eq!(pool.min_fee_bps, 0);
+    assert_eq!(pool.max_fee_bps, 0);
+    assert!(!pool.has_utilization_fee());
+}
+
+#[test]
+fn deployed_pool_migrates_to_current_version() {
+    let mut pool: Pool = decode_zero_copy(include_bytes!("../fixtures/layout/pool.bin"));
+
+    pool.migrate().unwrap();
+    assert_eq!(pool.version, POOL_VERSION);
+    assert_eq!(pool.total_supplied(), 6_000_000);
+
+    pool.migrate().unwrap();
+    assert_eq!(pool.version, POOL_VERSION);
+
+    pool.version = POOL_VERSION + 1;
+    assert!(pool.migrate().is_err());
+}
+
+#[test]
+fn deployed_config_layout() {
+    // The deployed config predates `reserved`; `migrate` reallocates it with zeroed bytes
+    let mut data = include_bytes!("../fixtures/layout/config.bin").to_vec();
+    assert_eq!(data.len(), 8 + 552);
+    data.resize(8 + Config::MAX_SIZE, 0);
+    let mut config: Config = decode_zero_copy(&data);
+
+    assert!(config.is_admin(&Pubkey::new_from_array([1; 32])));
+    assert_eq!(config.num_admins(), 1);
+    assert_eq!(config.authority, Pubkey::new_from_array([2; 32]));
+    assert!(!config.is_paused());
+    assert_eq!(config.authority_bump, 254);
+    assert_eq!(config.config_bump, 253);
+
+    assert!(!config.is_permissionless_pool_creation());
+    assert!(!config.restricts_withdrawal_destinations());
+    assert_eq!(config.version, 0);
+    assert_eq!(config.withdrawal_delay, 0);
+    assert_eq!(config.pausers, [Pubkey::default(); 4]);
+    assert_eq!(config.guardian, Pubkey::default());
+    assert_eq!(config.pool_creation_fee, 0);
+    assert_eq!(config.total_pool_creation_fees, 0);
+    assert_eq!(config.withdrawal_delay_locked_until, 0);
+    assert_eq!(config.reserved, [0; 256]);
+
+    config.migrate().unwrap();
+    assert_eq!(config.version, CONFIG_VERSION);
+}
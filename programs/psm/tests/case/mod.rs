@@ -1,4 +1,8 @@
 mod admin;
+mod cpi;
+mod idl;
 mod init;
+mod layout;
 mod pool;
+mod token2022;
 mod user;
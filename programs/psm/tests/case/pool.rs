@@ -1,16 +1,55 @@
+use std::mem::offset_of;
+
+use anchor_lang::prelude::Rent;
 use anchor_spl::token_interface::TokenAccount;
-use fixtures::test::TestFixture;
-use psm::state::pool::{Pool, PoolStatus};
+use fixtures::{event::logged_events, test::TestFixture};
+use psm::{
+    event::WithdrawalCancelledEvent,
+    instructions::{AllowedMintListAction, WithdrawalDestinationListAction},
+    state::{
+        config::{Config, CONFIG_VERSION, MIN_CUSTODIAN_DELAY_SECONDS},
+        pool::{Pool, PoolStatus, POOL_VERSION},
+        reserve::Reserve,
+    },
+};
 use solana_program_test::*;
-use solana_sdk::{signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 
 use crate::common::{
-    constants::{USDC_DECIMALS, USDC_MINT, USDT_DECIMALS, USDT_MINT},
+    constants::{MSOL_MINT, USDC_DECIMALS, USDC_MINT, USDT_DECIMALS, USDT_MINT},
     derivation::{
-        find_pool, find_pool_redemption_token_account, find_pool_settlement_token_account,
+        find_config, find_pending_withdrawal, find_pool, find_pool_redemption_token_account,
+        find_pool_settlement_token_account, find_reserve, find_reserve_redemption_token_account,
+        find_treasury,
+    },
+    faciliter::{
+        create_active_pool, create_associated_token_account, create_pool, init_program,
+        setup_full_test_context, view_pool, withdraw_from_pool,
+    },
+    instructions::{
+        create_activate_pool_instruction, create_add_pauser_instruction,
+        create_attach_reserve_instruction, create_cancel_withdrawal_instruction,
+        create_create_allowed_mint_list_instruction, create_create_pool_instruction,
+        create_create_reserve_instruction, create_create_withdrawal_destination_list_instruction,
+        create_detach_reserve_instruction, create_execute_withdrawal_instruction,
+        create_manage_allowed_mint_list_instruction,
+        create_manage_withdrawal_destination_list_instruction, create_migrate_instruction,
+        create_migrate_pool_instruction, create_permissionless_create_pool_instruction,
+        create_propose_withdrawal_instruction, create_restricted_sweep_settlement_instruction,
+        create_restricted_withdraw_instruction, create_set_custodian_instruction,
+        create_set_haircut_instruction, create_set_min_redeem_amount_instruction,
+        create_set_permissionless_pool_creation_instruction, create_set_pool_admin_instruction,
+        create_set_pool_creation_fee_instruction, create_set_pool_label_instruction,
+        create_set_pool_status_instruction, create_set_retire_settlement_instruction,
+        create_set_supply_period_limit_instruction, create_set_withdrawal_delay_instruction,
+        create_sweep_settlement_instruction, create_update_pause_flag_instruction,
+        create_withdraw_treasury_instruction, CreatePoolInstructionAccounts,
+        MigratePoolInstructionAccounts, WithdrawInstructionAccounts,
     },
-    faciliter::{create_pool, setup_full_test_context},
-    instructions::create_set_pool_status_instruction,
 };
 
 #[tokio::test]
@@ -58,12 +97,12 @@ async fn create_pool_success() -> anyhow::Result<()> {
     );
     assert!(pool.bump != 0, "Pool should have non-zero bump");
     assert_eq!(
-        u128::from_le_bytes(pool.total_redeemed),
+        pool.total_redeemed(),
         0,
         "Pool should have zero total redeemed initially"
     );
     assert_eq!(
-        u128::from_le_bytes(pool.total_supplied),
+        pool.total_supplied(),
         0,
         "Pool should have zero total supplied initially"
     );
@@ -118,9 +157,38 @@ async fn set_pool_status_success() -> anyhow::Result<()> {
             last_blockhash,
         );
 
-        ctx.banks_client.process_transaction(tx).await?;
+        let result = ctx.banks_client.process_transaction(tx).await;
+        assert!(
+            result.is_err(),
+            "Pools should only be activated through activate_pool"
+        );
     }
 
+    let result = test_f
+        .send_ixs(
+            &[create_activate_pool_instruction(
+                payer, USDC_MINT, USDT_MINT,
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Activation should require a supply period limit"
+    );
+
+    test_f
+        .send_ixs(
+            &[
+                create_set_supply_period_limit_instruction(
+                    payer, USDC_MINT, USDT_MINT, 3600, 1_000_000,
+                ),
+                create_activate_pool_instruction(payer, USDC_MINT, USDT_MINT),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
     let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
     assert_eq!(pool.status, PoolStatus::Active, "Pool should be active");
 
@@ -169,3 +237,1250 @@ async fn set_pool_status_success() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn retire_settlement_blocks_withdraw() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let payer = test_f.deployer.pubkey();
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_set_retire_settlement_instruction(
+                payer, USDC_MINT, USDT_MINT, true,
+            )],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert!(
+        pool.is_retiring_settlement(),
+        "Pool should retire settlement tokens"
+    );
+
+    create_associated_token_account(&test_f, &payer, &USDT_MINT).await?;
+    let withdraw_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_settlement_token_account(&pool_address),
+            withdraw_amount,
+        )
+        .await;
+
+    let result = withdraw_from_pool(
+        &test_f,
+        &test_f.deployer,
+        USDC_MINT,
+        USDT_MINT,
+        withdraw_amount,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Withdraw should fail while settlement tokens are retired"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_pool_success() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+    test_f.replicate_account_from_mainnet(&MSOL_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let payer = test_f.deployer.pubkey();
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let new_pool_address = find_pool(&USDC_MINT, &MSOL_MINT);
+    let custodian = Keypair::new().pubkey();
+    let pool_admin = Keypair::new().pubkey();
+
+    test_f
+        .send_ixs(
+            &[
+                create_set_haircut_instruction(payer, USDC_MINT, USDT_MINT, 25),
+                create_set_min_redeem_amount_instruction(
+                    payer,
+                    USDC_MINT,
+                    USDT_MINT,
+                    10 * 10_u64.pow(USDT_DECIMALS.into()),
+                ),
+                create_set_custodian_instruction(payer, USDC_MINT, USDT_MINT, custodian),
+                create_set_pool_admin_instruction(payer, USDC_MINT, USDT_MINT, pool_admin),
+                create_set_pool_label_instruction(payer, USDC_MINT, USDT_MINT, "USDT"),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let redemption_amount = 1000 * 10_u64.pow(USDC_DECIMALS.into());
+    let settlement_amount = 500 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            redemption_amount,
+        )
+        .await;
+    test_f
+        .mint_tokens(
+            &find_pool_settlement_token_account(&pool_address),
+            settlement_amount,
+        )
+        .await;
+
+    let migrate_ix = create_migrate_pool_instruction(MigratePoolInstructionAccounts {
+        admin: payer,
+        payer,
+        redemption_mint: USDC_MINT,
+        settlement_mint: USDT_MINT,
+        new_settlement_mint: MSOL_MINT,
+        redemption_token_program: spl_token::ID,
+        settlement_token_program: spl_token::ID,
+        new_settlement_token_program: spl_token::ID,
+    });
+
+    // Settlement funds leave through the withdrawal path, never through the migration
+    let result = test_f
+        .send_ixs(&[migrate_ix.clone()], &[&test_f.deployer])
+        .await;
+    assert!(
+        result.is_err(),
+        "Migration should fail while the pool holds settlement tokens"
+    );
+
+    create_associated_token_account(&test_f, &payer, &USDT_MINT).await?;
+    withdraw_from_pool(
+        &test_f,
+        &test_f.deployer,
+        USDC_MINT,
+        USDT_MINT,
+        settlement_amount,
+    )
+    .await?;
+
+    let result = test_f
+        .send_ixs(
+            &[
+                create_update_pause_flag_instruction(payer, true),
+                migrate_ix.clone(),
+            ],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err(), "Migration should fail while paused");
+
+    test_f.send_ixs(&[migrate_ix], &[&test_f.deployer]).await?;
+
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(
+        pool.status,
+        PoolStatus::Disabled,
+        "Old pool should be disabled"
+    );
+
+    let new_pool: Pool = test_f.load_and_deserialize(&new_pool_address).await;
+    assert_eq!(
+        new_pool.status,
+        PoolStatus::Disabled,
+        "New pool should wait for activate_pool"
+    );
+    assert_eq!(
+        new_pool.settlement_mint, MSOL_MINT,
+        "New pool should have the new settlement mint"
+    );
+    assert_eq!(new_pool.haircut_bps, 25);
+    assert_eq!(
+        new_pool.min_redeem_amount,
+        10 * 10_u64.pow(9),
+        "Minimum redeem amount should be rescaled to the new settlement decimals"
+    );
+    assert_eq!(new_pool.pending_custodian, custodian);
+    assert_eq!(new_pool.pending_custodian_at, pool.pending_custodian_at);
+    assert_eq!(new_pool.admin, pool_admin);
+    assert_eq!(new_pool.label(), Some("USDT"));
+    assert_eq!(
+        new_pool.supply_period_limit.duration_seconds,
+        pool.supply_period_limit.duration_seconds
+    );
+    assert_eq!(
+        new_pool.supply_period_limit.max_mint_amount,
+        pool.supply_period_limit.max_mint_amount
+    );
+    assert_eq!(
+        new_pool.total_supplied(),
+        u128::from(redemption_amount),
+        "New pool total supplied should include the migrated liquidity"
+    );
+
+    let new_redemption_account: TokenAccount = test_f
+        .load_and_deserialize(&find_pool_redemption_token_account(&new_pool_address))
+        .await;
+    assert_eq!(
+        new_redemption_account.amount, redemption_amount,
+        "Redemption liquidity should move to the new pool"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_activate_pool_instruction(
+                payer, USDC_MINT, MSOL_MINT,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let new_pool: Pool = test_f.load_and_deserialize(&new_pool_address).await;
+    assert_eq!(new_pool.status, PoolStatus::Active);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn attach_and_detach_reserve_success() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+    test_f.replicate_account_from_mainnet(&MSOL_MINT).await?;
+
+    create_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    create_pool(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    let payer = test_f.deployer.pubkey();
+    let reserve_address = find_reserve(&USDC_MINT);
+    let reserve_token_account = find_reserve_redemption_token_account(&reserve_address);
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                create_create_reserve_instruction(payer, payer, USDC_MINT, spl_token::ID),
+                create_attach_reserve_instruction(payer, USDC_MINT, USDT_MINT),
+                create_attach_reserve_instruction(payer, USDC_MINT, MSOL_MINT),
+            ],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_address).await;
+    assert_eq!(reserve.num_pools, 2, "Both pools should share the reserve");
+
+    for settlement_mint in [USDT_MINT, MSOL_MINT] {
+        let pool: Pool = test_f
+            .load_and_deserialize(&find_pool(&USDC_MINT, &settlement_mint))
+            .await;
+        assert_eq!(
+            pool.reserve, reserve_address,
+            "Pool should point at the reserve"
+        );
+        assert_eq!(
+            pool.redemption_token_account, reserve_token_account,
+            "Pool should redeem from the reserve token account"
+        );
+    }
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_detach_reserve_instruction(
+                payer, USDC_MINT, MSOL_MINT,
+            )],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_address).await;
+    assert_eq!(
+        reserve.num_pools, 1,
+        "Detached pool should leave the reserve"
+    );
+
+    let pool_address = find_pool(&USDC_MINT, &MSOL_MINT);
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert!(
+        !pool.has_reserve(),
+        "Detached pool should not have a reserve"
+    );
+    assert_eq!(
+        pool.redemption_token_account,
+        find_pool_redemption_token_account(&pool_address),
+        "Detached pool should use its own redemption token account again"
+    );
+
+    // A pool that isn't attached can't be detached again and drag the count down
+    let result = test_f
+        .send_ixs(
+            &[create_detach_reserve_instruction(
+                payer, USDC_MINT, MSOL_MINT,
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err(), "Detaching an unattached pool should fail");
+
+    let reserve: Reserve = test_f.load_and_deserialize(&reserve_address).await;
+    assert_eq!(reserve.num_pools, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sweep_settlement_to_custodian() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let payer = test_f.deployer.pubkey();
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let custodian = Keypair::new().pubkey();
+
+    create_associated_token_account(&test_f, &custodian, &USDT_MINT).await?;
+    let settlement_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_settlement_token_account(&pool_address),
+            settlement_amount,
+        )
+        .await;
+
+    test_f
+        .send_ixs(
+            &[create_set_custodian_instruction(
+                payer, USDC_MINT, USDT_MINT, custodian,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    // Even without a withdrawal delay, a new custodian waits before it receives sweeps
+    let sweep_ix =
+        create_sweep_settlement_instruction(custodian, USDC_MINT, USDT_MINT, spl_token::ID);
+    let result = test_f
+        .send_ixs(&[sweep_ix.clone()], &[&test_f.deployer])
+        .await;
+    assert!(
+        result.is_err(),
+        "A new custodian cannot be swept to right away"
+    );
+
+    test_f.advance_time(MIN_CUSTODIAN_DELAY_SECONDS).await;
+    test_f.send_ixs(&[sweep_ix], &[&test_f.deployer]).await?;
+
+    let pool_settlement_account: TokenAccount = test_f
+        .load_and_deserialize(&find_pool_settlement_token_account(&pool_address))
+        .await;
+    assert_eq!(
+        pool_settlement_account.amount, 0,
+        "Pool settlement balance should be swept"
+    );
+
+    let custodian_settlement_account: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address_with_program_id(
+            &custodian,
+            &USDT_MINT,
+            &spl_token::ID,
+        ))
+        .await;
+    assert_eq!(
+        custodian_settlement_account.amount, settlement_amount,
+        "Custodian should receive the settlement tokens"
+    );
+
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(
+        pool.total_withdrawn(),
+        u128::from(settlement_amount),
+        "Sweep should be recorded as a withdrawal"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timelocked_withdrawal() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let payer = test_f.deployer.pubkey();
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let withdrawal_delay = 3600;
+    let pauser = Keypair::new();
+    test_f.fund_account(&pauser.pubkey()).await;
+
+    create_associated_token_account(&test_f, &payer, &USDT_MINT).await?;
+    let withdraw_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_settlement_token_account(&pool_address),
+            withdraw_amount,
+        )
+        .await;
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                create_set_withdrawal_delay_instruction(payer, withdrawal_delay),
+                create_add_pauser_instruction(payer, pauser.pubkey()),
+            ],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let result = withdraw_from_pool(
+        &test_f,
+        &test_f.deployer,
+        USDC_MINT,
+        USDT_MINT,
+        withdraw_amount,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Instant withdraw should fail when withdrawals are timelocked"
+    );
+
+    let propose_withdrawal = || {
+        create_propose_withdrawal_instruction(
+            payer,
+            USDC_MINT,
+            USDT_MINT,
+            spl_token::ID,
+            withdraw_amount,
+        )
+    };
+    let execute_withdrawal =
+        || create_execute_withdrawal_instruction(payer, USDC_MINT, USDT_MINT, spl_token::ID);
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[propose_withdrawal()],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[execute_withdrawal()],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+        let result = ctx.banks_client.process_transaction(tx).await;
+        assert!(
+            result.is_err(),
+            "Withdrawal should not execute before the delay"
+        );
+
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_cancel_withdrawal_instruction(
+                pauser.pubkey(),
+                payer,
+                USDC_MINT,
+                USDT_MINT,
+            )],
+            Some(&pauser.pubkey()),
+            &[&pauser],
+            last_blockhash,
+        );
+        let outcome = ctx
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+        outcome.result?;
+
+        let events =
+            logged_events::<WithdrawalCancelledEvent>(&outcome.metadata.unwrap().log_messages);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].pool, pool_address);
+        assert_eq!(events[0].signer, pauser.pubkey());
+        assert_eq!(events[0].proposer, payer);
+        assert_eq!(events[0].amount, withdraw_amount);
+
+        let pending_withdrawal = ctx
+            .banks_client
+            .get_account(find_pending_withdrawal(&pool_address))
+            .await?;
+        assert!(
+            pending_withdrawal.is_none(),
+            "Pauser should be able to cancel a pending withdrawal"
+        );
+
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[propose_withdrawal()],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    test_f.advance_time(withdrawal_delay as i64).await;
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[execute_withdrawal()],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let admin_settlement_account: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address_with_program_id(
+            &payer,
+            &USDT_MINT,
+            &spl_token::ID,
+        ))
+        .await;
+    assert_eq!(
+        admin_settlement_account.amount, withdraw_amount,
+        "Admin should receive the settlement tokens after the delay"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn withdrawal_delay_decrease_and_custodian_change_wait_out_the_delay() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let admin = test_f.deployer.pubkey();
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let withdrawal_delay = 3600;
+    let custodian = Keypair::new().pubkey();
+
+    create_associated_token_account(&test_f, &admin, &USDT_MINT).await?;
+    create_associated_token_account(&test_f, &custodian, &USDT_MINT).await?;
+    let settlement_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_settlement_token_account(&pool_address),
+            settlement_amount,
+        )
+        .await;
+
+    test_f
+        .send_ixs(
+            &[create_set_withdrawal_delay_instruction(
+                admin,
+                withdrawal_delay,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    // Dropping the delay keeps the old one in force until it has run out
+    test_f
+        .send_ixs(&[create_set_withdrawal_delay_instruction(admin, 0)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.withdrawal_delay, 0);
+    assert!(config.withdrawal_delay_locked_until > test_f.get_clock().await.unix_timestamp);
+
+    let result = withdraw_from_pool(
+        &test_f,
+        &test_f.deployer,
+        USDC_MINT,
+        USDT_MINT,
+        settlement_amount,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Instant withdraw should fail until the previous delay has run out"
+    );
+
+    // A new custodian only takes over sweeps once the custodian delay has run out
+    test_f
+        .send_ixs(
+            &[create_set_custodian_instruction(
+                admin, USDC_MINT, USDT_MINT, custodian,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(pool.custodian, Pubkey::default());
+    assert_eq!(pool.pending_custodian, custodian);
+
+    let sweep_ix =
+        create_sweep_settlement_instruction(custodian, USDC_MINT, USDT_MINT, spl_token::ID);
+    let result = test_f
+        .send_ixs(&[sweep_ix.clone()], &[&test_f.deployer])
+        .await;
+    assert!(
+        result.is_err(),
+        "The pending custodian cannot sweep before the delay"
+    );
+
+    test_f.advance_time(withdrawal_delay as i64).await;
+    let result = test_f
+        .send_ixs(&[sweep_ix.clone()], &[&test_f.deployer])
+        .await;
+    assert!(
+        result.is_err(),
+        "The custodian delay outlasts a shorter withdrawal delay"
+    );
+
+    test_f
+        .advance_time(MIN_CUSTODIAN_DELAY_SECONDS - withdrawal_delay as i64)
+        .await;
+    test_f.send_ixs(&[sweep_ix], &[&test_f.deployer]).await?;
+
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(pool.custodian, custodian);
+    assert_eq!(pool.pending_custodian, Pubkey::default());
+    let custodian_settlement_account: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address_with_program_id(
+            &custodian,
+            &USDT_MINT,
+            &spl_token::ID,
+        ))
+        .await;
+    assert_eq!(custodian_settlement_account.amount, settlement_amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pauser_can_only_pause_pool() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let payer = test_f.deployer.pubkey();
+    let pauser = Keypair::new();
+    test_f.fund_account(&pauser.pubkey()).await;
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_add_pauser_instruction(payer, pauser.pubkey())],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_set_pool_status_instruction(
+                pauser.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                PoolStatus::Paused,
+            )],
+            Some(&pauser.pubkey()),
+            &[&pauser],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(
+        pool.status,
+        PoolStatus::Paused,
+        "Pauser should pause the pool"
+    );
+
+    {
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_set_pool_status_instruction(
+                pauser.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                PoolStatus::Active,
+            )],
+            Some(&pauser.pubkey()),
+            &[&pauser],
+            last_blockhash,
+        );
+        let result = ctx.banks_client.process_transaction(tx).await;
+        assert!(
+            result.is_err(),
+            "Pauser should not be able to re-enable the pool"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_pool_with_local_mints() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    init_program(&test_f).await?;
+
+    let redemption_mint = test_f.create_spl_mint(6).await;
+    let settlement_mint = test_f.create_spl_mint(9).await;
+
+    create_pool(&test_f, redemption_mint, settlement_mint).await?;
+
+    let pool: Pool = test_f
+        .load_and_deserialize(&find_pool(&redemption_mint, &settlement_mint))
+        .await;
+    assert_eq!(pool.redemption_token_decimals, 6);
+    assert_eq!(pool.settlement_token_decimals, 9);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pool_admin_manages_only_its_pool() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    create_pool(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    let admin = test_f.deployer.pubkey();
+    let pool_admin = Keypair::new();
+    test_f.fund_account(&pool_admin.pubkey()).await;
+
+    let set_min_redeem_amount = |redemption_mint, settlement_mint, min_redeem_amount| {
+        create_set_min_redeem_amount_instruction(
+            pool_admin.pubkey(),
+            redemption_mint,
+            settlement_mint,
+            min_redeem_amount,
+        )
+    };
+
+    let ix = set_min_redeem_amount(USDC_MINT, USDT_MINT, 500);
+    let result = test_f.send_ixs(&[ix], &[&pool_admin]).await;
+    assert!(result.is_err(), "Only admins manage pools by default");
+
+    test_f
+        .send_ixs(
+            &[create_set_pool_admin_instruction(
+                admin,
+                USDC_MINT,
+                USDT_MINT,
+                pool_admin.pubkey(),
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let ix = set_min_redeem_amount(USDC_MINT, USDT_MINT, 1_000);
+    test_f.send_ixs(&[ix], &[&pool_admin]).await?;
+    let pool: Pool = test_f
+        .load_and_deserialize(&find_pool(&USDC_MINT, &USDT_MINT))
+        .await;
+    assert_eq!(pool.admin, pool_admin.pubkey());
+    assert_eq!(pool.min_redeem_amount, 1_000);
+
+    let ix = set_min_redeem_amount(USDC_MINT, MSOL_MINT, 2_000);
+    let result = test_f.send_ixs(&[ix], &[&pool_admin]).await;
+    assert!(
+        result.is_err(),
+        "A pool admin should not manage other pools"
+    );
+
+    let result = test_f
+        .send_ixs(
+            &[create_set_pool_admin_instruction(
+                pool_admin.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                Keypair::new().pubkey(),
+            )],
+            &[&pool_admin],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Reassigning the pool admin stays with global admins"
+    );
+
+    // Nor can it redirect settlement funds or haircut redeemers
+    let result = test_f
+        .send_ixs(
+            &[create_set_custodian_instruction(
+                pool_admin.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                pool_admin.pubkey(),
+            )],
+            &[&pool_admin],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Setting the custodian stays with global admins"
+    );
+    let result = test_f
+        .send_ixs(
+            &[create_set_haircut_instruction(
+                pool_admin.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                9_999,
+            )],
+            &[&pool_admin],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Setting the haircut stays with global admins"
+    );
+    let pool: Pool = test_f
+        .load_and_deserialize(&find_pool(&USDC_MINT, &USDT_MINT))
+        .await;
+    assert!(!pool.has_custodian());
+    assert_eq!(pool.pending_custodian, Pubkey::default());
+    assert_eq!(pool.haircut_bps, 0);
+
+    test_f
+        .send_ixs(
+            &[create_set_pool_admin_instruction(
+                admin,
+                USDC_MINT,
+                USDT_MINT,
+                Pubkey::default(),
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    let ix = set_min_redeem_amount(USDC_MINT, USDT_MINT, 3_000);
+    let result = test_f.send_ixs(&[ix], &[&pool_admin]).await;
+    assert!(
+        result.is_err(),
+        "Removing the pool admin should revoke its access"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pool_creation_fee_goes_to_treasury() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let admin = test_f.deployer.pubkey();
+    let result = test_f
+        .send_ixs(&[create_set_pool_creation_fee_instruction(admin, 1)], &[
+            &test_f.deployer,
+        ])
+        .await;
+    assert!(
+        result.is_err(),
+        "The fee has to cover the treasury's rent exemption"
+    );
+
+    let fee = LAMPORTS_PER_SOL;
+    test_f
+        .send_ixs(&[create_set_pool_creation_fee_instruction(admin, fee)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+
+    create_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    create_pool(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    let treasury = find_treasury();
+    assert_eq!(test_f.get_account(&treasury).await.lamports, 2 * fee);
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.total_pool_creation_fees, 2 * fee);
+
+    let destination = Keypair::new().pubkey();
+    let result = test_f
+        .send_ixs(
+            &[create_withdraw_treasury_instruction(
+                admin,
+                destination,
+                2 * fee - 1,
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "The treasury cannot be left below rent exemption"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_withdraw_treasury_instruction(
+                admin,
+                destination,
+                2 * fee,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    assert_eq!(test_f.get_account(&destination).await.lamports, 2 * fee);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn permissionless_pool_creation_requires_allowed_mints() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let admin = test_f.deployer.pubkey();
+    let creator = Keypair::new();
+    test_f.fund_account(&creator.pubkey()).await;
+
+    test_f
+        .send_ixs(
+            &[
+                create_create_allowed_mint_list_instruction(admin, admin),
+                create_manage_allowed_mint_list_instruction(
+                    admin,
+                    AllowedMintListAction::AddMint { mint: USDC_MINT },
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let accounts = |settlement_mint| CreatePoolInstructionAccounts {
+        admin: creator.pubkey(),
+        payer: creator.pubkey(),
+        redemption_mint: USDC_MINT,
+        settlement_mint,
+        redemption_token_program: spl_token::ID,
+        settlement_token_program: spl_token::ID,
+    };
+    let create_usdt_pool_ix = create_permissionless_create_pool_instruction(accounts(USDT_MINT));
+
+    let result = test_f
+        .send_ixs(&[create_usdt_pool_ix.clone()], &[&creator])
+        .await;
+    assert!(
+        result.is_err(),
+        "Only admins can create pools while permissionless creation is disabled"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_set_permissionless_pool_creation_instruction(
+                admin, true,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let result = test_f
+        .send_ixs(&[create_usdt_pool_ix.clone()], &[&creator])
+        .await;
+    assert!(result.is_err(), "USDT is not on the allowed mint list");
+
+    test_f
+        .send_ixs(
+            &[create_manage_allowed_mint_list_instruction(
+                admin,
+                AllowedMintListAction::AddMint { mint: USDT_MINT },
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+    test_f
+        .send_ixs(&[create_usdt_pool_ix.clone()], &[&creator])
+        .await?;
+    let pool: Pool = test_f
+        .load_and_deserialize(&find_pool(&USDC_MINT, &USDT_MINT))
+        .await;
+    assert_eq!(pool.redemption_mint, USDC_MINT);
+    assert_eq!(pool.settlement_mint, USDT_MINT);
+
+    let create_msol_pool_ix = create_create_pool_instruction(accounts(MSOL_MINT));
+    let result = test_f.send_ixs(&[create_msol_pool_ix], &[&creator]).await;
+    assert!(
+        result.is_err(),
+        "Permissionless creation has to pass the allowed mint list"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pool_label_shows_in_view() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool = view_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    assert_eq!(pool.label, "");
+
+    test_f
+        .send_ixs(
+            &[create_set_pool_label_instruction(
+                test_f.deployer.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                "USDC/USDT main",
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let pool = view_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    assert_eq!(pool.label, "USDC/USDT main");
+    let pool: Pool = test_f
+        .load_and_deserialize(&find_pool(&USDC_MINT, &USDT_MINT))
+        .await;
+    assert_eq!(pool.label(), Some("USDC/USDT main"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn settlement_leaves_only_to_approved_destinations() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let admin = test_f.deployer.pubkey();
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let custodian = Keypair::new().pubkey();
+    create_associated_token_account(&test_f, &admin, &USDT_MINT).await?;
+    create_associated_token_account(&test_f, &custodian, &USDT_MINT).await?;
+
+    let settlement_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_settlement_token_account(&pool_address),
+            settlement_amount,
+        )
+        .await;
+
+    test_f
+        .send_ixs(
+            &[create_create_withdrawal_destination_list_instruction(
+                admin, admin,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let withdraw_amount = settlement_amount / 2;
+    let result = withdraw_from_pool(
+        &test_f,
+        &test_f.deployer,
+        USDC_MINT,
+        USDT_MINT,
+        withdraw_amount,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Withdrawals have to pass the destination list once it exists"
+    );
+
+    let accounts = || WithdrawInstructionAccounts {
+        admin,
+        redemption_mint: USDC_MINT,
+        settlement_mint: USDT_MINT,
+        settlement_token_program: spl_token::ID,
+    };
+    let result = test_f
+        .send_ixs(
+            &[create_restricted_withdraw_instruction(
+                accounts(),
+                withdraw_amount,
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err(), "The admin is not an approved destination");
+
+    test_f
+        .send_ixs(
+            &[
+                create_manage_withdrawal_destination_list_instruction(
+                    admin,
+                    WithdrawalDestinationListAction::AddOwner { owner: admin },
+                ),
+                create_restricted_withdraw_instruction(accounts(), withdraw_amount),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    test_f
+        .send_ixs(
+            &[
+                create_set_withdrawal_delay_instruction(admin, 3600),
+                create_set_custodian_instruction(admin, USDC_MINT, USDT_MINT, custodian),
+                create_manage_withdrawal_destination_list_instruction(
+                    admin,
+                    WithdrawalDestinationListAction::AddOwner { owner: custodian },
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let sweep_ix = create_restricted_sweep_settlement_instruction(
+        custodian,
+        USDC_MINT,
+        USDT_MINT,
+        spl_token::ID,
+    );
+    let result = test_f
+        .send_ixs(&[sweep_ix.clone()], &[&test_f.deployer])
+        .await;
+    assert!(
+        result.is_err(),
+        "New destinations are only approved after the withdrawal delay"
+    );
+
+    test_f.advance_time(MIN_CUSTODIAN_DELAY_SECONDS).await;
+    test_f.send_ixs(&[sweep_ix], &[&test_f.deployer]).await?;
+
+    let custodian_settlement_account: TokenAccount = test_f
+        .load_and_deserialize(&get_associated_token_address_with_program_id(
+            &custodian,
+            &USDT_MINT,
+            &spl_token::ID,
+        ))
+        .await;
+    assert_eq!(
+        custodian_settlement_account.amount,
+        settlement_amount - withdraw_amount
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_keeps_config_and_pool_at_current_version() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let admin = test_f.deployer.pubkey();
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.version, CONFIG_VERSION);
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(pool.version, POOL_VERSION);
+
+    let outsider = Keypair::new();
+    let result = test_f
+        .send_ixs(
+            &[create_migrate_instruction(
+                outsider.pubkey(),
+                admin,
+                Some((USDC_MINT, USDT_MINT)),
+            )],
+            &[&test_f.deployer, &outsider],
+        )
+        .await;
+    assert!(result.is_err(), "Only admins can migrate");
+
+    test_f
+        .send_ixs(
+            &[
+                create_migrate_instruction(admin, admin, None),
+                create_migrate_instruction(admin, admin, Some((USDC_MINT, USDT_MINT))),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let config: Config = test_f.load_and_deserialize(&find_config()).await;
+    assert_eq!(config.version, CONFIG_VERSION);
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(pool.version, POOL_VERSION);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_reallocates_a_config_without_reserved() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let admin = test_f.deployer.pubkey();
+    let config_address = find_config();
+
+    // Cut the config back to the size it was deployed with, before `reserved`
+    let deployed_len = 8 + offset_of!(Config, reserved);
+    let mut account = test_f.get_account(&config_address).await;
+    account.data.truncate(deployed_len);
+    account.data[8 + offset_of!(Config, version)] = 1;
+    account.lamports = Rent::default().minimum_balance(deployed_len);
+    test_f.set_account(&config_address, account).await;
+
+    let outsider = Keypair::new();
+    let result = test_f
+        .send_ixs(
+            &[create_migrate_instruction(outsider.pubkey(), admin, None)],
+            &[&test_f.deployer, &outsider],
+        )
+        .await;
+    assert!(result.is_err(), "Only admins can migrate");
+
+    test_f
+        .send_ixs(&[create_migrate_instruction(admin, admin, None)], &[
+            &test_f.deployer,
+        ])
+        .await?;
+
+    let account = test_f.get_account(&config_address).await;
+    assert_eq!(account.data.len(), 8 + Config::MAX_SIZE);
+    assert!(account.lamports >= Rent::default().minimum_balance(account.data.len()));
+
+    let config: Config = test_f.load_and_deserialize(&config_address).await;
+    assert_eq!(config.version, CONFIG_VERSION);
+    assert!(config.is_admin(&admin));
+    assert_eq!(config.reserved, [0; 256]);
+
+    Ok(())
+}
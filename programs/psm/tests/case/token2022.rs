@@ -0,0 +1,244 @@
+use anchor_spl::token_interface::TokenAccount;
+use fixtures::test::{MintExtension, TestFixture};
+use jup_stable::state::common::MAX_DURATION_SECONDS;
+use psm::state::pool::Pool;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::common::{
+    derivation::{
+        find_pool, find_pool_redemption_token_account, find_pool_settlement_token_account,
+    },
+    faciliter::init_program,
+    instructions::{
+        create_activate_pool_instruction, create_create_pool_instruction,
+        create_redeem_instruction, create_set_supply_period_limit_instruction,
+        create_supply_instruction, create_withdraw_instruction, CreatePoolInstructionAccounts,
+        RedeemInstructionAccounts, SupplyInstructionAccounts, WithdrawInstructionAccounts,
+    },
+};
+
+const DECIMALS: u8 = 6;
+
+async fn create_token2022_account(
+    test_f: &TestFixture,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> anyhow::Result<()> {
+    test_f
+        .send_ixs(
+            &[
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &test_f.deployer.pubkey(),
+                    owner,
+                    mint,
+                    &spl_token_2022::ID,
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await
+}
+
+#[tokio::test]
+async fn token2022_pool_supply_redeem_withdraw() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    init_program(&test_f).await?;
+
+    let admin = &test_f.deployer;
+    let redemption_mint = test_f.create_token2022_mint(DECIMALS, &[]).await;
+    let settlement_mint = test_f.create_token2022_mint(DECIMALS, &[]).await;
+
+    test_f
+        .send_ixs(
+            &[
+                create_create_pool_instruction(CreatePoolInstructionAccounts {
+                    admin: admin.pubkey(),
+                    payer: admin.pubkey(),
+                    redemption_mint,
+                    settlement_mint,
+                    redemption_token_program: spl_token_2022::ID,
+                    settlement_token_program: spl_token_2022::ID,
+                }),
+                create_set_supply_period_limit_instruction(
+                    admin.pubkey(),
+                    redemption_mint,
+                    settlement_mint,
+                    MAX_DURATION_SECONDS,
+                    u64::MAX,
+                ),
+                create_activate_pool_instruction(admin.pubkey(), redemption_mint, settlement_mint),
+            ],
+            &[admin],
+        )
+        .await?;
+
+    let pool_address = find_pool(&redemption_mint, &settlement_mint);
+    let pool_redemption_token_account = find_pool_redemption_token_account(&pool_address);
+    let pool_settlement_token_account = find_pool_settlement_token_account(&pool_address);
+
+    let admin_redemption_ata = get_associated_token_address_with_program_id(
+        &admin.pubkey(),
+        &redemption_mint,
+        &spl_token_2022::ID,
+    );
+    create_token2022_account(&test_f, &admin.pubkey(), &redemption_mint).await?;
+    let supply_amount = 1_000 * 10_u64.pow(DECIMALS.into());
+    test_f
+        .mint_tokens(&admin_redemption_ata, supply_amount)
+        .await;
+    test_f
+        .send_ixs(
+            &[create_supply_instruction(
+                SupplyInstructionAccounts {
+                    admin: admin.pubkey(),
+                    redemption_mint,
+                    settlement_mint,
+                    redemption_token_program: spl_token_2022::ID,
+                },
+                supply_amount,
+            )],
+            &[admin],
+        )
+        .await?;
+
+    let admin_redemption: TokenAccount = test_f.load_and_deserialize(&admin_redemption_ata).await;
+    assert_eq!(admin_redemption.amount, 0);
+    let pool_redemption: TokenAccount = test_f
+        .load_and_deserialize(&pool_redemption_token_account)
+        .await;
+    assert_eq!(pool_redemption.amount, supply_amount);
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(pool.total_supplied(), u128::from(supply_amount));
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+    let user_redemption_ata = get_associated_token_address_with_program_id(
+        &user.pubkey(),
+        &redemption_mint,
+        &spl_token_2022::ID,
+    );
+    let user_settlement_ata = get_associated_token_address_with_program_id(
+        &user.pubkey(),
+        &settlement_mint,
+        &spl_token_2022::ID,
+    );
+    create_token2022_account(&test_f, &user.pubkey(), &redemption_mint).await?;
+    create_token2022_account(&test_f, &user.pubkey(), &settlement_mint).await?;
+
+    let redeem_amount = 100 * 10_u64.pow(DECIMALS.into());
+    test_f
+        .mint_tokens(&user_settlement_ata, redeem_amount)
+        .await;
+    test_f
+        .send_ixs(
+            &[create_redeem_instruction(
+                RedeemInstructionAccounts {
+                    user: user.pubkey(),
+                    redemption_mint,
+                    settlement_mint,
+                    redemption_token_program: spl_token_2022::ID,
+                    settlement_token_program: spl_token_2022::ID,
+                },
+                redeem_amount,
+                0,
+            )],
+            &[&user],
+        )
+        .await?;
+
+    let user_settlement: TokenAccount = test_f.load_and_deserialize(&user_settlement_ata).await;
+    assert_eq!(user_settlement.amount, 0);
+    let user_redemption: TokenAccount = test_f.load_and_deserialize(&user_redemption_ata).await;
+    assert_eq!(user_redemption.amount, redeem_amount);
+    let pool_settlement: TokenAccount = test_f
+        .load_and_deserialize(&pool_settlement_token_account)
+        .await;
+    assert_eq!(pool_settlement.amount, redeem_amount);
+    let pool_redemption_after: TokenAccount = test_f
+        .load_and_deserialize(&pool_redemption_token_account)
+        .await;
+    assert_eq!(
+        pool_redemption_after.amount,
+        pool_redemption.amount - redeem_amount
+    );
+
+    let admin_settlement_ata = get_associated_token_address_with_program_id(
+        &admin.pubkey(),
+        &settlement_mint,
+        &spl_token_2022::ID,
+    );
+    create_token2022_account(&test_f, &admin.pubkey(), &settlement_mint).await?;
+    let withdraw_amount = pool_settlement.amount;
+    test_f
+        .send_ixs(
+            &[create_withdraw_instruction(
+                WithdrawInstructionAccounts {
+                    admin: admin.pubkey(),
+                    redemption_mint,
+                    settlement_mint,
+                    settlement_token_program: spl_token_2022::ID,
+                },
+                withdraw_amount,
+            )],
+            &[admin],
+        )
+        .await?;
+
+    let pool_settlement: TokenAccount = test_f
+        .load_and_deserialize(&pool_settlement_token_account)
+        .await;
+    assert_eq!(pool_settlement.amount, 0);
+    let admin_settlement: TokenAccount = test_f.load_and_deserialize(&admin_settlement_ata).await;
+    assert_eq!(admin_settlement.amount, withdraw_amount);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn token2022_transfer_fee_mint_rejected() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    init_program(&test_f).await?;
+
+    let admin = &test_f.deployer;
+    let redemption_mint = test_f.create_token2022_mint(DECIMALS, &[]).await;
+    // Refused even at a zero rate, which the fee authority could raise later
+    let settlement_mint = test_f
+        .create_token2022_mint(DECIMALS, &[MintExtension::TransferFee {
+            transfer_fee_basis_points: 0,
+            maximum_fee: u64::MAX,
+        }])
+        .await;
+
+    let tx = test_f
+        .tx()
+        .ixs(&[create_create_pool_instruction(
+            CreatePoolInstructionAccounts {
+                admin: admin.pubkey(),
+                payer: admin.pubkey(),
+                redemption_mint,
+                settlement_mint,
+                redemption_token_program: spl_token_2022::ID,
+                settlement_token_program: spl_token_2022::ID,
+            },
+        )])
+        .signers(&[admin])
+        .build()
+        .await?;
+    let outcome = test_f
+        .context
+        .borrow()
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    assert!(outcome.result.is_err());
+    assert!(outcome
+        .metadata
+        .map(|metadata| metadata.log_messages)
+        .unwrap_or_default()
+        .iter()
+        .any(|log| log.contains("Error Code: UnsupportedMintExtension.")));
+
+    Ok(())
+}
@@ -1,18 +1,34 @@
 use anchor_spl::token_interface::TokenAccount;
-use fixtures::test::TestFixture;
-use psm::state::pool::Pool;
+use fixtures::{event::logged_events, test::TestFixture};
+use psm::{
+    event::SupplyEvent,
+    instructions::FeeExemptListAction,
+    state::{
+        fee_exempt::FeeExemptList,
+        pool::{Pool, PoolStatus, RoundingMode},
+    },
+};
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer};
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 
 use crate::common::{
     constants::{MSOL_DECIMALS, MSOL_MINT, USDC_DECIMALS, USDC_MINT, USDT_DECIMALS, USDT_MINT},
     derivation::{
-        find_pool, find_pool_redemption_token_account, find_pool_settlement_token_account,
+        find_fee_exempt_list, find_pool, find_pool_redemption_token_account,
+        find_pool_settlement_token_account,
     },
     faciliter::{
-        create_active_pool, create_associated_token_account, redeem_from_pool,
-        setup_full_test_context, supply_pool, withdraw_from_pool,
+        create_active_pool, create_associated_token_account, quote_redeem, redeem_from_pool,
+        setup_full_test_context, supply_pool, view_pool, withdraw_from_pool,
+    },
+    instructions::{
+        create_create_fee_exempt_list_instruction, create_fee_exempt_redeem_instruction,
+        create_manage_fee_exempt_list_instruction, create_set_haircut_instruction,
+        create_set_min_redeem_amount_instruction, create_set_pool_admin_instruction,
+        create_set_rounding_mode_instruction, create_set_supply_period_limit_instruction,
+        create_set_utilization_fee_instruction, create_supply_instruction,
+        RedeemInstructionAccounts, SupplyInstructionAccounts,
     },
 };
 
@@ -55,7 +71,7 @@ async fn supply_success() -> anyhow::Result<()> {
 
     let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
     assert_eq!(
-        u128::from_le_bytes(pool.total_supplied),
+        pool.total_supplied(),
         u128::from(supply_amount),
         "Pool total supplied should be updated"
     );
@@ -70,6 +86,119 @@ async fn supply_success() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn supply_respects_period_limit() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let admin = &test_f.deployer;
+    let admin_redemption_ata =
+        get_associated_token_address_with_program_id(&admin.pubkey(), &USDC_MINT, &spl_token::ID);
+    create_associated_token_account(&test_f, &admin.pubkey(), &USDC_MINT).await?;
+
+    let max_supply_amount = 1000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(&admin_redemption_ata, 2 * max_supply_amount)
+        .await;
+
+    let pool_admin = Keypair::new();
+    test_f.fund_account(&pool_admin.pubkey()).await;
+    test_f
+        .send_ixs(
+            &[create_set_pool_admin_instruction(
+                admin.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                pool_admin.pubkey(),
+            )],
+            &[admin],
+        )
+        .await?;
+    let result = test_f
+        .send_ixs(
+            &[create_set_supply_period_limit_instruction(
+                pool_admin.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                0,
+                0,
+            )],
+            &[&pool_admin],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "Pool admins should not be able to change their own supply limit"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_set_supply_period_limit_instruction(
+                admin.pubkey(),
+                USDC_MINT,
+                USDT_MINT,
+                3600,
+                max_supply_amount,
+            )],
+            &[admin],
+        )
+        .await?;
+
+    let first_amount = 600 * 10_u64.pow(USDC_DECIMALS.into());
+    let accounts = SupplyInstructionAccounts {
+        admin: admin.pubkey(),
+        redemption_mint: USDC_MINT,
+        settlement_mint: USDT_MINT,
+        redemption_token_program: spl_token::ID,
+    };
+    let mut ctx = test_f.context.borrow_mut();
+    let last_blockhash = ctx.get_new_latest_blockhash().await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[create_supply_instruction(accounts, first_amount)],
+        Some(&admin.pubkey()),
+        &[admin],
+        last_blockhash,
+    );
+    let outcome = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await?;
+    drop(ctx);
+    outcome.result?;
+    let metadata = outcome.metadata.unwrap();
+
+    let events = logged_events::<SupplyEvent>(&metadata.log_messages);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].pool, find_pool(&USDC_MINT, &USDT_MINT));
+    assert_eq!(events[0].admin, admin.pubkey());
+    assert_eq!(events[0].amount, first_amount);
+    assert_eq!(events[0].window_supplied_amount, first_amount);
+    assert_eq!(events[0].total_supplied, u128::from(first_amount));
+
+    let second_amount = max_supply_amount - first_amount;
+    let result = supply_pool(&test_f, admin, USDC_MINT, USDT_MINT, second_amount + 1).await;
+    assert!(
+        result.is_err(),
+        "Supply beyond the window's limit should fail"
+    );
+
+    supply_pool(&test_f, admin, USDC_MINT, USDT_MINT, second_amount).await?;
+
+    test_f.advance_time(3600).await;
+    supply_pool(&test_f, admin, USDC_MINT, USDT_MINT, first_amount).await?;
+
+    let pool = view_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    assert_eq!(pool.supply_period_limit.minted_amount, first_amount);
+    assert_eq!(
+        pool.total_supplied,
+        u128::from(max_supply_amount + first_amount)
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn redeem_success() -> anyhow::Result<()> {
     let test_f = TestFixture::new().await;
@@ -100,7 +229,7 @@ async fn redeem_success() -> anyhow::Result<()> {
         .mint_tokens(&user_settlement_ata, redeem_amount)
         .await;
 
-    redeem_from_pool(&test_f, &user, USDC_MINT, USDT_MINT, redeem_amount).await?;
+    redeem_from_pool(&test_f, &user, USDC_MINT, USDT_MINT, redeem_amount, 0).await?;
 
     let user_settlement_account: TokenAccount =
         test_f.load_and_deserialize(&user_settlement_ata).await;
@@ -126,7 +255,7 @@ async fn redeem_success() -> anyhow::Result<()> {
 
     let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
     assert_eq!(
-        u128::from_le_bytes(pool.total_redeemed),
+        pool.total_redeemed(),
         u128::from(redeem_amount),
         "Pool total redeemed should be updated"
     );
@@ -225,7 +354,7 @@ async fn redeem_with_different_decimals() -> anyhow::Result<()> {
         .mint_tokens(&user_settlement_ata, redeem_amount)
         .await;
 
-    redeem_from_pool(&test_f, &user, USDC_MINT, MSOL_MINT, redeem_amount).await?;
+    redeem_from_pool(&test_f, &user, USDC_MINT, MSOL_MINT, redeem_amount, 0).await?;
 
     let user_redemption_account: TokenAccount =
         test_f.load_and_deserialize(&user_redemption_ata).await;
@@ -271,7 +400,7 @@ async fn redeem_with_different_decimals_2() -> anyhow::Result<()> {
         .mint_tokens(&user_settlement_ata, redeem_amount)
         .await;
 
-    redeem_from_pool(&test_f, &user, MSOL_MINT, USDC_MINT, redeem_amount).await?;
+    redeem_from_pool(&test_f, &user, MSOL_MINT, USDC_MINT, redeem_amount, 0).await?;
 
     let user_redemption_account: TokenAccount =
         test_f.load_and_deserialize(&user_redemption_ata).await;
@@ -285,3 +414,597 @@ async fn redeem_with_different_decimals_2() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn quote_redeem_matches_redeem() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &MSOL_MINT);
+    let pool_redemption_token_account = find_pool_redemption_token_account(&pool_address);
+    let supply_amount = 10000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(&pool_redemption_token_account, supply_amount)
+        .await;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let user_redemption_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDC_MINT, &spl_token::ID);
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &MSOL_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &MSOL_MINT).await?;
+
+    let redeem_amount = 1000 * 10_u64.pow(MSOL_DECIMALS.into()) + 1;
+    test_f
+        .mint_tokens(&user_settlement_ata, redeem_amount)
+        .await;
+
+    let quoted_amount = quote_redeem(&test_f, USDC_MINT, MSOL_MINT, redeem_amount).await?;
+
+    redeem_from_pool(&test_f, &user, USDC_MINT, MSOL_MINT, redeem_amount, 0).await?;
+
+    let user_redemption_account: TokenAccount =
+        test_f.load_and_deserialize(&user_redemption_ata).await;
+    assert_eq!(
+        quoted_amount, user_redemption_account.amount,
+        "Quote should match the redeemed amount"
+    );
+
+    let result = quote_redeem(&test_f, USDC_MINT, MSOL_MINT, supply_amount * 10_u64.pow(3)).await;
+    assert!(
+        result.is_err(),
+        "Quote should fail when the pool cannot cover the redemption"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redeem_below_min_amount_fails() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &MSOL_MINT);
+    let supply_amount = 10000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            supply_amount,
+        )
+        .await;
+
+    let min_redeem_amount = 10_u64.pow(MSOL_DECIMALS.into());
+    {
+        let payer = test_f.deployer.pubkey();
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_set_min_redeem_amount_instruction(
+                payer,
+                USDC_MINT,
+                MSOL_MINT,
+                min_redeem_amount,
+            )],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+
+        ctx.banks_client.process_transaction(tx).await?;
+    }
+
+    let pool: Pool = test_f.load_and_deserialize(&pool_address).await;
+    assert_eq!(
+        pool.min_redeem_amount, min_redeem_amount,
+        "Pool min redeem amount should be set"
+    );
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &MSOL_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &MSOL_MINT).await?;
+
+    test_f
+        .mint_tokens(&user_settlement_ata, min_redeem_amount)
+        .await;
+
+    let result = redeem_from_pool(
+        &test_f,
+        &user,
+        USDC_MINT,
+        MSOL_MINT,
+        min_redeem_amount - 1,
+        0,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Redeem below the min redeem amount should fail"
+    );
+
+    redeem_from_pool(&test_f, &user, USDC_MINT, MSOL_MINT, min_redeem_amount, 0).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redeem_with_rounding_modes() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, MSOL_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &MSOL_MINT);
+    let supply_amount = 10000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            supply_amount,
+        )
+        .await;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &MSOL_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &MSOL_MINT).await?;
+
+    let dust = 1;
+    let redeem_amount = 1000 * 10_u64.pow(MSOL_DECIMALS.into()) + dust;
+    test_f
+        .mint_tokens(&user_settlement_ata, redeem_amount)
+        .await;
+
+    for rounding_mode in [RoundingMode::Exact, RoundingMode::Refund] {
+        let payer = test_f.deployer.pubkey();
+        let mut ctx = test_f.context.borrow_mut();
+        let last_blockhash = ctx.get_new_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[create_set_rounding_mode_instruction(
+                payer,
+                USDC_MINT,
+                MSOL_MINT,
+                rounding_mode,
+            )],
+            Some(&payer),
+            &[&test_f.deployer],
+            last_blockhash,
+        );
+        ctx.banks_client.process_transaction(tx).await?;
+        drop(ctx);
+
+        let result = redeem_from_pool(&test_f, &user, USDC_MINT, MSOL_MINT, redeem_amount, 0).await;
+        match rounding_mode {
+            RoundingMode::Exact => assert!(
+                result.is_err(),
+                "Exact rounding should reject amounts that leave dust"
+            ),
+            _ => result?,
+        }
+    }
+
+    let user_settlement_account: TokenAccount =
+        test_f.load_and_deserialize(&user_settlement_ata).await;
+    assert_eq!(
+        user_settlement_account.amount, dust,
+        "Refund rounding should leave the un-creditable remainder with the user"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redeem_below_min_amount_out_fails() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let supply_amount = 10000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            supply_amount,
+        )
+        .await;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDT_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &USDT_MINT).await?;
+
+    let redeem_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(&user_settlement_ata, redeem_amount)
+        .await;
+
+    let result = redeem_from_pool(
+        &test_f,
+        &user,
+        USDC_MINT,
+        USDT_MINT,
+        redeem_amount,
+        redeem_amount + 1,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "Redeem should fail when the output is below min_amount_out"
+    );
+
+    redeem_from_pool(
+        &test_f,
+        &user,
+        USDC_MINT,
+        USDT_MINT,
+        redeem_amount,
+        redeem_amount,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redeem_applies_pool_haircut() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let supply_amount = 10000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            supply_amount,
+        )
+        .await;
+
+    let admin = test_f.deployer.pubkey();
+    let result = test_f
+        .send_ixs(
+            &[create_set_haircut_instruction(
+                admin, USDC_MINT, USDT_MINT, 10_000,
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(result.is_err(), "A full haircut should be rejected");
+
+    test_f
+        .send_ixs(
+            &[create_set_haircut_instruction(
+                admin, USDC_MINT, USDT_MINT, 50,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let user_redemption_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDC_MINT, &spl_token::ID);
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDT_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &USDT_MINT).await?;
+
+    let redeem_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(&user_settlement_ata, redeem_amount)
+        .await;
+
+    let expected_amount = redeem_amount * 9_950 / 10_000;
+    let quoted_amount = quote_redeem(&test_f, USDC_MINT, USDT_MINT, redeem_amount).await?;
+    assert_eq!(quoted_amount, expected_amount);
+
+    let result = redeem_from_pool(
+        &test_f,
+        &user,
+        USDC_MINT,
+        USDT_MINT,
+        redeem_amount,
+        redeem_amount,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "min_amount_out should be checked against the output after the haircut"
+    );
+
+    redeem_from_pool(
+        &test_f,
+        &user,
+        USDC_MINT,
+        USDT_MINT,
+        redeem_amount,
+        expected_amount,
+    )
+    .await?;
+
+    let user_redemption_account: TokenAccount =
+        test_f.load_and_deserialize(&user_redemption_ata).await;
+    assert_eq!(user_redemption_account.amount, expected_amount);
+    let user_settlement_account: TokenAccount =
+        test_f.load_and_deserialize(&user_settlement_ata).await;
+    assert_eq!(user_settlement_account.amount, 0);
+
+    let pool = view_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    assert_eq!(pool.haircut_bps, 50);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redeem_applies_utilization_fee() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let supply_amount = 10000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            supply_amount,
+        )
+        .await;
+
+    let admin = test_f.deployer.pubkey();
+    let result = test_f
+        .send_ixs(
+            &[create_set_utilization_fee_instruction(
+                admin, USDC_MINT, USDT_MINT, 100, 50,
+            )],
+            &[&test_f.deployer],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "The fee at zero utilization can't exceed the fee at full"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_set_utilization_fee_instruction(
+                admin, USDC_MINT, USDT_MINT, 0, 100,
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let user_redemption_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDC_MINT, &spl_token::ID);
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDT_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &USDT_MINT).await?;
+
+    let redeem_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(&user_settlement_ata, 2 * redeem_amount)
+        .await;
+
+    // A tenth of the redemption liquidity is used up after the first redemption, so 10 bps
+    let expected_amount = redeem_amount - redeem_amount * 10 / 10_000;
+    let quoted_amount = quote_redeem(&test_f, USDC_MINT, USDT_MINT, redeem_amount).await?;
+    assert_eq!(quoted_amount, expected_amount);
+
+    redeem_from_pool(
+        &test_f,
+        &user,
+        USDC_MINT,
+        USDT_MINT,
+        redeem_amount,
+        expected_amount,
+    )
+    .await?;
+
+    let user_redemption_account: TokenAccount =
+        test_f.load_and_deserialize(&user_redemption_ata).await;
+    assert_eq!(user_redemption_account.amount, expected_amount);
+
+    let second_quoted_amount = quote_redeem(&test_f, USDC_MINT, USDT_MINT, redeem_amount).await?;
+    assert!(
+        second_quoted_amount < expected_amount,
+        "The fee should grow as the redemption liquidity drains"
+    );
+
+    let pool = view_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    assert_eq!(pool.min_fee_bps, 0);
+    assert_eq!(pool.max_fee_bps, 100);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fee_exempt_user_skips_utilization_fee() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    let supply_amount = 10000 * 10_u64.pow(USDC_DECIMALS.into());
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            supply_amount,
+        )
+        .await;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let admin = test_f.deployer.pubkey();
+    test_f
+        .send_ixs(
+            &[
+                create_set_utilization_fee_instruction(admin, USDC_MINT, USDT_MINT, 0, 100),
+                create_create_fee_exempt_list_instruction(admin, admin, USDC_MINT, USDT_MINT),
+                create_manage_fee_exempt_list_instruction(
+                    admin,
+                    USDC_MINT,
+                    USDT_MINT,
+                    FeeExemptListAction::AddAddress {
+                        address: user.pubkey(),
+                    },
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let fee_exempt_list: FeeExemptList = test_f
+        .load_and_deserialize(&find_fee_exempt_list(&pool_address))
+        .await;
+    assert_eq!(fee_exempt_list.pool, pool_address);
+    assert!(fee_exempt_list.is_exempt(&user.pubkey()));
+
+    let user_redemption_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDC_MINT, &spl_token::ID);
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDT_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &USDT_MINT).await?;
+
+    let redeem_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(&user_settlement_ata, 2 * redeem_amount)
+        .await;
+
+    let redeem_accounts = || RedeemInstructionAccounts {
+        user: user.pubkey(),
+        redemption_mint: USDC_MINT,
+        settlement_mint: USDT_MINT,
+        redemption_token_program: spl_token::ID,
+        settlement_token_program: spl_token::ID,
+    };
+
+    test_f
+        .send_ixs(
+            &[create_fee_exempt_redeem_instruction(
+                redeem_accounts(),
+                redeem_amount,
+                redeem_amount,
+            )],
+            &[&user],
+        )
+        .await?;
+
+    let user_redemption_account: TokenAccount =
+        test_f.load_and_deserialize(&user_redemption_ata).await;
+    assert_eq!(
+        user_redemption_account.amount, redeem_amount,
+        "An exempt user should redeem without the fee"
+    );
+
+    test_f
+        .send_ixs(
+            &[create_manage_fee_exempt_list_instruction(
+                admin,
+                USDC_MINT,
+                USDT_MINT,
+                FeeExemptListAction::RemoveAddress {
+                    address: user.pubkey(),
+                },
+            )],
+            &[&test_f.deployer],
+        )
+        .await?;
+
+    let result = test_f
+        .send_ixs(
+            &[create_fee_exempt_redeem_instruction(
+                redeem_accounts(),
+                redeem_amount,
+                redeem_amount,
+            )],
+            &[&user],
+        )
+        .await;
+    assert!(
+        result.is_err(),
+        "A user taken off the list should pay the fee again"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn view_pool_decodes_state() -> anyhow::Result<()> {
+    let test_f = TestFixture::new().await;
+    let _test_context = setup_full_test_context(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    create_active_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+
+    let pool_address = find_pool(&USDC_MINT, &USDT_MINT);
+    test_f
+        .mint_tokens(
+            &find_pool_redemption_token_account(&pool_address),
+            10000 * 10_u64.pow(USDC_DECIMALS.into()),
+        )
+        .await;
+
+    let user = Keypair::new();
+    test_f.fund_account(&user.pubkey()).await;
+
+    let user_settlement_ata =
+        get_associated_token_address_with_program_id(&user.pubkey(), &USDT_MINT, &spl_token::ID);
+
+    create_associated_token_account(&test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(&test_f, &user.pubkey(), &USDT_MINT).await?;
+
+    let redeem_amount = 1000 * 10_u64.pow(USDT_DECIMALS.into());
+    test_f
+        .mint_tokens(&user_settlement_ata, redeem_amount)
+        .await;
+
+    redeem_from_pool(&test_f, &user, USDC_MINT, USDT_MINT, redeem_amount, 0).await?;
+
+    let pool_view = view_pool(&test_f, USDC_MINT, USDT_MINT).await?;
+    assert_eq!(pool_view.redemption_mint, USDC_MINT);
+    assert_eq!(pool_view.settlement_mint, USDT_MINT);
+    assert_eq!(pool_view.redemption_token_decimals, USDC_DECIMALS);
+    assert_eq!(pool_view.settlement_token_decimals, USDT_DECIMALS);
+    assert_eq!(pool_view.status, PoolStatus::Active);
+    assert_eq!(
+        pool_view.total_redeemed,
+        u128::from(redeem_amount),
+        "View should decode the redeemed total"
+    );
+
+    Ok(())
+}
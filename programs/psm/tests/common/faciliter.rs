@@ -1,16 +1,41 @@
+use anchor_lang::AnchorDeserialize;
 use anyhow::Result;
-use fixtures::test::TestFixture;
-use psm::state::pool::PoolStatus;
+use fixtures::{oracle::DovesPriceFeedBuilder, test::TestFixture};
+use jup_stable::{
+    instructions::OracleConfig,
+    state::{
+        benefactor::BenefactorStatus,
+        common::{PeriodLimitTemplate, MAX_DURATION_SECONDS},
+        vault::VaultStatus,
+    },
+};
+use jupusd_client::jup_stable::{
+    derivation as stable_pda, instructions as stable_instructions,
+    instructions::{
+        ApproveCollateralInstructionAccounts, CreateBenefactorInstructionAccounts,
+        CreateBenefactorInstructionArgs, CreateVaultInstructionAccounts,
+        InitInstructionAccounts as StableInitInstructionAccounts, InitInstructionArgs,
+        MintInstructionAccounts,
+    },
+};
+use psm::state::pool::PoolView;
 use solana_sdk::{
     bpf_loader_upgradeable::get_program_data_address, pubkey::Pubkey, signature::Keypair,
     signer::Signer, transaction::Transaction,
 };
-
-use crate::common::instructions::{
-    create_add_admin_instruction, create_create_pool_instruction, create_init_instruction,
-    create_redeem_instruction, create_set_pool_status_instruction, create_supply_instruction,
-    create_withdraw_instruction, CreatePoolInstructionAccounts, InitInstructionAccounts,
-    RedeemInstructionAccounts, SupplyInstructionAccounts, WithdrawInstructionAccounts,
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::{
+    constants::{USDC_DECIMALS, USDC_MINT},
+    derivation::find_authority,
+    instructions::{
+        create_activate_pool_instruction, create_add_admin_instruction,
+        create_create_pool_instruction, create_init_instruction, create_quote_redeem_instruction,
+        create_redeem_instruction, create_set_supply_period_limit_instruction,
+        create_supply_instruction, create_view_pool_instruction, create_withdraw_instruction,
+        CreatePoolInstructionAccounts, InitInstructionAccounts, RedeemInstructionAccounts,
+        SupplyInstructionAccounts, WithdrawInstructionAccounts,
+    },
 };
 
 pub async fn init_program(test_f: &TestFixture) -> Result<()> {
@@ -23,16 +48,9 @@ pub async fn init_program(test_f: &TestFixture) -> Result<()> {
         program_data,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[create_init_instruction(accounts)],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(&[create_init_instruction(accounts)], &[&test_f.deployer])
+        .await?;
 
     Ok(())
 }
@@ -53,16 +71,11 @@ pub async fn create_pool(
         settlement_token_program: spl_token::ID,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[create_create_pool_instruction(accounts)],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(&[create_create_pool_instruction(accounts)], &[
+            &test_f.deployer
+        ])
+        .await?;
 
     Ok(())
 }
@@ -83,24 +96,22 @@ pub async fn create_active_pool(
         settlement_token_program: spl_token::ID,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[
-            create_create_pool_instruction(accounts),
-            create_set_pool_status_instruction(
-                payer,
-                redemption_mint,
-                settlement_mint,
-                PoolStatus::Active,
-            ),
-        ],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(
+            &[
+                create_create_pool_instruction(accounts),
+                create_set_supply_period_limit_instruction(
+                    payer,
+                    redemption_mint,
+                    settlement_mint,
+                    MAX_DURATION_SECONDS,
+                    u64::MAX,
+                ),
+                create_activate_pool_instruction(payer, redemption_mint, settlement_mint),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
 
     Ok(())
 }
@@ -110,25 +121,19 @@ pub async fn create_associated_token_account(
     owner: &Pubkey,
     mint: &Pubkey,
 ) -> Result<()> {
-    let payer = test_f.deployer.pubkey();
-
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[
-            spl_associated_token_account::instruction::create_associated_token_account(
-                &test_f.deployer.pubkey(),
-                owner,
-                mint,
-                &spl_token::ID,
-            ),
-        ],
-        Some(&payer),
-        &[&test_f.deployer],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(
+            &[
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &test_f.deployer.pubkey(),
+                    owner,
+                    mint,
+                    &spl_token::ID,
+                ),
+            ],
+            &[&test_f.deployer],
+        )
+        .await?;
 
     Ok(())
 }
@@ -147,16 +152,9 @@ pub async fn supply_pool(
         redemption_token_program: spl_token::ID,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[create_supply_instruction(accounts, amount)],
-        Some(&admin.pubkey()),
-        &[admin],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(&[create_supply_instruction(accounts, amount)], &[admin])
+        .await?;
 
     Ok(())
 }
@@ -167,6 +165,7 @@ pub async fn redeem_from_pool(
     redemption_mint: Pubkey,
     settlement_mint: Pubkey,
     amount: u64,
+    min_amount_out: u64,
 ) -> Result<()> {
     let accounts = RedeemInstructionAccounts {
         user: user.pubkey(),
@@ -176,16 +175,12 @@ pub async fn redeem_from_pool(
         settlement_token_program: spl_token::ID,
     };
 
-    let mut ctx = test_f.context.borrow_mut();
-    let last_blockhash = ctx.get_new_latest_blockhash().await?;
-    let tx = Transaction::new_signed_with_payer(
-        &[create_redeem_instruction(accounts, amount)],
-        Some(&user.pubkey()),
-        &[user],
-        last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(tx).await?;
+    test_f
+        .send_ixs(
+            &[create_redeem_instruction(accounts, amount, min_amount_out)],
+            &[user],
+        )
+        .await?;
 
     Ok(())
 }
@@ -204,34 +199,86 @@ pub async fn withdraw_from_pool(
         settlement_token_program: spl_token::ID,
     };
 
+    test_f
+        .send_ixs(&[create_withdraw_instruction(accounts, amount)], &[admin])
+        .await?;
+
+    Ok(())
+}
+
+pub async fn quote_redeem(
+    test_f: &TestFixture,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+    amount: u64,
+) -> Result<u64> {
+    let payer = test_f.deployer.pubkey();
+
     let mut ctx = test_f.context.borrow_mut();
     let last_blockhash = ctx.get_new_latest_blockhash().await?;
     let tx = Transaction::new_signed_with_payer(
-        &[create_withdraw_instruction(accounts, amount)],
-        Some(&admin.pubkey()),
-        &[admin],
+        &[create_quote_redeem_instruction(
+            redemption_mint,
+            settlement_mint,
+            amount,
+        )],
+        Some(&payer),
+        &[&test_f.deployer],
         last_blockhash,
     );
 
-    ctx.banks_client.process_transaction(tx).await?;
+    let simulation = ctx.banks_client.simulate_transaction(tx).await?;
+    simulation
+        .result
+        .ok_or_else(|| anyhow::anyhow!("Missing simulation result"))??;
+    let return_data = simulation
+        .simulation_details
+        .and_then(|details| details.return_data)
+        .ok_or_else(|| anyhow::anyhow!("Missing return data"))?;
 
-    Ok(())
+    Ok(u64::from_le_bytes(return_data.data.as_slice().try_into()?))
 }
 
-#[allow(dead_code)]
-pub async fn add_admin(test_f: &TestFixture, new_admin: Pubkey) -> Result<()> {
+pub async fn view_pool(
+    test_f: &TestFixture,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+) -> Result<PoolView> {
     let payer = test_f.deployer.pubkey();
 
     let mut ctx = test_f.context.borrow_mut();
     let last_blockhash = ctx.get_new_latest_blockhash().await?;
     let tx = Transaction::new_signed_with_payer(
-        &[create_add_admin_instruction(payer, new_admin)],
+        &[create_view_pool_instruction(
+            redemption_mint,
+            settlement_mint,
+        )],
         Some(&payer),
         &[&test_f.deployer],
         last_blockhash,
     );
 
-    ctx.banks_client.process_transaction(tx).await?;
+    let simulation = ctx.banks_client.simulate_transaction(tx).await?;
+    simulation
+        .result
+        .ok_or_else(|| anyhow::anyhow!("Missing simulation result"))??;
+    let return_data = simulation
+        .simulation_details
+        .and_then(|details| details.return_data)
+        .ok_or_else(|| anyhow::anyhow!("Missing return data"))?;
+
+    Ok(PoolView::try_from_slice(&return_data.data)?)
+}
+
+#[allow(dead_code)]
+pub async fn add_admin(test_f: &TestFixture, new_admin: Pubkey) -> Result<()> {
+    let payer = test_f.deployer.pubkey();
+
+    test_f
+        .send_ixs(&[create_add_admin_instruction(payer, new_admin)], &[
+            &test_f.deployer,
+        ])
+        .await?;
 
     Ok(())
 }
@@ -261,3 +308,171 @@ pub async fn setup_full_test_context(
         settlement_mint,
     })
 }
+
+/// A jup-stable deployment minting jupUSD against USDC, for the pool instructions that CPI into it.
+#[allow(dead_code)]
+pub struct JupStableContext {
+    pub lp_mint: Pubkey,
+    pub custodian: Pubkey,
+    /// Doves feed pricing USDC at 1 USD, passed as remaining account
+    pub oracle: Pubkey,
+    /// Holds USDC and mints jupUSD through its own benefactor
+    pub user: Keypair,
+}
+
+/// Initializes both programs, with an enabled jup-stable USDC vault and active benefactors for
+/// the PSM authority and a funded user. Neither charges fees, so jupUSD and USDC trade 1:1.
+#[allow(dead_code)]
+pub async fn setup_jup_stable_test_context(test_f: &TestFixture) -> Result<JupStableContext> {
+    let payer = test_f.deployer.pubkey();
+    let lp_mint = Keypair::new();
+    let custodian = Keypair::new().pubkey();
+    let oracle = Pubkey::new_unique();
+    let user = Keypair::new();
+
+    test_f.replicate_account_from_mainnet(&USDC_MINT).await?;
+    init_program(test_f).await?;
+
+    test_f
+        .send_ixs(
+            &[stable_instructions::create_init_instruction(
+                StableInitInstructionAccounts {
+                    payer,
+                    upgrade_authority: payer,
+                    program_data: get_program_data_address(&jup_stable::ID),
+                    mint: lp_mint.pubkey(),
+                    token_program: spl_token::ID,
+                },
+                InitInstructionArgs {
+                    decimals: USDC_DECIMALS,
+                    name: "Jupiter USD".to_string(),
+                    symbol: "JUPUSD".to_string(),
+                    uri: "https://jup.ag/jupusd".to_string(),
+                    peg_price_usd: 10_000,
+                    is_mint_redeem_enabled: true,
+                    default_vault_period_limit: PeriodLimitTemplate::default(),
+                    default_benefactor_period_limit: PeriodLimitTemplate::default(),
+                },
+            )],
+            &[&test_f.deployer, &lp_mint],
+        )
+        .await?;
+
+    let now = test_f.get_clock().await.unix_timestamp;
+    test_f
+        .set_account(&oracle, DovesPriceFeedBuilder::new().timestamp(now).build())
+        .await;
+    test_f.fund_account(&user.pubkey()).await;
+
+    let max_amount = 1_000_000 * 10_u64.pow(USDC_DECIMALS.into());
+    let mut ixs = vec![
+        stable_instructions::create_approve_collateral_instruction(
+            ApproveCollateralInstructionAccounts {
+                authority: payer,
+                payer,
+                mint: USDC_MINT,
+            },
+        ),
+        stable_instructions::create_create_vault_instruction(CreateVaultInstructionAccounts {
+            authority: payer,
+            payer,
+            mint: USDC_MINT,
+            token_program: spl_token::ID,
+        }),
+        stable_instructions::create_set_custodian_instruction(payer, USDC_MINT, custodian),
+        stable_instructions::create_update_vault_oracle_instruction(
+            payer,
+            USDC_MINT,
+            0,
+            OracleConfig::Doves(oracle),
+        ),
+        stable_instructions::create_set_vault_status_instruction(
+            payer,
+            USDC_MINT,
+            VaultStatus::Enabled,
+        ),
+        stable_instructions::create_update_vault_period_limit_instruction(
+            payer, USDC_MINT, 0, 3600, max_amount, max_amount,
+        ),
+        stable_instructions::create_update_config_period_limit_instruction(
+            payer, 0, 3600, max_amount, max_amount,
+        ),
+    ];
+    for benefactor_authority in [find_authority(), user.pubkey()] {
+        let benefactor = stable_pda::find_benefactor(&benefactor_authority);
+        ixs.extend([
+            stable_instructions::create_create_benefactor_instruction(
+                CreateBenefactorInstructionAccounts {
+                    authority: payer,
+                    payer,
+                    benefactor_authority,
+                    registry_page: 0,
+                },
+                CreateBenefactorInstructionArgs {
+                    mint_fee_rate: 0,
+                    redeem_fee_rate: 0,
+                },
+            ),
+            stable_instructions::create_set_benefactor_status_instruction(
+                payer,
+                benefactor,
+                BenefactorStatus::Active,
+            ),
+            stable_instructions::create_update_benefactor_period_limit_instruction(
+                payer, benefactor, 0, 3600, max_amount, max_amount,
+            ),
+        ]);
+    }
+    test_f.send_ixs(&ixs, &[&test_f.deployer]).await?;
+
+    create_associated_token_account(test_f, &custodian, &USDC_MINT).await?;
+    create_associated_token_account(test_f, &user.pubkey(), &USDC_MINT).await?;
+    create_associated_token_account(test_f, &user.pubkey(), &lp_mint.pubkey()).await?;
+    test_f
+        .mint_tokens(
+            &get_associated_token_address(&user.pubkey(), &USDC_MINT),
+            1_000 * 10_u64.pow(USDC_DECIMALS.into()),
+        )
+        .await;
+
+    Ok(JupStableContext {
+        lp_mint: lp_mint.pubkey(),
+        custodian,
+        oracle,
+        user,
+    })
+}
+
+/// Mints `amount` jupUSD into the user's associated token account through jup-stable, so the
+/// supply backs it and it can be redeemed again.
+#[allow(dead_code)]
+pub async fn mint_jupusd(
+    test_f: &TestFixture,
+    stable_context: &JupStableContext,
+    amount: u64,
+) -> Result<()> {
+    let user = stable_context.user.pubkey();
+
+    test_f
+        .send_ixs(
+            &[stable_instructions::create_mint_instruction(
+                amount,
+                0,
+                MintInstructionAccounts {
+                    user,
+                    benefactor: stable_pda::find_benefactor(&user),
+                    custodian: stable_context.custodian,
+                    custodian_token_account: None,
+                    vault_mint: USDC_MINT,
+                    lp_mint: stable_context.lp_mint,
+                    vault_token_program: spl_token::ID,
+                    lp_token_program: spl_token::ID,
+                    remaining_accounts: vec![stable_context.oracle],
+                },
+            )],
+            &[&stable_context.user],
+        )
+        .await?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
 pub mod constants;
-pub mod derivation;
 pub mod faciliter;
-pub mod instructions;
+
+pub use jupusd_client::psm::{derivation, instructions};
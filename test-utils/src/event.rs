@@ -0,0 +1,81 @@
+use anchor_lang::{event::EVENT_IX_TAG_LE, AnchorDeserialize, Discriminator};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::transaction::Transaction;
+
+use crate::test::TestFixture;
+
+/// Raw payloads (event discriminator + Borsh data) of the Anchor CPI events a transaction emitted.
+pub struct CpiEvents(Vec<Vec<u8>>);
+
+impl CpiEvents {
+    /// Decodes every emitted event of type `T`, in emission order.
+    pub fn decode<T: AnchorDeserialize + Discriminator>(&self) -> Vec<T> {
+        self.0
+            .iter()
+            .filter_map(|payload| payload.strip_prefix(T::DISCRIMINATOR))
+            .map(|data| T::deserialize(&mut &data[..]).unwrap())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize { self.0.len() }
+
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+}
+
+/// Simulates `tx` to capture its inner instructions, then processes it. Banks only reports
+/// inner instructions for simulations, so the transaction effectively runs twice.
+pub async fn process_transaction_with_events(
+    test_f: &TestFixture,
+    tx: Transaction,
+) -> Result<CpiEvents> {
+    let ctx = test_f.context.borrow();
+    let simulation = ctx.banks_client.simulate_transaction(tx.clone()).await?;
+    if let Some(Err(err)) = simulation.result {
+        return Err(err.into());
+    }
+
+    let inner_instructions = simulation
+        .simulation_details
+        .and_then(|details| details.inner_instructions)
+        .ok_or_else(|| anyhow!("simulation did not record inner instructions"))?;
+
+    let events = inner_instructions
+        .iter()
+        .flatten()
+        .filter_map(|ix| ix.instruction.data.strip_prefix(EVENT_IX_TAG_LE))
+        .map(<[u8]>::to_vec)
+        .collect();
+
+    ctx.banks_client.process_transaction(tx).await?;
+    Ok(CpiEvents(events))
+}
+
+/// Decodes the `T` events emitted with `emit!`, which only show up as `Program data:` logs.
+pub fn logged_events<T: AnchorDeserialize + Discriminator>(logs: &[String]) -> Vec<T> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter_map(|payload| {
+            let data = payload.strip_prefix(T::DISCRIMINATOR)?;
+            Some(T::deserialize(&mut &data[..]).unwrap())
+        })
+        .collect()
+}
+
+/// Asserts exactly one `T` was emitted and returns it.
+pub fn assert_event<T: AnchorDeserialize + Discriminator>(events: &CpiEvents) -> T {
+    let mut decoded = events.decode::<T>();
+    assert_eq!(
+        decoded.len(),
+        1,
+        "expected exactly one event, got {}",
+        decoded.len()
+    );
+    decoded.remove(0)
+}
+
+/// Asserts no `T` was emitted.
+pub fn assert_no_event<T: AnchorDeserialize + Discriminator>(events: &CpiEvents) {
+    assert!(events.decode::<T>().is_empty(), "expected no event");
+}
@@ -0,0 +1,64 @@
+use std::{env, fs, path::Path, process::Command};
+
+use anyhow::{ensure, Context, Result};
+use serde_json::{Map, Value};
+
+/// IDL sections client codegen derives account and event layouts from.
+const LAYOUT_SECTIONS: [&str; 4] = ["accounts", "types", "events", "errors"];
+
+/// Regenerates the IDL of the program at `program_dir` with `anchor idl build` and compares its
+/// layout sections against the snapshot in `tests/idl/layouts.json`. The snapshot is only
+/// (re)written with `UPDATE_IDL=1`; a missing snapshot or `anchor` binary fails the check.
+pub fn check_idl_layouts(program_dir: &str) -> Result<()> {
+    let program_dir = Path::new(program_dir);
+    let out_dir = env::temp_dir().join(format!("idl-{}", std::process::id()));
+    fs::create_dir_all(&out_dir)?;
+    let idl_path = out_dir.join(format!(
+        "{}.json",
+        program_dir.file_name().unwrap().to_string_lossy()
+    ));
+
+    let output = Command::new("anchor")
+        .args(["idl", "build", "-o"])
+        .arg(&idl_path)
+        .current_dir(program_dir)
+        .output()
+        .context("failed to run anchor, the IDL layout check needs the anchor CLI")?;
+    ensure!(
+        output.status.success(),
+        "anchor idl build failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let idl: Value = serde_json::from_slice(&fs::read(&idl_path)?)?;
+    let layouts = LAYOUT_SECTIONS
+        .iter()
+        .map(|section| (section.to_string(), idl[section].clone()))
+        .collect::<Map<_, _>>();
+    let layouts = Value::Object(layouts);
+
+    let snapshot_path = program_dir.join("tests/idl/layouts.json");
+    if env::var("UPDATE_IDL").is_ok_and(|value| value == "1") {
+        fs::create_dir_all(snapshot_path.parent().unwrap())?;
+        let snapshot = serde_json::to_string_pretty(&layouts)? + "\n";
+        fs::write(&snapshot_path, snapshot)?;
+        return Ok(());
+    }
+
+    let snapshot = fs::read(&snapshot_path).with_context(|| {
+        format!(
+            "failed to read {}; generate it with UPDATE_IDL=1",
+            snapshot_path.display()
+        )
+    })?;
+    let snapshot: Value = serde_json::from_slice(&snapshot)?;
+    for section in LAYOUT_SECTIONS {
+        ensure!(
+            snapshot[section] == layouts[section],
+            "IDL `{section}` drifted from {}; rerun with UPDATE_IDL=1 and regenerate the clients",
+            snapshot_path.display()
+        );
+    }
+
+    Ok(())
+}
@@ -1,2 +1,7 @@
+pub mod event;
+pub mod idl;
+pub mod oracle;
+pub mod scenario;
 pub mod test;
+pub mod tx;
 pub mod utils;
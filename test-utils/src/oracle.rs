@@ -0,0 +1,240 @@
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use doves::AgPriceFeed;
+use jup_stable::oracle::{PYTH_RECEIVER_PROGRAM_ID, SWITCHBOARD_ON_DEMAND_PROGRAM_ID};
+use pyth_solana_receiver_sdk::price_update::{PriceFeedMessage, PriceUpdateV2, VerificationLevel};
+use solana_rent::Rent;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use switchboard_on_demand::{
+    CurrentResult, Discriminator, OracleSubmission, PullFeedAccountData, PRECISION,
+};
+
+/// Builds a `PriceUpdateV2` account from scratch, owned by the Pyth receiver program.
+pub struct PythPriceUpdateBuilder {
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    posted_slot: u64,
+    verification_level: VerificationLevel,
+}
+
+impl PythPriceUpdateBuilder {
+    /// Defaults to a fully verified $1.00 price with zero confidence interval, posted at slot 1.
+    pub fn new(feed_id: [u8; 32]) -> Self {
+        Self {
+            feed_id,
+            price: 100_000_000,
+            conf: 0,
+            exponent: -8,
+            publish_time: 0,
+            posted_slot: 1,
+            verification_level: VerificationLevel::Full,
+        }
+    }
+
+    pub fn price(mut self, price: i64) -> Self {
+        self.price = price;
+        self
+    }
+
+    pub fn conf(mut self, conf: u64) -> Self {
+        self.conf = conf;
+        self
+    }
+
+    pub fn exponent(mut self, exponent: i32) -> Self {
+        self.exponent = exponent;
+        self
+    }
+
+    pub fn publish_time(mut self, publish_time: i64) -> Self {
+        self.publish_time = publish_time;
+        self
+    }
+
+    pub fn posted_slot(mut self, posted_slot: u64) -> Self {
+        self.posted_slot = posted_slot;
+        self
+    }
+
+    pub fn verification_level(mut self, verification_level: VerificationLevel) -> Self {
+        self.verification_level = verification_level;
+        self
+    }
+
+    pub fn build(&self) -> Account {
+        let price_update = PriceUpdateV2 {
+            write_authority: Pubkey::default(),
+            verification_level: self.verification_level,
+            price_message: PriceFeedMessage {
+                feed_id: self.feed_id,
+                price: self.price,
+                conf: self.conf,
+                exponent: self.exponent,
+                publish_time: self.publish_time,
+                prev_publish_time: self.publish_time,
+                ema_price: self.price,
+                ema_conf: self.conf,
+            },
+            posted_slot: self.posted_slot,
+        };
+
+        let mut data = Vec::with_capacity(PriceUpdateV2::LEN);
+        price_update.try_serialize(&mut data).unwrap();
+        data.resize(PriceUpdateV2::LEN, 0);
+
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: PYTH_RECEIVER_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+}
+
+/// Builds a `PullFeedAccountData` account from scratch, owned by the Switchboard on-demand
+/// program. Values are fixed point with `PRECISION` (18) decimals.
+pub struct SwitchboardPullFeedBuilder {
+    value: i128,
+    std_dev: i128,
+    last_update_slot: u64,
+    last_update_timestamp: i64,
+}
+
+impl Default for SwitchboardPullFeedBuilder {
+    fn default() -> Self { Self::new() }
+}
+
+impl SwitchboardPullFeedBuilder {
+    /// Defaults to a $1.00 price with zero standard deviation, updated at slot 1.
+    pub fn new() -> Self {
+        Self {
+            value: 10_i128.pow(PRECISION),
+            std_dev: 0,
+            last_update_slot: 1,
+            last_update_timestamp: 0,
+        }
+    }
+
+    pub fn value(mut self, value: i128) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn std_dev(mut self, std_dev: i128) -> Self {
+        self.std_dev = std_dev;
+        self
+    }
+
+    pub fn last_update_slot(mut self, last_update_slot: u64) -> Self {
+        self.last_update_slot = last_update_slot;
+        self
+    }
+
+    pub fn last_update_timestamp(mut self, last_update_timestamp: i64) -> Self {
+        self.last_update_timestamp = last_update_timestamp;
+        self
+    }
+
+    pub fn build(&self) -> Account {
+        // Several fields are private, so start from zeroed memory and fill in what the
+        // on-chain reader looks at: a single submission and the current result.
+        let mut feed: PullFeedAccountData = bytemuck::Zeroable::zeroed();
+        feed.submissions[0] = OracleSubmission {
+            slot: self.last_update_slot,
+            landed_at: self.last_update_slot,
+            value: self.value,
+            ..bytemuck::Zeroable::zeroed()
+        };
+        feed.submission_timestamps[0] = self.last_update_timestamp;
+        feed.min_responses = 1;
+        feed.min_sample_size = 1;
+        feed.max_staleness = u32::MAX;
+        feed.last_update_timestamp = self.last_update_timestamp;
+        feed.result = CurrentResult {
+            value: self.value,
+            std_dev: self.std_dev,
+            mean: self.value,
+            range: 0,
+            min_value: self.value,
+            max_value: self.value,
+            num_samples: 1,
+            submission_idx: 0,
+            padding1: [0; 6],
+            slot: self.last_update_slot,
+            min_slot: self.last_update_slot,
+            max_slot: self.last_update_slot,
+        };
+
+        let mut data = PullFeedAccountData::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&feed));
+
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+}
+
+/// Builds a doves `AgPriceFeed` account, owned by the doves program.
+pub struct DovesPriceFeedBuilder {
+    price: i64,
+    expo: i32,
+    timestamp: i64,
+}
+
+impl Default for DovesPriceFeedBuilder {
+    fn default() -> Self { Self::new() }
+}
+
+impl DovesPriceFeedBuilder {
+    /// Defaults to a $1.00 price with 8 decimals, published at timestamp 0.
+    pub fn new() -> Self {
+        Self {
+            price: 100_000_000,
+            expo: -8,
+            timestamp: 0,
+        }
+    }
+
+    pub fn price(mut self, price: i64) -> Self {
+        self.price = price;
+        self
+    }
+
+    pub fn expo(mut self, expo: i32) -> Self {
+        self.expo = expo;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn build(&self) -> Account {
+        // Decode a feed from zeroed bytes so every field we don't care about gets its zero
+        // value, then overwrite the ones the on-chain reader uses.
+        let scratch = vec![0u8; 8 * 1024];
+        let mut feed = AgPriceFeed::try_deserialize_unchecked(&mut &scratch[..]).unwrap();
+        feed.price = self.price.try_into().unwrap();
+        feed.expo = self.expo.try_into().unwrap();
+        feed.timestamp = self.timestamp;
+
+        let mut data = Vec::new();
+        feed.try_serialize(&mut data).unwrap();
+
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: doves::ID_CONST,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+}
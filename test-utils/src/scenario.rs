@@ -0,0 +1,1120 @@
+//! Declarative regression scenarios.
+//!
+//! A scenario is a TOML file describing the accounts to replicate from mainnet, the vaults and
+//! PSM pools to create, and a sequence of steps with their expected outcome. [`run_scenario`]
+//! sets everything up on a [`TestFixture`] and fails on the first step whose outcome differs
+//! from the expectation.
+//!
+//! ```toml
+//! name = "mint limit blocks the second mint"
+//!
+//! [[vaults]]
+//! id = "usdc"
+//! decimals = 6
+//! oracle = { kind = "pyth", price = 1.0 }
+//! period_limit = { duration_seconds = 3600, max_mint_amount = 1500000, max_redeem_amount = 1500000 }
+//! user_balance = 10000000
+//!
+//! [[steps]]
+//! kind = "mint"
+//! vault = "usdc"
+//! amount = 1000000
+//!
+//! [[steps]]
+//! kind = "mint"
+//! vault = "usdc"
+//! amount = 1000000
+//! expect = "MintLimitExceeded"
+//! ```
+//!
+//! Mints are referred to by vault id, or `jupusd` for the stablecoin itself.
+//!
+//! An `assert_invariants` step checks that jup-stable and PSM state agree with the token balances
+//! they account for; see [`Step::AssertInvariants`].
+//!
+//! [`Scenario::random`] generates a reproducible scenario from a seed instead, checking invariants
+//! after every step without predicting individual outcomes.
+
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result};
+use jup_stable::{
+    instructions::OracleConfig,
+    state::{
+        benefactor::BenefactorStatus,
+        common::{PeriodLimitTemplate, MAX_DURATION_SECONDS},
+        vault::VaultStatus,
+    },
+};
+use jupusd_client::{
+    jup_stable::{derivation as stable_pda, instructions as stable_ix},
+    psm::{derivation as psm_pda, instructions as psm_ix},
+};
+use serde::{Deserialize, Deserializer};
+use solana_program_pack::Pack;
+use solana_sdk::{
+    bpf_loader_upgradeable::get_program_data_address, instruction::Instruction, pubkey::Pubkey,
+    signature::Keypair, signer::Signer,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+use crate::{
+    oracle::{DovesPriceFeedBuilder, PythPriceUpdateBuilder, SwitchboardPullFeedBuilder},
+    test::TestFixture,
+};
+
+const JUPUSD: &str = "jupusd";
+const JUPUSD_DECIMALS: u8 = 6;
+const DEFAULT_LIMIT_DURATION_SECONDS: u64 = 3600;
+const DEFAULT_LIMIT_AMOUNT: u64 = 1_000_000_000 * 10_u64.pow(JUPUSD_DECIMALS as u32);
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    /// Mainnet accounts cloned into the bank before setup, as base58 addresses.
+    #[serde(default)]
+    pub replicate: Vec<String>,
+    #[serde(default)]
+    pub benefactor: BenefactorSpec,
+    #[serde(default)]
+    pub vaults: Vec<VaultSpec>,
+    #[serde(default)]
+    pub pools: Vec<PoolSpec>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenefactorSpec {
+    #[serde(default)]
+    pub mint_fee_rate: u16,
+    #[serde(default)]
+    pub redeem_fee_rate: u16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VaultSpec {
+    pub id: String,
+    /// Collateral mint. A fresh SPL mint with `decimals` is created when omitted.
+    pub mint: Option<String>,
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    pub oracle: OracleSpec,
+    /// Vault period limit at index 0. Defaults to a limit no scenario step should reach.
+    pub period_limit: Option<PeriodLimitSpec>,
+    /// Raw collateral amount credited to the scenario user.
+    #[serde(default)]
+    pub user_balance: u64,
+    /// Raw collateral amount seeded into the vault token account, available to redeems.
+    #[serde(default)]
+    pub liquidity: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OracleSpec {
+    pub kind: OracleKind,
+    /// USD price, written to a synthetic feed stamped with the current clock.
+    #[serde(default = "default_price")]
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleKind {
+    Pyth,
+    Switchboard,
+    Doves,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultStatusSpec {
+    Enabled,
+    Disabled,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeriodLimitSpec {
+    pub duration_seconds: u64,
+    pub max_mint_amount: u64,
+    pub max_redeem_amount: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolSpec {
+    pub id: String,
+    /// Mint paid out by the pool.
+    pub redemption: String,
+    /// Mint the pool takes in.
+    pub settlement: String,
+    /// Raw redemption amount the admin supplies once the pool is active.
+    #[serde(default)]
+    pub supply: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Step {
+    SetPrice {
+        vault: String,
+        price: f64,
+    },
+    AdvanceTime {
+        seconds: i64,
+    },
+    /// Replaces the vault period limit at index 0.
+    SetVaultLimit {
+        vault: String,
+        duration_seconds: u64,
+        max_mint_amount: u64,
+        max_redeem_amount: u64,
+    },
+    SetVaultStatus {
+        vault: String,
+        status: VaultStatusSpec,
+    },
+    Mint {
+        vault: String,
+        amount: u64,
+        #[serde(default)]
+        min_amount_out: u64,
+        #[serde(default)]
+        expect: Expect,
+    },
+    Redeem {
+        vault: String,
+        amount: u64,
+        #[serde(default)]
+        min_amount_out: u64,
+        #[serde(default)]
+        expect: Expect,
+    },
+    PsmSupply {
+        pool: String,
+        amount: u64,
+        /// Supplies out of the scenario user's balance instead of crediting the admin, so jupUSD
+        /// supplied this way stays backed by a mint.
+        #[serde(default)]
+        from_user: bool,
+        #[serde(default)]
+        expect: Expect,
+    },
+    PsmRedeem {
+        pool: String,
+        amount: u64,
+        #[serde(default)]
+        min_amount_out: u64,
+        #[serde(default)]
+        expect: Expect,
+    },
+    /// Checks the scenario user's raw token balance.
+    AssertBalance {
+        mint: String,
+        amount: u64,
+    },
+    /// Checks value is conserved across both programs:
+    /// - the jupUSD supply matches the jup-stable tracked supply and what the user, the admin and
+    ///   the pools hold, and is covered by the vaults' net outstanding mints
+    /// - every pool's settlement balance matches what it took in minus what left through
+    ///   withdrawals and retirements
+    /// - every pool holds at least what was supplied minus what its redemptions took in
+    ///
+    /// Pools supplying jupUSD at setup are credited outside jup-stable and break the first check.
+    AssertInvariants,
+}
+
+/// Expected outcome of a transaction step: `"success"`, `"failure"`, `"any"`, or the name of the
+/// Anchor error the program must fail with (e.g. `"MintLimitExceeded"`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Expect {
+    #[default]
+    Success,
+    Failure,
+    Any,
+    Error(String),
+}
+
+impl<'de> Deserialize<'de> for Expect {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let expect = String::deserialize(deserializer)?;
+        Ok(match expect.as_str() {
+            "success" => Expect::Success,
+            "failure" => Expect::Failure,
+            "any" => Expect::Any,
+            _ => Expect::Error(expect),
+        })
+    }
+}
+
+fn default_decimals() -> u8 { 6 }
+
+fn default_price() -> f64 { 1.0 }
+
+impl FromStr for Scenario {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> { Ok(toml::from_str(s)?) }
+}
+
+impl Scenario {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?
+            .parse()
+            .with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Generates `steps` random limit updates, status flips, price moves, clock warps, mints and
+    /// redeems against a Pyth and a Doves vault, each followed by an `assert_invariants` step.
+    /// Transaction steps accept any outcome. The same `seed` always yields the same scenario, and
+    /// the seed is part of its name so a failure can be replayed.
+    pub fn random(seed: u64, steps: usize) -> Self {
+        const VAULTS: [(&str, OracleKind); 2] =
+            [("pyth", OracleKind::Pyth), ("doves", OracleKind::Doves)];
+
+        let mut rng = SplitMix64(seed);
+        let unit = 10_u64.pow(JUPUSD_DECIMALS.into());
+        let vaults = VAULTS
+            .iter()
+            .map(|(id, kind)| VaultSpec {
+                id: id.to_string(),
+                mint: None,
+                decimals: JUPUSD_DECIMALS,
+                oracle: OracleSpec {
+                    kind: *kind,
+                    price: default_price(),
+                },
+                period_limit: None,
+                user_balance: 1_000_000 * unit,
+                liquidity: 1_000_000 * unit,
+            })
+            .collect();
+
+        let mut scenario_steps = Vec::with_capacity(2 * steps);
+        for _ in 0..steps {
+            let vault = VAULTS[rng.range(0, VAULTS.len() as u64 - 1) as usize]
+                .0
+                .to_string();
+            match rng.range(0, 7) {
+                0 => scenario_steps.push(Step::SetVaultLimit {
+                    vault,
+                    duration_seconds: rng.range(60, DEFAULT_LIMIT_DURATION_SECONDS),
+                    max_mint_amount: rng.range(1_000, 20_000) * unit,
+                    max_redeem_amount: rng.range(1_000, 20_000) * unit,
+                }),
+                1 => scenario_steps.push(Step::SetVaultStatus {
+                    vault,
+                    status: if rng.range(0, 3) == 0 {
+                        VaultStatusSpec::Disabled
+                    } else {
+                        VaultStatusSpec::Enabled
+                    },
+                }),
+                2 => scenario_steps.push(Step::SetPrice {
+                    vault,
+                    price: 0.99 + rng.range(0, 200) as f64 / 10_000.0,
+                }),
+                3 => {
+                    scenario_steps.push(Step::AdvanceTime {
+                        seconds: rng.range(1, 2 * DEFAULT_LIMIT_DURATION_SECONDS) as i64,
+                    });
+                    // Feeds would go stale past the vault staleness threshold, re-stamp them
+                    scenario_steps.extend(VAULTS.iter().map(|(id, _)| Step::SetPrice {
+                        vault: id.to_string(),
+                        price: default_price(),
+                    }));
+                },
+                4 | 5 => scenario_steps.push(Step::Mint {
+                    vault,
+                    amount: rng.range(1, 5_000) * unit,
+                    min_amount_out: 0,
+                    expect: Expect::Any,
+                }),
+                _ => scenario_steps.push(Step::Redeem {
+                    vault,
+                    amount: rng.range(1, 5_000) * unit,
+                    min_amount_out: 0,
+                    expect: Expect::Any,
+                }),
+            }
+            scenario_steps.push(Step::AssertInvariants);
+        }
+
+        Scenario {
+            name: format!("random scenario (seed {seed})"),
+            replicate: vec![],
+            benefactor: BenefactorSpec::default(),
+            vaults,
+            pools: vec![],
+            steps: scenario_steps,
+        }
+    }
+}
+
+/// SplitMix64, enough to spread a seed into reproducible scenario choices.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `low..=high`, with a modulo bias small enough not to matter here.
+    fn range(&mut self, low: u64, high: u64) -> u64 { low + self.next() % (high - low + 1) }
+}
+
+/// Sets up the scenario on `test_f` and runs its steps in order.
+pub async fn run_scenario(test_f: &TestFixture, scenario: &Scenario) -> Result<()> {
+    let runner = Runner::setup(test_f, scenario)
+        .await
+        .with_context(|| format!("{}: setup", scenario.name))?;
+
+    for (index, step) in scenario.steps.iter().enumerate() {
+        runner
+            .run_step(step)
+            .await
+            .with_context(|| format!("{}: step {index} ({step:?})", scenario.name))?;
+    }
+
+    Ok(())
+}
+
+struct Oracle {
+    kind: OracleKind,
+    account: Pubkey,
+    feed_id: [u8; 32],
+}
+
+struct Vault {
+    mint: Pubkey,
+    custodian: Pubkey,
+    oracle: Oracle,
+}
+
+struct Pool {
+    address: Pubkey,
+    redemption_mint: Pubkey,
+    settlement_mint: Pubkey,
+}
+
+struct Runner<'a> {
+    test_f: &'a TestFixture,
+    user: Keypair,
+    benefactor: Pubkey,
+    lp_mint: Pubkey,
+    mints: HashMap<String, Pubkey>,
+    vaults: HashMap<String, Vault>,
+    pools: HashMap<String, Pool>,
+}
+
+impl<'a> Runner<'a> {
+    async fn setup(test_f: &'a TestFixture, scenario: &Scenario) -> Result<Runner<'a>> {
+        for address in &scenario.replicate {
+            let address = Pubkey::from_str(address)
+                .map_err(|err| anyhow!("invalid replicate address {address}: {err}"))?;
+            test_f.replicate_account_from_mainnet(&address).await?;
+        }
+
+        let admin = test_f.deployer.pubkey();
+        let lp_mint = Keypair::new();
+        let limit = default_period_limit();
+        test_f
+            .send_ixs(
+                &[
+                    stable_ix::create_init_instruction(
+                        stable_ix::InitInstructionAccounts {
+                            payer: admin,
+                            upgrade_authority: admin,
+                            program_data: get_program_data_address(&jup_stable::ID),
+                            mint: lp_mint.pubkey(),
+                            token_program: spl_token::ID,
+                        },
+                        stable_ix::InitInstructionArgs {
+                            decimals: JUPUSD_DECIMALS,
+                            name: "Jupiter USD".to_string(),
+                            symbol: "JUPUSD".to_string(),
+                            uri: "https://jup.ag/jupusd".to_string(),
+                            peg_price_usd: 10_000,
+                            is_mint_redeem_enabled: true,
+                            default_vault_period_limit: PeriodLimitTemplate::default(),
+                            default_benefactor_period_limit: PeriodLimitTemplate::default(),
+                        },
+                    ),
+                    stable_ix::create_update_config_period_limit_instruction(
+                        admin,
+                        0,
+                        limit.duration_seconds,
+                        limit.max_mint_amount,
+                        limit.max_redeem_amount,
+                    ),
+                ],
+                &[&test_f.deployer, &lp_mint],
+            )
+            .await?;
+
+        let user = Keypair::new();
+        test_f.fund_account(&user.pubkey()).await;
+
+        let benefactor = stable_pda::find_benefactor(&user.pubkey());
+        test_f
+            .send_ixs(
+                &[
+                    stable_ix::create_create_benefactor_instruction(
+                        stable_ix::CreateBenefactorInstructionAccounts {
+                            authority: admin,
+                            payer: admin,
+                            benefactor_authority: user.pubkey(),
+                            registry_page: 0,
+                        },
+                        stable_ix::CreateBenefactorInstructionArgs {
+                            mint_fee_rate: scenario.benefactor.mint_fee_rate,
+                            redeem_fee_rate: scenario.benefactor.redeem_fee_rate,
+                        },
+                    ),
+                    stable_ix::create_set_benefactor_status_instruction(
+                        admin,
+                        benefactor,
+                        BenefactorStatus::Active,
+                    ),
+                    stable_ix::create_update_benefactor_period_limit_instruction(
+                        admin,
+                        benefactor,
+                        0,
+                        limit.duration_seconds,
+                        limit.max_mint_amount,
+                        limit.max_redeem_amount,
+                    ),
+                ],
+                &[&test_f.deployer],
+            )
+            .await?;
+
+        let mut runner = Runner {
+            test_f,
+            user,
+            benefactor,
+            lp_mint: lp_mint.pubkey(),
+            mints: HashMap::from([(JUPUSD.to_string(), lp_mint.pubkey())]),
+            vaults: HashMap::new(),
+            pools: HashMap::new(),
+        };
+        runner
+            .ensure_token_account(&runner.user.pubkey(), &runner.lp_mint)
+            .await?;
+
+        for (index, spec) in scenario.vaults.iter().enumerate() {
+            runner.create_vault(index, spec).await?;
+        }
+
+        if !scenario.pools.is_empty() {
+            test_f
+                .send_ixs(
+                    &[psm_ix::create_init_instruction(
+                        psm_ix::InitInstructionAccounts {
+                            payer: admin,
+                            upgrade_authority: admin,
+                            program_data: get_program_data_address(&psm::ID),
+                        },
+                    )],
+                    &[&test_f.deployer],
+                )
+                .await?;
+        }
+        for spec in &scenario.pools {
+            runner.create_pool(spec).await?;
+        }
+
+        Ok(runner)
+    }
+
+    async fn create_vault(&mut self, index: usize, spec: &VaultSpec) -> Result<()> {
+        if self.mints.contains_key(&spec.id) {
+            bail!("duplicate mint id {}", spec.id);
+        }
+
+        let test_f = self.test_f;
+        let admin = test_f.deployer.pubkey();
+        let mint = match &spec.mint {
+            Some(mint) => {
+                Pubkey::from_str(mint).map_err(|err| anyhow!("invalid mint {mint}: {err}"))?
+            },
+            None => test_f.create_spl_mint(spec.decimals).await,
+        };
+
+        let oracle = Oracle {
+            kind: spec.oracle.kind,
+            account: Keypair::new().pubkey(),
+            feed_id: [index as u8 + 1; 32],
+        };
+        self.write_price(&oracle, spec.oracle.price).await?;
+        let oracle_config = match oracle.kind {
+            OracleKind::Pyth => OracleConfig::Pyth(oracle.feed_id, oracle.account),
+            OracleKind::Switchboard => OracleConfig::SwitchboardOnDemand(oracle.account),
+            OracleKind::Doves => OracleConfig::Doves(oracle.account),
+        };
+
+        let custodian = Keypair::new().pubkey();
+        let default_limit = default_period_limit();
+        let period_limit = spec.period_limit.as_ref().unwrap_or(&default_limit);
+        test_f
+            .send_ixs(
+                &[
+                    stable_ix::create_approve_collateral_instruction(
+                        stable_ix::ApproveCollateralInstructionAccounts {
+                            authority: admin,
+                            payer: admin,
+                            mint,
+                        },
+                    ),
+                    stable_ix::create_create_vault_instruction(
+                        stable_ix::CreateVaultInstructionAccounts {
+                            authority: admin,
+                            payer: admin,
+                            mint,
+                            token_program: spl_token::ID,
+                        },
+                    ),
+                    stable_ix::create_set_custodian_instruction(admin, mint, custodian),
+                    stable_ix::create_update_vault_oracle_instruction(
+                        admin,
+                        mint,
+                        0,
+                        oracle_config,
+                    ),
+                    stable_ix::create_update_vault_period_limit_instruction(
+                        admin,
+                        mint,
+                        0,
+                        period_limit.duration_seconds,
+                        period_limit.max_mint_amount,
+                        period_limit.max_redeem_amount,
+                    ),
+                    stable_ix::create_set_vault_status_instruction(
+                        admin,
+                        mint,
+                        VaultStatus::Enabled,
+                    ),
+                ],
+                &[&test_f.deployer],
+            )
+            .await?;
+
+        self.ensure_token_account(&custodian, &mint).await?;
+        let user_token_account = self
+            .ensure_token_account(&self.user.pubkey(), &mint)
+            .await?;
+        test_f
+            .mint_tokens(&user_token_account, spec.user_balance)
+            .await;
+        if spec.liquidity > 0 {
+            let vault_token_account = stable_pda::find_vault_token_account(&mint);
+            test_f
+                .mint_tokens(&vault_token_account, spec.liquidity)
+                .await;
+        }
+
+        self.mints.insert(spec.id.clone(), mint);
+        self.vaults.insert(spec.id.clone(), Vault {
+            mint,
+            custodian,
+            oracle,
+        });
+
+        Ok(())
+    }
+
+    async fn create_pool(&mut self, spec: &PoolSpec) -> Result<()> {
+        if self.pools.contains_key(&spec.id) {
+            bail!("duplicate pool id {}", spec.id);
+        }
+
+        let test_f = self.test_f;
+        let admin = test_f.deployer.pubkey();
+        let redemption_mint = self.mint(&spec.redemption)?;
+        let settlement_mint = self.mint(&spec.settlement)?;
+        let pool = Pool {
+            address: psm_pda::find_pool(&redemption_mint, &settlement_mint),
+            redemption_mint,
+            settlement_mint,
+        };
+
+        test_f
+            .send_ixs(
+                &[
+                    psm_ix::create_create_pool_instruction(psm_ix::CreatePoolInstructionAccounts {
+                        admin,
+                        payer: admin,
+                        redemption_mint: pool.redemption_mint,
+                        settlement_mint: pool.settlement_mint,
+                        redemption_token_program: spl_token::ID,
+                        settlement_token_program: spl_token::ID,
+                    }),
+                    psm_ix::create_set_supply_period_limit_instruction(
+                        admin,
+                        pool.redemption_mint,
+                        pool.settlement_mint,
+                        MAX_DURATION_SECONDS,
+                        u64::MAX,
+                    ),
+                    psm_ix::create_activate_pool_instruction(
+                        admin,
+                        pool.redemption_mint,
+                        pool.settlement_mint,
+                    ),
+                ],
+                &[&test_f.deployer],
+            )
+            .await?;
+
+        let admin_token_account = self
+            .ensure_token_account(&admin, &pool.redemption_mint)
+            .await?;
+        if spec.supply > 0 {
+            test_f.mint_tokens(&admin_token_account, spec.supply).await;
+            test_f
+                .send_ixs(&[psm_supply_ix(admin, &pool, spec.supply)], &[
+                    &test_f.deployer
+                ])
+                .await?;
+        }
+
+        self.pools.insert(spec.id.clone(), pool);
+
+        Ok(())
+    }
+
+    async fn run_step(&self, step: &Step) -> Result<()> {
+        let test_f = self.test_f;
+        match step {
+            Step::SetPrice { vault, price } => {
+                let vault = self.vault(vault)?;
+                self.write_price(&vault.oracle, *price).await
+            },
+            Step::AdvanceTime { seconds } => {
+                test_f.advance_time(*seconds).await;
+                Ok(())
+            },
+            Step::SetVaultLimit {
+                vault,
+                duration_seconds,
+                max_mint_amount,
+                max_redeem_amount,
+            } => {
+                let ix = stable_ix::create_update_vault_period_limit_instruction(
+                    test_f.deployer.pubkey(),
+                    self.vault(vault)?.mint,
+                    0,
+                    *duration_seconds,
+                    *max_mint_amount,
+                    *max_redeem_amount,
+                );
+                test_f.send_ixs(&[ix], &[&test_f.deployer]).await
+            },
+            Step::SetVaultStatus { vault, status } => {
+                let status = match status {
+                    VaultStatusSpec::Enabled => VaultStatus::Enabled,
+                    VaultStatusSpec::Disabled => VaultStatus::Disabled,
+                };
+                let ix = stable_ix::create_set_vault_status_instruction(
+                    test_f.deployer.pubkey(),
+                    self.vault(vault)?.mint,
+                    status,
+                );
+                test_f.send_ixs(&[ix], &[&test_f.deployer]).await
+            },
+            Step::Mint {
+                vault,
+                amount,
+                min_amount_out,
+                expect,
+            } => {
+                let ix = self.mint_ix(self.vault(vault)?, *amount, *min_amount_out);
+                self.send(&[ix], &[&self.user], expect).await
+            },
+            Step::Redeem {
+                vault,
+                amount,
+                min_amount_out,
+                expect,
+            } => {
+                let ix = self.redeem_ix(self.vault(vault)?, *amount, *min_amount_out);
+                self.send(&[ix], &[&self.user], expect).await
+            },
+            Step::PsmSupply {
+                pool,
+                amount,
+                from_user,
+                expect,
+            } => {
+                let pool = self.pool(pool)?;
+                let admin = test_f.deployer.pubkey();
+                let admin_token_account =
+                    get_associated_token_address(&admin, &pool.redemption_mint);
+                if !from_user {
+                    let balance = self.balance(&admin_token_account).await?;
+                    test_f
+                        .mint_tokens(&admin_token_account, balance.saturating_add(*amount))
+                        .await;
+                    return self
+                        .send(
+                            &[psm_supply_ix(admin, pool, *amount)],
+                            &[&test_f.deployer],
+                            expect,
+                        )
+                        .await;
+                }
+
+                let decimals = self.mint_state(&pool.redemption_mint).await?.decimals;
+                let transfer_ix = spl_token::instruction::transfer_checked(
+                    &spl_token::ID,
+                    &get_associated_token_address(&self.user.pubkey(), &pool.redemption_mint),
+                    &pool.redemption_mint,
+                    &admin_token_account,
+                    &self.user.pubkey(),
+                    &[],
+                    *amount,
+                    decimals,
+                )?;
+                self.send(
+                    &[transfer_ix, psm_supply_ix(admin, pool, *amount)],
+                    &[&self.user, &test_f.deployer],
+                    expect,
+                )
+                .await
+            },
+            Step::PsmRedeem {
+                pool,
+                amount,
+                min_amount_out,
+                expect,
+            } => {
+                let ix = psm_redeem_ix(
+                    self.user.pubkey(),
+                    self.pool(pool)?,
+                    *amount,
+                    *min_amount_out,
+                );
+                self.send(&[ix], &[&self.user], expect).await
+            },
+            Step::AssertBalance { mint, amount } => {
+                let token_account =
+                    get_associated_token_address(&self.user.pubkey(), &self.mint(mint)?);
+                let balance = self.balance(&token_account).await?;
+                if balance != *amount {
+                    bail!("expected {mint} balance {amount}, got {balance}");
+                }
+                Ok(())
+            },
+            Step::AssertInvariants => self.assert_invariants().await,
+        }
+    }
+
+    async fn assert_invariants(&self) -> Result<()> {
+        let test_f = self.test_f;
+        let supply = self.mint_state(&self.lp_mint).await?.supply;
+
+        let config: jup_stable::state::config::Config = test_f
+            .load_and_deserialize(&stable_pda::find_config())
+            .await;
+        if config.tracked_supply != supply {
+            bail!(
+                "jupUSD supply {supply}, jup-stable tracked supply {}",
+                config.tracked_supply
+            );
+        }
+
+        let mut net_outstanding = 0_u128;
+        for vault in self.vaults.values() {
+            let vault: jup_stable::state::vault::Vault = test_f
+                .load_and_deserialize(&stable_pda::find_vault(&vault.mint))
+                .await;
+            net_outstanding += vault.net_outstanding();
+        }
+        if net_outstanding < supply as u128 {
+            bail!("jupUSD supply {supply}, vaults net outstanding {net_outstanding}");
+        }
+
+        let admin = self.test_f.deployer.pubkey();
+        let mut holders = vec![
+            get_associated_token_address(&self.user.pubkey(), &self.lp_mint),
+            get_associated_token_address(&admin, &self.lp_mint),
+        ];
+        for pool in self.pools.values() {
+            let address = pool.address;
+            let pool: psm::state::pool::Pool = test_f.load_and_deserialize(&address).await;
+            let redemption_balance = self.balance(&pool.redemption_token_account).await?;
+            let settlement_balance = self.balance(&pool.settlement_token_account).await?;
+
+            let settlement_held = pool
+                .total_redeemed()
+                .checked_sub(pool.total_withdrawn() + pool.total_retired())
+                .ok_or_else(|| {
+                    anyhow!("pool {address} paid out more settlement than it took in")
+                })?;
+            if settlement_balance as u128 != settlement_held {
+                bail!(
+                    "pool {address} holds {settlement_balance} settlement, expected \
+                     {settlement_held}"
+                );
+            }
+
+            let redeemed = normalize(
+                pool.total_redeemed(),
+                pool.settlement_token_decimals,
+                pool.redemption_token_decimals,
+            );
+            if redemption_balance as u128 + redeemed < pool.total_supplied() {
+                bail!(
+                    "pool {address} holds {redemption_balance} redemption after {redeemed} \
+                     redeemed, {} supplied",
+                    pool.total_supplied()
+                );
+            }
+
+            if pool.redemption_mint == self.lp_mint {
+                holders.push(pool.redemption_token_account);
+            }
+            if pool.settlement_mint == self.lp_mint {
+                holders.push(pool.settlement_token_account);
+            }
+        }
+
+        let mut held = 0_u64;
+        for holder in &holders {
+            if self.account_exists(holder).await? {
+                held += self.balance(holder).await?;
+            }
+        }
+        if held != supply {
+            bail!("jupUSD supply {supply}, held {held}");
+        }
+
+        Ok(())
+    }
+
+    /// Processes `ixs` in one transaction and compares the outcome with `expect`.
+    async fn send(&self, ixs: &[Instruction], signers: &[&Keypair], expect: &Expect) -> Result<()> {
+        let tx = self.test_f.tx().ixs(ixs).signers(signers).build().await?;
+        let outcome = self
+            .test_f
+            .context
+            .borrow()
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+
+        let logs = outcome
+            .metadata
+            .map(|metadata| metadata.log_messages)
+            .unwrap_or_default();
+        match (expect, outcome.result) {
+            (Expect::Any, _) | (Expect::Success, Ok(())) | (Expect::Failure, Err(_)) => Ok(()),
+            (Expect::Error(name), Err(err)) => {
+                let code = format!("Error Code: {name}.");
+                if !logs.iter().any(|log| log.contains(&code)) {
+                    bail!("expected {name}, failed with {err}\n{}", logs.join("\n"));
+                }
+                Ok(())
+            },
+            (Expect::Success, Err(err)) => {
+                bail!("expected success, got {err}\n{}", logs.join("\n"))
+            },
+            (_, Ok(())) => bail!("expected {expect:?}, transaction succeeded"),
+        }
+    }
+
+    async fn write_price(&self, oracle: &Oracle, price: f64) -> Result<()> {
+        let clock = self.test_f.get_clock().await;
+        let account = match oracle.kind {
+            OracleKind::Pyth => PythPriceUpdateBuilder::new(oracle.feed_id)
+                .price(scale_price(price, 8)?)
+                .publish_time(clock.unix_timestamp)
+                .build(),
+            OracleKind::Switchboard => SwitchboardPullFeedBuilder::new()
+                .value((price * 1e18).round() as i128)
+                .last_update_slot(clock.slot)
+                .last_update_timestamp(clock.unix_timestamp)
+                .build(),
+            OracleKind::Doves => DovesPriceFeedBuilder::new()
+                .price(scale_price(price, 8)?)
+                .timestamp(clock.unix_timestamp)
+                .build(),
+        };
+        self.test_f.set_account(&oracle.account, account).await;
+
+        Ok(())
+    }
+
+    async fn ensure_token_account(&self, owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+        let address = get_associated_token_address(owner, mint);
+        if !self.account_exists(&address).await? {
+            self.test_f
+                .send_ixs(
+                    &[create_associated_token_account(
+                        &self.test_f.deployer.pubkey(),
+                        owner,
+                        mint,
+                        &spl_token::ID,
+                    )],
+                    &[&self.test_f.deployer],
+                )
+                .await?;
+        }
+
+        Ok(address)
+    }
+
+    async fn account_exists(&self, address: &Pubkey) -> Result<bool> {
+        Ok(self
+            .test_f
+            .context
+            .borrow()
+            .banks_client
+            .get_account(*address)
+            .await?
+            .is_some())
+    }
+
+    async fn mint_state(&self, mint: &Pubkey) -> Result<spl_token::state::Mint> {
+        let account = self
+            .test_f
+            .context
+            .borrow()
+            .banks_client
+            .get_account(*mint)
+            .await?
+            .ok_or_else(|| anyhow!("mint {mint} does not exist"))?;
+
+        Ok(spl_token::state::Mint::unpack(&account.data)?)
+    }
+
+    async fn balance(&self, token_account: &Pubkey) -> Result<u64> {
+        let account = self
+            .test_f
+            .context
+            .borrow()
+            .banks_client
+            .get_account(*token_account)
+            .await?
+            .ok_or_else(|| anyhow!("token account {token_account} does not exist"))?;
+
+        Ok(spl_token::state::Account::unpack(&account.data)?.amount)
+    }
+
+    fn mint(&self, id: &str) -> Result<Pubkey> {
+        self.mints
+            .get(id)
+            .copied()
+            .ok_or_else(|| anyhow!("unknown mint {id}"))
+    }
+
+    fn vault(&self, id: &str) -> Result<&Vault> {
+        self.vaults
+            .get(id)
+            .ok_or_else(|| anyhow!("unknown vault {id}"))
+    }
+
+    fn pool(&self, id: &str) -> Result<&Pool> {
+        self.pools
+            .get(id)
+            .ok_or_else(|| anyhow!("unknown pool {id}"))
+    }
+
+    fn mint_ix(&self, vault: &Vault, amount: u64, min_amount_out: u64) -> Instruction {
+        stable_ix::create_mint_instruction(
+            amount,
+            min_amount_out,
+            stable_ix::MintInstructionAccounts {
+                user: self.user.pubkey(),
+                benefactor: self.benefactor,
+                custodian: vault.custodian,
+                custodian_token_account: None,
+                vault_mint: vault.mint,
+                lp_mint: self.lp_mint,
+                vault_token_program: spl_token::ID,
+                lp_token_program: spl_token::ID,
+                remaining_accounts: vec![vault.oracle.account],
+            },
+        )
+    }
+
+    fn redeem_ix(&self, vault: &Vault, amount: u64, min_amount_out: u64) -> Instruction {
+        stable_ix::create_redeem_instruction(
+            amount,
+            min_amount_out,
+            stable_ix::RedeemInstructionAccounts {
+                user: self.user.pubkey(),
+                benefactor: self.benefactor,
+                vault_mint: vault.mint,
+                lp_mint: self.lp_mint,
+                vault_token_program: spl_token::ID,
+                lp_token_program: spl_token::ID,
+                remaining_accounts: vec![vault.oracle.account],
+            },
+        )
+    }
+}
+
+fn default_period_limit() -> PeriodLimitSpec {
+    PeriodLimitSpec {
+        duration_seconds: DEFAULT_LIMIT_DURATION_SECONDS,
+        max_mint_amount: DEFAULT_LIMIT_AMOUNT,
+        max_redeem_amount: DEFAULT_LIMIT_AMOUNT,
+    }
+}
+
+fn scale_price(price: f64, decimals: i32) -> Result<i64> {
+    let scaled = (price * 10_f64.powi(decimals)).round();
+    if !scaled.is_finite() || scaled.abs() > i64::MAX as f64 {
+        bail!("price {price} out of range");
+    }
+    Ok(scaled as i64)
+}
+
+/// `amount` in `from_decimals` units converted to `to_decimals`, rounding down.
+fn normalize(amount: u128, from_decimals: u8, to_decimals: u8) -> u128 {
+    if from_decimals >= to_decimals {
+        amount / 10_u128.pow((from_decimals - to_decimals).into())
+    } else {
+        amount * 10_u128.pow((to_decimals - from_decimals).into())
+    }
+}
+
+fn psm_supply_ix(admin: Pubkey, pool: &Pool, amount: u64) -> Instruction {
+    psm_ix::create_supply_instruction(
+        psm_ix::SupplyInstructionAccounts {
+            admin,
+            redemption_mint: pool.redemption_mint,
+            settlement_mint: pool.settlement_mint,
+            redemption_token_program: spl_token::ID,
+        },
+        amount,
+    )
+}
+
+fn psm_redeem_ix(user: Pubkey, pool: &Pool, amount: u64, min_amount_out: u64) -> Instruction {
+    psm_ix::create_redeem_instruction(
+        psm_ix::RedeemInstructionAccounts {
+            user,
+            redemption_mint: pool.redemption_mint,
+            settlement_mint: pool.settlement_mint,
+            redemption_token_program: spl_token::ID,
+            settlement_token_program: spl_token::ID,
+        },
+        amount,
+        min_amount_out,
+    )
+}
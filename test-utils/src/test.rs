@@ -1,9 +1,18 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::Mutex};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Mutex,
+};
 
-use anchor_lang::{system_program, Id};
+use anchor_lang::{system_program, Discriminator, Id, ZeroCopy};
 use anchor_spl::metadata::Metadata;
 use anyhow::Result;
 use bincode::deserialize;
+use futures::future::join_all;
+use jup_stable::state::{
+    benefactor::Benefactor, common::PeriodLimit, config::Config, vault::Vault,
+};
 use once_cell::sync::Lazy;
 use solana_account::{Account, AccountSharedData};
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -11,13 +20,19 @@ use solana_program::hash::Hash;
 use solana_program_pack::Pack;
 use solana_program_test::{ProgramTest, ProgramTestContext};
 use solana_sdk::{
-    clock::Clock, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair,
-    signer::Signer, sysvar,
+    clock::Clock, instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, system_instruction, sysvar, transaction::Transaction,
+};
+use spl_token_2022::extension::{
+    transfer_fee::instruction::initialize_transfer_fee_config, transfer_hook, ExtensionType,
 };
 
-use crate::utils::{
-    add_external_program_to_genesis, clone_keypair, create_funded_system_program_account,
-    patch_program_data_account, RUST_LOG_DEFAULT,
+use crate::{
+    tx::TxBuilder,
+    utils::{
+        add_external_program_to_genesis, clone_keypair, create_funded_system_program_account,
+        patch_program_data_account, zero_copy_range, RUST_LOG_DEFAULT,
+    },
 };
 
 const MAINNET_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
@@ -29,14 +44,76 @@ impl AccountCache {
     fn new() -> Self { Self(HashMap::new()) }
 }
 
+/// Token-2022 mint extensions supported by [`TestFixture::create_token2022_mint`].
+pub enum MintExtension {
+    TransferFee {
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    TransferHook {
+        program_id: Pubkey,
+    },
+}
+
+impl MintExtension {
+    fn extension_type(&self) -> ExtensionType {
+        match self {
+            MintExtension::TransferFee { .. } => ExtensionType::TransferFeeConfig,
+            MintExtension::TransferHook { .. } => ExtensionType::TransferHook,
+        }
+    }
+
+    fn initialize_instruction(&self, mint: &Pubkey, authority: &Pubkey) -> Instruction {
+        match self {
+            MintExtension::TransferFee {
+                transfer_fee_basis_points,
+                maximum_fee,
+            } => initialize_transfer_fee_config(
+                &spl_token_2022::ID,
+                mint,
+                Some(authority),
+                Some(authority),
+                *transfer_fee_basis_points,
+                *maximum_fee,
+            )
+            .unwrap(),
+            MintExtension::TransferHook { program_id } => transfer_hook::instruction::initialize(
+                &spl_token_2022::ID,
+                mint,
+                Some(*authority),
+                Some(*program_id),
+            )
+            .unwrap(),
+        }
+    }
+}
+
 pub struct TestFixture {
     pub context: Rc<RefCell<ProgramTestContext>>,
     pub deployer: Keypair,
     pub rpc_client: RpcClient,
+    tracked_accounts: RefCell<HashSet<Pubkey>>,
+}
+
+/// An account holding `PeriodLimit` windows.
+pub enum PeriodLimitAccount {
+    Config(Pubkey),
+    Vault(Pubkey),
+    Benefactor(Pubkey),
+}
+
+/// Account and clock state captured by [`TestFixture::snapshot`].
+pub struct Snapshot {
+    accounts: HashMap<Pubkey, Option<Account>>,
+    clock: Clock,
 }
 
 impl TestFixture {
-    pub async fn new() -> TestFixture {
+    pub async fn new() -> TestFixture { Self::new_with_programs(&[]).await }
+
+    /// Like [`TestFixture::new`], additionally deploying `programs` as `(name, id)` pairs, e.g.
+    /// downstream programs that CPI into jup-stable or the PSM.
+    pub async fn new_with_programs(programs: &[(&'static str, Pubkey)]) -> TestFixture {
         let rpc_client = RpcClient::new_with_timeout(
             MAINNET_RPC_URL.to_string(),
             std::time::Duration::from_secs(60),
@@ -48,6 +125,9 @@ impl TestFixture {
         let deployer_pubkey = deployer.pubkey();
         program.add_upgradeable_program_to_genesis("jup_stable", &jup_stable::ID);
         program.add_upgradeable_program_to_genesis("psm", &psm::ID);
+        for (name, program_id) in programs {
+            program.add_upgradeable_program_to_genesis(name, program_id);
+        }
 
         add_external_program_to_genesis(
             &mut program,
@@ -63,6 +143,7 @@ impl TestFixture {
             context: Rc::clone(&context),
             deployer,
             rpc_client,
+            tracked_accounts: RefCell::new(HashSet::new()),
         };
 
         patch_program_data_account(&s, &jup_stable::ID, Some(deployer_pubkey)).await;
@@ -79,6 +160,7 @@ impl TestFixture {
             executable: false,
             rent_epoch: 0,
         };
+        self.track(&[*address]);
         self.context
             .borrow_mut()
             .set_account(address, &account.into());
@@ -95,21 +177,26 @@ impl TestFixture {
             .unwrap();
 
         account.data[offset..offset + data.len()].copy_from_slice(data);
+        self.track(&[address]);
 
         self.context
             .borrow_mut()
             .set_account(&address, &account.into());
     }
 
+    /// Overwrites the balance of `token_account`. Token-2022 accounts keep their extensions,
+    /// only the base account state at the start of the data is rewritten.
     pub async fn mint_tokens(&self, token_account: &Pubkey, amount: u64) {
         let account = self.get_account(token_account).await;
 
-        let mut token_account_state =
-            spl_token_2022::state::Account::unpack(&account.data).unwrap();
+        let mut token_account_state = spl_token_2022::state::Account::unpack_from_slice(
+            &account.data[..spl_token_2022::state::Account::LEN],
+        )
+        .unwrap();
 
         token_account_state.amount = amount;
 
-        let mut buf = vec![0; 165];
+        let mut buf = vec![0; spl_token_2022::state::Account::LEN];
         token_account_state.pack_into_slice(&mut buf);
         self.patch_account(*token_account, 0, &buf).await;
     }
@@ -141,6 +228,7 @@ impl TestFixture {
     }
 
     pub async fn set_account(&self, address: &Pubkey, account: solana_sdk::account::Account) {
+        self.track(&[*address]);
         self.context
             .borrow_mut()
             .set_account(address, &account.into());
@@ -174,6 +262,171 @@ impl TestFixture {
             .unwrap();
     }
 
+    pub fn warp_to_slot(&self, slot: u64) { self.context.borrow_mut().warp_to_slot(slot).unwrap(); }
+
+    pub fn tx(&self) -> TxBuilder<'_> { TxBuilder::new(self) }
+
+    /// Registers accounts to be captured by [`TestFixture::snapshot`]. Accounts written through
+    /// the fixture and [`TxBuilder`] are tracked automatically; anything only touched by raw
+    /// `banks_client` transactions has to be registered here.
+    pub fn track(&self, addresses: &[Pubkey]) {
+        self.tracked_accounts
+            .borrow_mut()
+            .extend(addresses.iter().copied());
+    }
+
+    /// Captures every tracked account and the clock. The bank cannot enumerate its accounts, so
+    /// only tracked accounts are covered.
+    pub async fn snapshot(&self) -> Snapshot {
+        let addresses: Vec<Pubkey> = self.tracked_accounts.borrow().iter().copied().collect();
+
+        let mut accounts = HashMap::with_capacity(addresses.len());
+        for address in addresses {
+            let account = self
+                .context
+                .borrow_mut()
+                .banks_client
+                .get_account(address)
+                .await
+                .unwrap();
+            // Programs and sysvars are never rewritten on restore
+            if account
+                .as_ref()
+                .is_some_and(|a| a.executable || a.owner == sysvar::ID)
+            {
+                continue;
+            }
+            accounts.insert(address, account);
+        }
+
+        Snapshot {
+            accounts,
+            clock: self.get_clock().await,
+        }
+    }
+
+    /// Resets tracked accounts and the clock to `snapshot`. Accounts first tracked after the
+    /// snapshot was taken are closed.
+    pub async fn restore(&self, snapshot: &Snapshot) {
+        let tracked: Vec<Pubkey> = self.tracked_accounts.borrow().iter().copied().collect();
+
+        let mut ctx = self.context.borrow_mut();
+        for address in tracked {
+            match snapshot.accounts.get(&address) {
+                Some(Some(account)) => ctx.set_account(&address, &account.clone().into()),
+                Some(None) => ctx.set_account(&address, &AccountSharedData::default()),
+                None => {
+                    let current = ctx.banks_client.get_account(address).await.unwrap();
+                    if current.is_some_and(|a| !a.executable && a.owner != sysvar::ID) {
+                        ctx.set_account(&address, &AccountSharedData::default());
+                    }
+                },
+            }
+        }
+        ctx.set_sysvar(&snapshot.clock);
+    }
+
+    /// Sends `ixs` in a single transaction, paid for by the first signer.
+    pub async fn send_ixs(&self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+        self.tx().ixs(ixs).signers(signers).send().await
+    }
+
+    /// Submits `txs` all at once within the current slot, so the bank interleaves them the way a
+    /// leader would under contention instead of one per blockhash. Build them against a shared
+    /// blockhash with [`TxBuilder::blockhash`]. Outcomes come back in `txs` order.
+    pub async fn process_concurrently(&self, txs: Vec<Transaction>) -> Vec<Result<()>> {
+        let banks_client = self.context.borrow().banks_client.clone();
+        join_all(txs.into_iter().map(|tx| {
+            let banks_client = banks_client.clone();
+            async move {
+                banks_client.process_transaction(tx).await?;
+                Ok(())
+            }
+        }))
+        .await
+    }
+
+    /// Loads the zero-copy `T` at `address`, applies `mutate` and writes it back. Useful for
+    /// setting up states the program would not reach on its own, e.g. near-overflow totals.
+    pub async fn mutate_zero_copy<T: Discriminator + ZeroCopy>(
+        &self,
+        address: &Pubkey,
+        mutate: impl FnOnce(&mut T),
+    ) {
+        let mut account = self.get_account(address).await;
+        let range = zero_copy_range::<T>(&account.data);
+
+        // Copy out rather than casting in place, account data carries no alignment guarantee
+        let mut state: T = bytemuck::pod_read_unaligned(&account.data[range.clone()]);
+        mutate(&mut state);
+        account.data[range].copy_from_slice(bytemuck::bytes_of(&state));
+
+        self.set_account(address, account).await;
+    }
+
+    pub async fn load_period_limit(
+        &self,
+        target: &PeriodLimitAccount,
+        index: usize,
+    ) -> PeriodLimit {
+        match target {
+            PeriodLimitAccount::Config(address) => {
+                self.load_and_deserialize::<Config>(address)
+                    .await
+                    .period_limits[index]
+            },
+            PeriodLimitAccount::Vault(address) => {
+                self.load_and_deserialize::<Vault>(address)
+                    .await
+                    .period_limits[index]
+            },
+            PeriodLimitAccount::Benefactor(address) => {
+                self.load_and_deserialize::<Benefactor>(address)
+                    .await
+                    .period_limits[index]
+            },
+        }
+    }
+
+    /// Warps the clock just past the end of the current `index` window of `target` and returns
+    /// the window as it was before warping, for use with [`TestFixture::assert_window_rolled`].
+    pub async fn advance_past_window(
+        &self,
+        target: &PeriodLimitAccount,
+        index: usize,
+    ) -> PeriodLimit {
+        let limit = self.load_period_limit(target, index).await;
+        assert!(
+            limit.duration_seconds > 0,
+            "period limit {index} is disabled"
+        );
+
+        let now = self.get_clock().await.unix_timestamp;
+        let window_end = limit.window_start + limit.duration_seconds as i64;
+        self.advance_time((window_end - now).max(0) + 1).await;
+
+        limit
+    }
+
+    /// Asserts the `index` window of `target` was rolled since `previous` was read, i.e. the
+    /// latest mint or redeem opened a fresh window instead of accruing into the stale one.
+    pub async fn assert_window_rolled(
+        &self,
+        target: &PeriodLimitAccount,
+        index: usize,
+        previous: &PeriodLimit,
+    ) -> PeriodLimit {
+        let limit = self.load_period_limit(target, index).await;
+        assert!(
+            limit.window_start >= previous.window_start + previous.duration_seconds as i64,
+            "window {index} was not rolled"
+        );
+        assert!(limit.minted_amount <= limit.max_mint_amount);
+        assert!(limit.redeemed_amount <= limit.max_redeem_amount);
+
+        limit
+    }
+
     pub async fn get_minimum_rent_for_size(&self, size: usize) -> u64 {
         self.context
             .borrow_mut()
@@ -217,8 +470,117 @@ impl TestFixture {
         .unwrap()
     }
 
+    /// Creates a local SPL Token mint with the deployer as mint authority.
+    pub async fn create_spl_mint(&self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let authority = self.deployer.pubkey();
+        let space = spl_token::state::Mint::LEN;
+
+        let instructions = [
+            system_instruction::create_account(
+                &authority,
+                &mint.pubkey(),
+                self.get_minimum_rent_for_size(space).await,
+                space as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &authority,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ];
+
+        self.process_mint_instructions(&instructions, &mint).await;
+
+        mint.pubkey()
+    }
+
+    /// Creates an SPL Token account for `mint` owned by `owner` at a fresh, non-associated address.
+    pub async fn create_spl_token_account(&self, mint: &Pubkey, owner: &Pubkey) -> Result<Pubkey> {
+        let account = Keypair::new();
+        let payer = self.deployer.pubkey();
+        let space = spl_token::state::Account::LEN;
+
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                self.get_minimum_rent_for_size(space).await,
+                space as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::ID,
+                &account.pubkey(),
+                mint,
+                owner,
+            )?,
+        ];
+        self.send_ixs(&instructions, &[&self.deployer, &account])
+            .await?;
+
+        Ok(account.pubkey())
+    }
+
+    /// Creates a local Token-2022 mint with the given extensions and the deployer as mint and
+    /// extension authority.
+    pub async fn create_token2022_mint(
+        &self,
+        decimals: u8,
+        extensions: &[MintExtension],
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let authority = self.deployer.pubkey();
+        let extension_types: Vec<ExtensionType> = extensions
+            .iter()
+            .map(MintExtension::extension_type)
+            .collect();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(
+            &extension_types,
+        )
+        .unwrap();
+
+        let mut instructions = vec![system_instruction::create_account(
+            &authority,
+            &mint.pubkey(),
+            self.get_minimum_rent_for_size(space).await,
+            space as u64,
+            &spl_token_2022::ID,
+        )];
+        instructions.extend(
+            extensions
+                .iter()
+                .map(|extension| extension.initialize_instruction(&mint.pubkey(), &authority)),
+        );
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::ID,
+                &mint.pubkey(),
+                &authority,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        );
+
+        self.process_mint_instructions(&instructions, &mint).await;
+
+        mint.pubkey()
+    }
+
+    async fn process_mint_instructions(&self, instructions: &[Instruction], mint: &Keypair) {
+        self.send_ixs(instructions, &[&self.deployer, mint])
+            .await
+            .unwrap();
+    }
+
     pub async fn replicate_account_from_mainnet(&self, account_pubkey: &Pubkey) -> Result<()> {
         let mut cache = GLOBAL_CACHE.lock().unwrap();
+        self.track(&[*account_pubkey]);
 
         if let Some(cached_account) = cache.0.get(account_pubkey) {
             self.context.borrow_mut().set_account(
@@ -0,0 +1,139 @@
+use anyhow::Result;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, signature::Keypair, signer::Signer,
+    transaction::Transaction,
+};
+
+use crate::test::TestFixture;
+
+/// Fluent builder for a single transaction against the test bank.
+///
+/// The fee payer defaults to the first signer, or the bank payer when there are no signers.
+pub struct TxBuilder<'a> {
+    test_f: &'a TestFixture,
+    instructions: Vec<Instruction>,
+    signers: Vec<&'a Keypair>,
+    payer: Option<&'a Keypair>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    blockhash: Option<Hash>,
+}
+
+impl<'a> TxBuilder<'a> {
+    pub fn new(test_f: &'a TestFixture) -> Self {
+        Self {
+            test_f,
+            instructions: vec![],
+            signers: vec![],
+            payer: None,
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            blockhash: None,
+        }
+    }
+
+    pub fn ix(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    pub fn ixs(mut self, instructions: &[Instruction]) -> Self {
+        self.instructions.extend_from_slice(instructions);
+        self
+    }
+
+    pub fn signer(mut self, signer: &'a Keypair) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    pub fn signers(mut self, signers: &[&'a Keypair]) -> Self {
+        self.signers.extend_from_slice(signers);
+        self
+    }
+
+    pub fn payer(mut self, payer: &'a Keypair) -> Self {
+        self.payer = Some(payer);
+        self
+    }
+
+    pub fn compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    pub fn compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Signs against `blockhash` instead of a fresh one, e.g. to build a batch for
+    /// [`TestFixture::process_concurrently`] without advancing the bank.
+    pub fn blockhash(mut self, blockhash: Hash) -> Self {
+        self.blockhash = Some(blockhash);
+        self
+    }
+
+    /// Signs the transaction against a fresh blockhash, unless one was pinned.
+    pub async fn build(self) -> Result<Transaction> {
+        let mut instructions = vec![];
+        if let Some(units) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        if let Some(micro_lamports) = self.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                micro_lamports,
+            ));
+        }
+        instructions.extend(self.instructions);
+
+        let accounts: Vec<_> = instructions
+            .iter()
+            .flat_map(|ix| ix.accounts.iter().map(|meta| meta.pubkey))
+            .collect();
+        self.test_f.track(&accounts);
+
+        let bank_payer = self.test_f.payer_keypair();
+        let payer = self
+            .payer
+            .or_else(|| self.signers.first().copied())
+            .unwrap_or(&bank_payer);
+
+        let mut signers = vec![payer];
+        for signer in self.signers {
+            if !signers.iter().any(|s| s.pubkey() == signer.pubkey()) {
+                signers.push(signer);
+            }
+        }
+
+        let last_blockhash = match self.blockhash {
+            Some(blockhash) => blockhash,
+            None => {
+                self.test_f
+                    .context
+                    .borrow_mut()
+                    .get_new_latest_blockhash()
+                    .await?
+            },
+        };
+        Ok(Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &signers,
+            last_blockhash,
+        ))
+    }
+
+    pub async fn send(self) -> Result<()> {
+        let test_f = self.test_f;
+        let tx = self.build().await?;
+        test_f
+            .context
+            .borrow()
+            .banks_client
+            .process_transaction(tx)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fs::read, mem};
+use std::{any::type_name, fs::read, mem, ops::Range};
 
 use anchor_lang::{prelude::UpgradeableLoaderState, system_program, Discriminator, ZeroCopy};
 use solana_account::Account;
@@ -147,7 +147,32 @@ pub fn bpf_loader_upgradeable_program_accounts(
     ]
 }
 
-pub fn load_zero_copy<T: Discriminator + ZeroCopy>(account: &mut Account) -> &mut T {
+/// Byte range of the `T` body in zero-copy account data, after checking the discriminator.
+pub(crate) fn zero_copy_range<T: Discriminator + ZeroCopy>(data: &[u8]) -> Range<usize> {
     let disc = T::DISCRIMINATOR;
-    bytemuck::from_bytes_mut(&mut account.data[disc.len()..mem::size_of::<T>() + disc.len()])
+    let range = disc.len()..disc.len() + mem::size_of::<T>();
+    assert!(
+        data.len() >= range.end && data.starts_with(disc),
+        "account is not a {}",
+        type_name::<T>()
+    );
+    range
+}
+
+/// Decodes a dump of zero-copy account data, e.g. a fixture loaded with `include_bytes!` that has
+/// no alignment guarantee. The dump must hold exactly the discriminator and a `T`.
+pub fn decode_zero_copy<T: Discriminator + ZeroCopy>(data: &[u8]) -> T {
+    let range = zero_copy_range::<T>(data);
+    assert_eq!(
+        data.len(),
+        range.end,
+        "dump size does not match {}",
+        type_name::<T>()
+    );
+    bytemuck::pod_read_unaligned(&data[range])
+}
+
+pub fn load_zero_copy<T: Discriminator + ZeroCopy>(account: &mut Account) -> &mut T {
+    let range = zero_copy_range::<T>(&account.data);
+    bytemuck::from_bytes_mut(&mut account.data[range])
 }